@@ -0,0 +1,82 @@
+//! How many allocations the simulation makes, counted by a wrapper around the system allocator,
+//! alongside the time the allocation-free paths take.
+//!
+//! Run with `cargo bench --bench allocations`. The counts are printed before the timings,
+//! and the run fails if stepping with a reused buffer allocates at all.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use undeath::{Cell, Grid};
+
+/// The system allocator, counting every allocation.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// How many allocations `f` makes each time, on average over `runs` calls.
+fn allocations_per_call(runs: usize, mut f: impl FnMut()) -> f64 {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..runs {
+        f();
+    }
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / runs as f64
+}
+
+/// A deterministic half-full `size`×`size` grid, as in the `grid` benches.
+fn soup(size: i32) -> Grid {
+    let mut grid = Grid::new(size, size);
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for y in 0..size {
+        for x in 0..size {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            if state & 1 == 1 {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    grid
+}
+
+fn stepping(c: &mut Criterion) {
+    for size in [8, 64] {
+        let mut grid = soup(size);
+        let mut neighbours = Grid::new(size, size);
+        let step = allocations_per_call(1000, || grid.step());
+        let step_with = allocations_per_call(1000, || grid.step_with(&mut neighbours));
+        let neighbours_into = allocations_per_call(1000, || grid.neighbours_into(&mut neighbours));
+        println!(
+            "{size}×{size}: step {step} allocations per call, step_with {step_with}, neighbours_into {neighbours_into}"
+        );
+        assert_eq!(step_with, 0.0, "stepping with a reused buffer allocated");
+        assert_eq!(neighbours_into, 0.0, "counting neighbours allocated");
+
+        c.bench_function(&format!("step_with/{size}"), |bench| {
+            bench.iter(|| black_box(&mut grid).step_with(&mut neighbours))
+        });
+    }
+}
+
+criterion_group!(benches, stepping);
+criterion_main!(benches);
@@ -0,0 +1,222 @@
+//! Baselines for the grid operations used in the solver's inner loops.
+//!
+//! Run with `cargo bench --bench grid`.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use undeath::{grid::SIZE, testing, Cell, Grid};
+
+/// The sides of the larger grids the simulation is also measured on, where the whole-grid loops dominate.
+const LARGE_SIZES: [i32; 2] = [64, 256];
+
+/// A deterministic half-full `size`×`size` grid, so that results are comparable between runs.
+fn soup(size: i32) -> Grid {
+    let mut grid = Grid::new(size, size);
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for y in 0..size {
+        for x in 0..size {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            if state & 1 == 1 {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    grid
+}
+
+fn arithmetic(c: &mut Criterion) {
+    let a = soup(SIZE);
+    let b = Grid::fill(Cell::one());
+    c.bench_function("add_assign", |bench| {
+        bench.iter(|| {
+            let mut grid = a.clone();
+            grid += black_box(&b);
+            grid
+        })
+    });
+    c.bench_function("sub_assign", |bench| {
+        bench.iter(|| {
+            let mut grid = a.clone();
+            grid -= black_box(&b);
+            grid
+        })
+    });
+    c.bench_function("sub_assign_saturating", |bench| {
+        bench.iter(|| {
+            let mut grid = a.clone();
+            grid.sub_assign_saturating(black_box(&b));
+            grid
+        })
+    });
+}
+
+fn simulation(c: &mut Criterion) {
+    let grid = soup(SIZE);
+    c.bench_function("neighbours", |bench| {
+        bench.iter(|| black_box(&grid).neighbours())
+    });
+    c.bench_function("step", |bench| {
+        bench.iter(|| {
+            let mut grid = black_box(&grid).clone();
+            grid.step();
+            grid
+        })
+    });
+    c.bench_function("step_bitparallel", |bench| {
+        bench.iter(|| {
+            let mut grid = black_box(&grid).clone();
+            grid.step_bitparallel();
+            grid
+        })
+    });
+    c.bench_function("alive_cells", |bench| {
+        bench.iter(|| black_box(&grid).alive_cells().count())
+    });
+}
+
+/// The same operations as [`simulation`], on larger grids, with one group per operation so that sizes compare side by side.
+fn large_simulation(c: &mut Criterion) {
+    let grids = LARGE_SIZES.map(soup);
+    let mut group = c.benchmark_group("neighbours");
+    for grid in &grids {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(grid.width()),
+            grid,
+            |bench, grid| bench.iter(|| black_box(grid).neighbours()),
+        );
+    }
+    group.finish();
+    let mut group = c.benchmark_group("step");
+    for grid in &grids {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(grid.width()),
+            grid,
+            |bench, grid| {
+                bench.iter(|| {
+                    let mut grid = black_box(grid).clone();
+                    grid.step();
+                    grid
+                })
+            },
+        );
+    }
+    group.finish();
+    let mut group = c.benchmark_group("step_bitparallel");
+    for grid in &grids {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(grid.width()),
+            grid,
+            |bench, grid| {
+                bench.iter(|| {
+                    let mut grid = black_box(grid).clone();
+                    grid.step_bitparallel();
+                    grid
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// [`Grid::neighbours_into`], which adds the rows straight into its output,
+/// against the separable version it replaced, which sums the rows into a scratch buffer first.
+fn fused_against_separable(c: &mut Criterion) {
+    let mut group = c.benchmark_group("neighbours_into");
+    for size in [SIZE, 64, 256] {
+        let grid = soup(size);
+        let mut out = Grid::new(size, size);
+        group.bench_with_input(BenchmarkId::new("fused", size), &grid, |bench, grid| {
+            bench.iter(|| black_box(grid).neighbours_into(&mut out))
+        });
+        let mut windows = Vec::new();
+        group.bench_with_input(BenchmarkId::new("separable", size), &grid, |bench, grid| {
+            bench.iter(|| {
+                testing::separable_neighbours_into(black_box(grid), &mut out, &mut windows)
+            })
+        });
+    }
+    group.finish();
+}
+
+/// How many frames [`rendering`] draws in each iteration, about as many as a second of an animation at full speed.
+const FRAMES: usize = 10_000;
+
+fn rendering(c: &mut Criterion) {
+    let grid = soup(SIZE);
+    c.bench_function("render", |bench| bench.iter(|| black_box(&grid).render()));
+    let mut buffer = String::new();
+    c.bench_function("render_into", |bench| {
+        bench.iter(|| {
+            buffer.clear();
+            black_box(&grid).render_into(&mut buffer);
+        })
+    });
+
+    // Many frames in a row, as when animating: a fresh string for every frame, or one buffer reused throughout.
+    let guess = testing::apply_guesses(&grid, &[(1, 1, true), (4, 2, false), (6, 5, true)]);
+    let mut group = c.benchmark_group("render_10k");
+    group.sample_size(10);
+    group.bench_function("grid/render", |bench| {
+        bench.iter(|| {
+            (0..FRAMES)
+                .map(|_| black_box(&grid).render().len())
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("grid/render_into", |bench| {
+        bench.iter(|| {
+            for _ in 0..FRAMES {
+                buffer.clear();
+                black_box(&grid).render_into(&mut buffer);
+            }
+        })
+    });
+    group.bench_function("guess/render", |bench| {
+        bench.iter(|| {
+            (0..FRAMES)
+                .map(|_| black_box(&guess).render().len())
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("guess/render_into", |bench| {
+        bench.iter(|| {
+            for _ in 0..FRAMES {
+                buffer.clear();
+                black_box(&guess).render_into(&mut buffer);
+            }
+        })
+    });
+    group.finish();
+}
+
+/// [`Grid::canonical`] and [`Grid::canonical_symmetric`], which rank every translation rather than build each one.
+fn canonical_forms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("canonical");
+    for size in [SIZE, 64] {
+        let grid = soup(size);
+        group.bench_with_input(
+            BenchmarkId::new("translations", size),
+            &grid,
+            |bench, grid| bench.iter(|| black_box(grid).canonical()),
+        );
+        group.bench_with_input(BenchmarkId::new("symmetric", size), &grid, |bench, grid| {
+            bench.iter(|| black_box(grid).canonical_symmetric())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    arithmetic,
+    simulation,
+    large_simulation,
+    fused_against_separable,
+    rendering,
+    canonical_forms
+);
+criterion_main!(benches);
@@ -0,0 +1,178 @@
+//! Baselines for the solver: guess propagation, and whole searches.
+//!
+//! Run with `cargo bench --bench solver`. Everything here is deterministic,
+//! so results are comparable between runs and between branches.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use undeath::{
+    bruteforce,
+    grid::SIZE,
+    rng::{RandomSource, Rng},
+    searcher::Strategy,
+    solve_with, testing, Cell, Grid, Guess, Options, SearchResult, Searcher, SolverSession,
+};
+
+fn glider() -> Grid {
+    Grid::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/glider.gol")).unwrap()
+}
+
+/// A fixed sequence of guesses, spread over the grid, to reach a dense mid-search state.
+fn script() -> Vec<(i32, i32, bool)> {
+    (0..24)
+        .map(|i| ((i * 3) % 8, (i * 5) % 8, i % 3 == 0))
+        .collect()
+}
+
+fn guesses(c: &mut Criterion) {
+    let target = glider();
+    let script = script();
+    let mid_search = testing::apply_guesses(&target, &script[..12]);
+
+    c.bench_function("guess_clone", |bench| {
+        bench.iter(|| black_box(&mid_search).clone())
+    });
+    c.bench_function("guess_alive_empty", |bench| {
+        bench.iter(|| testing::apply_guesses(black_box(&target), &[(6, 6, true)]))
+    });
+    c.bench_function("propagate_mid_search", |bench| {
+        bench.iter(|| testing::apply_guesses(black_box(&target), black_box(&script)))
+    });
+    c.bench_function("guess_default", |bench| bench.iter(Guess::default));
+}
+
+fn searches(c: &mut Criterion) {
+    let target = glider();
+    c.bench_function("search_glider_100k", |bench| {
+        bench.iter(|| {
+            let mut searcher = Searcher::new(black_box(&target).clone());
+            match searcher.search(100_000).unwrap() {
+                SearchResult::Found(grid, _) => Some(grid),
+                _ => None,
+            }
+        })
+    });
+    c.bench_function("search_glider_blocks_1k", |bench| {
+        bench.iter(|| {
+            let mut searcher = Searcher::new(black_box(&target).clone()).block_propagation(true);
+            match searcher.search(1_000).unwrap() {
+                SearchResult::Found(grid, _) => Some(grid),
+                _ => None,
+            }
+        })
+    });
+
+    // A blinker, with predecessors confined to the 4×4 window around it.
+    let blinker = Grid::from_text("\n\n\n..###");
+    let mut mask = Grid::default();
+    for y in 2..6 {
+        for x in 1..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    c.bench_function("search_all_masked_blinker", |bench| {
+        bench.iter(|| {
+            let mut searcher = Searcher::with_mask(black_box(&blinker).clone(), &mask);
+            let mut count = 0;
+            loop {
+                match searcher.search(usize::MAX).unwrap() {
+                    SearchResult::Found(_, _) => count += 1,
+                    SearchResult::Unsatisfiable => break count,
+                    _ => {}
+                }
+            }
+        })
+    });
+    c.bench_function("bruteforce_masked_blinker", |bench| {
+        bench.iter(|| bruteforce::predecessors(black_box(&blinker), &mask).unwrap())
+    });
+}
+
+/// Dense targets: random half-full soups, stepped once so that they certainly have predecessors.
+fn dense_soups() -> Vec<Grid> {
+    (1..=2)
+        .map(|seed| {
+            let mut rng = Rng::new(seed);
+            let mut soup = Grid::default();
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    if rng.gen_bool(0.5) {
+                        soup.set_wrapped(x, y, Cell::one());
+                    }
+                }
+            }
+            soup.step();
+            soup
+        })
+        .collect()
+}
+
+/// Depth-first search rarely finds a predecessor of a dense soup within its budget; local search usually does.
+fn local_search(c: &mut Criterion) {
+    let local = Strategy::Local {
+        noise_percent: Strategy::DEFAULT_NOISE_PERCENT,
+        restart_after: Strategy::DEFAULT_RESTART_AFTER,
+    };
+    for (i, soup) in dense_soups().into_iter().enumerate() {
+        for (name, strategy) in [("dfs_100k", Strategy::DepthFirst), ("local", local)] {
+            c.bench_function(&format!("search_soup_{}_{name}", i + 1), |bench| {
+                bench.iter(|| {
+                    let mut searcher = Searcher::new(black_box(&soup).clone()).strategy(strategy);
+                    match searcher.search(100_000).unwrap() {
+                        SearchResult::Found(grid, _) => Some(grid),
+                        _ => None,
+                    }
+                })
+            });
+        }
+    }
+}
+
+/// Many small targets: random soups a fifth full, stepped once so that they certainly have predecessors.
+fn sparse_soups(count: u64) -> Vec<Grid> {
+    let mut rng = Rng::new(469);
+    (0..count)
+        .map(|_| {
+            let mut soup = Grid::default();
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    if rng.gen_bool(0.2) {
+                        soup.set_wrapped(x, y, Cell::one());
+                    }
+                }
+            }
+            soup.step();
+            soup
+        })
+        .collect()
+}
+
+/// Solving many targets in a row, each from scratch and through one reused session.
+/// The budget is small, so that per-target setup is a visible part of the cost.
+fn batches(c: &mut Criterion) {
+    let targets = sparse_soups(1000);
+    let options = Options {
+        max_iterations: 100,
+    };
+    c.bench_function("solve_1000_targets_fresh", |bench| {
+        bench.iter(|| {
+            targets
+                .iter()
+                .filter(|target| matches!(solve_with(black_box(target), &options), Ok(Some(_))))
+                .count()
+        })
+    });
+    c.bench_function("solve_1000_targets_session", |bench| {
+        let mut session = SolverSession::new();
+        bench.iter(|| {
+            targets
+                .iter()
+                .filter(|target| matches!(session.solve(black_box(target), &options), Ok(Some(_))))
+                .count()
+        })
+    });
+}
+
+criterion_group!(benches, guesses, searches, local_search, batches);
+criterion_main!(benches);
@@ -0,0 +1,35 @@
+//! Finds a single predecessor of the glider, using `undeath` as a library.
+//!
+//! Run with `cargo run --release --example predecessor`.
+
+use undeath::{
+    grid::Grid,
+    searcher::{SearchResult, Searcher},
+    string::hconcat_all,
+    Error,
+};
+
+fn main() -> Result<(), Error> {
+    let target = Grid::from_file("glider.gol")?;
+    let mut searcher = Searcher::new(target.clone());
+    loop {
+        match searcher.search(100_000)? {
+            SearchResult::Found(predecessor, _) => {
+                let mut stepped = predecessor.clone();
+                stepped.step();
+                println!(
+                    "{}",
+                    hconcat_all(&[&predecessor.render(), &stepped.render()], "   ")
+                );
+                break;
+            }
+            SearchResult::Working(_) => {}
+            SearchResult::Unsatisfiable => {
+                println!("The glider has no predecessor on this grid.");
+                break;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,24 @@
+//! Prints the three sparsest predecessors of the glider among the first thousand found.
+//!
+//! Run with `cargo run --release --example sparsest`.
+
+use undeath::{grid::Grid, string::hconcat_all, Error};
+
+fn main() -> Result<(), Error> {
+    let target = Grid::from_file("glider.gol")?;
+    let mut parents = target
+        .predecessors()
+        .take(1_000)
+        .collect::<Result<Vec<_>, _>>()?;
+    parents.sort_by_key(|parent| parent.alive_cells().count());
+
+    let renders = parents.iter().take(3).map(Grid::render).collect::<Vec<_>>();
+    println!(
+        "{}",
+        hconcat_all(
+            &renders.iter().map(String::as_str).collect::<Vec<_>>(),
+            "   "
+        )
+    );
+    Ok(())
+}
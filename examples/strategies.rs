@@ -0,0 +1,104 @@
+//! Compares how quickly depth-first, best-first, beam, local and hybrid search find a first predecessor
+//! of each pattern in the corpus, alongside some statistics of each pattern.
+//!
+//! Run with `cargo run --release --example strategies`.
+
+use std::time::Instant;
+
+use undeath::{
+    grid::Grid,
+    searcher::{SearchResult, Searcher, Strategy},
+    string::{human_duration, large_number, ColumnAlignment, Table},
+    Error,
+};
+
+/// Give up on a pattern after this many iterations.
+const BUDGET: usize = 10_000_000;
+
+/// The iterations taken to find a first predecessor, or `None` if there was none within the budget
+/// or the search gave up.
+fn first_predecessor(target: &Grid, strategy: Strategy) -> Result<Option<usize>, Error> {
+    let mut searcher = Searcher::new(target.clone()).strategy(strategy);
+    let mut total = 0;
+    while total < BUDGET {
+        match searcher.search(BUDGET - total)? {
+            SearchResult::Found(_, iterations) => return Ok(Some(total + iterations)),
+            SearchResult::Working(iterations) => total += iterations,
+            _ => return Ok(None),
+        }
+    }
+    Ok(None)
+}
+
+fn main() -> Result<(), Error> {
+    let mut paths = std::fs::read_dir("tests/corpus")
+        .map_err(|e| Error::io("tests/corpus", e))?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "gol"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let strategies = [
+        ("Depth-first", Strategy::DepthFirst),
+        (
+            "Best-first",
+            Strategy::BestFirst {
+                max_open: Strategy::DEFAULT_MAX_OPEN,
+            },
+        ),
+        ("Beam (width 64)", Strategy::Beam { width: 64 }),
+        (
+            "Local",
+            Strategy::Local {
+                noise_percent: Strategy::DEFAULT_NOISE_PERCENT,
+                restart_after: Strategy::DEFAULT_RESTART_AFTER,
+            },
+        ),
+        (
+            "Hybrid",
+            Strategy::Hybrid {
+                max_violations: Strategy::DEFAULT_MAX_VIOLATIONS,
+                radius: Strategy::DEFAULT_RADIUS,
+            },
+        ),
+    ];
+    let mut table = Table::new();
+    table.header(
+        ["Pattern", "Population", "Spread", "Components"]
+            .into_iter()
+            .chain(strategies.iter().map(|(name, _)| *name)),
+    );
+    for column in 1..=strategies.len() + 3 {
+        table.align(column, ColumnAlignment::Right);
+    }
+    for path in paths {
+        let target = Grid::from_file(&path)?;
+        let mut row = vec![path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()];
+        let stats = target.stats();
+        row.push(large_number(stats.population));
+        row.push(
+            stats
+                .spread
+                .map_or("none".to_owned(), |spread| format!("{spread:.2}")),
+        );
+        row.push(large_number(stats.components));
+        for (_, strategy) in strategies {
+            let start = Instant::now();
+            row.push(match first_predecessor(&target, strategy)? {
+                Some(iterations) => format!(
+                    "{} in {}",
+                    large_number(iterations),
+                    human_duration(start.elapsed())
+                ),
+                None => "none".to_owned(),
+            });
+        }
+        table.add_row(row);
+    }
+    println!("{}", table.render());
+    Ok(())
+}
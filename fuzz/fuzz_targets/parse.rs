@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use undeath::{Error, Grid};
+
+// Parsing arbitrary bytes must never panic: it either produces a grid or reports where the input is malformed.
+fuzz_target!(|data: &[u8]| {
+    match Grid::from_bytes(data) {
+        Ok(grid) => {
+            // Whatever was parsed must also survive rendering.
+            let _ = grid.render();
+        }
+        Err(Error::Parse { line, column, .. }) => {
+            assert!(line >= 1 && column >= 1);
+        }
+        Err(error) => panic!("unexpected error: {error}"),
+    }
+});
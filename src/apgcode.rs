@@ -0,0 +1,158 @@
+//! Naming patterns by apgcode, as Catagolue does.
+//!
+//! An apgcode such as `xq4_153` is a prefix classifying the pattern, here a spaceship of period 4,
+//! then an underscore and the pattern's cells in the extended Wechsler format. The cells are cut into strips
+//! five rows tall, from the top, and each column of a strip is written as one character from `0` to `v`,
+//! the base 32 digit whose bits, least significant first, are the column's cells from the top.
+//! `z` starts the next strip. Runs of empty columns are shortened: `w` is two, `x` three,
+//! and `y` followed by a base 36 digit `n` is `4 + n`. Empty columns at the end of a strip are left out.
+//!
+//! Of the eight orientations of a pattern, Catagolue names it by the one with the shortest code,
+//! and then the first in ASCII order, so that a pattern has the same code however it is turned.
+
+use crate::{
+    grid::{Cell, Grid, Symmetry, MAX_SIZE, MIN_SIZE, SIZE},
+    Error,
+};
+
+/// The digits of the extended Wechsler format, in order of value.
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// The longest run of empty columns that a single `y` can stand for.
+const MAX_ZEROS: usize = 4 + 35;
+
+/// Appends a run of `zeros` empty columns, using the shortest characters for it.
+fn push_zeros(code: &mut String, mut zeros: usize) {
+    while zeros > MAX_ZEROS {
+        code.push_str("yz");
+        zeros -= MAX_ZEROS;
+    }
+    match zeros {
+        0 => {}
+        1 => code.push('0'),
+        2 => code.push('w'),
+        3 => code.push('x'),
+        _ => {
+            code.push('y');
+            code.push(DIGITS[zeros - 4] as char);
+        }
+    }
+}
+
+/// The extended Wechsler code of `grid` as it is, without trying other orientations.
+fn encode_orientation(grid: &Grid) -> String {
+    let Some((left, top, width, height)) = grid.bounding_box() else {
+        return "0".to_owned();
+    };
+    let alive = |x: i32, y: i32| {
+        y < height
+            && grid
+                .get_wrapped(
+                    (left + x).rem_euclid(grid.width()),
+                    (top + y).rem_euclid(grid.height()),
+                )
+                .value
+                > 0
+    };
+    let mut code = String::new();
+    for strip in 0..(height + 4) / 5 {
+        if strip > 0 {
+            code.push('z');
+        }
+        let mut zeros = 0;
+        for x in 0..width {
+            let column = (0..5)
+                .filter(|row| alive(x, 5 * strip + row))
+                .fold(0, |column, row| column | 1 << row);
+            if column == 0 {
+                zeros += 1;
+            } else {
+                push_zeros(&mut code, zeros);
+                zeros = 0;
+                code.push(DIGITS[column] as char);
+            }
+        }
+    }
+    code
+}
+
+/// The extended Wechsler code of the alive cells of `grid`, in Catagolue's choice of orientation.
+pub(crate) fn encode(grid: &Grid) -> String {
+    Symmetry::ALL
+        .into_iter()
+        .map(|symmetry| encode_orientation(&grid.transformed(symmetry)))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        .expect("there are eight symmetries")
+}
+
+/// Reads an apgcode, or just its extended Wechsler part, with the pattern's top left corner at the grid's.
+pub(crate) fn decode(code: &str) -> Result<Grid, Error> {
+    let (prefix, wechsler) = match code.split_once('_') {
+        Some((prefix, _)) if !prefix.starts_with('x') => {
+            return Err(Error::InvalidInput(format!(
+                "only apgcodes starting with `x` give the cells of a pattern, but this starts with `{prefix}`"
+            )));
+        }
+        Some((prefix, wechsler)) => (prefix.chars().count() + 1, wechsler),
+        None => (0, code),
+    };
+    let error = |index: usize, message: String| Error::Parse {
+        line: 1,
+        column: prefix + index + 1,
+        message,
+    };
+    if wechsler.is_empty() {
+        return Err(error(0, "the code has no cells".to_owned()));
+    }
+    let mut alive = Vec::new();
+    let (mut x, mut strip) = (0usize, 0usize);
+    let mut chars = wechsler.chars().enumerate();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '0'..='9' | 'a'..='v' => {
+                let column = c.to_digit(32).expect("a base 32 digit");
+                let rows = (u32::BITS - column.leading_zeros()) as usize;
+                if rows > 0 && (x >= MAX_SIZE as usize || 5 * strip + rows > MAX_SIZE as usize) {
+                    return Err(Error::InvalidInput(format!(
+                        "the pattern does not fit on the largest grid, {MAX_SIZE}×{MAX_SIZE}"
+                    )));
+                }
+                alive.extend(
+                    (0..5)
+                        .filter(|row| column >> row & 1 != 0)
+                        .map(|row| (x, 5 * strip + row)),
+                );
+                x += 1;
+            }
+            'w' => x += 2,
+            'x' => x += 3,
+            'y' => match chars.next() {
+                Some((_, digit @ ('0'..='9' | 'a'..='z'))) => {
+                    x += 4 + digit.to_digit(36).expect("a base 36 digit") as usize;
+                }
+                _ => {
+                    return Err(error(
+                        index,
+                        "`y` must be followed by a digit from `0` to `z`".to_owned(),
+                    ))
+                }
+            },
+            'z' => {
+                x = 0;
+                strip += 1;
+            }
+            c => return Err(error(index, format!("unexpected `{c}` in an apgcode"))),
+        }
+    }
+    let width = alive.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+    let height = alive.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+    let mut grid = if width <= SIZE as usize && height <= SIZE as usize {
+        Grid::default()
+    } else {
+        Grid::new((width as i32).max(MIN_SIZE), (height as i32).max(MIN_SIZE))
+    };
+    for (x, y) in alive {
+        grid.set_wrapped(x as i32, y as i32, Cell::one());
+    }
+    Ok(grid)
+}
@@ -0,0 +1,284 @@
+//! An exhaustive predecessor search, used as an independent oracle for the [`Searcher`](crate::Searcher).
+//!
+//! Nothing here is clever: every assignment of the cells allowed to vary is stepped and compared with the target.
+//! This makes it far too slow for real patterns, but easy to trust.
+//! [`unsatisfiable_window`] only looks at a small window of the target at a time,
+//! and checks its rows as it goes rather than stepping whole grids, but still rules out only assignments that fail.
+
+use crate::{
+    grid::{Grid, Topology, SIZE},
+    Cell, Error,
+};
+
+/// The most cells [`predecessors`] will vary, giving at most 2<sup>25</sup> assignments to check.
+pub const MAX_FREE_CELLS: usize = 25;
+
+/// Finds every predecessor of `next` whose alive cells all lie in `mask`,
+/// by stepping each of the 2<sup>n</sup> assignments of the `n` positive cells of `mask`.
+/// Cells outside `mask` are taken to be dead.
+///
+/// The result is sorted, and is exactly the set of such predecessors.
+/// Masks with more than [`MAX_FREE_CELLS`] cells, or of another size than `next`, are rejected with [`Error::InvalidInput`].
+///
+/// ```
+/// use undeath::{bruteforce, Cell, Grid};
+///
+/// let blinker = Grid::from_text("\n\n .#\n .#\n .#\n");
+/// let mut mask = Grid::default();
+/// for x in 0..4 {
+///     for y in 1..5 {
+///         mask.set_wrapped(x, y, Cell::one());
+///     }
+/// }
+/// let parents = bruteforce::predecessors(&blinker, &mask)?;
+/// assert!(parents.iter().any(|parent| *parent == Grid::from_text("\n\n\n.###\n")));
+/// # Ok::<(), undeath::Error>(())
+/// ```
+pub fn predecessors(next: &Grid, mask: &Grid) -> Result<Vec<Grid>, Error> {
+    let mut result = Vec::new();
+    for_each_predecessor(
+        next,
+        &Grid::new(next.width(), next.height()),
+        mask,
+        |grid| {
+            result.push(grid);
+            true
+        },
+    )?;
+    result.sort();
+    Ok(result)
+}
+
+/// Finds a predecessor of `next` in which the positive cells of `alive` are alive,
+/// the positive cells of `free` may be either alive or dead, and every other cell is dead.
+/// Returns `None` if there is no such predecessor.
+///
+/// As with [`predecessors`], `free` may have at most [`MAX_FREE_CELLS`] positive cells,
+/// and all three grids must be the same size.
+pub fn completion(next: &Grid, alive: &Grid, free: &Grid) -> Result<Option<Grid>, Error> {
+    let mut result = None;
+    for_each_predecessor(next, alive, free, |grid| {
+        result = Some(grid);
+        false
+    })?;
+    Ok(result)
+}
+
+/// Calls `found` with each predecessor of `next` that extends `alive` by some of the positive cells of `free`,
+/// until it returns `false`.
+fn for_each_predecessor(
+    next: &Grid,
+    alive: &Grid,
+    free: &Grid,
+    mut found: impl FnMut(Grid) -> bool,
+) -> Result<(), Error> {
+    check_sizes(next, alive, free)?;
+    let free_cells = free.alive_cells().collect::<Vec<_>>();
+    if free_cells.len() > MAX_FREE_CELLS {
+        return Err(Error::InvalidInput(format!(
+            "a brute-force search can vary at most {MAX_FREE_CELLS} cells, but the mask has {}",
+            free_cells.len()
+        )));
+    }
+
+    for assignment in 0u32..1 << free_cells.len() {
+        // The predecessor is stepped on the target's topology.
+        let mut grid = alive.clone().with_topology(next.topology());
+        for (i, &(x, y)) in free_cells.iter().enumerate() {
+            if assignment & (1 << i) != 0 {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+        let mut stepped = grid.clone();
+        stepped.step();
+        if stepped == *next && !found(grid) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Fails with [`Error::InvalidInput`] unless `alive` and `free` are the same size as `next`.
+fn check_sizes(next: &Grid, alive: &Grid, free: &Grid) -> Result<(), Error> {
+    match [alive, free].iter().find(|grid| !grid.same_size(next)) {
+        Some(grid) => Err(Error::InvalidInput(format!(
+            "the target is {}×{}, but a mask is {}×{}",
+            next.width(),
+            next.height(),
+            grid.width(),
+            grid.height()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The default side length of the windows [`unsatisfiable_window`] checks.
+pub const DEFAULT_WINDOW: usize = 4;
+
+/// The largest side length of the windows [`unsatisfiable_window`] checks,
+/// so that the previous-frame cells around a window fit across a [`SIZE`]×[`SIZE`] grid.
+pub const MAX_WINDOW: usize = SIZE as usize - 2;
+
+/// The cells of the row `y` of the previous frame from `x` to `x + width`, as bits where bit `i` is column `x + i`.
+/// On a plane, the cells past its edges are never set.
+fn row_bits(grid: &Grid, topology: Topology, x: i32, y: i32, width: usize) -> u32 {
+    let on_grid = |x: i32| {
+        topology == Topology::Torus
+            || (0..grid.width()).contains(&x) && (0..grid.height()).contains(&y)
+    };
+    (0..width as i32)
+        .filter(|&i| on_grid(x + i) && grid.get_wrapped(x + i, y).value > 0)
+        .fold(0, |bits, i| bits | 1 << i)
+}
+
+/// Whether the middle row `b`, between `a` and `c`, steps to `target` in every column but the outer two.
+fn row_steps_to(a: u32, b: u32, c: u32, target: u32, window: usize) -> bool {
+    (0..window).all(|j| {
+        let block = |row: u32| (row >> j & 0b111).count_ones();
+        let alive = b >> (j + 1) & 1 != 0;
+        let neighbours = block(a) + block(b) + block(c) - alive as u32;
+        let next = neighbours == 3 || alive && neighbours == 2;
+        next == (target >> j & 1 != 0)
+    })
+}
+
+/// One `window`×`window` window of a target, and the previous-frame cells around it.
+struct Window {
+    window: usize,
+    /// For each of the `window + 2` previous-frame rows, the cells that must be alive, as in [`row_bits`].
+    fixed: Vec<u32>,
+    /// Likewise, the cells that may be either alive or dead.
+    free: Vec<u32>,
+    /// For each target row, the cells that must be alive.
+    target: Vec<u32>,
+    /// For each target row, the pairs of previous-frame rows above it already known to lead nowhere,
+    /// indexed by `a << width | b`.
+    failed: Vec<Vec<bool>>,
+}
+
+impl Window {
+    /// Every value of the previous-frame row `r`: the fixed cells, with each subset of the free ones.
+    fn options(&self, r: usize) -> impl Iterator<Item = u32> {
+        let (fixed, free) = (self.fixed[r], self.free[r]);
+        let mut subset = Some(0u32);
+        std::iter::from_fn(move || {
+            let current = subset?;
+            // The next subset of `free` in increasing order, wrapping back to zero after the last.
+            let next = current.wrapping_sub(free) & free;
+            subset = (next != 0).then_some(next);
+            Some(fixed | current)
+        })
+    }
+
+    /// Whether the previous-frame rows after `a` and `b`, the ones above target row `t`, can be chosen
+    /// so that every target row from `t` on is right.
+    fn extends(&mut self, t: usize, a: u32, b: u32) -> bool {
+        if t == self.window {
+            return true;
+        }
+        let state = (a << (self.window + 2) | b) as usize;
+        if self.failed[t][state] {
+            return false;
+        }
+        let options = self.options(t + 2).collect::<Vec<_>>();
+        for c in options {
+            if row_steps_to(a, b, c, self.target[t], self.window) && self.extends(t + 1, b, c) {
+                return true;
+            }
+        }
+        self.failed[t][state] = true;
+        false
+    }
+
+    fn satisfiable(&mut self) -> bool {
+        let firsts = self.options(0).collect::<Vec<_>>();
+        let seconds = self.options(1).collect::<Vec<_>>();
+        firsts
+            .iter()
+            .any(|&a| seconds.iter().any(|&b| self.extends(0, a, b)))
+    }
+}
+
+/// Finds a `window`×`window` window of `next` that no previous frame extending `alive` by some of the positive
+/// cells of `free` steps to, and returns the position of its top-left cell. Returns `None` if there is no such window.
+///
+/// Only the `window + 2` square of previous-frame cells around each window is considered,
+/// and cells within it are treated as independent even where the square wraps onto itself,
+/// so this only ever finds windows that rule out every predecessor: it can miss contradictions, but never invents them.
+/// The work for each window grows as 2<sup>3(`window` + 2)</sup> at worst,
+/// `window` must be between 1 and [`MAX_WINDOW`], and the three grids the same size.
+///
+/// On a [`Topology::Plane`], the previous-frame cells past the edges are dead, and only windows
+/// that lie wholly on the grid are checked, since nothing constrains the target past its edges.
+///
+/// ```
+/// use undeath::{bruteforce, Cell, Grid};
+///
+/// // A blinker cannot be made from a single row of cells.
+/// let blinker = Grid::from_text("\n\n\n..###");
+/// let mut row = Grid::default();
+/// for x in 2..5 {
+///     row.set_wrapped(x, 3, Cell::one());
+/// }
+/// let window = bruteforce::unsatisfiable_window(&blinker, &Grid::default(), &row, bruteforce::DEFAULT_WINDOW)?;
+/// assert!(window.is_some());
+/// let full = Grid::fill(Cell::one());
+/// assert_eq!(bruteforce::unsatisfiable_window(&blinker, &Grid::default(), &full, 4)?, None);
+/// # Ok::<(), undeath::Error>(())
+/// ```
+pub fn unsatisfiable_window(
+    next: &Grid,
+    alive: &Grid,
+    free: &Grid,
+    window: usize,
+) -> Result<Option<(i32, i32)>, Error> {
+    if !(1..=MAX_WINDOW).contains(&window) {
+        return Err(Error::InvalidInput(format!(
+            "windows must be between 1 and {MAX_WINDOW} cells wide, but {window} were asked for"
+        )));
+    }
+    check_sizes(next, alive, free)?;
+    let width = window + 2;
+    let mut failed = vec![vec![false; 1 << (2 * width)]; window];
+    // The windows that fit on a plane start at most `window - 1` cells before its far edges.
+    let topology = next.topology();
+    let (last_x, last_y) = match topology {
+        Topology::Torus => (next.width(), next.height()),
+        Topology::Plane => (
+            next.width() - window as i32 + 1,
+            next.height() - window as i32 + 1,
+        ),
+    };
+    for y in 0..last_y {
+        for x in 0..last_x {
+            let rows = 0..width as i32;
+            for row in &mut failed {
+                row.fill(false);
+            }
+            let mut check = Window {
+                window,
+                fixed: rows
+                    .clone()
+                    .map(|r| row_bits(alive, topology, x - 1, y - 1 + r, width))
+                    .collect(),
+                free: rows
+                    .map(|r| row_bits(free, topology, x - 1, y - 1 + r, width))
+                    .collect(),
+                target: (0..window as i32)
+                    .map(|t| row_bits(next, topology, x, y + t, window))
+                    .collect(),
+                failed,
+            };
+            for r in 0..width {
+                // A cell both alive and free is simply alive.
+                check.free[r] &= !check.fixed[r];
+            }
+            let satisfiable = check.satisfiable();
+            failed = check.failed;
+            if !satisfiable {
+                return Ok(Some((x, y)));
+            }
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,125 @@
+//! Choosing how many iterations to search at a time, so that each batch takes about as long as wanted.
+//!
+//! The cost of an iteration varies by orders of magnitude between targets and between phases of one search,
+//! so any fixed amount is too many for some and too few for others.
+//! [`Cadence`] measures how fast recent batches went, and sizes the next one to take the target duration.
+
+use std::time::{Duration, Instant};
+
+/// A source of the current time, which tests can replace with one they control.
+pub trait Clock {
+    /// The time elapsed since some fixed point, which must never decrease.
+    fn now(&self) -> Duration;
+}
+
+/// The real monotonic clock, counting from when it was made.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Sizes batches of iterations to take about `target` each, from a moving average of the recent rate.
+///
+/// ```
+/// use std::time::Duration;
+/// use undeath::cadence::{Cadence, SystemClock};
+///
+/// let mut cadence = Cadence::new(Duration::from_millis(100), SystemClock::default());
+/// for _ in 0..20 {
+///     // Each iteration takes a microsecond.
+///     let iterations = cadence.slice();
+///     cadence.record(iterations, Duration::from_micros(iterations as u64));
+/// }
+/// assert!((99_000..=100_000).contains(&cadence.slice()));
+/// ```
+pub struct Cadence {
+    target: Duration,
+    clock: Box<dyn Clock + Send>,
+    /// The moving average of iterations per second, once there is a measurement.
+    rate: Option<f64>,
+    slice: usize,
+}
+
+impl Cadence {
+    /// How many iterations the first batch has, before anything has been measured.
+    pub const INITIAL_SLICE: usize = 1_000;
+    /// The batch duration that [`Cadence::default`] aims for.
+    pub const DEFAULT_TARGET: Duration = Duration::from_millis(100);
+    /// How much weight each new measurement gets in the moving average.
+    const SMOOTHING: f64 = 0.5;
+    /// How many times larger one batch may be than the last, so that one unusually fast batch
+    /// cannot make the next one take far too long.
+    const MAX_GROWTH: usize = 4;
+
+    /// Aims for batches that take `target` each, timed by `clock`.
+    pub fn new(target: Duration, clock: impl Clock + Send + 'static) -> Self {
+        Self {
+            target,
+            clock: Box::new(clock),
+            rate: None,
+            slice: Self::INITIAL_SLICE,
+        }
+    }
+
+    /// How many iterations the next batch should have.
+    pub fn slice(&self) -> usize {
+        self.slice
+    }
+
+    /// The duration each batch aims for.
+    pub fn target(&self) -> Duration {
+        self.target
+    }
+
+    /// The current time on this cadence's clock, to pass to [`Cadence::finish`] once a batch is done.
+    pub fn start(&self) -> Duration {
+        self.clock.now()
+    }
+
+    /// Records that a batch which began at `started` has just finished after `iterations` iterations.
+    pub fn finish(&mut self, iterations: usize, started: Duration) {
+        let elapsed = self.clock.now().saturating_sub(started);
+        self.record(iterations, elapsed);
+    }
+
+    /// Records that a batch of `iterations` iterations took `elapsed`, and sizes the next batch.
+    /// Batches too quick to measure double the next one, within the limit on growth.
+    pub fn record(&mut self, iterations: usize, elapsed: Duration) {
+        if iterations == 0 {
+            return;
+        }
+        let limit = self.slice.saturating_mul(Self::MAX_GROWTH);
+        if elapsed.is_zero() {
+            self.slice = self.slice.saturating_mul(2).min(limit);
+            return;
+        }
+        let sample = iterations as f64 / elapsed.as_secs_f64();
+        let rate = match self.rate {
+            Some(rate) => rate + Self::SMOOTHING * (sample - rate),
+            None => sample,
+        };
+        self.rate = Some(rate);
+        // The float-to-integer cast saturates, so absurd rates cannot overflow.
+        self.slice = ((rate * self.target.as_secs_f64()) as usize).clamp(1, limit);
+    }
+}
+
+impl Default for Cadence {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TARGET, SystemClock::default())
+    }
+}
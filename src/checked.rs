@@ -0,0 +1,17 @@
+//! Internal consistency checks that are too slow to leave on, enabled with the `checked` feature.
+//!
+//! Every check goes through [`checked_assert!`], and each call site notes its cost:
+//! `O(1)` checks guard the unchecked grid accessors, and `O(size²)` checks recompute derived state from scratch.
+
+/// Like `assert!`, but only compiled in with the `checked` feature, independently of `debug_assertions`.
+/// Without the feature, neither the condition nor the message is evaluated.
+macro_rules! checked_assert {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "checked")]
+        {
+            assert!($($arg)+);
+        }
+    };
+}
+
+pub(crate) use checked_assert;
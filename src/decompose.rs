@@ -0,0 +1,289 @@
+//! Splitting a masked predecessor problem into independent parts.
+//!
+//! A previous-frame cell only affects the target cells in the 3×3 block around it,
+//! so two previous-frame cells more than two apart never affect the same target cell.
+//! If the cells of a mask fall into groups that far apart, the predecessors confined to the mask
+//! are exactly the unions of one predecessor confined to each group, each of the target around that group.
+//! Each group is then a much smaller search, and their predecessors combine freely:
+//! a predecessor of the whole is the union of one of each, and the count is the product of their counts.
+//!
+//! Target cells out of reach of every mask cell have no alive neighbours in any such predecessor,
+//! so they are solved without searching: they must be dead, and if one is alive there is no predecessor at all.
+//!
+//! On the default 8×8 torus, cells are never more than four apart, so only fairly thin masks split up.
+
+use std::iter::FusedIterator;
+
+use log::debug;
+
+use crate::{
+    grid::Topology,
+    searcher::{SearchResult, Searcher},
+    Cell, Error, Grid, Guess, Options,
+};
+
+/// One independent part of a [`Decomposition`].
+#[derive(Debug, Clone)]
+pub struct Component {
+    /// The target cells within reach of `mask`, with every other cell dead.
+    pub target: Grid,
+    /// The cells a predecessor of `target` may have alive.
+    pub mask: Grid,
+}
+
+/// A predecessor problem confined to a mask, split by [`decompose`] into [`Component`]s
+/// whose predecessors combine freely.
+#[derive(Debug, Clone)]
+pub struct Decomposition {
+    /// The whole target.
+    next: Grid,
+    /// The whole mask, the union of the components' masks.
+    mask: Grid,
+    components: Vec<Component>,
+}
+
+/// The positive cells of `grid`, in row-major order.
+fn positive_cells(grid: &Grid) -> impl Iterator<Item = (i32, i32)> + '_ {
+    (0..grid.height()).flat_map(move |y| {
+        (0..grid.width())
+            .filter(move |&x| grid.get_wrapped(x, y).value > 0)
+            .map(move |x| (x, y))
+    })
+}
+
+/// The cells of `mask` that are at most two apart from `(x, y)`, including `(x, y)` itself if it is in `mask`.
+fn linked(mask: &Grid, x: i32, y: i32) -> impl Iterator<Item = (i32, i32)> + '_ {
+    (-2..=2).flat_map(move |dy| {
+        (-2..=2)
+            .map(move |dx| {
+                (
+                    (x + dx).rem_euclid(mask.width()),
+                    (y + dy).rem_euclid(mask.height()),
+                )
+            })
+            .filter(|&(nx, ny)| mask.get_wrapped(nx, ny).value > 0)
+    })
+}
+
+/// Splits the problem of finding predecessors of `next` whose alive cells all lie in `mask`
+/// into independent components, one for each group of mask cells that are at most two apart from another in the group.
+///
+/// Returns `None` if some alive cell of `next` is out of reach of every mask cell, so that there is no such predecessor.
+///
+/// # Panics
+/// If `next` and `mask` are different sizes, which [`Grid::check_same_size`] checks without panicking.
+///
+/// ```
+/// use undeath::{decompose::decompose, Cell, Grid, Options};
+///
+/// // Two rows of three, four rows apart, which step to two vertical blinkers.
+/// let mut mask = Grid::default();
+/// for x in 1..4 {
+///     mask.set_wrapped(x, 1, Cell::one());
+///     mask.set_wrapped(x + 3, 5, Cell::one());
+/// }
+/// let mut next = mask.clone();
+/// next.step();
+///
+/// let decomposition = decompose(&next, &mask).expect("every alive cell is within reach of the mask");
+/// assert_eq!(decomposition.components().len(), 2);
+/// assert_eq!(decomposition.count_with(&Options::default())?, 1);
+/// assert!(decomposition.solve_with(&Options::default())? == Some(mask));
+/// # Ok::<(), undeath::Error>(())
+/// ```
+pub fn decompose(next: &Grid, mask: &Grid) -> Option<Decomposition> {
+    next.assert_same_size(mask);
+    let (width, height) = (next.width(), next.height());
+    let mut unassigned = mask.clone();
+    let mut reached = Grid::new(width, height);
+    let mut components = Vec::new();
+    for (x, y) in positive_cells(mask) {
+        if unassigned.get_wrapped(x, y).value == 0 {
+            continue;
+        }
+        let mut component = Grid::new(width, height);
+        let mut pending = vec![(x, y)];
+        unassigned.set_wrapped(x, y, Cell::zero());
+        while let Some((cx, cy)) = pending.pop() {
+            component.set_wrapped(cx, cy, Cell::one());
+            let neighbours = linked(&unassigned, cx, cy).collect::<Vec<_>>();
+            for (nx, ny) in neighbours {
+                unassigned.set_wrapped(nx, ny, Cell::zero());
+                pending.push((nx, ny));
+            }
+        }
+        let mut target = Grid::new(width, height).with_topology(next.topology());
+        for (cx, cy) in positive_cells(&component) {
+            for (nx, ny) in std::iter::once((cx, cy)).chain(next.neighbour_positions(cx, cy)) {
+                reached.set_wrapped(nx, ny, Cell::one());
+                target.set_wrapped(nx, ny, next.get_wrapped(nx, ny));
+            }
+        }
+        components.push(Component {
+            target,
+            mask: component,
+        });
+    }
+    if next.and(&reached.not()).alive_cells().next().is_some() {
+        debug!("an alive target cell is out of reach of the mask");
+        return None;
+    }
+    debug!("decomposed the mask into {} components", components.len());
+    Some(Decomposition {
+        next: next.clone(),
+        mask: mask.clone(),
+        components,
+    })
+}
+
+/// Finds up to `limit` predecessors of `component`, spending iterations from `remaining`.
+fn component_predecessors(
+    component: &Component,
+    limit: usize,
+    remaining: &mut usize,
+    options: &Options,
+) -> Result<Vec<Grid>, Error> {
+    let mut searcher = Searcher::with_mask(component.target.clone(), &component.mask);
+    let mut solutions = Vec::new();
+    while solutions.len() < limit {
+        if *remaining == 0 {
+            return Err(Error::BudgetExhausted {
+                iterations: options.max_iterations,
+            });
+        }
+        match searcher.search(*remaining)? {
+            SearchResult::Found(grid, iterations) => {
+                *remaining = remaining.saturating_sub(iterations);
+                solutions.push(grid);
+            }
+            SearchResult::Working(iterations) => {
+                *remaining = remaining.saturating_sub(iterations);
+            }
+            SearchResult::Unsatisfiable => break,
+            SearchResult::Inconclusive => {
+                return Err(Error::Solver(
+                    "depth-first search gave up before exhausting the search space".to_owned(),
+                ))
+            }
+        }
+    }
+    Ok(solutions)
+}
+
+impl Decomposition {
+    /// The independent parts, in row-major order of their first mask cell.
+    /// There are none if the mask is empty.
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    /// Finds one predecessor, the union of the first one found for each component.
+    /// The parts are combined with [`Guess::merge`], checking them against the whole target.
+    ///
+    /// As with [`solve_with`](crate::solve_with), returns `Ok(None)` only if some component has no predecessor,
+    /// and running out of the budget, shared by every component, is an error.
+    pub fn solve_with(&self, options: &Options) -> Result<Option<Grid>, Error> {
+        let mut remaining = options.max_iterations;
+        let mut parts = Vec::with_capacity(self.components.len());
+        for component in &self.components {
+            match component_predecessors(component, 1, &mut remaining, options)?.pop() {
+                Some(grid) => parts.push(grid),
+                None => return Ok(None),
+            }
+        }
+        // Only now that every component has a predecessor is their union sure to be consistent.
+        let nothing = Grid::new(self.next.width(), self.next.height());
+        let mut assembled = Guess::from_partial(&self.next, &nothing, &self.mask.not());
+        for (component, grid) in self.components.iter().zip(&parts) {
+            let part = Guess::from_partial(&self.next, grid, &component.mask.and(&grid.not()));
+            assembled = assembled.merge(&part, &self.next).map_err(|conflict| {
+                Error::Solver(format!(
+                    "the predecessors of the components do not combine: {conflict}"
+                ))
+            })?;
+        }
+        Ok(Some(assembled.alive()))
+    }
+
+    /// Counts the predecessors, the product of how many each component has.
+    /// Every component is enumerated in full, within a budget shared by all of them.
+    pub fn count_with(&self, options: &Options) -> Result<u128, Error> {
+        let mut remaining = options.max_iterations;
+        let mut count: u128 = 1;
+        for component in &self.components {
+            let found = component_predecessors(component, usize::MAX, &mut remaining, options)?;
+            // The masks are disjoint, so the product is at most 2^64.
+            count *= found.len() as u128;
+            if count == 0 {
+                break;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Enumerates each component in full, within a budget shared by all of them,
+    /// and returns every predecessor of the whole, combining one of each component's lazily.
+    pub fn predecessors_with(&self, options: &Options) -> Result<Product, Error> {
+        let mut remaining = options.max_iterations;
+        let mut parts = Vec::new();
+        for component in &self.components {
+            let found = component_predecessors(component, usize::MAX, &mut remaining, options)?;
+            let empty = found.is_empty();
+            parts.push(found);
+            if empty {
+                break;
+            }
+        }
+        let done = parts.iter().any(Vec::is_empty);
+        Ok(Product {
+            width: self.next.width(),
+            height: self.next.height(),
+            topology: self.next.topology(),
+            indices: vec![0; parts.len()],
+            parts,
+            done,
+        })
+    }
+}
+
+/// The predecessors of a [`Decomposition`]: every union of one predecessor of each component,
+/// with the last component varying fastest.
+#[derive(Debug, Clone)]
+pub struct Product {
+    /// How many columns the grids have.
+    width: i32,
+    /// How many rows the grids have.
+    height: i32,
+    /// What lies past the edges of the grids.
+    topology: Topology,
+    parts: Vec<Vec<Grid>>,
+    /// Which predecessor of each component the next union takes.
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl Iterator for Product {
+    type Item = Grid;
+
+    fn next(&mut self) -> Option<Grid> {
+        if self.done {
+            return None;
+        }
+        let mut grid = Grid::new(self.width, self.height).with_topology(self.topology);
+        for (part, &index) in self.parts.iter().zip(&self.indices) {
+            grid += &part[index];
+        }
+        self.done = true;
+        for (part, index) in self.parts.iter().zip(&mut self.indices).rev() {
+            *index += 1;
+            if *index < part.len() {
+                self.done = false;
+                break;
+            }
+            *index = 0;
+        }
+        Some(grid)
+    }
+}
+
+impl FusedIterator for Product {}
@@ -0,0 +1,182 @@
+//! Where a search spends its effort, cell by cell.
+//!
+//! The [`Searcher::contradiction_heatmap`](crate::Searcher::contradiction_heatmap) shows where guesses fail;
+//! an [`EffortReport`] shows where cells are decided, and how: by branching on them, or by propagation from a guess
+//! on another cell. A cell order that keeps branching on one corner while the contradictions are elsewhere
+//! shows up here first.
+
+use crate::{
+    grid::{Cell, Grid, SIZE},
+    string::hconcat_all,
+    typed_grid::BoolGrid,
+};
+
+/// Per-cell counts of how each cell came to be decided, accumulated over a search of a grid of one size.
+/// The totals match the [`SearchStats`](crate::searcher::SearchStats) of the same search:
+/// [`EffortReport::total_branched`] is its `decisions`, and [`EffortReport::total_propagated`] its `deductions`.
+///
+/// ```
+/// use undeath::{Grid, Searcher};
+///
+/// let mut searcher = Searcher::new(Grid::from_text("\n\n\n..###"));
+/// searcher.search(1_000)?;
+/// let effort = searcher.effort_report();
+/// assert_eq!(effort.total_branched(), searcher.stats().decisions);
+/// assert_eq!(effort.total_propagated(), searcher.stats().deductions);
+/// # Ok::<(), undeath::Error>(())
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct EffortReport {
+    /// How many columns the grid has.
+    width: i32,
+    /// How many rows the grid has.
+    height: i32,
+    /// How many times each cell was branched on, indexed by `y * width + x`.
+    branched: Vec<u64>,
+    /// How many times each cell was decided by propagation from a guess on another cell.
+    propagated: Vec<u64>,
+    /// The sum of the depths at which each cell was decided, either way.
+    depth_sum: Vec<u64>,
+}
+
+/// An empty report for a [`SIZE`]×[`SIZE`] grid.
+impl Default for EffortReport {
+    fn default() -> Self {
+        Self::new(SIZE, SIZE)
+    }
+}
+
+impl EffortReport {
+    /// An empty report for a `width`×`height` grid.
+    pub fn new(width: i32, height: i32) -> Self {
+        let cells = (width * height) as usize;
+        Self {
+            width,
+            height,
+            branched: vec![0; cells],
+            propagated: vec![0; cells],
+            depth_sum: vec![0; cells],
+        }
+    }
+
+    /// Empties the report, and makes it for a `width`×`height` grid, reusing its memory where it can.
+    pub(crate) fn reset(&mut self, width: i32, height: i32) {
+        self.width = width;
+        self.height = height;
+        for values in [
+            &mut self.branched,
+            &mut self.propagated,
+            &mut self.depth_sum,
+        ] {
+            values.clear();
+            values.resize((width * height) as usize, 0);
+        }
+    }
+
+    /// The index of `(x, y)`, which must lie in the grid, in the arrays of the report.
+    fn index(&self, x: i32, y: i32) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    /// A grid holding each of `values`, saturating at `i32::MAX`.
+    fn to_grid(&self, values: &[u64]) -> Grid {
+        let mut grid = Grid::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let value = i32::try_from(values[self.index(x, y)]).unwrap_or(i32::MAX);
+                grid.set_wrapped(x, y, Cell { value });
+            }
+        }
+        grid
+    }
+
+    /// Records a guess on `(x, y)` that made a guess `depth` guesses below the root,
+    /// and the other cells it decided by propagation.
+    pub(crate) fn record_decision(&mut self, x: i32, y: i32, depth: usize, deduced: &BoolGrid) {
+        let i = self.index(x, y);
+        self.branched[i] += 1;
+        self.depth_sum[i] += depth as u64;
+        for (x, y) in deduced.iter() {
+            let i = self.index(x, y);
+            self.propagated[i] += 1;
+            self.depth_sum[i] += depth as u64;
+        }
+    }
+
+    /// How many times `(x, y)` was branched on.
+    /// Assumes `x` lies in `0..width` and `y` in `0..height`.
+    pub fn branched(&self, x: i32, y: i32) -> u64 {
+        self.branched[self.index(x, y)]
+    }
+
+    /// How many times `(x, y)` was decided by propagation from a guess on another cell.
+    /// Assumes `x` lies in `0..width` and `y` in `0..height`.
+    pub fn propagated(&self, x: i32, y: i32) -> u64 {
+        self.propagated[self.index(x, y)]
+    }
+
+    /// How many guesses below the root `(x, y)` was decided, on average, or `None` if it never was.
+    /// Cells decided by the guess that made the root's first child are at depth one.
+    /// Assumes `x` lies in `0..width` and `y` in `0..height`.
+    pub fn mean_depth(&self, x: i32, y: i32) -> Option<f64> {
+        let i = self.index(x, y);
+        let decided = self.branched[i] + self.propagated[i];
+        (decided > 0).then(|| self.depth_sum[i] as f64 / decided as f64)
+    }
+
+    /// How many times any cell was branched on.
+    pub fn total_branched(&self) -> u64 {
+        self.branched.iter().sum()
+    }
+
+    /// How many times any cell was decided by propagation.
+    pub fn total_propagated(&self) -> u64 {
+        self.propagated.iter().sum()
+    }
+
+    /// [`EffortReport::branched`] for every cell, saturating at `i32::MAX`.
+    pub fn branched_grid(&self) -> Grid {
+        self.to_grid(&self.branched)
+    }
+
+    /// [`EffortReport::propagated`] for every cell, saturating at `i32::MAX`.
+    pub fn propagated_grid(&self) -> Grid {
+        self.to_grid(&self.propagated)
+    }
+
+    /// [`EffortReport::mean_depth`] for every cell, rounded to the nearest whole depth, and zero if it was never decided.
+    pub fn mean_depth_grid(&self) -> Grid {
+        let mut grid = Grid::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let value = self
+                    .mean_depth(x, y)
+                    .map_or(0, |depth| depth.round() as i32);
+                grid.set_wrapped(x, y, Cell { value });
+            }
+        }
+        grid
+    }
+
+    /// Renders a titled heat map of each metric, side by side.
+    pub fn render(&self) -> String {
+        let block = |title: &str, grid: Grid| format!("{title}\n{}", grid.render_heatmap());
+        hconcat_all(
+            &[
+                &block("Branched on", self.branched_grid()),
+                &block("Propagated", self.propagated_grid()),
+                &block("Mean depth", self.mean_depth_grid()),
+            ],
+            "   ",
+        )
+    }
+}
+
+impl std::fmt::Debug for EffortReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EffortReport")
+            .field("total_branched", &self.total_branched())
+            .field("total_propagated", &self.total_propagated())
+            .finish_non_exhaustive()
+    }
+}
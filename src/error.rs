@@ -0,0 +1,75 @@
+use std::{fmt::Display, path::PathBuf};
+
+/// Everything that can go wrong in this crate.
+///
+/// ```
+/// use undeath::{Error, Grid};
+///
+/// match Grid::from_file("does-not-exist.gol").map_err(Error::from) {
+///     Err(Error::Io { path, .. }) => assert_eq!(path.to_str(), Some("does-not-exist.gol")),
+///     _ => panic!("expected an IO error"),
+/// }
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading or writing a file failed.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A pattern could not be parsed.
+    /// Lines and columns are counted from one.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The solver reached a state that should be impossible.
+    /// This always indicates a bug in the solver, not a problem with its input.
+    Solver(String),
+    /// The input was well-formed but not something we can work with.
+    InvalidInput(String),
+    /// The search ran for its whole iteration budget without reaching an answer.
+    /// This says nothing about whether an answer exists.
+    BudgetExhausted { iterations: usize },
+}
+
+impl Error {
+    /// Wraps an IO error with the path of the file being accessed.
+    pub fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { path, source } => {
+                write!(f, "could not access {}: {source}", path.display())
+            }
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "parse error at line {line}, column {column}: {message}"),
+            Error::Solver(message) => write!(f, "internal solver error: {message}"),
+            Error::InvalidInput(message) => write!(f, "invalid input: {message}"),
+            Error::BudgetExhausted { iterations } => {
+                write!(f, "gave up after {iterations} iterations without an answer")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Where a pattern's forward evolution ends up: how long it takes to settle into a cycle, and how long that cycle is.
+//!
+//! On the torus there are finitely many grids, so every evolution eventually repeats itself:
+//! after a transient of `μ` generations, it cycles with some period `λ`.
+//! [`report`] finds both with Brent's algorithm, which keeps only two grids however long the evolution is,
+//! or with a hash map of every generation seen, which steps each generation only once.
+
+use std::collections::HashMap;
+
+use crate::Grid;
+
+/// How [`report`] remembers the generations it has seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CycleMemory {
+    /// Brent's algorithm, keeping two grids at a time.
+    /// It steps up to about three times as many generations as the transient and period together.
+    #[default]
+    Constant,
+    /// A hash map of every generation, from which the cycle is found as soon as it first repeats.
+    Hashed,
+}
+
+/// A summary of a pattern's forward evolution, from [`Grid::evolution_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EvolutionReport {
+    /// How many generations pass before the pattern enters its cycle,
+    /// or `None` if no cycle was found within the step limit.
+    pub transient_len: Option<usize>,
+    /// The length of the cycle, or `None` if no cycle was found within the step limit.
+    pub period: Option<usize>,
+    /// The most alive cells in any generation examined.
+    pub max_population: usize,
+    /// The fewest alive cells in any generation examined.
+    pub min_population: usize,
+    /// The first generation with no alive cells, if one was examined.
+    pub died_at: Option<usize>,
+    /// The first generation that recurs, which starts the cycle, if one was found.
+    pub first_repeat: Option<Grid>,
+}
+
+/// Population statistics over the generations examined so far.
+struct Populations {
+    generation: usize,
+    max: usize,
+    min: usize,
+    died_at: Option<usize>,
+}
+
+impl Populations {
+    fn new(start: &Grid) -> Self {
+        let mut populations = Self {
+            generation: 0,
+            max: 0,
+            min: usize::MAX,
+            died_at: None,
+        };
+        populations.record(start);
+        populations
+    }
+
+    /// Records the next generation in order.
+    fn record(&mut self, grid: &Grid) {
+        let population = grid.alive_cells().count();
+        self.max = self.max.max(population);
+        self.min = self.min.min(population);
+        if population == 0 && self.died_at.is_none() {
+            self.died_at = Some(self.generation);
+        }
+        self.generation += 1;
+    }
+
+    fn finish(self, cycle: Option<(usize, usize, Grid)>) -> EvolutionReport {
+        let (transient_len, period, first_repeat) = match cycle {
+            Some((mu, lambda, grid)) => (Some(mu), Some(lambda), Some(grid)),
+            None => (None, None, None),
+        };
+        EvolutionReport {
+            transient_len,
+            period,
+            max_population: self.max,
+            min_population: self.min,
+            died_at: self.died_at,
+            first_repeat,
+        }
+    }
+}
+
+fn stepped(grid: &Grid) -> Grid {
+    let mut grid = grid.clone();
+    grid.step_bitparallel();
+    grid
+}
+
+/// Follows the evolution of `start` to find its transient and period,
+/// giving up if the cycle has not been found after stepping `max_steps` generations ahead.
+///
+/// The hashed mode finds every cycle that starts and first repeats within `max_steps`,
+/// but Brent's algorithm may need to look up to about three times as far ahead to find the same cycle.
+/// Every generation up to the end of the first cycle is examined, so the population statistics
+/// cover the whole evolution whenever a cycle is found.
+/// Cells with a positive value count as alive, whatever their value.
+pub fn report(start: &Grid, max_steps: usize, memory: CycleMemory) -> EvolutionReport {
+    let start = start.clamp_scalar(0, 1);
+    match memory {
+        CycleMemory::Constant => brent(&start, max_steps),
+        CycleMemory::Hashed => hashed(&start, max_steps),
+    }
+}
+
+fn brent(start: &Grid, max_steps: usize) -> EvolutionReport {
+    let mut populations = Populations::new(start);
+    // Find the period by moving the hare on, and teleporting the tortoise to it at each power of two.
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = start.clone();
+    let mut hare = stepped(start);
+    populations.record(&hare);
+    while tortoise != hare {
+        if populations.generation > max_steps {
+            return populations.finish(None);
+        }
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare.step_bitparallel();
+        populations.record(&hare);
+        lambda += 1;
+    }
+    // With the hare `lambda` generations ahead, the two first meet where the cycle starts.
+    let mut mu = 0;
+    tortoise = start.clone();
+    hare = start.clone();
+    for _ in 0..lambda {
+        hare.step_bitparallel();
+    }
+    while tortoise != hare {
+        tortoise.step_bitparallel();
+        hare.step_bitparallel();
+        mu += 1;
+    }
+    populations.finish(Some((mu, lambda, tortoise)))
+}
+
+fn hashed(start: &Grid, max_steps: usize) -> EvolutionReport {
+    let mut populations = Populations::new(start);
+    let mut seen = HashMap::from([(start.clone(), 0)]);
+    let mut current = start.clone();
+    for generation in 1..=max_steps {
+        current.step_bitparallel();
+        if let Some(&mu) = seen.get(&current) {
+            return populations.finish(Some((mu, generation - mu, current)));
+        }
+        populations.record(&current);
+        seen.insert(current.clone(), generation);
+    }
+    populations.finish(None)
+}
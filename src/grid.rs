@@ -1,12 +1,44 @@
 use std::{
-    mem::MaybeUninit,
+    fmt::Write,
+    fs::File,
+    io::{BufRead, BufReader},
     ops::{Add, AddAssign, Sub, SubAssign},
-    path::Path,
+    path::{Path, PathBuf},
+    str::FromStr,
 };
 
+use crate::{
+    checked::checked_assert,
+    evolution::{self, CycleMemory, EvolutionReport},
+    rle::{Rle, RleError},
+    searcher::{Predecessors, Searcher},
+    solve::PredecessorOutcome,
+    Error,
+};
+
+/// The width and height of [`Grid::default`], and of the grids that [`Grid::from_text`] reads from patterns small enough for it.
+/// A [`Grid`] made with [`Grid::new`], or read from a larger pattern, can be any width and height from [`MIN_SIZE`] up.
 pub const SIZE: i32 = 8;
 
-#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// The smallest width or height of a [`Grid`], so that the eight neighbours of each cell are distinct cells.
+pub const MIN_SIZE: i32 = 3;
+
+/// The largest width or height of a [`Grid`]. Patterns read by [`Grid::from_text`] wider or taller than this wrap around;
+/// [`str::parse`] and [`Grid::from_file`] report them as a [`ParseGridError`] instead.
+pub const MAX_SIZE: i32 = 1024;
+
+/// The value of a single cell of a [`Grid`].
+/// For a Life board this is `0` for dead and `1` for alive;
+/// other grids, such as neighbour counts, store arbitrary integers.
+///
+/// ```
+/// use undeath::Cell;
+///
+/// let mut cell = Cell::zero();
+/// cell += Cell::one();
+/// assert!(cell == Cell::one());
+/// ```
+#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct Cell {
     pub value: i32,
@@ -65,30 +97,183 @@ impl SubAssign for Cell {
     }
 }
 
-/// A toroidal grid for Life.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
-#[repr(transparent)]
+/// Which cells around a cell count as its neighbours.
+///
+/// ```
+/// use undeath::grid::Neighbourhood;
+///
+/// assert_eq!(Neighbourhood::Moore.size(), 8);
+/// assert_eq!(Neighbourhood::VonNeumann.positions(0, 0, 8, 8).collect::<Vec<_>>(), [(7, 0), (0, 7), (0, 1), (1, 0)]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Neighbourhood {
+    /// The eight cells that share an edge or a corner with the cell.
+    #[default]
+    Moore,
+    /// The four cells that share an edge with the cell.
+    VonNeumann,
+}
+
+impl Neighbourhood {
+    /// How many neighbours each cell has.
+    pub fn size(self) -> i32 {
+        match self {
+            Neighbourhood::Moore => 8,
+            Neighbourhood::VonNeumann => 4,
+        }
+    }
+
+    /// Whether the cell `(dx, dy)` away from a cell is one of its neighbours.
+    pub fn contains(self, dx: i32, dy: i32) -> bool {
+        match self {
+            Neighbourhood::Moore => dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0),
+            Neighbourhood::VonNeumann => dx.abs() + dy.abs() == 1,
+        }
+    }
+
+    /// The neighbours of `(x, y)` on a `width`×`height` grid, wrapped to `0..width` and `0..height`.
+    /// The Moore neighbours are in the order of [`Grid::neighbour_positions`].
+    #[inline]
+    pub fn positions(
+        self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> impl Iterator<Item = (i32, i32)> {
+        let (positions, count) = match self {
+            Neighbourhood::Moore => (moore_positions(x, y, width, height), 8),
+            Neighbourhood::VonNeumann => {
+                let left = (x + width - 1) % width;
+                let right = (x + 1) % width;
+                let above = (y + height - 1) % height;
+                let below = (y + 1) % height;
+                let padding = (x, y);
+                (
+                    [
+                        (left, y),
+                        (x, above),
+                        (x, below),
+                        (right, y),
+                        padding,
+                        padding,
+                        padding,
+                        padding,
+                    ],
+                    4,
+                )
+            }
+        };
+        positions.into_iter().take(count)
+    }
+}
+
+/// What lies past the edges of a [`Grid`].
+///
+/// ```
+/// use undeath::{grid::Topology, Grid};
+///
+/// // A blinker against the left edge.
+/// let blinker = Grid::from_text("#\n#\n#");
+/// let mut torus = blinker.clone();
+/// torus.step();
+/// assert!(torus == Grid::from_text("\n##.....#"));
+/// // On a plane, the cell it would grow past the edge is never born.
+/// let mut plane = blinker.with_topology(Topology::Plane);
+/// plane.step();
+/// assert!(plane == Grid::from_text("\n##").with_topology(Topology::Plane));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Topology {
+    /// The edges wrap around, so the right neighbours of the last column are in the first, and likewise for rows.
+    /// Every cell has all the neighbours of its [`Neighbourhood`].
+    #[default]
+    Torus,
+    /// The grid is a window onto an infinite plane whose cells outside it are always dead.
+    /// A cell on an edge has only five Moore neighbours, and one in a corner three.
+    /// Nothing outside the grid is stepped, so a pattern can grow past the edge without the cells there being born.
+    Plane,
+}
+
+impl Topology {
+    /// Whether `neighbour`, one of the wrapped neighbours of `cell` on a grid at least [`MIN_SIZE`] on each side,
+    /// really is next to it rather than on the far side of an edge.
+    #[inline]
+    pub(crate) fn adjacent(self, (x, y): (i32, i32), (nx, ny): (i32, i32)) -> bool {
+        self == Topology::Torus || ((nx - x).abs() <= 1 && (ny - y).abs() <= 1)
+    }
+}
+
+/// A grid for Life, on a torus unless its [`Topology`] says otherwise.
+///
+/// Every grid has a width and a height, which are both [`SIZE`] unless it was made with [`Grid::new`]
+/// or read from a larger pattern. Operations that combine two grids, such as [`Grid::and`], panic if their sizes differ.
+/// Two grids with the same cells but different topologies are not equal.
+///
+/// ```
+/// use undeath::{Cell, Grid};
+///
+/// // A blinker oscillates with period two.
+/// let mut grid = Grid::default();
+/// for x in 2..5 {
+///     grid.set_wrapped(x, 3, Cell::one());
+/// }
+/// let mut stepped = grid.clone();
+/// stepped.step();
+/// assert!(stepped != grid);
+/// stepped.step();
+/// assert!(stepped == grid);
+/// ```
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Grid {
+    /// How many columns the grid has.
+    width: i32,
+    /// How many rows the grid has.
+    height: i32,
     /// Row-major.
-    /// A cell (x, y) is at `x + y * SIZE`.
-    cells: [Cell; (SIZE * SIZE) as usize],
+    /// A cell (x, y) is at `x + y * width`.
+    cells: Vec<Cell>,
+    /// What lies past the edges.
+    topology: Topology,
 }
 
+/// A [`SIZE`]×[`SIZE`] grid of dead cells.
 impl Default for Grid {
     fn default() -> Self {
-        Self {
-            cells: unsafe { std::mem::zeroed() },
-        }
+        Self::new(SIZE, SIZE)
+    }
+}
+
+/// Shows each row in the `.gol` text format, with `#` for positive cells and `.` for the rest.
+impl std::fmt::Debug for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Grid").field(&DebugRows(self)).finish()
     }
 }
 
+struct DebugRows<'a>(&'a Grid);
+
+impl std::fmt::Debug for DebugRows<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.0.cells.chunks_exact(self.0.width as usize).map(|row| {
+                row.iter()
+                    .map(|cell| if cell.value > 0 { '#' } else { '.' })
+                    .collect::<String>()
+            }))
+            .finish()
+    }
+}
+
+// These loops are written over whole arrays, without indexing, so that the compiler vectorises them.
+// Like `Grid::mul_scalar`, they saturate at the bounds of `i32` rather than overflowing,
+// so that debug and release builds agree.
 impl<'a> AddAssign<&'a Grid> for Grid {
     #[inline]
     fn add_assign(&mut self, rhs: &'a Self) {
-        for i in 0..(SIZE * SIZE) as usize {
-            unsafe {
-                *self.cells.get_unchecked_mut(i) += *rhs.cells.get_unchecked(i);
-            }
+        self.assert_same_size(rhs);
+        for (cell, rhs) in self.cells.iter_mut().zip(&rhs.cells) {
+            cell.value = cell.value.saturating_add(rhs.value);
         }
     }
 }
@@ -96,129 +281,1567 @@ impl<'a> AddAssign<&'a Grid> for Grid {
 impl<'a> SubAssign<&'a Grid> for Grid {
     #[inline]
     fn sub_assign(&mut self, rhs: &'a Grid) {
-        for i in 0..(SIZE * SIZE) as usize {
-            unsafe {
-                *self.cells.get_unchecked_mut(i) -= *rhs.cells.get_unchecked(i);
-            }
+        self.assert_same_size(rhs);
+        for (cell, rhs) in self.cells.iter_mut().zip(&rhs.cells) {
+            cell.value = cell.value.saturating_sub(rhs.value);
+        }
+    }
+}
+
+/// Parses the `.gol` text format as [`Grid::from_text`] does, except that a pattern too large for [`MAX_SIZE`],
+/// or with no rows at all, is an error rather than wrapping around or being an empty grid.
+///
+/// ```
+/// use undeath::{grid::ParseGridError, Grid};
+///
+/// let glider: Grid = ".#\n..#\n###".parse()?;
+/// assert!(glider == Grid::from_text(".#\n..#\n###"));
+/// assert_eq!("".parse::<Grid>(), Err(ParseGridError::Empty));
+/// assert!(matches!(
+///     ".".repeat(2000).parse::<Grid>(),
+///     Err(ParseGridError::LineTooLong { line: 1, length: 2000, pattern: (2000, 1) })
+/// ));
+/// # Ok::<(), ParseGridError>(())
+/// ```
+impl FromStr for Grid {
+    type Err = ParseGridError;
+
+    fn from_str(text: &str) -> Result<Self, ParseGridError> {
+        let mut rows = TextRows::new(TextFormat::Gol, false);
+        for line in text.split_inclusive('\n') {
+            rows.push(line)?;
         }
+        rows.finish()
     }
 }
 
 impl Grid {
-    pub fn fill(cell: Cell) -> Self {
+    /// A `width`×`height` grid of dead cells, on a torus.
+    ///
+    /// ```
+    /// use undeath::{Cell, Grid};
+    ///
+    /// let mut grid = Grid::new(20, 6);
+    /// assert_eq!((grid.width(), grid.height()), (20, 6));
+    /// grid.set_wrapped(19, 5, Cell::one());
+    /// assert_eq!(grid.neighbours().get_wrapped(0, 0).value, 1);
+    /// ```
+    ///
+    /// # Panics
+    /// If either side is less than [`MIN_SIZE`] or more than [`MAX_SIZE`].
+    pub fn new(width: i32, height: i32) -> Self {
+        assert!(
+            (MIN_SIZE..=MAX_SIZE).contains(&width) && (MIN_SIZE..=MAX_SIZE).contains(&height),
+            "each side of a grid must be between {MIN_SIZE} and {MAX_SIZE}, not {width}×{height}"
+        );
         Self {
-            cells: [cell; (SIZE * SIZE) as usize],
+            width,
+            height,
+            cells: vec![Cell::zero(); (width * height) as usize],
+            topology: Topology::Torus,
+        }
+    }
+
+    /// A [`SIZE`]×[`SIZE`] grid with every cell set to `cell`.
+    pub fn fill(cell: Cell) -> Self {
+        let mut grid = Self::default();
+        grid.cells.fill(cell);
+        grid
+    }
+
+    /// How many columns the grid has.
+    #[inline]
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// How many rows the grid has.
+    #[inline]
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// What lies past the edges of the grid.
+    #[inline]
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// The same cells, with `topology` past the edges.
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// A grid of dead cells with the size and topology of this one.
+    fn blank(&self) -> Grid {
+        Grid::new(self.width, self.height).with_topology(self.topology)
+    }
+
+    /// Makes this a `width`×`height` grid of dead cells on a torus, reusing its memory where it can.
+    pub(crate) fn reset(&mut self, width: i32, height: i32) {
+        self.width = width;
+        self.height = height;
+        self.topology = Topology::Torus;
+        self.cells.clear();
+        self.cells.resize((width * height) as usize, Cell::zero());
+    }
+
+    /// Whether `other` has the same width and height as this grid.
+    #[inline]
+    pub fn same_size(&self, other: &Grid) -> bool {
+        (self.width, self.height) == (other.width, other.height)
+    }
+
+    /// Fails with [`Error::InvalidInput`] unless `other` is the same size as this grid.
+    /// The operations combining two grids, such as [`Grid::and`] and `+=`, panic in that case instead,
+    /// so call this first on grids whose sizes come from outside the program.
+    ///
+    /// ```
+    /// use undeath::{Error, Grid};
+    ///
+    /// assert!(Grid::default().check_same_size(&Grid::new(8, 8)).is_ok());
+    /// assert!(matches!(
+    ///     Grid::default().check_same_size(&Grid::new(20, 6)),
+    ///     Err(Error::InvalidInput(message)) if message == "cannot combine grids of different sizes, 8×8 and 20×6"
+    /// ));
+    /// ```
+    pub fn check_same_size(&self, other: &Grid) -> Result<(), Error> {
+        if self.same_size(other) {
+            Ok(())
+        } else {
+            Err(Error::InvalidInput(format!(
+                "cannot combine grids of different sizes, {}×{} and {}×{}",
+                self.width, self.height, other.width, other.height
+            )))
+        }
+    }
+
+    /// Panics unless `other` is the same size as this grid, for the operations whose callers have already checked.
+    #[inline]
+    #[track_caller]
+    pub(crate) fn assert_same_size(&self, other: &Grid) {
+        if let Err(Error::InvalidInput(message)) = self.check_same_size(other) {
+            panic!("{message}");
+        }
+    }
+
+    /// Subtracts `rhs` from this grid, clamping each cell at zero rather than letting it go negative.
+    /// This is the operation for removing one set of cells from another.
+    ///
+    /// # Panics
+    /// If the grids are different sizes, which [`Grid::check_same_size`] checks without panicking.
+    #[inline]
+    pub fn sub_assign_saturating(&mut self, rhs: &Grid) {
+        self.assert_same_size(rhs);
+        for (cell, rhs) in self.cells.iter_mut().zip(&rhs.cells) {
+            cell.value = cell.value.saturating_sub(rhs.value).max(0);
+        }
+    }
+
+    /// The cells positive in both this grid and `rhs`, as `0` or `1` whatever the values were.
+    /// This treats the grids as sets of positive cells, unlike the numeric [`AddAssign`] and [`SubAssign`].
+    ///
+    /// # Panics
+    /// If the grids are different sizes, as do the other operations combining two grids.
+    /// [`Grid::check_same_size`] checks that without panicking.
+    ///
+    /// ```
+    /// use undeath::{Cell, Grid};
+    ///
+    /// let row = Grid::from_text("###");
+    /// let column = Grid::from_text(".#\n.#\n.#");
+    /// assert!(row.and(&column) == Grid::from_text(".#"));
+    /// assert!(row.or(&column) == Grid::from_text("###\n.#\n.#"));
+    /// assert!(row.xor(&column) == Grid::from_text("#.#\n.#\n.#"));
+    /// assert_eq!(row.not().alive_cells().count(), 61);
+    ///
+    /// // Counts become plain booleans.
+    /// let counts = row.neighbours();
+    /// assert_eq!(counts.get_wrapped(1, 1).value, 3);
+    /// assert!(counts.or(&Grid::default()) == counts.clamp_scalar(0, 1));
+    /// ```
+    pub fn and(&self, rhs: &Grid) -> Grid {
+        let mut result = self.clone();
+        result.and_assign(rhs);
+        result
+    }
+
+    /// The cells positive in this grid or `rhs`, as `0` or `1`. See [`Grid::and`].
+    pub fn or(&self, rhs: &Grid) -> Grid {
+        let mut result = self.clone();
+        result.or_assign(rhs);
+        result
+    }
+
+    /// The cells positive in exactly one of this grid and `rhs`, as `0` or `1`. See [`Grid::and`].
+    pub fn xor(&self, rhs: &Grid) -> Grid {
+        let mut result = self.clone();
+        result.xor_assign(rhs);
+        result
+    }
+
+    /// The cells that are not positive in this grid, as `0` or `1`. See [`Grid::and`].
+    pub fn not(&self) -> Grid {
+        let mut result = self.clone();
+        result.not_assign();
+        result
+    }
+
+    /// Like [`Grid::and`], but in place.
+    #[inline]
+    pub fn and_assign(&mut self, rhs: &Grid) {
+        self.assert_same_size(rhs);
+        for (cell, rhs) in self.cells.iter_mut().zip(&rhs.cells) {
+            cell.value = i32::from(cell.value > 0 && rhs.value > 0);
+        }
+    }
+
+    /// Like [`Grid::or`], but in place.
+    #[inline]
+    pub fn or_assign(&mut self, rhs: &Grid) {
+        self.assert_same_size(rhs);
+        for (cell, rhs) in self.cells.iter_mut().zip(&rhs.cells) {
+            cell.value = i32::from(cell.value > 0 || rhs.value > 0);
+        }
+    }
+
+    /// Like [`Grid::xor`], but in place.
+    #[inline]
+    pub fn xor_assign(&mut self, rhs: &Grid) {
+        self.assert_same_size(rhs);
+        for (cell, rhs) in self.cells.iter_mut().zip(&rhs.cells) {
+            cell.value = i32::from((cell.value > 0) != (rhs.value > 0));
+        }
+    }
+
+    /// Like [`Grid::not`], but in place.
+    #[inline]
+    pub fn not_assign(&mut self) {
+        for cell in self.cells.iter_mut() {
+            cell.value = i32::from(cell.value <= 0);
+        }
+    }
+
+    /// Every value multiplied by `factor`, saturating at the bounds of `i32` rather than overflowing.
+    ///
+    /// ```
+    /// use undeath::{Cell, Grid};
+    ///
+    /// let mut grid = Grid::from_text("#");
+    /// grid.set_wrapped(1, 0, Cell { value: i32::MAX / 2 + 1 });
+    /// let scaled = grid.mul_scalar(2);
+    /// assert_eq!(scaled.get_wrapped(0, 0).value, 2);
+    /// assert_eq!(scaled.get_wrapped(1, 0).value, i32::MAX);
+    /// assert_eq!(scaled.clamp_scalar(0, 1).get_wrapped(1, 0).value, 1);
+    /// ```
+    pub fn mul_scalar(&self, factor: i32) -> Grid {
+        let mut result = self.clone();
+        result.mul_scalar_assign(factor);
+        result
+    }
+
+    /// Every value limited to `min..=max`.
+    ///
+    /// # Panics
+    /// If `min` is greater than `max`.
+    pub fn clamp_scalar(&self, min: i32, max: i32) -> Grid {
+        let mut result = self.clone();
+        result.clamp_scalar_assign(min, max);
+        result
+    }
+
+    /// Like [`Grid::mul_scalar`], but in place.
+    #[inline]
+    pub fn mul_scalar_assign(&mut self, factor: i32) {
+        for cell in self.cells.iter_mut() {
+            cell.value = cell.value.saturating_mul(factor);
+        }
+    }
+
+    /// Like [`Grid::clamp_scalar`], but in place.
+    ///
+    /// # Panics
+    /// If `min` is greater than `max`.
+    #[inline]
+    pub fn clamp_scalar_assign(&mut self, min: i32, max: i32) {
+        for cell in self.cells.iter_mut() {
+            cell.value = cell.value.clamp(min, max);
         }
     }
 
+    /// Reads a cell without bounds checks, for use in hot loops.
+    /// Prefer [`Grid::get_wrapped`] elsewhere.
+    ///
+    /// # Safety
+    /// `x` must lie in `0..width` and `y` in `0..height`, for the [`Grid::width`] and [`Grid::height`] of this grid.
     #[inline]
     pub unsafe fn get(&self, x: i32, y: i32) -> Cell {
-        *self.cells.get_unchecked((x + y * SIZE) as usize)
+        // O(1).
+        checked_assert!(
+            (0..self.width).contains(&x) && (0..self.height).contains(&y),
+            "({x}, {y}) is out of bounds"
+        );
+        *self.cells.get_unchecked((x + y * self.width) as usize)
     }
 
+    /// Writes a cell without bounds checks, for use in hot loops.
+    /// Prefer [`Grid::set_wrapped`] elsewhere.
+    ///
+    /// # Safety
+    /// `x` must lie in `0..width` and `y` in `0..height`, for the [`Grid::width`] and [`Grid::height`] of this grid.
     #[inline]
     pub unsafe fn set(&mut self, x: i32, y: i32, cell: Cell) {
-        *self.cells.get_unchecked_mut((x + y * SIZE) as usize) = cell;
+        // O(1).
+        checked_assert!(
+            (0..self.width).contains(&x) && (0..self.height).contains(&y),
+            "({x}, {y}) is out of bounds"
+        );
+        *self.cells.get_unchecked_mut((x + y * self.width) as usize) = cell;
     }
 
-    /// Assumes `x` and `y` aren't less than or equal to `-SIZE`.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`, for the size of this grid.
     #[inline]
     pub fn get_wrapped(&self, x: i32, y: i32) -> Cell {
-        unsafe { self.get((x + SIZE) % SIZE, (y + SIZE) % SIZE) }
+        let (width, height) = (self.width, self.height);
+        unsafe { self.get((x + width) % width, (y + height) % height) }
     }
 
-    /// Assumes `x` and `y` aren't less than or equal to `-SIZE`.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`, for the size of this grid.
     #[inline]
     pub fn set_wrapped(&mut self, x: i32, y: i32, cell: Cell) {
-        unsafe { self.set((x + SIZE) % SIZE, (y + SIZE) % SIZE, cell) }
+        let (width, height) = (self.width, self.height);
+        unsafe { self.set((x + width) % width, (y + height) % height, cell) }
     }
 
+    /// Adds to a cell without bounds checks, for use in hot loops.
+    /// Prefer [`Grid::set_add_wrapped`] elsewhere.
+    ///
+    /// # Safety
+    /// `x` must lie in `0..width` and `y` in `0..height`, for the [`Grid::width`] and [`Grid::height`] of this grid.
     #[inline]
     pub unsafe fn set_add(&mut self, x: i32, y: i32, cell: Cell) {
-        *self.cells.get_unchecked_mut((x + y * SIZE) as usize) += cell;
+        // O(1).
+        checked_assert!(
+            (0..self.width).contains(&x) && (0..self.height).contains(&y),
+            "({x}, {y}) is out of bounds"
+        );
+        *self.cells.get_unchecked_mut((x + y * self.width) as usize) += cell;
     }
 
-    /// Assumes `x` and `y` aren't less than or equal to `-SIZE`.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`, for the size of this grid.
     #[inline]
     pub fn set_add_wrapped(&mut self, x: i32, y: i32, cell: Cell) {
-        let x = (x + SIZE) % SIZE;
-        let y = (y + SIZE) % SIZE;
+        let x = (x + self.width) % self.width;
+        let y = (y + self.height) % self.height;
         unsafe {
             self.set_add(x, y, cell);
         }
     }
 
-    /// The list of proper neighbours.
-    /// All clamped to `0..SIZE`.
+    /// The proper neighbours of `(x, y)`, which must be on the grid, in a fixed order.
+    /// All wrapped to `0..width` and `0..height`, for the size of this grid.
+    /// There are eight on a torus, but fewer at the edges of a [`Topology::Plane`].
     #[inline]
-    pub fn neighbour_positions(x: i32, y: i32) -> [(i32, i32); 8] {
-        [
-            ((x + -1 + SIZE) % SIZE, (y + -1 + SIZE) % SIZE),
-            ((x + -1 + SIZE) % SIZE, (y + 0 + SIZE) % SIZE),
-            ((x + -1 + SIZE) % SIZE, (y + 1 + SIZE) % SIZE),
-            ((x + 0 + SIZE) % SIZE, (y + -1 + SIZE) % SIZE),
-            ((x + 0 + SIZE) % SIZE, (y + 1 + SIZE) % SIZE),
-            ((x + 1 + SIZE) % SIZE, (y + -1 + SIZE) % SIZE),
-            ((x + 1 + SIZE) % SIZE, (y + 0 + SIZE) % SIZE),
-            ((x + 1 + SIZE) % SIZE, (y + 1 + SIZE) % SIZE),
-        ]
+    pub fn neighbour_positions(&self, x: i32, y: i32) -> impl Iterator<Item = (i32, i32)> {
+        let topology = self.topology;
+        moore_positions(x, y, self.width, self.height)
+            .into_iter()
+            .filter(move |&neighbour| topology.adjacent((x, y), neighbour))
+    }
+
+    /// The least of this grid's translations around the torus, so that translations of one pattern
+    /// share a canonical form.
+    ///
+    /// Rather than build and compare all `W·H` translations of a `W`×`H` grid, this ranks every row read
+    /// from every column, then every column of those ranks read from every row, in `O(WH log WH)` time:
+    /// one sort of the cells, then `O(log W + log H)` linear rounds.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let glider = Grid::from_text("\n.#\n..#\n###");
+    /// let moved = Grid::from_text("\n\n\n\n..#\n...#\n.###");
+    /// assert!(glider.canonical() == moved.canonical());
+    /// ```
+    pub fn canonical(&self) -> Grid {
+        let (width, height) = (self.width as usize, self.height as usize);
+        // `rows[x + y * width]` ranks row `y` read from column `x` around the torus against every such row.
+        let rows = rotation_ranks(dense_ranks(&self.cells), width);
+        let mut columns = vec![0; rows.len()];
+        for y in 0..height {
+            for x in 0..width {
+                columns[y + x * height] = rows[x + y * width];
+            }
+        }
+        // Row-major order compares whole rows first, so `grids[y + x * height]` ranks the grid
+        // read from `(x, y)` against every translation.
+        let grids = rotation_ranks(columns, height);
+        let least = (0..grids.len())
+            .min_by_key(|&i| grids[i])
+            .expect("a grid has cells");
+        let (left, top) = ((least / height) as i32, (least % height) as i32);
+        let mut translated = self.blank();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                translated.set_wrapped(x - left, y - top, self.get_wrapped(x, y));
+            }
+        }
+        translated
     }
 
-    pub fn alive_cells(&self) -> impl Iterator<Item = (i32, i32)> + use<'_> {
-        (0..SIZE)
-            .flat_map(|x| (0..SIZE).map(move |y| (x, y)))
-            .filter(|(x, y)| unsafe { self.get(*x, *y) }.value > 0)
+    /// Like [`Grid::canonical`], but also treating rotations and reflections of a pattern as the same,
+    /// so that the eight orientations of a glider share a canonical form.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let glider = Grid::from_text(".#\n..#\n###");
+    /// // The glider turned a quarter clockwise, and moved.
+    /// let turned = Grid::from_text("\n\n\n....#\n....#.#\n....##");
+    /// assert!(glider.canonical() != turned.canonical());
+    /// assert!(glider.canonical_symmetric() == turned.canonical_symmetric());
+    /// ```
+    pub fn canonical_symmetric(&self) -> Grid {
+        Symmetry::ALL
+            .into_iter()
+            .map(|symmetry| self.transformed(symmetry).canonical())
+            .min()
+            .expect("there are eight symmetries")
     }
 
-    pub fn from_file(path: impl AsRef<Path>) -> Self {
-        let contents = std::fs::read_to_string(path).unwrap();
-        let mut result = Self::default();
-        for (y, line) in contents.lines().enumerate() {
-            for (x, char) in line.chars().enumerate() {
-                let cell = Cell {
-                    value: if " .".contains(char) { 0 } else { 1 },
-                };
-                result.set_wrapped(x as i32, y as i32, cell);
+    /// The grid rotated or reflected by `symmetry`, around the torus.
+    /// Symmetries that swap the axes, such as a quarter turn, also swap the width and height of the grid.
+    pub fn transformed(&self, symmetry: Symmetry) -> Grid {
+        let mut transformed = if symmetry.swaps_axes() {
+            Grid::new(self.height, self.width).with_topology(self.topology)
+        } else {
+            self.blank()
+        };
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (to_x, to_y) = symmetry.apply(x, y, self.width, self.height);
+                transformed.set_wrapped(to_x, to_y, self.get_wrapped(x, y));
             }
         }
-        result
+        transformed
+    }
+
+    /// The rotations and reflections, other than [`Symmetry::Identity`], that leave the pattern the same
+    /// up to a translation around the torus.
+    ///
+    /// ```
+    /// use undeath::{grid::Symmetry, Grid};
+    ///
+    /// let blinker = Grid::from_text("\n\n\n..###");
+    /// assert_eq!(
+    ///     blinker.symmetries(),
+    ///     [Symmetry::HalfTurn, Symmetry::FlipLeftRight, Symmetry::FlipTopBottom]
+    /// );
+    /// let boat = Grid::from_text("##\n#.#\n.#");
+    /// assert_eq!(boat.symmetries(), [Symmetry::FlipDiagonal]);
+    /// let glider = Grid::from_text(".#\n..#\n###");
+    /// assert_eq!(glider.symmetries(), []);
+    /// ```
+    pub fn symmetries(&self) -> Vec<Symmetry> {
+        let canonical = self.canonical();
+        Symmetry::ALL[1..]
+            .iter()
+            .copied()
+            .filter(|&symmetry| self.transformed(symmetry).canonical() == canonical)
+            .collect()
+    }
+
+    /// The coordinates of the cells with a positive value, in row-major order:
+    /// sorted by `y`, then by `x`.
+    pub fn alive_cells(&self) -> AliveCells<'_> {
+        // Starts past the end of the row before the first, so that the first call reads the first row.
+        AliveCells {
+            cells: &self.cells,
+            width: self.width,
+            height: self.height,
+            x: self.width,
+            y: -1,
+            bits: 0,
+        }
+    }
+
+    /// Splits the alive cells into objects: groups of cells connected through their eight neighbours,
+    /// wrapping around the torus, so that an object straddling an edge stays whole.
+    /// Components are in row-major order of their first cell.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let grid = Grid::from_text("##...#\n##....#\n.....##");
+    /// let components = grid.components();
+    /// assert_eq!(components.len(), 2);
+    /// assert_eq!(components[0].population(), 4);
+    /// assert_eq!(components[1].cells, [(5, 0), (6, 1), (5, 2), (6, 2)]);
+    /// assert_eq!(components[1].bounding_box, (5, 0, 2, 3));
+    /// ```
+    pub fn components(&self) -> Vec<Component> {
+        let (labels, count) = self.component_labels();
+        let mut cells = vec![Vec::new(); count];
+        for (x, y) in self.alive_cells() {
+            // Every alive cell is labelled.
+            cells[labels[(x + y * self.width) as usize].unwrap()].push((x, y));
+        }
+        cells
+            .into_iter()
+            .map(|cells| Component {
+                // Every component has a cell.
+                bounding_box: bounding_box(&cells, self.width, self.height).unwrap(),
+                cells,
+            })
+            .collect()
+    }
+
+    /// The number of [`Grid::components`], without collecting their cells.
+    pub fn component_count(&self) -> usize {
+        self.component_labels().1
+    }
+
+    /// Summarises the alive cells: how many there are, where they are centred, and how far they spread.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let stats = Grid::from_text("\n.###").stats();
+    /// assert_eq!(stats.population, 3);
+    /// assert_eq!(stats.bounding_box, (3, 1));
+    /// let (x, y) = stats.centroid.unwrap();
+    /// assert!((x - 2.0).abs() < 1e-9 && (y - 1.0).abs() < 1e-9);
+    /// assert_eq!(
+    ///     stats.to_string(),
+    ///     "3 alive (4.7%), centred at (2.00, 1.00), spread 0.82, in a 3×1 box, 1 component"
+    /// );
+    /// ```
+    pub fn stats(&self) -> GridStats {
+        let cells = self.alive_cells().collect::<Vec<_>>();
+        let centroid = circular_mean(cells.iter().map(|&(x, _)| x), self.width)
+            .zip(circular_mean(cells.iter().map(|&(_, y)| y), self.height));
+        let spread = centroid.map(|(centre_x, centre_y)| {
+            // The displacement to the nearest copy of each cell around the torus, along an axis of length `size`.
+            let offset = |coordinate: i32, centre: f64, size: i32| {
+                let size = size as f64;
+                (coordinate as f64 - centre + size / 2.0).rem_euclid(size) - size / 2.0
+            };
+            let total = cells
+                .iter()
+                .map(|&(x, y)| {
+                    offset(x, centre_x, self.width).powi(2)
+                        + offset(y, centre_y, self.height).powi(2)
+                })
+                .sum::<f64>();
+            (total / cells.len() as f64).sqrt()
+        });
+        GridStats {
+            population: cells.len(),
+            density: cells.len() as f64 / self.cells.len() as f64,
+            centroid,
+            spread,
+            bounding_box: bounding_box(&cells, self.width, self.height)
+                .map_or((0, 0), |(_, _, width, height)| (width, height)),
+            components: self.component_count(),
+        }
+    }
+
+    /// The smallest rectangle containing every alive cell, as `(x, y, width, height)`,
+    /// wrapping around the torus when that makes it smaller, like [`Component::bounding_box`].
+    /// On a [`Topology::Plane`] it never wraps.
+    /// There is none if every cell is dead.
+    pub fn bounding_box(&self) -> Option<(i32, i32, i32, i32)> {
+        let cells = self.alive_cells().collect::<Vec<_>>();
+        match self.topology {
+            Topology::Torus => bounding_box(&cells, self.width, self.height),
+            Topology::Plane => {
+                let (left, top, right, bottom) = cells.iter().fold(
+                    (i32::MAX, i32::MAX, i32::MIN, i32::MIN),
+                    |(left, top, right, bottom), &(x, y)| {
+                        (left.min(x), top.min(y), right.max(x), bottom.max(y))
+                    },
+                );
+                (!cells.is_empty()).then(|| (left, top, right - left + 1, bottom - top + 1))
+            }
+        }
+    }
+
+    /// Translates the alive cells so that their [`Grid::bounding_box`] starts at the origin,
+    /// and returns the result with the box's width and height.
+    ///
+    /// ```
+    /// use undeath::{grid::Anchor, Grid};
+    ///
+    /// // Shrink a pattern to its bounding box, with a margin of one cell all round.
+    /// let blinker = Grid::from_text("\n\n.....###");
+    /// let (trimmed, (width, height)) = blinker.trimmed();
+    /// assert_eq!((width, height), (3, 1));
+    /// let padded = trimmed.reembed(width + 2, height + 2, Anchor::Center).unwrap();
+    /// assert!(padded == Grid::from_text("\n.###"));
+    /// ```
+    pub fn trimmed(&self) -> (Grid, (i32, i32)) {
+        match self.bounding_box() {
+            Some((x, y, width, height)) => {
+                let mut trimmed = self.blank();
+                for (cell_x, cell_y) in self.alive_cells() {
+                    trimmed.set_wrapped(cell_x - x, cell_y - y, self.get_wrapped(cell_x, cell_y));
+                }
+                (trimmed, (width, height))
+            }
+            None => (self.blank(), (0, 0)),
+        }
+    }
+
+    /// Moves the pattern into the `width`×`height` rectangle at the top left of the grid, placed by `anchor`,
+    /// leaving every cell outside it dead.
+    /// Patterns straddling the seam of the torus are moved whole.
+    ///
+    /// The grid keeps its size, so the rectangle can be at most that large.
+    /// Fails with [`ReembedError::CutOff`] if any alive cell would fall outside it;
+    /// see [`Grid::reembed_clipped`] to drop those cells instead.
+    pub fn reembed(&self, width: i32, height: i32, anchor: Anchor) -> Result<Grid, ReembedError> {
+        let (grid, cut_off) = self.reembed_counting(width, height, anchor)?;
+        match cut_off {
+            0 => Ok(grid),
+            cells => Err(ReembedError::CutOff { cells }),
+        }
+    }
+
+    /// Like [`Grid::reembed`], but drops any alive cells that fall outside the rectangle.
+    pub fn reembed_clipped(
+        &self,
+        width: i32,
+        height: i32,
+        anchor: Anchor,
+    ) -> Result<Grid, ReembedError> {
+        Ok(self.reembed_counting(width, height, anchor)?.0)
+    }
+
+    /// Re-embeds the grid as [`Grid::reembed_clipped`] does, and counts the alive cells it drops.
+    fn reembed_counting(
+        &self,
+        width: i32,
+        height: i32,
+        anchor: Anchor,
+    ) -> Result<(Grid, usize), ReembedError> {
+        if !(1..=self.width).contains(&width) || !(1..=self.height).contains(&height) {
+            return Err(ReembedError::InvalidSize { width, height });
+        }
+        let mut result = self.blank();
+        let Some((x, y, box_width, box_height)) = self.bounding_box() else {
+            return Ok((result, 0));
+        };
+        let (left, top) = match anchor {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Center => (
+                (width - box_width).div_euclid(2),
+                (height - box_height).div_euclid(2),
+            ),
+            Anchor::Offset(left, top) => (left, top),
+        };
+        let mut cut_off = 0;
+        for (cell_x, cell_y) in self.alive_cells() {
+            let new_x = left + (cell_x - x).rem_euclid(self.width);
+            let new_y = top + (cell_y - y).rem_euclid(self.height);
+            if (0..width).contains(&new_x) && (0..height).contains(&new_y) {
+                result.set_wrapped(new_x, new_y, self.get_wrapped(cell_x, cell_y));
+            } else {
+                cut_off += 1;
+            }
+        }
+        Ok((result, cut_off))
+    }
+
+    /// Labels each alive cell with the index of its component, and counts the components.
+    fn component_labels(&self) -> (Vec<Option<usize>>, usize) {
+        let mut labels = vec![None; self.cells.len()];
+        let mut count = 0;
+        let mut stack = Vec::new();
+        for (x, y) in self.alive_cells() {
+            if labels[(x + y * self.width) as usize].is_some() {
+                continue;
+            }
+            labels[(x + y * self.width) as usize] = Some(count);
+            stack.push((x, y));
+            while let Some((x, y)) = stack.pop() {
+                for (nx, ny) in self.neighbour_positions(x, y) {
+                    let index = (nx + ny * self.width) as usize;
+                    if self.cells[index].value > 0 && labels[index].is_none() {
+                        labels[index] = Some(count);
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+            count += 1;
+        }
+        (labels, count)
+    }
+
+    /// Reads a grid from a file: as RLE if its extension is `.rle`, as [`Grid::from_rle`] does,
+    /// in the plaintext format if it is `.cells`, as [`Grid::from_cells`] does,
+    /// and otherwise in the `.gol` text format, as [`str::parse`] does.
+    /// Text files are read one line at a time, so memory use does not grow with their length,
+    /// and a file with no rows, or too large for [`MAX_SIZE`], is an error as in [`ParseGridError`].
+    /// [`Grid::from_file_with_wrap`] can fold patterns that are too large around the grid instead.
+    ///
+    /// A [`LoadError`] says whether the file could not be read or its contents are not a pattern.
+    /// A line that is not valid UTF-8 is reported as [`ParseGridError::InvalidUtf8`],
+    /// with the column of the first invalid byte counted in characters.
+    ///
+    /// ```
+    /// use undeath::{
+    ///     grid::{LoadError, ParseGridError},
+    ///     Grid,
+    /// };
+    ///
+    /// let path = std::env::temp_dir().join("undeath-invalid-utf8.gol");
+    /// std::fs::write(&path, b"........\n..\xe9#.\n").unwrap();
+    /// assert!(matches!(
+    ///     Grid::from_file(&path),
+    ///     Err(LoadError::Parse {
+    ///         source: ParseGridError::InvalidUtf8 { line: 2, column: 3 },
+    ///         ..
+    ///     })
+    /// ));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// assert!(matches!(
+    ///     Grid::from_file("does-not-exist.gol"),
+    ///     Err(LoadError::Io { .. })
+    /// ));
+    /// ```
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        Self::from_file_with_wrap(path, false)
+    }
+
+    /// Reads a grid from a file as [`Grid::from_file`] does if `allow_wrap` is not set.
+    ///
+    /// If it is, a pattern too large for the grid is not an error, but wraps around it:
+    /// a text file is read as [`Grid::from_text`] reads it, so that an empty file is an empty grid,
+    /// and an `.rle` file larger than its header, or than [`MAX_SIZE`], is read onto a grid
+    /// no larger than that, with the cells past its edges folded back onto it.
+    /// This is rarely what is wanted, since the folded cells can overlap and make a different pattern.
+    ///
+    /// ```
+    /// use undeath::{grid::LoadError, rle::RleError, Grid};
+    ///
+    /// let path = std::env::temp_dir().join("undeath-wrap.rle");
+    /// std::fs::write(&path, "x = 3, y = 1\n10o!\n").unwrap();
+    /// assert!(matches!(
+    ///     Grid::from_file_with_wrap(&path, false),
+    ///     Err(LoadError::Rle {
+    ///         source: RleError::OutOfBounds { pattern: (10, 1), .. },
+    ///         ..
+    ///     })
+    /// ));
+    /// // The last two of the ten cells fold back onto the first two columns of the 8×8 grid.
+    /// let grid = Grid::from_file_with_wrap(&path, true).unwrap();
+    /// assert!(grid == Grid::from_text("########"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn from_file_with_wrap(
+        path: impl AsRef<Path>,
+        allow_wrap: bool,
+    ) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let format = match extension.as_deref() {
+            Some("rle") => {
+                let text = std::fs::read_to_string(path).map_err(|e| LoadError::io(path, e))?;
+                return Rle::read(&text, allow_wrap)
+                    .map(|rle| rle.grid)
+                    .map_err(|source| LoadError::Rle {
+                        path: path.to_owned(),
+                        source,
+                    });
+            }
+            Some("cells") => TextFormat::Cells,
+            _ => TextFormat::Gol,
+        };
+        let file = File::open(path).map_err(|e| LoadError::io(path, e))?;
+        Self::read_text(
+            BufReader::new(file),
+            path,
+            TextRows::new(format, allow_wrap),
+        )
+    }
+
+    /// Reads a pattern as [`Grid::from_file`] does, then places it on a `width`×`height` grid so that
+    /// at least `margin` dead cells lie between it and every edge.
+    /// The pattern's bounding box starts `margin` cells from the left and the top.
+    ///
+    /// Fails with [`LoadError::NoRoom`] if the pattern with its margin does not fit on the grid,
+    /// which is never grown to make room.
+    /// To keep the margin dead in predecessors too, search with a mask of the cells inside it,
+    /// as [`Searcher::with_mask`](crate::Searcher::with_mask) takes.
+    ///
+    /// ```
+    /// use undeath::{grid::LoadError, Grid};
+    ///
+    /// let path = std::env::temp_dir().join("undeath-margin.gol");
+    /// std::fs::write(&path, "\n\n\n\n\n......#\n.......#\n.....###\n").unwrap();
+    /// let glider = Grid::from_file_with_margin(&path, 2, 8, 8).unwrap();
+    /// assert!(glider == Grid::from_text("\n\n...#\n....#\n..###"));
+    /// // Three cells on each side of the glider take a 9×9 grid.
+    /// let glider = Grid::from_file_with_margin(&path, 3, 9, 9).unwrap();
+    /// assert_eq!(glider.bounding_box(), Some((3, 3, 3, 3)));
+    /// assert!(matches!(
+    ///     Grid::from_file_with_margin(&path, 3, 8, 8),
+    ///     Err(LoadError::NoRoom { pattern: (3, 3), margin: 3, grid: (8, 8), .. })
+    /// ));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    /// # Panics
+    /// If `width` or `height` is outside `MIN_SIZE..=MAX_SIZE`, as for [`Grid::new`].
+    pub fn from_file_with_margin(
+        path: impl AsRef<Path>,
+        margin: u32,
+        width: i32,
+        height: i32,
+    ) -> Result<Self, LoadError> {
+        let mut placed = Grid::new(width, height);
+        let path = path.as_ref();
+        let (trimmed, pattern) = Self::from_file(path)?.trimmed();
+        let fits =
+            |side: i32, room: i32| i64::from(side) + 2 * i64::from(margin) <= i64::from(room);
+        if !fits(pattern.0, width) || !fits(pattern.1, height) {
+            return Err(LoadError::NoRoom {
+                path: path.to_owned(),
+                pattern,
+                margin,
+                grid: (width, height),
+            });
+        }
+        // It fits, so the margin is less than half the grid.
+        let margin = margin as i32;
+        for (x, y) in trimmed.alive_cells() {
+            placed.set_wrapped(x + margin, y + margin, trimmed.get_wrapped(x, y));
+        }
+        Ok(placed)
+    }
+
+    /// Reads a pattern as [`Grid::from_file`] does, then moves its bounding box to the middle of the grid,
+    /// rounding up and to the left as [`Anchor::Center`] does, so that the pattern does not favour any edge.
+    ///
+    /// Returns the grid with the offset the pattern was moved by: the cell at `(x, y)` in the file
+    /// is at `(x + dx, y + dy)` on the grid, wrapping around it, so subtracting the offset
+    /// takes the cells of a predecessor back to the file's frame. An empty pattern is not moved.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let path = std::env::temp_dir().join("undeath-centered.gol");
+    /// std::fs::write(&path, ".#\n..#\n###\n").unwrap();
+    /// let (glider, offset) = Grid::from_file_centered(&path).unwrap();
+    /// // Five dead columns around a glider on an 8×8 grid leave two on the left and three on the right.
+    /// assert_eq!(offset, (2, 2));
+    /// assert!(glider == Grid::from_text("\n\n...#\n....#\n..###"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn from_file_centered(path: impl AsRef<Path>) -> Result<(Self, (i32, i32)), LoadError> {
+        let grid = Self::from_file(path)?;
+        let Some((left, top, ..)) = grid.bounding_box() else {
+            return Ok((grid, (0, 0)));
+        };
+        let centred = grid
+            .reembed(grid.width(), grid.height(), Anchor::Center)
+            .expect("the whole grid has room for its own pattern");
+        let (x, y, ..) = centred
+            .bounding_box()
+            .expect("moving a pattern keeps its cells");
+        Ok((centred, (x - left, y - top)))
+    }
+
+    /// Parses a pattern in the run-length encoded format that Golly and LifeWiki use:
+    /// `#` comment lines, a header line such as `x = 3, y = 3, rule = B3/S23`, then runs of `b` for dead cells
+    /// and `o` for alive ones, with `$` between rows and `!` at the end.
+    /// The grid is as large as [`Rle::parse`] makes it, and only the comments are dropped.
+    ///
+    /// A pattern with more cells than its header says, or larger than [`MAX_SIZE`], is an error
+    /// rather than wrapping around the grid.
+    ///
+    /// ```
+    /// use undeath::{rle::RleError, Grid};
+    ///
+    /// let glider = Grid::from_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n")?;
+    /// assert!(glider == Grid::from_text(".#\n..#\n###"));
+    /// assert!(matches!(
+    ///     Grid::from_rle("x = 3, y = 3\n4o!"),
+    ///     Err(RleError::OutOfBounds { line: 2, column: 2, .. })
+    /// ));
+    /// # Ok::<(), RleError>(())
+    /// ```
+    pub fn from_rle(text: &str) -> Result<Self, RleError> {
+        Rle::read(text, false).map(|rle| rle.grid)
+    }
+
+    /// Parses a grid in the `.gol` text format: one line per row,
+    /// where spaces and `.` are dead cells and any other character is alive.
+    /// Columns are counted in characters, so a multi-byte character is a single cell.
+    ///
+    /// A pattern that fits in [`SIZE`]×[`SIZE`] is read onto a grid of that size.
+    /// A larger one is read onto a grid as wide as its longest line and as tall as its number of lines,
+    /// but no smaller than [`MIN_SIZE`] on either side.
+    ///
+    /// ```
+    /// use undeath::{Cell, Grid};
+    ///
+    /// let grid = Grid::from_text("é.#");
+    /// assert!(grid.get_wrapped(0, 0) == Cell::one());
+    /// assert!(grid.get_wrapped(1, 0) == Cell::zero());
+    /// assert!(grid.get_wrapped(2, 0) == Cell::one());
+    /// ```
+    pub fn from_text(text: &str) -> Self {
+        Self::read_text(
+            text.as_bytes(),
+            Path::new(""),
+            TextRows::new(TextFormat::Gol, true),
+        )
+        .expect("reading from a string cannot fail, and a string is valid UTF-8")
+    }
+
+    /// Parses a grid in the `.gol` text format from raw bytes, such as the contents of a file read elsewhere.
+    /// Invalid UTF-8 is reported as in [`Grid::from_file`], and large patterns wrap around as in [`Grid::from_text`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::read_text(
+            bytes,
+            Path::new(""),
+            TextRows::new(TextFormat::Gol, true),
+        )?)
+    }
+
+    /// Parses a grid in the plaintext `.cells` format that LifeWiki uses: one line per row,
+    /// with `.` for dead cells and `O` for alive ones, and lines starting with `!`, such as `!Name: Glider`,
+    /// as comments. Any other character is an [`Error::Parse`] naming its line and column.
+    /// The grid is as large as [`Grid::from_text`] makes it, and patterns with no rows or too large for it
+    /// are errors as in [`str::parse`].
+    ///
+    /// ```
+    /// use undeath::{Error, Grid};
+    ///
+    /// let glider = Grid::from_cells("!Name: Glider\n!\n.O\n..O\nOOO\n")?;
+    /// assert!(glider == Grid::from_text(".#\n..#\n###"));
+    /// assert!(matches!(
+    ///     Grid::from_cells("!Name: Glider\n.O\n..#"),
+    ///     Err(Error::Parse { line: 3, column: 3, .. })
+    /// ));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn from_cells(text: &str) -> Result<Self, Error> {
+        Ok(Self::read_text(
+            text.as_bytes(),
+            Path::new(""),
+            TextRows::new(TextFormat::Cells, false),
+        )?)
+    }
+
+    /// Feeds `rows` the lines of a text format one at a time, reusing a single line buffer.
+    /// `path` is only used to report errors.
+    fn read_text(
+        mut reader: impl BufRead,
+        path: &Path,
+        mut rows: TextRows,
+    ) -> Result<Self, LoadError> {
+        let parse_error = |source| LoadError::Parse {
+            path: path.to_owned(),
+            source,
+        };
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            if reader
+                .read_until(b'\n', &mut buffer)
+                .map_err(|e| LoadError::io(path, e))?
+                == 0
+            {
+                break;
+            }
+            let line = std::str::from_utf8(&buffer).map_err(|e| {
+                // The prefix before the invalid byte is valid by definition.
+                let valid = std::str::from_utf8(&buffer[..e.valid_up_to()]).unwrap();
+                parse_error(ParseGridError::InvalidUtf8 {
+                    line: rows.lines + 1,
+                    column: valid.chars().count() + 1,
+                })
+            })?;
+            rows.push(line).map_err(parse_error)?;
+        }
+        rows.finish().map_err(parse_error)
+    }
+
+    /// Iterates over the grids that become this one after one step.
+    /// See [`Predecessors`] for how long each item may take to find.
+    pub fn predecessors(&self) -> Predecessors {
+        Searcher::new(self.clone()).into_iter()
+    }
+    /// Finds a grid that becomes this one after one step, as [`solve`](crate::solve) does with the default [`Options`](crate::Options).
+    ///
+    /// This may search for a long time before giving up: up to the default budget of ten million iterations.
+    /// Running out of budget is [`PredecessorOutcome::Unknown`]; any other error the solver returns is passed on.
+    ///
+    /// ```
+    /// use undeath::{Grid, PredecessorOutcome};
+    ///
+    /// let blinker = Grid::from_text("\n\n\n..###");
+    /// let Ok(PredecessorOutcome::Found(parent)) = blinker.predecessor() else {
+    ///     panic!("a blinker has a predecessor");
+    /// };
+    /// let mut stepped = parent.clone();
+    /// stepped.step();
+    /// assert!(stepped == blinker);
+    /// ```
+    pub fn predecessor(&self) -> Result<PredecessorOutcome, Error> {
+        match crate::solve(self) {
+            Ok(Some(parent)) => Ok(PredecessorOutcome::Found(parent)),
+            Ok(None) => Ok(PredecessorOutcome::ProvenNone),
+            Err(Error::BudgetExhausted { .. }) => Ok(PredecessorOutcome::Unknown),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Finds up to `limit` distinct grids that become this one after one step, as [`solve_all`](crate::solve_all) does.
+    /// Fewer than `limit` are found only if they are all there are.
+    ///
+    /// Like [`Grid::predecessor`], this may search for a long time,
+    /// and if the budget runs out, any predecessors found so far are discarded.
+    /// To take predecessors one at a time, with no budget, see [`Grid::predecessors`].
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let blinker = Grid::from_text("\n\n\n..###");
+    /// let parents = blinker.predecessors_up_to(3)?.found().unwrap();
+    /// assert_eq!(parents.len(), 3);
+    /// for mut parent in parents {
+    ///     parent.step();
+    ///     assert!(parent == blinker);
+    /// }
+    /// # Ok::<(), undeath::Error>(())
+    /// ```
+    pub fn predecessors_up_to(&self, limit: usize) -> Result<PredecessorOutcome<Vec<Grid>>, Error> {
+        match crate::solve_all(self, limit) {
+            Ok(parents) if parents.is_empty() => Ok(PredecessorOutcome::ProvenNone),
+            Ok(parents) => Ok(PredecessorOutcome::Found(parents)),
+            Err(Error::BudgetExhausted { .. }) => Ok(PredecessorOutcome::Unknown),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Encodes the alive cells in the run-length encoded format that Golly and LifeWiki use, so that they can be
+    /// pasted into Golly: a header line giving the width and height of their [`Grid::bounding_box`], then its rows,
+    /// with `b` for dead cells, `o` for alive ones, `$` between rows, and `!` at the end,
+    /// broken into lines of at most 70 characters as Golly writes them.
+    /// Where the pattern was on the grid is left out; an [`Rle`] with a position keeps it.
+    /// The empty grid is a pattern of no rows, `x = 0, y = 0`.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let glider = Grid::from_text("\n..#\n...#\n.###");
+    /// assert_eq!(glider.to_rle(), "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+    /// assert_eq!(Grid::default().to_rle(), "x = 0, y = 0, rule = B3/S23\n!\n");
+    /// ```
+    pub fn to_rle(&self) -> String {
+        let (x, y, width, height) = self.bounding_box().unwrap_or_default();
+        format!(
+            "x = {width}, y = {height}, rule = B3/S23\n{}\n",
+            crate::rle::encode_body(self, (x, y), (width, height))
+        )
+    }
+
+    /// The extended Wechsler code of the alive cells, the part of an apgcode after the underscore,
+    /// in the orientation Catagolue chooses, so that a pattern has the same code however it is turned or placed.
+    /// The prefix classifying the pattern, such as `xs4` for a still life of four cells, is left out.
+    /// The empty grid is `0`. See [`apgcode`](crate::apgcode) for the format.
+    ///
+    /// Catagolue's patterns are on the plane. Here the pattern is cut out of the torus along the widest gaps
+    /// of empty rows and columns, as in [`Grid::bounding_box`], so a pattern with little room around it
+    /// may be cut elsewhere, and given another code.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let glider = Grid::from_text("\n..#\n...#\n.###");
+    /// assert_eq!(glider.to_apgcode(), "153");
+    /// assert_eq!(Grid::from_text("\n\n....###").to_apgcode(), "7");
+    /// ```
+    pub fn to_apgcode(&self) -> String {
+        crate::apgcode::encode(self)
+    }
+
+    /// Reads a pattern from its apgcode, such as `xq4_153`, or from just the extended Wechsler part after the underscore.
+    /// The pattern's top left corner is at the grid's, which is [`SIZE`]×[`SIZE`] if the pattern fits
+    /// and otherwise just large enough for it, but no smaller than [`MIN_SIZE`] on either side.
+    ///
+    /// Fails with [`Error::Parse`], with the column of the offending character, if the code is malformed,
+    /// and with [`Error::InvalidInput`] if the pattern is larger than [`MAX_SIZE`] or the code, like `yl144_1_16_...`
+    /// for linear growth, does not give the cells of a pattern.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let glider = Grid::from_apgcode("xq4_153")?;
+    /// assert!(glider == Grid::from_text("###\n..#\n.#"));
+    /// assert!(Grid::from_apgcode("xs4_3#").is_err());
+    /// # Ok::<(), undeath::Error>(())
+    /// ```
+    pub fn from_apgcode(code: &str) -> Result<Self, Error> {
+        crate::apgcode::decode(code)
+    }
+
+    /// Writes the grid in the `.gol` text format that [`Grid::from_text`] reads, with `#` for alive cells
+    /// and `.` for dead ones. Rows stop at their last alive cell, and the text at the last row with one,
+    /// so the empty grid is the empty string.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let glider = Grid::from_text("\n..#\n...#\n.###");
+    /// assert_eq!(glider.to_text(), "\n..#\n...#\n.###\n");
+    /// assert!(Grid::from_text(&glider.to_text()) == glider);
+    /// ```
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let mut rows_ended = 0;
+        for row in self.cells.chunks_exact(self.width as usize) {
+            let Some(last) = row.iter().rposition(|cell| cell.value > 0) else {
+                rows_ended += 1;
+                continue;
+            };
+            out.extend(std::iter::repeat_n('\n', rows_ended));
+            out.extend(
+                row[..=last]
+                    .iter()
+                    .map(|cell| if cell.value > 0 { '#' } else { '.' }),
+            );
+            rows_ended = 1;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Writes the whole grid in the `.gol` text format, with `#` for alive cells and `.` for dead ones.
+    /// Unlike [`Grid::to_text`], every row is written, as wide as the grid, so the text keeps the grid's size.
+    ///
+    /// A grid that the rows alone would not be read back as, because it is on a [`Topology::Plane`]
+    /// or smaller than [`SIZE`]×[`SIZE`] without being that size, starts with a line such as `!size 5x5`
+    /// or `!size 20x6 plane`, which the text readers take as its exact size and topology.
+    /// So reading the text back with [`str::parse`] gives the same grid,
+    /// as long as its cells are all dead or alive: other values are written as `#` if positive.
+    ///
+    /// ```
+    /// use undeath::{grid::Topology, Grid};
+    ///
+    /// let glider = Grid::from_text("\n..#\n...#\n.###");
+    /// assert_eq!(glider.to_gol_string().lines().nth(3), Some(".###...."));
+    /// assert_eq!(glider.to_gol_string().lines().count(), 8);
+    /// assert!(glider.to_gol_string().parse::<Grid>().unwrap() == glider);
+    ///
+    /// let small = Grid::new(5, 3).with_topology(Topology::Plane);
+    /// assert_eq!(small.to_gol_string(), "!size 5x3 plane\n.....\n.....\n.....\n");
+    /// assert!(small.to_gol_string().parse::<Grid>().unwrap() == small);
+    /// ```
+    pub fn to_gol_string(&self) -> String {
+        let mut out = String::with_capacity(self.cells.len() + self.height as usize);
+        let padded = self.width <= SIZE && self.height <= SIZE;
+        if self.topology == Topology::Plane || padded && (self.width, self.height) != (SIZE, SIZE) {
+            out.push_str(&format!("{SIZE_HEADER}{}x{}", self.width, self.height));
+            if self.topology == Topology::Plane {
+                out.push_str(" plane");
+            }
+            out.push('\n');
+        }
+        for row in self.gol_rows() {
+            out.push_str(&row);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Each row of the grid in the `.gol` text format, as wide as the grid.
+    pub(crate) fn gol_rows(&self) -> impl Iterator<Item = String> + '_ {
+        self.cells.chunks_exact(self.width as usize).map(|row| {
+            row.iter()
+                .map(|cell| if cell.value > 0 { '#' } else { '.' })
+                .collect()
+        })
+    }
+
+    /// Writes [`Grid::to_gol_string`] to the file at `path`, replacing it if it exists.
+    /// The file is written in the `.gol` format whatever its extension, so [`Grid::from_file`]
+    /// reads it back as the same grid if its extension is neither `.rle` nor `.cells`.
+    ///
+    /// Fails with [`Error::InvalidInput`], without writing anything, if a cell is neither dead nor alive,
+    /// as in a grid of neighbour counts, since the file could not be read back as the same grid.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let path = std::env::temp_dir().join("undeath-to-file.gol");
+    /// let blinker = Grid::from_text("\n.###");
+    /// blinker.to_file(&path)?;
+    /// assert!(Grid::from_file(&path)? == blinker);
+    /// assert!(blinker.neighbours().to_file(&path).is_err());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        if let Some(cell) = self
+            .cells
+            .iter()
+            .find(|cell| !(0..=1).contains(&cell.value))
+        {
+            return Err(Error::InvalidInput(format!(
+                "cannot write a cell of {} to {}: a `.gol` file only holds dead and alive cells",
+                cell.value,
+                path.display()
+            )));
+        }
+        std::fs::write(path, self.to_gol_string()).map_err(|e| Error::io(path, e))
     }
 
     /// Renders the grid to a string.
     pub fn render(&self) -> String {
-        let border = std::iter::repeat_n('─', 2 * SIZE as usize).collect::<String>();
-
-        let column_numbers = (0..SIZE).map(|i| format!("{i:2}")).collect::<String>();
-        let mut output = format!("    {column_numbers} \n   ┌{border}┐\n");
-        for y in 0..SIZE {
-            let mut row = String::new();
-            for x in 0..SIZE {
-                row += match unsafe { self.get(x, y) }.value {
-                    0 => "  ",
-                    _ => "██",
-                };
+        let mut output = String::new();
+        self.render_into(&mut output);
+        output
+    }
+
+    /// Appends the rendering of [`Grid::render`] to `out`, reserving the space it needs up front.
+    /// Reusing one buffer across frames avoids allocating a new string each time.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let grid = Grid::from_text(".#\n#.");
+    /// let mut buffer = String::new();
+    /// grid.render_into(&mut buffer);
+    /// assert_eq!(buffer, grid.render());
+    /// ```
+    pub fn render_into(&self, out: &mut String) {
+        // All coordinates passed to the closure lie in `0..width` and `0..height`.
+        render_frame_into(out, self.width, self.height, |x, y| {
+            match unsafe { self.get(x, y) }.value {
+                0 => "  ",
+                _ => "██",
             }
-            output += &format!("{y:2} │");
-            output += &row;
-            output.push('│');
-            output.push('\n');
-        }
-        output += &format!("   └{border}┘");
+        });
+    }
+
+    /// Renders the grid as a heat map, shading each cell by its value relative to the largest:
+    /// blank for zero or less, then `░`, `▒`, `▓` and `█` for each further quarter of the largest value.
+    ///
+    /// ```
+    /// use undeath::{Cell, Grid};
+    ///
+    /// let mut counts = Grid::default();
+    /// counts.set_wrapped(0, 0, Cell { value: 1 });
+    /// counts.set_wrapped(1, 0, Cell { value: 8 });
+    /// let heatmap = counts.render_heatmap();
+    /// assert!(heatmap.lines().nth(2).unwrap().starts_with(" 0 │░░██  "));
+    /// ```
+    pub fn render_heatmap(&self) -> String {
+        const SHADES: [&str; 5] = ["  ", "░░", "▒▒", "▓▓", "██"];
+        let max = self.cells.iter().map(|cell| cell.value).max().unwrap_or(0);
+        let mut output = String::new();
+        render_frame_into(&mut output, self.width, self.height, |x, y| {
+            let value = self.get_wrapped(x, y).value;
+            if value <= 0 {
+                return SHADES[0];
+            }
+            // Rounds up, so that every positive value is visible.
+            let shade = ((value as i64 * 4 + max as i64 - 1) / max as i64) as usize;
+            SHADES[shade.clamp(1, 4)]
+        });
+        output
+    }
+
+    /// Renders the grid with each of its [`Grid::components`] in its own colour,
+    /// cycling through six ANSI colours in the order of the components.
+    pub fn render_components(&self) -> String {
+        const COLOURS: [&str; 6] = [
+            "\x1b[31m██\x1b[0m",
+            "\x1b[32m██\x1b[0m",
+            "\x1b[33m██\x1b[0m",
+            "\x1b[34m██\x1b[0m",
+            "\x1b[35m██\x1b[0m",
+            "\x1b[36m██\x1b[0m",
+        ];
+        let (labels, _) = self.component_labels();
+        let mut output = String::new();
+        render_frame_into(&mut output, self.width, self.height, |x, y| {
+            match labels[(x + y * self.width) as usize] {
+                Some(label) => COLOURS[label % COLOURS.len()],
+                None => "  ",
+            }
+        });
         output
     }
 
     /// Work out the amount of neighbours of this cell.
     pub fn neighbours(&self) -> Self {
-        let mut neighbours = self.hcount().vcount();
-        neighbours -= self;
+        let mut neighbours = Self::new(self.width, self.height);
+        self.neighbours_into(&mut neighbours);
         neighbours
     }
 
+    /// Like [`Grid::neighbours_into`], but fails with [`Error::InvalidInput`], leaving `out` as it was,
+    /// if `out` is a different size from this grid.
+    pub fn try_neighbours_into(&self, out: &mut Grid) -> Result<(), Error> {
+        self.check_same_size(out)?;
+        self.neighbours_into(out);
+        Ok(())
+    }
+
+    /// Work out the amount of neighbours of each cell, writing the result into `out`.
+    /// The eight cells around each cell are added up in a single pass over three rows at a time.
+    /// This is as fast as summing each row over a window of three first and then adding up three windows,
+    /// and faster on large grids, without the scratch space for the windows, so that nothing is allocated.
+    /// On a [`Topology::Plane`], the cells past the edges count as dead.
+    ///
+    /// # Panics
+    /// If `out` is a different size from this grid. [`Grid::try_neighbours_into`] fails instead.
+    pub fn neighbours_into(&self, out: &mut Grid) {
+        self.assert_same_size(out);
+        let (width, height) = (self.width as usize, self.height as usize);
+        let torus = self.topology == Topology::Torus;
+        let row = |y: usize| &self.cells[y * width..(y + 1) * width];
+        for (y, out) in out.cells.chunks_exact_mut(width).enumerate() {
+            // On a plane, the rows past the top and bottom edges are dead.
+            let above = if y > 0 || torus {
+                row((y + height - 1) % height)
+            } else {
+                &DEAD_ROW[..width]
+            };
+            let below = if y + 1 < height || torus {
+                row((y + 1) % height)
+            } else {
+                &DEAD_ROW[..width]
+            };
+            let (current, out) = (row(y), &mut out[..width]);
+            for x in 1..width - 1 {
+                out[x].value = above[x - 1].value
+                    + above[x].value
+                    + above[x + 1].value
+                    + current[x - 1].value
+                    + current[x + 1].value
+                    + below[x - 1].value
+                    + below[x].value
+                    + below[x + 1].value;
+            }
+            // Only the two end cells wrap around, so the rest is summed without any modulo.
+            // On a plane, what they would wrap around to is dead.
+            let column = |x: usize| above[x].value + current[x].value + below[x].value;
+            let wrap = i32::from(torus);
+            out[0].value = wrap * column(width - 1) + above[0].value + below[0].value + column(1);
+            out[width - 1].value = column(width - 2)
+                + above[width - 1].value
+                + below[width - 1].value
+                + wrap * column(0);
+        }
+    }
+
+    /// [`Grid::neighbours_into`] as it was before it made a single pass:
+    /// every row is summed over a window of three cells into `windows` first,
+    /// and the count for a cell is then the sum of the three windows around it, minus the cell itself.
+    /// Kept for [`crate::testing`] to check and measure the two against each other.
+    #[cfg(feature = "testing")]
+    pub(crate) fn separable_neighbours_into(&self, out: &mut Grid, windows: &mut Vec<i32>) {
+        self.assert_same_size(out);
+        let (width, height) = (self.width as usize, self.height as usize);
+        let wrap = i32::from(self.topology == Topology::Torus);
+        windows.clear();
+        windows.resize(width * height, 0);
+        for (window, row) in windows
+            .chunks_exact_mut(width)
+            .zip(self.cells.chunks_exact(width))
+        {
+            for (window, cells) in window[1..width - 1].iter_mut().zip(row.windows(3)) {
+                *window = cells[0].value + cells[1].value + cells[2].value;
+            }
+            window[0] = wrap * row[width - 1].value + row[0].value + row[1].value;
+            window[width - 1] = row[width - 2].value + row[width - 1].value + wrap * row[0].value;
+        }
+
+        let window = |y: usize| &windows[y * width..(y + 1) * width];
+        for (y, (out, row)) in out
+            .cells
+            .chunks_exact_mut(width)
+            .zip(self.cells.chunks_exact(width))
+            .enumerate()
+        {
+            // On a plane, the windows past the top and bottom edges are dead.
+            let above = (y > 0 || wrap == 1).then(|| window((y + height - 1) % height));
+            let below = (y + 1 < height || wrap == 1).then(|| window((y + 1) % height));
+            for (x, (out, cell)) in out.iter_mut().zip(row).enumerate() {
+                out.value = window(y)[x] - cell.value
+                    + above.map_or(0, |above| above[x])
+                    + below.map_or(0, |below| below[x]);
+            }
+        }
+    }
+
+    /// Work out the amount of neighbours of each cell in `neighbourhood`.
+    /// For the Moore neighbourhood, this is [`Grid::neighbours`].
+    pub fn neighbours_in(&self, neighbourhood: Neighbourhood) -> Self {
+        if neighbourhood == Neighbourhood::Moore {
+            return self.neighbours();
+        }
+        let mut neighbours = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let count = neighbourhood
+                    .positions(x, y, self.width, self.height)
+                    .filter(|&neighbour| self.topology.adjacent((x, y), neighbour))
+                    .map(|(x2, y2)| unsafe { self.get(x2, y2) }.value)
+                    .sum();
+                unsafe {
+                    neighbours.set(x, y, Cell { value: count });
+                }
+            }
+        }
+        neighbours
+    }
+
+    /// Sums the values within Chebyshev distance `radius` of each cell around the torus, including the cell itself.
+    /// For a Life board, this counts the alive cells in the `(2 · radius + 1)`-wide square around each cell.
+    /// Each cell is counted at most once, even when the square is wider than the grid and would wrap onto itself.
+    ///
+    /// The sums are separable, so this takes prefix sums along each row and then each column,
+    /// which costs `O(width · height)` whatever the radius.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let glider = Grid::from_text(".#\n..#\n###");
+    /// assert_eq!(glider.neighbourhood_counts(1).get_wrapped(1, 1).value, 5);
+    /// assert_eq!(glider.neighbourhood_counts(0).get_wrapped(1, 1).value, 0);
+    /// assert_eq!(glider.neighbourhood_counts(4).get_wrapped(6, 6).value, 5);
+    /// ```
+    pub fn neighbourhood_counts(&self, radius: u32) -> Grid {
+        // The sum of each window of cells along a line of `length` cells, with the window for index `i` centred on `i`.
+        // The window spans the whole line once it reaches halfway round.
+        let mut prefix = vec![0; 2 * self.width.max(self.height) as usize + 1];
+        let mut window_sums =
+            |length: i32, line: &dyn Fn(usize) -> i32, write: &mut dyn FnMut(usize, i32)| {
+                let length = length as usize;
+                let span = (2 * radius as usize + 1).min(length);
+                let back = radius as usize % length;
+                for i in 0..2 * length {
+                    prefix[i + 1] = prefix[i] + line(i % length);
+                }
+                for i in 0..length {
+                    let start = (i + length - back) % length;
+                    write(i, prefix[start + span] - prefix[start]);
+                }
+            };
+
+        let mut rows = Grid::new(self.width, self.height);
+        for y in 0..self.height {
+            window_sums(
+                self.width,
+                &|x| unsafe { self.get(x as i32, y) }.value,
+                &mut |x, value| {
+                    unsafe { rows.set(x as i32, y, Cell { value }) };
+                },
+            );
+        }
+        let mut counts = Grid::new(self.width, self.height);
+        for x in 0..self.width {
+            window_sums(
+                self.height,
+                &|y| unsafe { rows.get(x, y as i32) }.value,
+                &mut |y, value| {
+                    unsafe { counts.set(x, y as i32, Cell { value }) };
+                },
+            );
+        }
+        counts
+    }
+
+    /// The cells within Chebyshev distance one of an alive cell of this grid, as a mask.
+    /// A predecessor confined to this mask only has alive cells next to where the target does,
+    /// which most small patterns' predecessors do, so it is a good first region to search.
+    /// It is only a heuristic: predecessors with cells further out, such as ones that die, are excluded.
+    ///
+    /// ```
+    /// use undeath::{Grid, Searcher};
+    ///
+    /// let blinker = Grid::from_text("\n\n\n..###");
+    /// let mask = blinker.suggested_mask();
+    /// assert_eq!(mask.alive_cells().count(), 15);
+    /// assert!(Searcher::with_mask(blinker, &mask).into_iter().next().is_some());
+    /// ```
+    pub fn suggested_mask(&self) -> Grid {
+        self.clamp_scalar(0, 1)
+            .neighbourhood_counts(1)
+            .clamp_scalar(0, 1)
+    }
+
+    /// Compute the next step of the simulation, with Life's rule applied to the neighbours in `neighbourhood`:
+    /// a cell is born with three alive neighbours and survives with two or three.
+    /// For the Moore neighbourhood, this is [`Grid::step`].
+    ///
+    /// ```
+    /// use undeath::{grid::Neighbourhood, Grid};
+    ///
+    /// // Without the diagonals, no cell around a row of three has three neighbours,
+    /// // and only the middle of the row has two.
+    /// let mut row = Grid::from_text("###");
+    /// row.step_in(Neighbourhood::VonNeumann);
+    /// assert!(row == Grid::from_text(".#"));
+    /// ```
+    pub fn step_in(&mut self, neighbourhood: Neighbourhood) {
+        let neighbours = self.neighbours_in(neighbourhood);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let alive = unsafe { self.get(x, y) }.value > 0;
+                let value = match unsafe { neighbours.get(x, y) }.value {
+                    3 => 1,
+                    2 if alive => 1,
+                    _ => 0,
+                };
+                unsafe {
+                    self.set(x, y, Cell { value });
+                }
+            }
+        }
+    }
+
     /// Compute the next step of the simulation.
     pub fn step(&mut self) {
-        let neighbours = self.neighbours();
-        for x in 0..SIZE {
-            for y in 0..SIZE {
+        let mut neighbours = Self::new(self.width, self.height);
+        self.step_with(&mut neighbours);
+    }
+
+    /// Like [`Grid::step`], but counts the neighbours into `neighbours`, which is resized to fit if it has to be,
+    /// so that stepping over and over with the same buffer allocates nothing.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let mut blinker = Grid::from_text("\n\n ###\n");
+    /// // The buffer is the wrong size at first, so it grows to 8×8 once and is then reused.
+    /// let mut neighbours = Grid::new(3, 3);
+    /// blinker.step_with(&mut neighbours);
+    /// assert_eq!((neighbours.width(), neighbours.height()), (8, 8));
+    /// blinker.step_with(&mut neighbours);
+    /// assert!(blinker == Grid::from_text("\n\n ###\n"));
+    /// ```
+    pub fn step_with(&mut self, neighbours: &mut Grid) {
+        if !self.same_size(neighbours) {
+            neighbours.reset(self.width, self.height);
+        }
+        self.neighbours_into(neighbours);
+        for x in 0..self.width {
+            for y in 0..self.height {
                 let current = unsafe { self.get(x, y) };
                 let count_neighbours = unsafe { neighbours.get(x, y) };
                 // The rules are that the output cell is alive if:
@@ -227,13 +1850,7 @@ impl Grid {
                 let new_value = Cell {
                     value: match count_neighbours.value {
                         3 => 1,
-                        2 => {
-                            if current.value > 0 {
-                                1
-                            } else {
-                                0
-                            }
-                        }
+                        2 if current.value > 0 => 1,
                         _ => 0,
                     },
                 };
@@ -244,41 +1861,1104 @@ impl Grid {
         }
     }
 
-    /// Each output cell is the sum of the values of the input cell and its vertical neighbours.
-    fn vcount(&self) -> Self {
-        let mut result: [MaybeUninit<Cell>; (SIZE * SIZE) as usize] = MaybeUninit::uninit_array();
+    /// Compute the next step of the simulation, returning the coordinates of every cell whose value changed,
+    /// in row-major order. The result is empty exactly when the grid is a still life.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let mut block = Grid::from_text("\n .##\n .##\n");
+    /// assert!(block.step_changes().is_empty());
+    ///
+    /// let mut blinker = Grid::from_text("\n\n ###\n");
+    /// assert_eq!(blinker.step_changes().len(), 4);
+    /// assert_eq!(blinker.step_changes().len(), 4);
+    /// ```
+    pub fn step_changes(&mut self) -> Vec<(i32, i32)> {
+        let mut changes = Vec::new();
+        self.step_into_changes(&mut changes);
+        changes
+    }
 
-        for x in 0..SIZE {
-            for y in 0..SIZE {
-                result[(x + y * SIZE) as usize].write(
-                    self.get_wrapped(x, y - 1)
-                        + self.get_wrapped(x, y)
-                        + self.get_wrapped(x, y + 1),
-                );
+    /// Like [`Grid::step_changes`], but writes the changed cells into `changes`,
+    /// which is cleared first, so that its allocation can be reused across steps.
+    pub fn step_into_changes(&mut self, changes: &mut Vec<(i32, i32)>) {
+        let previous = self.clone();
+        self.step_bitparallel();
+        changes.clear();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                // Both coordinates are in bounds.
+                if unsafe { previous.get(x, y) != self.get(x, y) } {
+                    changes.push((x, y));
+                }
             }
         }
+    }
 
-        Grid {
-            cells: unsafe { MaybeUninit::array_assume_init(result) },
+    /// Counts, for each cell, in how many of the first `steps` generations of this grid it is alive,
+    /// starting with this one. [`Grid::render_heatmap`] shows where a pattern spends its time.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// // The middle of a blinker is always alive, and the ends of each phase every other generation.
+    /// let heat = Grid::from_text("\n.###").heatmap(10);
+    /// assert_eq!(heat.get_wrapped(2, 1).value, 10);
+    /// assert_eq!(heat.get_wrapped(1, 1).value, 5);
+    /// assert_eq!(heat.get_wrapped(2, 0).value, 5);
+    /// assert!(heat.render_heatmap().contains(" 1 │  ▒▒██▒▒        │"));
+    /// ```
+    pub fn heatmap(&self, steps: usize) -> Grid {
+        let mut heat = Grid::new(self.width, self.height);
+        let mut current = self.clone();
+        for _ in 0..steps {
+            heat += &current.clamp_scalar(0, 1);
+            current.step_bitparallel();
         }
+        heat
     }
 
-    /// Each output cell is the sum of the values of the input cell and its horizontal neighbours.
-    fn hcount(&self) -> Self {
-        let mut result: [MaybeUninit<Cell>; (SIZE * SIZE) as usize] = MaybeUninit::uninit_array();
+    /// The cells alive in any of the first `steps` generations of this grid, starting with this one, as a mask.
+    /// Each generation's own parent lies within this, so as a mask for [`Searcher::with_mask`],
+    /// searching back from a later generation still finds the history the pattern actually had.
+    pub fn envelope(&self, steps: usize) -> Grid {
+        self.heatmap(steps).clamp_scalar(0, 1)
+    }
 
-        for x in 0..SIZE {
-            for y in 0..SIZE {
-                result[(x + y * SIZE) as usize].write(
-                    self.get_wrapped(x - 1, y)
-                        + self.get_wrapped(x, y)
-                        + self.get_wrapped(x + 1, y),
-                );
+    /// Follows this grid's forward evolution until it cycles, using Brent's algorithm,
+    /// and reports how long it takes to settle down, its period, and its population along the way.
+    /// See [`evolution::report`] for how `max_steps` limits the search.
+    ///
+    /// ```
+    /// use undeath::Grid;
+    ///
+    /// let blinker = Grid::from_text("\n.###");
+    /// let report = blinker.evolution_report(100);
+    /// assert_eq!((report.transient_len, report.period), (Some(0), Some(2)));
+    /// assert_eq!((report.min_population, report.max_population), (3, 3));
+    /// assert_eq!(report.died_at, None);
+    /// ```
+    pub fn evolution_report(&self, max_steps: usize) -> EvolutionReport {
+        evolution::report(self, max_steps, CycleMemory::Constant)
+    }
+
+    /// Like [`Grid::evolution_report`], but remembering every generation in a hash map.
+    /// This uses memory for each generation, but steps through each one only once.
+    pub fn evolution_report_hashed(&self, max_steps: usize) -> EvolutionReport {
+        evolution::report(self, max_steps, CycleMemory::Hashed)
+    }
+
+    /// Compute the next step of the simulation, like [`Grid::step`],
+    /// but operating on one machine word per row instead of one integer per cell.
+    /// Each row is packed into the low `width` bits of a word, the eight neighbours of every cell in the row
+    /// are summed at once with a bit-sliced counter, and the result is unpacked again.
+    /// Grids wider than 64 cells do not fit in a word, and are stepped with [`Grid::step`].
+    pub fn step_bitparallel(&mut self) {
+        if self.width > 64 {
+            return self.step();
+        }
+        let mask = u64::MAX >> (64 - self.width);
+        let (width, height) = (self.width as usize, self.height as usize);
+
+        let mut rows = vec![0u64; height];
+        for (row, cells) in rows.iter_mut().zip(self.cells.chunks_exact(width)) {
+            for (x, cell) in cells.iter().enumerate() {
+                *row |= ((cell.value > 0) as u64) << x;
+            }
+        }
+
+        // Bit `x` of the result is the value of cell `x - 1` (or `x + 1`), wrapping around the torus.
+        // On a plane, nothing wraps, and the rows past the top and bottom are dead.
+        let wrap = if self.topology == Topology::Torus {
+            u64::MAX
+        } else {
+            0
+        };
+        let from_left = |row: u64| ((row << 1) | (row >> (width - 1) & wrap)) & mask;
+        let from_right = |row: u64| (row >> 1) | ((row & wrap & 1) << (width - 1));
+
+        let mut next_rows = vec![0u64; height];
+        for y in 0..height {
+            let above = if y == 0 {
+                rows[height - 1] & wrap
+            } else {
+                rows[y - 1]
+            };
+            let current = rows[y];
+            let below = if y == height - 1 {
+                rows[0] & wrap
+            } else {
+                rows[y + 1]
+            };
+
+            // Count the neighbours of each cell in three bit planes, modulo eight.
+            // A cell with eight neighbours then has a count of zero, which correctly kills it.
+            let (mut ones, mut twos, mut fours) = (0u64, 0u64, 0u64);
+            for neighbour in [
+                from_left(above),
+                above,
+                from_right(above),
+                from_left(current),
+                from_right(current),
+                from_left(below),
+                below,
+                from_right(below),
+            ] {
+                let carry_ones = ones & neighbour;
+                ones ^= neighbour;
+                let carry_twos = twos & carry_ones;
+                twos ^= carry_ones;
+                fours ^= carry_twos;
+            }
+
+            // Alive next frame with exactly three neighbours, or with two neighbours if alive now.
+            next_rows[y] = twos & !fours & (ones | current);
+        }
+
+        for (row, cells) in next_rows.iter().zip(self.cells.chunks_exact_mut(width)) {
+            for (x, cell) in cells.iter_mut().enumerate() {
+                cell.value = ((row >> x) & 1) as i32;
             }
         }
+    }
+}
+
+/// A row of dead cells as long as any grid's, for the rows past the edges of a plane in [`Grid::neighbours_into`].
+static DEAD_ROW: [Cell; MAX_SIZE as usize] = [Cell { value: 0 }; MAX_SIZE as usize];
+
+/// The most bytes a rendered frame of a `width`×`height` grid can take, when every cell is drawn with a three-byte character.
+fn render_capacity(width: i32, height: i32) -> usize {
+    let (width, height) = (width as usize, height as usize);
+    // Two rows of column numbers, then the top border, the rows and the bottom border,
+    // each with a margin for the row numbers.
+    (height + 4) * (6 * width + 16)
+}
+
+/// Appends a bordered, numbered frame of a `width`×`height` grid to `out`, drawing each cell as the two columns given by `cell`.
+/// Each column is numbered by its last digit, and grids wider than ten cells have the tens above, at every tenth column.
+/// This is shared by [`Grid::render_into`] and [`crate::Guess::render_into`].
+pub(crate) fn render_frame_into(
+    out: &mut String,
+    width: i32,
+    height: i32,
+    cell: impl Fn(i32, i32) -> &'static str,
+) {
+    out.reserve(render_capacity(width, height));
+    // The row numbers take two columns, or more for taller grids.
+    let margin = (height - 1).to_string().len().max(2);
+    // Writing to a `String` never fails.
+    if width > 10 {
+        write!(out, "{:margin$}  ", "").unwrap();
+        for x in (0..width).step_by(10) {
+            let label = if x == 0 { String::new() } else { x.to_string() };
+            let columns = 2 * (width - x).min(10) as usize;
+            write!(out, "{label:columns$}").unwrap();
+        }
+        out.push_str(" \n");
+    }
+    write!(out, "{:margin$}  ", "").unwrap();
+    for x in 0..width {
+        write!(out, "{:2}", x % 10).unwrap();
+    }
+    write!(out, " \n{:margin$} ┌", "").unwrap();
+    out.extend(std::iter::repeat_n('─', 2 * width as usize));
+    out.push_str("┐\n");
+    for y in 0..height {
+        write!(out, "{y:margin$} │").unwrap();
+        for x in 0..width {
+            out.push_str(cell(x, y));
+        }
+        out.push_str("│\n");
+    }
+    write!(out, "{:margin$} └", "").unwrap();
+    out.extend(std::iter::repeat_n('─', 2 * width as usize));
+    out.push('┘');
+}
+
+/// An object on a grid: a group of alive cells connected through their eight neighbours,
+/// found by [`Grid::components`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// The cells of the component, in row-major order.
+    pub cells: Vec<(i32, i32)>,
+    /// The smallest rectangle containing every cell, as `(x, y, width, height)`, with `x` and `y` on the grid.
+    /// The rectangle runs past the right and bottom edges and wraps around
+    /// when that makes it smaller, as it does for a component straddling the seam of the torus.
+    pub bounding_box: (i32, i32, i32, i32),
+}
+
+impl Component {
+    /// The number of cells in the component.
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+/// A summary of a grid's alive cells, from [`Grid::stats`].
+/// It displays as a single line.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct GridStats {
+    /// The number of alive cells.
+    pub population: usize,
+    /// The fraction of cells that are alive.
+    pub density: f64,
+    /// The mean position of the alive cells, taken around the torus on each axis.
+    ///
+    /// An ordinary mean depends on where the torus is cut: a block straddling the seam at `x = 0`
+    /// has cells at `x = width - 1` and `x = 0`, whose ordinary mean is in the middle of the grid.
+    /// Instead, each coordinate is placed at an angle around a circle, the mean of those points is taken,
+    /// and its angle is turned back into a coordinate, which for that block is `width - 0.5`.
+    /// This is `None` if there are no alive cells, or if they are balanced all the way round an axis,
+    /// like an unbroken row, so that the mean of the points is the centre of the circle and has no angle.
+    pub centroid: Option<(f64, f64)>,
+    /// The radius of gyration: the root mean square distance of the alive cells from the centroid,
+    /// measured to the nearest copy of each cell around the torus.
+    /// This is `None` when there is no centroid.
+    pub spread: Option<f64>,
+    /// The width and height of the [`Grid::bounding_box`], or zero if there are no alive cells.
+    pub bounding_box: (i32, i32),
+    /// The number of [`Grid::components`].
+    pub components: usize,
+}
+
+impl std::fmt::Display for GridStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} alive ({:.1}%), ",
+            self.population,
+            self.density * 100.0
+        )?;
+        match (self.centroid, self.spread) {
+            (Some((x, y)), Some(spread)) => {
+                write!(f, "centred at ({x:.2}, {y:.2}), spread {spread:.2}, ")?
+            }
+            _ => write!(f, "no centre, ")?,
+        }
+        let (width, height) = self.bounding_box;
+        write!(
+            f,
+            "in a {width}×{height} box, {} component{}",
+            self.components,
+            if self.components == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// The circular mean of `coordinates` around an axis of the torus `size` cells long, or `None` if there are none
+/// or they balance out. See [`GridStats::centroid`].
+fn circular_mean(coordinates: impl Iterator<Item = i32>, size: i32) -> Option<f64> {
+    let size = size as f64;
+    let (mut cos, mut sin, mut count) = (0.0, 0.0, 0);
+    for coordinate in coordinates {
+        let angle = coordinate as f64 * std::f64::consts::TAU / size;
+        cos += angle.cos();
+        sin += angle.sin();
+        count += 1;
+    }
+    if count == 0 || cos.hypot(sin) < 1e-9 * count as f64 {
+        return None;
+    }
+    let mean = (sin.atan2(cos) * size / std::f64::consts::TAU).rem_euclid(size);
+    // A mean just below zero can round up to `size` itself.
+    Some(if mean < size { mean } else { 0.0 })
+}
 
-        Grid {
-            cells: unsafe { MaybeUninit::array_assume_init(result) },
+/// A rotation or reflection of the grid, from [`Grid::symmetries`] and for [`Grid::transformed`].
+/// Rotations are clockwise, with `y` counting downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    QuarterTurn,
+    HalfTurn,
+    ThreeQuarterTurn,
+    /// Swapping left and right.
+    FlipLeftRight,
+    /// Swapping top and bottom.
+    FlipTopBottom,
+    /// Reflecting in the diagonal from the top left to the bottom right, swapping `x` and `y`.
+    FlipDiagonal,
+    /// Reflecting in the diagonal from the top right to the bottom left.
+    FlipAntiDiagonal,
+}
+
+impl Symmetry {
+    /// Every symmetry of the square, starting with [`Symmetry::Identity`].
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::QuarterTurn,
+        Symmetry::HalfTurn,
+        Symmetry::ThreeQuarterTurn,
+        Symmetry::FlipLeftRight,
+        Symmetry::FlipTopBottom,
+        Symmetry::FlipDiagonal,
+        Symmetry::FlipAntiDiagonal,
+    ];
+
+    /// Whether the symmetry turns rows into columns, so that it swaps the width and height of a grid.
+    fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Symmetry::QuarterTurn
+                | Symmetry::ThreeQuarterTurn
+                | Symmetry::FlipDiagonal
+                | Symmetry::FlipAntiDiagonal
+        )
+    }
+
+    /// Where the cell at `(x, y)` of a `width`×`height` grid moves to, on the transformed grid.
+    fn apply(self, x: i32, y: i32, width: i32, height: i32) -> (i32, i32) {
+        let (last_x, last_y) = (width - 1, height - 1);
+        match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::QuarterTurn => (last_y - y, x),
+            Symmetry::HalfTurn => (last_x - x, last_y - y),
+            Symmetry::ThreeQuarterTurn => (y, last_x - x),
+            Symmetry::FlipLeftRight => (last_x - x, y),
+            Symmetry::FlipTopBottom => (x, last_y - y),
+            Symmetry::FlipDiagonal => (y, x),
+            Symmetry::FlipAntiDiagonal => (last_y - y, last_x - x),
+        }
+    }
+}
+
+impl std::fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Symmetry::Identity => "identity",
+            Symmetry::QuarterTurn => "quarter turn",
+            Symmetry::HalfTurn => "half turn",
+            Symmetry::ThreeQuarterTurn => "three-quarter turn",
+            Symmetry::FlipLeftRight => "left-right flip",
+            Symmetry::FlipTopBottom => "top-bottom flip",
+            Symmetry::FlipDiagonal => "diagonal flip",
+            Symmetry::FlipAntiDiagonal => "anti-diagonal flip",
+        })
+    }
+}
+
+/// The text formats [`Grid::from_file`] reads a row per line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextFormat {
+    /// The permissive `.gol` format, where anything but a space or `.` is alive.
+    Gol,
+    /// LifeWiki's plaintext format, with `!` comments, `.` and `O`.
+    Cells,
+}
+
+/// The rows of a pattern in one of the [`TextFormat`]s, gathered a line at a time.
+/// [`str::parse`], [`Grid::from_file`] and the other text readers all go through this, so they agree on every pattern.
+struct TextRows {
+    format: TextFormat,
+    /// Whether rows and columns past [`MAX_SIZE`] wrap around, rather than being an error.
+    wrap: bool,
+    alive: Vec<(usize, usize)>,
+    width: usize,
+    /// The number of rows so far, which is also the index of the next one.
+    rows: usize,
+    /// The number of lines so far, counting comments as well as rows.
+    lines: usize,
+    /// The exact width and height, and the topology, if the first line is a header such as `!size 5x5 plane`.
+    header: Option<(usize, usize, Topology)>,
+    /// The first line longer than [`MAX_SIZE`], and how long it is, if not wrapping.
+    too_long: Option<(usize, usize)>,
+}
+
+impl TextRows {
+    fn new(format: TextFormat, wrap: bool) -> Self {
+        Self {
+            format,
+            wrap,
+            alive: Vec::new(),
+            width: 0,
+            rows: 0,
+            lines: 0,
+            header: None,
+            too_long: None,
+        }
+    }
+
+    /// Reads the next line, with or without its line ending.
+    fn push(&mut self, line: &str) -> Result<(), ParseGridError> {
+        self.lines += 1;
+        if self.lines == 1 {
+            if let Some(fields) = line.trim_end().strip_prefix(SIZE_HEADER) {
+                self.header = Some(parse_size_header(fields)?);
+                return Ok(());
+            }
+        }
+        let line = match self.format {
+            TextFormat::Gol => line.trim_end_matches(['\n', '\r']),
+            TextFormat::Cells if line.starts_with('!') => return Ok(()),
+            TextFormat::Cells => line.trim_end(),
+        };
+        let y = self.rows;
+        self.rows += 1;
+        if !self.wrap {
+            let length = line.chars().count();
+            if length > MAX_SIZE as usize {
+                self.too_long.get_or_insert((self.lines, length));
+            }
+            if y >= MAX_SIZE as usize || self.too_long.is_some() {
+                // Measured so that the error can say how large the whole pattern is, but otherwise ignored.
+                self.width = self.width.max(length);
+                return Ok(());
+            }
+        }
+        for (x, char) in line.chars().enumerate() {
+            self.width = self.width.max(x + 1);
+            let is_alive = match (self.format, char) {
+                (TextFormat::Gol, _) => !" .".contains(char),
+                (TextFormat::Cells, '.') => false,
+                (TextFormat::Cells, 'O') => true,
+                (TextFormat::Cells, _) => {
+                    return Err(ParseGridError::UnexpectedCharacter {
+                        line: self.lines,
+                        column: x + 1,
+                        character: char,
+                    })
+                }
+            };
+            if is_alive {
+                if let Some((width, height, _)) = self.header {
+                    if !self.wrap && (x >= width || y >= height) {
+                        return Err(ParseGridError::OutsideHeader {
+                            line: self.lines,
+                            column: x + 1,
+                            width,
+                            height,
+                        });
+                    }
+                }
+                self.alive.push((x, y));
+            }
         }
+        Ok(())
+    }
+
+    /// The grid of the lines read so far.
+    fn finish(self) -> Result<Grid, ParseGridError> {
+        if !self.wrap {
+            if self.rows == 0 && self.header.is_none() {
+                return Err(ParseGridError::Empty);
+            }
+            let pattern = (self.width, self.rows);
+            if let Some((line, length)) = self.too_long {
+                return Err(ParseGridError::LineTooLong {
+                    line,
+                    length,
+                    pattern,
+                });
+            }
+            if self.rows > MAX_SIZE as usize {
+                return Err(ParseGridError::TooManyRows {
+                    rows: self.rows,
+                    pattern,
+                });
+            }
+        }
+        let (width, height, topology) = match self.header {
+            Some(header) => header,
+            None if self.width <= SIZE as usize && self.rows <= SIZE as usize => {
+                (SIZE as usize, SIZE as usize, Topology::Torus)
+            }
+            None => {
+                let side = |length: usize| length.clamp(MIN_SIZE as usize, MAX_SIZE as usize);
+                (side(self.width), side(self.rows), Topology::Torus)
+            }
+        };
+        let mut result = Grid::new(width as i32, height as i32).with_topology(topology);
+        for (x, y) in self.alive {
+            // Rows and columns past the largest grid wrap around.
+            // Reducing them before converting to `i32` means that even absurdly long lines cannot overflow,
+            // and leaves the coordinates in `0..width` and `0..height`.
+            let (x, y) = (x % width, y % height);
+            unsafe { result.set(x as i32, y as i32, Cell::one()) };
+        }
+        Ok(result)
+    }
+}
+
+/// The start of the line that gives the exact size of a grid in the text formats, as [`Grid::to_gol_string`] writes it.
+const SIZE_HEADER: &str = "!size ";
+
+/// Reads the rest of a `!size` line, such as `5x5` or `20x6 plane`.
+fn parse_size_header(fields: &str) -> Result<(usize, usize, Topology), ParseGridError> {
+    let invalid = || ParseGridError::InvalidHeader {
+        header: format!("{SIZE_HEADER}{fields}"),
+    };
+    let mut fields = fields.split_whitespace();
+    let (width, height) = fields
+        .next()
+        .and_then(|size| size.split_once('x'))
+        .ok_or_else(invalid)?;
+    let side = |side: &str| {
+        side.parse::<usize>()
+            .ok()
+            .filter(|side| (MIN_SIZE as usize..=MAX_SIZE as usize).contains(side))
+            .ok_or_else(invalid)
+    };
+    let topology = match fields.next() {
+        None | Some("torus") => Topology::Torus,
+        Some("plane") => Topology::Plane,
+        Some(_) => return Err(invalid()),
+    };
+    if fields.next().is_some() {
+        return Err(invalid());
+    }
+    Ok((side(width)?, side(height)?, topology))
+}
+
+/// Why a pattern in one of the text formats could not be read onto a [`Grid`],
+/// by [`str::parse`], [`Grid::from_cells`], [`Grid::from_bytes`] or [`Grid::from_file`],
+/// or why rows of a grid could not be deserialised with the `serde` feature.
+/// Lines and columns are counted from one, and each serialised row is a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseGridError {
+    /// There were no rows at all.
+    Empty,
+    /// This line, the first too long, has more cells than the widest grid, [`MAX_SIZE`].
+    /// `pattern` is the width and height of the whole pattern: its longest line and its number of rows.
+    LineTooLong {
+        line: usize,
+        length: usize,
+        pattern: (usize, usize),
+    },
+    /// There are more rows than the tallest grid, [`MAX_SIZE`]. `pattern` is as for [`ParseGridError::LineTooLong`].
+    TooManyRows {
+        rows: usize,
+        pattern: (usize, usize),
+    },
+    /// A `.cells` pattern has a character other than `.` and `O` outside its comments,
+    /// or a serialised row one other than `.` and `#`.
+    UnexpectedCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    /// A line is not valid UTF-8, from the character at this column on.
+    /// Only bytes, as read by [`Grid::from_bytes`] and [`Grid::from_file`], can be invalid.
+    InvalidUtf8 { line: usize, column: usize },
+    /// A serialised row is not as long as the first, which is `width` cells long.
+    UnevenRow {
+        line: usize,
+        length: usize,
+        width: usize,
+    },
+    /// The serialised rows make a grid narrower or shorter than the smallest, [`MIN_SIZE`].
+    TooSmall { width: usize, height: usize },
+    /// The first line starts with `!size ` but is not a size from [`MIN_SIZE`] to [`MAX_SIZE`] on each side,
+    /// such as `!size 5x5`, optionally followed by `plane` or `torus`.
+    InvalidHeader { header: String },
+    /// An alive cell lies outside the `width`×`height` that the `!size` line gives.
+    OutsideHeader {
+        line: usize,
+        column: usize,
+        width: usize,
+        height: usize,
+    },
+}
+
+impl std::fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseGridError::Empty => write!(f, "the pattern has no rows"),
+            ParseGridError::LineTooLong {
+                line,
+                length,
+                pattern: (width, height),
+            } => write!(
+                f,
+                "the pattern is {width}×{height}, which does not fit on the largest grid, {MAX_SIZE}×{MAX_SIZE}: \
+                 line {line} is {length} cells long"
+            ),
+            ParseGridError::TooManyRows {
+                rows,
+                pattern: (width, height),
+            } => write!(
+                f,
+                "the pattern is {width}×{height}, which does not fit on the largest grid, {MAX_SIZE}×{MAX_SIZE}: \
+                 it has {rows} rows"
+            ),
+            ParseGridError::UnexpectedCharacter {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "unexpected `{character}` at line {line}, column {column}; {CELL_CHARACTERS}"
+            ),
+            ParseGridError::InvalidUtf8 { line, column } => {
+                write!(f, "invalid UTF-8 at line {line}, column {column}")
+            }
+            ParseGridError::UnevenRow {
+                line,
+                length,
+                width,
+            } => write!(
+                f,
+                "line {line} is {length} cells long, but the first is {width}"
+            ),
+            ParseGridError::TooSmall { width, height } => write!(
+                f,
+                "the pattern is {width}×{height}, but the smallest grid is {MIN_SIZE}×{MIN_SIZE}"
+            ),
+            ParseGridError::InvalidHeader { header } => write!(
+                f,
+                "`{header}` on line 1 is not a size such as `!size 5x5` or `!size 20x6 plane`, \
+                 from {MIN_SIZE} to {MAX_SIZE} on each side"
+            ),
+            ParseGridError::OutsideHeader {
+                line,
+                column,
+                width,
+                height,
+            } => write!(
+                f,
+                "the alive cell at line {line}, column {column} is outside the {width}×{height} grid of the `!size` line"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseGridError {}
+
+/// The characters that [`ParseGridError::UnexpectedCharacter`] allows, in each format that checks them.
+const CELL_CHARACTERS: &str =
+    "a .cells pattern has only `.` and `O`, and a serialised grid only `.` and `#`";
+
+/// Errors with a position in the text are parse errors, and the others invalid input.
+impl From<ParseGridError> for Error {
+    fn from(error: ParseGridError) -> Self {
+        match error {
+            ParseGridError::LineTooLong {
+                line,
+                length,
+                pattern: (width, height),
+            } => Error::Parse {
+                line,
+                column: MAX_SIZE as usize + 1,
+                message: format!(
+                    "the pattern is {width}×{height}, which does not fit on the largest grid, {MAX_SIZE}×{MAX_SIZE}: \
+                     the line is {length} cells long"
+                ),
+            },
+            ParseGridError::UnexpectedCharacter {
+                line,
+                column,
+                character,
+            } => Error::Parse {
+                line,
+                column,
+                message: format!("unexpected `{character}`; {CELL_CHARACTERS}"),
+            },
+            ParseGridError::UnevenRow {
+                line,
+                length,
+                width,
+            } => Error::Parse {
+                line,
+                column: length.min(width) + 1,
+                message: format!("the line is {length} cells long, but the first is {width}"),
+            },
+            ParseGridError::InvalidHeader { header } => Error::Parse {
+                line: 1,
+                column: 1,
+                message: format!("`{header}` is not a size such as `!size 5x5`"),
+            },
+            ParseGridError::OutsideHeader {
+                line,
+                column,
+                width,
+                height,
+            } => Error::Parse {
+                line,
+                column,
+                message: format!(
+                    "the alive cell is outside the {width}×{height} grid of the `!size` line"
+                ),
+            },
+            ParseGridError::InvalidUtf8 { line, column } => Error::Parse {
+                line,
+                column,
+                message: "invalid UTF-8".to_owned(),
+            },
+            error => Error::InvalidInput(error.to_string()),
+        }
+    }
+}
+
+/// Why [`Grid::from_file`] could not read a pattern.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LoadError {
+    /// The file could not be opened or read.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A text file was read, but is not a pattern that fits on a grid.
+    Parse {
+        path: PathBuf,
+        source: ParseGridError,
+    },
+    /// An `.rle` file was read, but is not a pattern that fits on a grid.
+    Rle { path: PathBuf, source: RleError },
+    /// The pattern, `pattern` wide and tall, would not fit on the `grid` wide and tall grid
+    /// with `margin` dead cells on every side, as [`Grid::from_file_with_margin`] asked.
+    NoRoom {
+        path: PathBuf,
+        pattern: (i32, i32),
+        margin: u32,
+        grid: (i32, i32),
+    },
+}
+
+impl LoadError {
+    fn io(path: &Path, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.to_owned(),
+            source,
+        }
+    }
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io { path, source } => {
+                write!(f, "could not access {}: {source}", path.display())
+            }
+            LoadError::Parse { path, source } => {
+                write!(f, "could not read {}: {source}", path.display())
+            }
+            LoadError::Rle { path, source } => {
+                write!(f, "could not read {}: {source}", path.display())
+            }
+            LoadError::NoRoom {
+                path,
+                pattern: (width, height),
+                margin,
+                grid: (grid_width, grid_height),
+            } => write!(
+                f,
+                "the {width}×{height} pattern in {} needs {}×{} with a margin of {margin}, \
+                 more than its {grid_width}×{grid_height} grid",
+                path.display(),
+                i64::from(*width) + 2 * i64::from(*margin),
+                i64::from(*height) + 2 * i64::from(*margin)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io { source, .. } => Some(source),
+            LoadError::Parse { source, .. } => Some(source),
+            LoadError::Rle { source, .. } => Some(source),
+            LoadError::NoRoom { .. } => None,
+        }
+    }
+}
+
+/// Each kind of failure becomes the [`Error`] it would be on its own, dropping the path of a parse error,
+/// and a pattern with no room for its margin is invalid input.
+impl From<LoadError> for Error {
+    fn from(error: LoadError) -> Self {
+        match error {
+            LoadError::Io { path, source } => Error::Io { path, source },
+            LoadError::Parse { source, .. } => source.into(),
+            LoadError::Rle { source, .. } => source.into(),
+            error @ LoadError::NoRoom { .. } => Error::InvalidInput(error.to_string()),
+        }
+    }
+}
+
+/// Where [`Grid::reembed`] places a pattern's bounding box within the new rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+    /// At the top left corner of the rectangle.
+    #[default]
+    TopLeft,
+    /// In the middle of the rectangle, rounding up and to the left.
+    Center,
+    /// With its top left corner at this position, which may be outside the rectangle.
+    Offset(i32, i32),
+}
+
+/// Why [`Grid::reembed`] could not move a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReembedError {
+    /// The rectangle is empty, or larger than the grid.
+    InvalidSize { width: i32, height: i32 },
+    /// This many alive cells would fall outside the rectangle.
+    CutOff { cells: usize },
+}
+
+impl std::fmt::Display for ReembedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReembedError::InvalidSize { width, height } => write!(
+                f,
+                "cannot embed into {width}×{height}: each side must be between 1 and that side of the grid"
+            ),
+            ReembedError::CutOff { cells } => {
+                write!(f, "{cells} alive cells would be cut off")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReembedError {}
+
+/// The smallest rectangle containing every one of `cells`, as in [`Component::bounding_box`],
+/// or `None` if there are no cells.
+fn bounding_box(
+    cells: &[(i32, i32)],
+    grid_width: i32,
+    grid_height: i32,
+) -> Option<(i32, i32, i32, i32)> {
+    if cells.is_empty() {
+        return None;
+    }
+    let (x, width) = covering_arc(cells.iter().map(|&(x, _)| x), grid_width);
+    let (y, height) = covering_arc(cells.iter().map(|&(_, y)| y), grid_height);
+    Some((x, y, width, height))
+}
+
+/// The shortest run of coordinates in `0..size`, wrapping around, that contains every one of `coordinates`,
+/// as its start and length. Ties go to the smallest start.
+fn covering_arc(coordinates: impl Iterator<Item = i32>, size: i32) -> (i32, i32) {
+    let mut occupied = vec![false; size as usize];
+    for coordinate in coordinates {
+        occupied[coordinate as usize] = true;
+    }
+    // The run starts just after the longest gap, and covers everything else.
+    let mut best = (0, size);
+    for start in 0..size {
+        if !occupied[start as usize] {
+            continue;
+        }
+        let gap = (1..size)
+            .take_while(|back| !occupied[(start - back).rem_euclid(size) as usize])
+            .count() as i32;
+        if size - gap < best.1 {
+            best = (start, size - gap);
+        }
+    }
+    best
+}
+
+/// Ranks every rotation of the strings of `len` ranks laid end to end in `ranks` against each other,
+/// so that the rotation of string `i` starting at `j` gets the rank at `j + i * len`.
+/// Rotations that read the same share a rank. Each round doubles the length compared, as in a suffix array,
+/// and takes linear time, so there are `O(log len)` rounds of `O(ranks.len())` each.
+fn rotation_ranks(mut ranks: Vec<u32>, len: usize) -> Vec<u32> {
+    let mut compared = 1;
+    // Rotations that agree on their first `len` ranks agree on all of them.
+    while compared < len
+        && ranks
+            .iter()
+            .max()
+            .is_some_and(|&most| most as usize + 1 < ranks.len())
+    {
+        let later: Vec<u32> = (0..ranks.len())
+            .map(|i| {
+                let (string, start) = (i - i % len, i % len);
+                ranks[string + (start + compared) % len]
+            })
+            .collect();
+        // Every rank is less than the number of ranks, so two passes of a counting sort order the pairs.
+        let order = counting_sort(counting_sort(0..ranks.len(), &later), &ranks);
+        let mut paired = vec![0; ranks.len()];
+        for pair in order.windows(2) {
+            let differs = (ranks[pair[1]], later[pair[1]]) != (ranks[pair[0]], later[pair[0]]);
+            paired[pair[1]] = paired[pair[0]] + differs as u32;
+        }
+        ranks = paired;
+        compared *= 2;
+    }
+    ranks
+}
+
+/// The indices `order` lists, stably sorted by their `keys`, each of which must be less than `keys.len()`.
+fn counting_sort(order: impl IntoIterator<Item = usize>, keys: &[u32]) -> Vec<usize> {
+    let mut starts = vec![0; keys.len() + 1];
+    for &key in keys {
+        starts[key as usize + 1] += 1;
+    }
+    for i in 1..starts.len() {
+        starts[i] += starts[i - 1];
+    }
+    let mut sorted = vec![0; keys.len()];
+    for i in order {
+        let start = &mut starts[keys[i] as usize];
+        sorted[*start] = i;
+        *start += 1;
+    }
+    sorted
+}
+
+/// How many distinct keys are less than each of `keys`.
+fn dense_ranks<T: Ord>(keys: &[T]) -> Vec<u32> {
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    order.sort_unstable_by_key(|&i| &keys[i]);
+    let mut ranks = vec![0; keys.len()];
+    let mut rank = 0;
+    for pair in order.windows(2) {
+        if keys[pair[1]] != keys[pair[0]] {
+            rank += 1;
+        }
+        ranks[pair[1]] = rank;
+    }
+    ranks
+}
+
+/// The eight neighbours of `(x, y)` on a `width`×`height` grid, in the order of [`Grid::neighbour_positions`].
+#[inline]
+fn moore_positions(x: i32, y: i32, width: i32, height: i32) -> [(i32, i32); 8] {
+    let left = (x + width - 1) % width;
+    let right = (x + 1) % width;
+    let above = (y + height - 1) % height;
+    let below = (y + 1) % height;
+    [
+        (left, above),
+        (left, y),
+        (left, below),
+        (x, above),
+        (x, below),
+        (right, above),
+        (right, y),
+        (right, below),
+    ]
+}
+
+/// An iterator over the positive cells of a [`Grid`], returned by [`Grid::alive_cells`].
+/// Each row is read 64 cells at a time.
+pub struct AliveCells<'a> {
+    cells: &'a [Cell],
+    width: i32,
+    height: i32,
+    /// The column that `bits` starts at.
+    x: i32,
+    /// The row that `bits` was taken from.
+    y: i32,
+    /// A mask of the positive cells in the current run of the row that have not yet been yielded.
+    bits: u64,
+}
+
+impl Iterator for AliveCells<'_> {
+    type Item = (i32, i32);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.bits == 0 {
+            if self.x + 64 < self.width {
+                self.x += 64;
+            } else if self.y + 1 < self.height {
+                self.x = 0;
+                self.y += 1;
+            } else {
+                return None;
+            }
+            let start = (self.x + self.y * self.width) as usize;
+            let end = start + (self.width - self.x).min(64) as usize;
+            self.bits = self.cells[start..end]
+                .iter()
+                .enumerate()
+                .fold(0, |bits, (x, cell)| bits | ((cell.value > 0) as u64) << x);
+        }
+        let x = self.x + self.bits.trailing_zeros() as i32;
+        // Clear the lowest set bit.
+        self.bits &= self.bits - 1;
+        Some((x, self.y))
+    }
+}
+
+/// A grid together with its neighbour counts, which are kept up to date as single cells change.
+/// This avoids recomputing [`Grid::neighbours`] from scratch after every edit.
+///
+/// ```
+/// use undeath::grid::NeighbourField;
+/// use undeath::Grid;
+///
+/// let mut field = NeighbourField::new(Grid::default());
+/// for (x, y) in [(1, 1), (2, 1), (3, 1), (2, 1), (7, 0)] {
+///     field.toggle(x, y);
+/// }
+/// assert!(*field.counts() == field.grid().neighbours());
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct NeighbourField {
+    grid: Grid,
+    counts: Grid,
+}
+
+impl NeighbourField {
+    pub fn new(grid: Grid) -> Self {
+        let counts = grid.neighbours();
+        Self { grid, counts }
+    }
+
+    /// Recomputes the neighbour counts from scratch.
+    pub fn rebuild(&mut self) {
+        self.grid.neighbours_into(&mut self.counts);
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// The amount of neighbours of each cell of [`NeighbourField::grid`].
+    pub fn counts(&self) -> &Grid {
+        &self.counts
+    }
+
+    pub fn into_grid(self) -> Grid {
+        self.grid
+    }
+
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`, for the size of the grid.
+    #[inline]
+    pub fn get(&self, x: i32, y: i32) -> Cell {
+        self.grid.get_wrapped(x, y)
+    }
+
+    /// Sets a cell, adjusting the counts of its eight neighbours by the change in its value.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`, for the size of the grid.
+    pub fn set(&mut self, x: i32, y: i32, cell: Cell) {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let x = (x + width) % width;
+        let y = (y + height) % height;
+        // Both coordinates have been wrapped onto the grid, as have the neighbour positions.
+        unsafe {
+            let delta = cell - self.grid.get(x, y);
+            self.grid.set(x, y, cell);
+            for (nx, ny) in self.grid.neighbour_positions(x, y) {
+                self.counts.set_add(nx, ny, delta);
+            }
+        }
+        // O(width · height).
+        checked_assert!(
+            self.counts == self.grid.neighbours(),
+            "neighbour counts drifted after setting ({x}, {y})"
+        );
+    }
+
+    /// Flips a cell between alive and dead.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`, for the size of the grid.
+    pub fn toggle(&mut self, x: i32, y: i32) {
+        let cell = if self.get(x, y).value > 0 {
+            Cell::zero()
+        } else {
+            Cell::one()
+        };
+        self.set(x, y, cell);
     }
 }
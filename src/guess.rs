@@ -1,135 +1,1074 @@
-use crate::grid::{Cell, Grid, SIZE};
+use std::{cmp::Ordering, fmt::Display};
+
+use log::trace;
+
+#[cfg(feature = "provenance")]
+use crate::provenance::{render_trace, Provenance, TraceStep};
+use crate::{
+    checked::checked_assert,
+    grid::{render_frame_into, Grid, Neighbourhood, Topology, MAX_SIZE, MIN_SIZE, SIZE},
+    typed_grid::{BoolGrid, CountGrid},
+    Error,
+};
+
+const CONFLICT_ALIVE: &str = "cell must be alive but was already dead";
+const CONFLICT_DEAD: &str = "cell must be dead but was already alive";
+const BLOCK_FORCED: &str = "forced in every consistent assignment of its 2×2 block";
+const BLOCK_INCONSISTENT: &str = "no assignment of a 2×2 block is consistent";
+/// The rule blamed for the contradiction of a guess read by [`Guess::parse_compact`], which does not record the real one.
+const PARSED_CONTRADICTION: &str = "contradictory when encoded";
+
+/// Why [`Guess::merge`] could not combine two guesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub x: i32,
+    pub y: i32,
+    /// What went wrong at `(x, y)`: either the guesses decided it differently,
+    /// or the rule of propagation that found its constraint unsatisfiable.
+    pub reason: &'static str,
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the guesses conflict at ({}, {}): {}",
+            self.x, self.y, self.reason
+        )
+    }
+}
+
+impl std::error::Error for MergeConflict {}
 
 /// A guess for what the previous frame could look like.
+///
+/// A guess is for a grid of one size, and can only be refined against a next frame of that size:
+/// operations given a grid of another size panic.
 #[derive(Clone)]
 pub struct Guess {
     /// The grid of cells we have guessed were alive on the previous frame.
-    alive: Grid,
+    alive: BoolGrid,
     /// The grid of cells we have guessed were dead on the previous frame.
-    dead: Grid,
-    /// The minimum amount of neighbours a given cell has on the previous frame, given this guess to be correct.
-    min_neighbours: Grid,
-    /// The maximum amount of neighbours a given cell has on the previous frame, given this guess to be correct.
-    max_neighbours: Grid,
-    /// True if we know this guess leads to a logical contradiction.
-    found_contradiction: bool,
-    /// The cells that we want to try making alive.
-    try_alive: Grid,
+    dead: BoolGrid,
+    /// How many neighbours of each cell are guessed to be alive on the previous frame.
+    alive_neighbours: CountGrid,
+    /// How many neighbours of each cell are guessed to be dead on the previous frame.
+    /// The remaining `size - alive - dead` neighbours are undecided, for the size of `neighbourhood`.
+    /// The rules only ever count neighbours this way, so a neighbour that can never be alive
+    /// must be counted here from the start rather than left out of [`Guess::neighbours`].
+    dead_neighbours: CountGrid,
+    /// The decided cells that were guessed directly, rather than deduced from other cells.
+    decisions: BoolGrid,
+    /// How many cells of each row are decided. A fully decided row stays decided in every refinement of this guess.
+    decided_in_row: Vec<u16>,
+    /// If tracked, how many cells were deduced after deciding each cell, decayed as the search backtracks.
+    impact: Option<CountGrid>,
+    /// If we know this guess leads to a logical contradiction, the rule that found it.
+    contradiction: Option<&'static str>,
+    /// If we know this guess leads to a logical contradiction, the cell whose constraint could not be satisfied.
+    contradiction_cell: Option<(i32, i32)>,
     /// The cells that we want to try making alive.
-    try_dead: Grid,
+    try_alive: BoolGrid,
+    /// The cells that we want to try making dead.
+    try_dead: BoolGrid,
+    /// Which cells count as neighbours, under Life's rule.
+    neighbourhood: Neighbourhood,
+    /// What lies past the edges. On a plane, the neighbours past an edge are counted in `dead_neighbours`.
+    topology: Topology,
+    /// The reasoning since the last guess.
+    #[cfg(feature = "provenance")]
+    provenance: Provenance,
 }
 
+/// A guess with every cell undecided on a [`SIZE`]×[`SIZE`] grid, for predecessors under Life's rule.
 impl Default for Guess {
     fn default() -> Self {
-        Self {
-            alive: Default::default(),
-            dead: Default::default(),
-            min_neighbours: Default::default(),
-            max_neighbours: Grid::fill(Cell { value: 8 }),
-            found_contradiction: false,
-            try_alive: Default::default(),
-            try_dead: Default::default(),
-        }
+        Self::new(SIZE, SIZE)
     }
 }
 
 impl Guess {
-    pub fn render(&self) -> String {
-        let border = std::iter::repeat_n('─', 2 * SIZE as usize).collect::<String>();
-
-        let column_numbers = (0..SIZE).map(|i| format!("{i:2}")).collect::<String>();
-        let mut output = format!("    {column_numbers} \n   ┌{border}┐\n");
-        for y in 0..SIZE {
-            let mut row = String::new();
-            for x in 0..SIZE {
-                row += match (
-                    unsafe { self.alive.get(x, y) }.value,
-                    unsafe { self.dead.get(x, y) }.value,
-                ) {
-                    (1, _) => "██",
-                    (_, 1) => "  ",
-                    (_, _) => "▒▒",
+    /// A guess with every cell undecided on a `width`×`height` grid, for predecessors under Life's rule.
+    ///
+    /// ```
+    /// use undeath::{Grid, Guess};
+    ///
+    /// let guess = Guess::new(20, 6);
+    /// assert_eq!((guess.width(), guess.height()), (20, 6));
+    /// assert!(guess.to_string().starts_with("0 of 20×6 decided, consistent\n"));
+    /// ```
+    ///
+    /// # Panics
+    /// As [`Grid::new`] does.
+    pub fn new(width: i32, height: i32) -> Self {
+        Self::empty(Neighbourhood::Moore, width, height)
+    }
+
+    /// A guess with the cells of `alive` decided alive and the cells of `dead` decided dead,
+    /// as if each had been guessed in turn, with propagation after each.
+    /// If they contradict each other or `next`, it stops at the first contradiction, which
+    /// [`Guess::found_contradiction`] then reports.
+    ///
+    /// # Panics
+    /// If the three grids are not all the same size. [`Guess::try_from_partial`] fails instead.
+    pub fn from_partial(next: &Grid, alive: &Grid, dead: &Grid) -> Self {
+        Self::from_partial_in(Neighbourhood::Moore, next, alive, dead)
+    }
+
+    /// Like [`Guess::from_partial`], but fails with [`Error::InvalidInput`] if the three grids are not all the same size.
+    pub fn try_from_partial(next: &Grid, alive: &Grid, dead: &Grid) -> Result<Self, Error> {
+        next.check_same_size(alive)?;
+        next.check_same_size(dead)?;
+        Ok(Self::from_partial(next, alive, dead))
+    }
+
+    /// A guess with every cell undecided on a `width`×`height` torus, for predecessors under Life's rule in `neighbourhood`.
+    /// [`Guess::new`] is the one for the Moore neighbourhood.
+    ///
+    /// # Panics
+    /// As [`Grid::new`] does.
+    pub fn empty(neighbourhood: Neighbourhood, width: i32, height: i32) -> Self {
+        Self::empty_on(neighbourhood, Topology::Torus, width, height)
+    }
+
+    /// Like [`Guess::empty`], but on a grid with `topology`.
+    /// On a [`Topology::Plane`], the neighbours past the edges start out dead,
+    /// so a cell on an edge has at most five alive neighbours and one in a corner three.
+    ///
+    /// ```
+    /// use undeath::{grid::Topology, Cell, Grid, Guess};
+    ///
+    /// // A cell born in the corner of a plane has only three neighbours to be born from, so they are all alive.
+    /// let next = Grid::from_text("#").with_topology(Topology::Plane);
+    /// let guess = Guess::from_partial(&next, &Grid::default(), &Grid::from_text("#"));
+    /// assert_eq!(guess.topology(), Topology::Plane);
+    /// for (x, y) in [(1, 0), (0, 1), (1, 1)] {
+    ///     assert!(guess.alive().get_wrapped(x, y) == Cell::one());
+    /// }
+    /// // On a torus, it could be born from any three of eight.
+    /// let guess = Guess::from_partial(&Grid::from_text("#"), &Grid::default(), &Grid::from_text("#"));
+    /// assert!(guess.alive().get_wrapped(1, 1) == Cell::zero());
+    /// ```
+    ///
+    /// # Panics
+    /// As [`Grid::new`] does.
+    pub fn empty_on(
+        neighbourhood: Neighbourhood,
+        topology: Topology,
+        width: i32,
+        height: i32,
+    ) -> Self {
+        let mut guess = Self {
+            alive: BoolGrid::new(width, height),
+            dead: BoolGrid::new(width, height),
+            alive_neighbours: CountGrid::new(width, height),
+            dead_neighbours: CountGrid::new(width, height),
+            decisions: BoolGrid::new(width, height),
+            decided_in_row: vec![0; height as usize],
+            impact: None,
+            contradiction: None,
+            contradiction_cell: None,
+            try_alive: BoolGrid::new(width, height),
+            try_dead: BoolGrid::new(width, height),
+            neighbourhood,
+            topology,
+            #[cfg(feature = "provenance")]
+            provenance: Provenance::default(),
+        };
+        guess.count_outside_neighbours();
+        guess
+    }
+
+    /// Makes this the same as [`Guess::empty_on`] for `neighbourhood`, `topology`, `width` and `height`,
+    /// reusing its memory where it can. An impact that was tracked is still tracked, from zero.
+    pub(crate) fn reset(
+        &mut self,
+        neighbourhood: Neighbourhood,
+        topology: Topology,
+        width: i32,
+        height: i32,
+    ) {
+        for cells in [
+            &mut self.alive,
+            &mut self.dead,
+            &mut self.decisions,
+            &mut self.try_alive,
+            &mut self.try_dead,
+        ] {
+            cells.reset(width, height);
+        }
+        for counts in [&mut self.alive_neighbours, &mut self.dead_neighbours]
+            .into_iter()
+            .chain(self.impact.as_mut())
+        {
+            counts.reset(width, height);
+        }
+        self.decided_in_row.clear();
+        self.decided_in_row.resize(height as usize, 0);
+        self.contradiction = None;
+        self.contradiction_cell = None;
+        self.neighbourhood = neighbourhood;
+        self.topology = topology;
+        #[cfg(feature = "provenance")]
+        self.provenance.clear();
+        self.count_outside_neighbours();
+    }
+
+    /// Counts the neighbours past the edges of a plane as dead, as they always are.
+    /// The counts must not include them already.
+    fn count_outside_neighbours(&mut self) {
+        if self.topology == Topology::Torus {
+            return;
+        }
+        let (width, height) = (self.width(), self.height());
+        let edges = (0..height).flat_map(|y| {
+            let step = if y == 0 || y == height - 1 {
+                1
+            } else {
+                width - 1
+            };
+            (0..width).step_by(step as usize).map(move |x| (x, y))
+        });
+        for (x, y) in edges.collect::<Vec<_>>() {
+            let outside = self.neighbourhood.size() - self.neighbours(x, y).count() as i32;
+            self.dead_neighbours.saturating_add(x, y, outside);
+        }
+    }
+
+    /// What lies past the edges of the guess, as for the [`Grid`] it was made for.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// How many columns the guess has.
+    #[inline]
+    pub fn width(&self) -> i32 {
+        self.alive.width()
+    }
+
+    /// How many rows the guess has.
+    #[inline]
+    pub fn height(&self) -> i32 {
+        self.alive.height()
+    }
+
+    /// How many cells the guess has.
+    fn cell_count(&self) -> usize {
+        (self.width() * self.height()) as usize
+    }
+
+    /// Panics unless `next` is the size of this guess, before anything reads it unchecked.
+    #[inline]
+    #[track_caller]
+    fn assert_next_size(&self, next: &Grid) {
+        assert!(
+            (next.width(), next.height()) == (self.width(), self.height()),
+            "cannot refine a {}×{} guess against a {}×{} grid",
+            self.width(),
+            self.height(),
+            next.width(),
+            next.height()
+        );
+    }
+
+    /// Like [`Guess::from_partial`], but for predecessors under Life's rule in `neighbourhood`.
+    /// Both take the [`Topology`] of `next`.
+    pub fn from_partial_in(
+        neighbourhood: Neighbourhood,
+        next: &Grid,
+        alive: &Grid,
+        dead: &Grid,
+    ) -> Self {
+        next.assert_same_size(alive);
+        next.assert_same_size(dead);
+        let mut guess = Self::empty_on(neighbourhood, next.topology(), next.width(), next.height());
+        for y in 0..next.height() {
+            for x in 0..next.width() {
+                if alive.get_wrapped(x, y).value > 0 && !guess.found_contradiction() {
+                    guess.guess_alive(next, x, y);
+                }
+                if dead.get_wrapped(x, y).value > 0 && !guess.found_contradiction() {
+                    guess.guess_dead(next, x, y);
+                }
+            }
+        }
+        guess
+    }
+
+    /// Combines two partial guesses, such as predecessors of two separate regions found separately,
+    /// into a fresh guess that decides every cell either one decides, followed by one pass of propagation.
+    ///
+    /// Cells guessed directly in either guess count as guessed in the result, and the rest as deduced.
+    /// Only the decided cells are combined: neither guess's suggestions, impact or contradiction carry over.
+    ///
+    /// # Errors
+    /// If some cell is decided alive in one guess and dead in the other, the first such cell in row-major order.
+    /// Otherwise, if the combined cells contradict `next`, the cell where propagation found it.
+    ///
+    /// # Panics
+    /// If the guesses are for different neighbourhoods or topologies, or `next` and the guesses are not all the same size.
+    ///
+    /// ```
+    /// use undeath::{Grid, Guess};
+    ///
+    /// // A blinker's predecessor, with the top and bottom halves of the grid decided separately.
+    /// let next = Grid::from_text("\n\n\n..###");
+    /// let predecessor = Grid::from_text("\n\n...#\n...#\n...#");
+    /// let top = Grid::from_text(&"########\n".repeat(4));
+    /// let half = |mask: &Grid| {
+    ///     Guess::from_partial(&next, &predecessor.and(mask), &predecessor.not().and(mask))
+    /// };
+    /// let merged = half(&top).merge(&half(&top.not()), &next)?;
+    /// assert!(merged.alive() == predecessor);
+    /// assert_eq!(merged.decided_count(), 64);
+    ///
+    /// // A cell decided both ways.
+    /// let clash = Guess::from_partial(&next, &Grid::default(), &Grid::from_text("\n\n\n...#"));
+    /// let Err(conflict) = half(&top).merge(&clash, &next) else {
+    ///     panic!("(3, 3) is alive in the top half");
+    /// };
+    /// assert_eq!((conflict.x, conflict.y), (3, 3));
+    /// # Ok::<(), undeath::guess::MergeConflict>(())
+    /// ```
+    pub fn merge(&self, other: &Guess, next: &Grid) -> Result<Guess, MergeConflict> {
+        assert_eq!(
+            self.neighbourhood, other.neighbourhood,
+            "cannot merge guesses for different neighbourhoods"
+        );
+        assert_eq!(
+            self.topology, other.topology,
+            "cannot merge guesses for different topologies"
+        );
+        self.assert_next_size(next);
+        other.assert_next_size(next);
+        let mut clashes = self.alive.clone();
+        clashes &= &other.dead;
+        let mut reversed = self.dead.clone();
+        reversed &= &other.alive;
+        clashes |= &reversed;
+        if let Some((x, y)) = clashes.iter().next() {
+            return Err(MergeConflict {
+                x,
+                y,
+                reason: "decided alive in one guess and dead in the other",
+            });
+        }
+
+        let mut merged = Self::empty_on(
+            self.neighbourhood,
+            self.topology,
+            self.width(),
+            self.height(),
+        );
+        let mut queue = Vec::with_capacity(self.cell_count());
+        let mut consistent = Ok(());
+        'assert: for (alive, cells) in [
+            (true, &self.alive),
+            (false, &self.dead),
+            (true, &other.alive),
+            (false, &other.dead),
+        ] {
+            for (x, y) in cells.iter() {
+                consistent = if alive {
+                    merged.guess_alive_with_queue(next, x, y, &mut queue)
+                } else {
+                    merged.guess_dead_with_queue(next, x, y, &mut queue)
                 };
+                if consistent.is_err() {
+                    break 'assert;
+                }
             }
-            output += &format!("{y:2} │");
-            output += &row;
-            output.push('│');
-            output.push('\n');
         }
-        output += &format!("   └{border}┘");
+        if consistent.is_ok() {
+            let _ = merged.propagate_constraints(next, queue);
+        }
+        merged.decisions = self.decisions.clone();
+        merged.decisions |= &other.decisions;
+        match (merged.contradiction, merged.contradiction_cell) {
+            (Some(reason), Some((x, y))) => Err(MergeConflict { x, y, reason }),
+            _ => Ok(merged),
+        }
+    }
+
+    /// Which cells this guess counts as neighbours.
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+
+    /// Renders the guess to a string, with undecided cells shaded.
+    /// Dead cells in fully decided rows are dotted.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        self.render_into(&mut output);
         output
     }
 
+    /// Appends the rendering of [`Guess::render`] to `out`. See [`Grid::render_into`].
+    pub fn render_into(&self, out: &mut String) {
+        // All coordinates passed to the closure lie on the guess.
+        render_frame_into(out, self.width(), self.height(), |x, y| {
+            match unsafe {
+                (
+                    self.alive.test_unchecked(x, y),
+                    self.dead.test_unchecked(x, y),
+                )
+            } {
+                (true, _) => "██",
+                (_, true) if self.row_decided(y) => "··",
+                (_, true) => "  ",
+                (_, _) => "▒▒",
+            }
+        });
+    }
+
+    /// The cells guessed to be alive on the previous frame, with the topology of the guess.
     pub fn alive(&self) -> Grid {
-        self.alive.clone()
+        self.alive.to_grid().with_topology(self.topology)
     }
 
+    /// The cells guessed to be dead on the previous frame, with the topology of the guess.
+    /// Cells in neither [`Guess::alive`] nor [`Guess::dead`] are undecided.
     pub fn dead(&self) -> Grid {
-        self.dead.clone()
+        self.dead.to_grid().with_topology(self.topology)
+    }
+
+    pub(crate) fn try_alive(&self) -> &BoolGrid {
+        &self.try_alive
     }
 
-    pub fn try_alive(&self) -> Grid {
-        self.try_alive.clone()
+    pub(crate) fn try_dead(&self) -> &BoolGrid {
+        &self.try_dead
     }
 
-    pub fn try_dead(&self) -> Grid {
-        self.try_dead.clone()
+    /// Whether every cell in row `y` is guessed either alive or dead.
+    /// `y` must be between `0` and the height of the guess.
+    pub fn row_decided(&self, y: i32) -> bool {
+        self.decided_in_row[y as usize] as i32 == self.width()
     }
 
-    pub unsafe fn guessed_alive(&self, x: i32, y: i32) -> bool {
-        self.alive.get(x, y).value > 0
+    /// The cells that are neither guessed alive nor guessed dead, row by row.
+    /// Fully decided rows are skipped without looking at their cells.
+    pub fn unknown_cells(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let width = self.width();
+        (0..self.height())
+            .filter(|&y| !self.row_decided(y))
+            .flat_map(move |y| (0..width).map(move |x| (x, y)))
+            // Both coordinates are on the guess.
+            .filter(|&(x, y)| unsafe { !self.guessed_alive(x, y) && !self.guessed_dead(x, y) })
     }
 
-    pub unsafe fn guessed_dead(&self, x: i32, y: i32) -> bool {
-        self.dead.get(x, y).value > 0
+    /// The number of cells guessed either alive or dead.
+    pub fn decided_count(&self) -> usize {
+        self.decided_in_row
+            .iter()
+            .map(|&count| count as usize)
+            .sum()
+    }
+
+    /// The cells that were guessed directly, whether alive or dead, rather than deduced from other cells.
+    pub fn decided_cells(&self) -> Grid {
+        self.decisions.to_grid()
+    }
+
+    /// The cells that were deduced from other cells by propagation, whether alive or dead.
+    /// Every cell in [`Guess::alive`] or [`Guess::dead`] is in exactly one of this and [`Guess::decided_cells`].
+    pub fn forced_cells(&self) -> Grid {
+        let mut forced = self.alive.clone();
+        forced |= &self.dead;
+        forced -= &self.decisions;
+        forced.to_grid()
+    }
+
+    /// Renders the guess like [`Guess::render`], but distinguishing guessed cells from deduced ones:
+    /// alive cells are `██` if guessed and `▓▓` if deduced, and dead cells are `╳╳` if guessed and blank if deduced.
+    pub fn render_origins(&self) -> String {
+        let mut output = String::new();
+        // All coordinates passed to the closure lie on the guess.
+        render_frame_into(&mut output, self.width(), self.height(), |x, y| {
+            match unsafe {
+                (
+                    self.alive.test_unchecked(x, y),
+                    self.dead.test_unchecked(x, y),
+                    self.decisions.test_unchecked(x, y),
+                )
+            } {
+                (true, _, true) => "██",
+                (true, _, _) => "▓▓",
+                (_, true, true) => "╳╳",
+                (_, true, _) => "  ",
+                (_, _, _) => "▒▒",
+            }
+        });
+        output
+    }
+
+    /// Reads a guess in the compact encoding that [`Guess`]'s `Display` implementation writes,
+    /// for tests and bug reports. The `+` and `-` suggestions of the alternate form are optional.
+    ///
+    /// Only the decided cells, the suggestions and whether the guess was contradictory are encoded,
+    /// so the neighbour counts are rebuilt from the decided cells, every decided cell counts as deduced
+    /// rather than guessed, and a contradictory guess blames no particular rule or cell.
+    /// No propagation is done, so the guess is exactly as encoded.
+    /// The header gives the width and height of the guess, or for a square guess, its number of cells.
+    ///
+    /// ```
+    /// use undeath::{Grid, Guess};
+    ///
+    /// let text = "5 of 64 decided, consistent\n#.?\n\n\n\n\n\n\n..#";
+    /// let guess = Guess::parse_compact(text)?;
+    /// assert!(guess.alive() == Grid::from_text("#\n\n\n\n\n\n\n..#"));
+    /// assert_eq!(guess.decided_count(), 5);
+    /// assert!(guess.to_string().starts_with("5 of 64 decided, consistent\n#.??????\n"));
+    /// # Ok::<(), undeath::Error>(())
+    /// ```
+    pub fn parse_compact(text: &str) -> Result<Self, Error> {
+        let error = |line: usize, column: usize, message: String| Error::Parse {
+            line,
+            column,
+            message,
+        };
+        let mut lines = text.lines();
+        let header = lines.next().unwrap_or_default();
+        let (counts, state) = header.split_once(" decided, ").ok_or_else(|| {
+            error(
+                1,
+                1,
+                format!(
+                    "expected a header such as `3 of 64 decided, consistent`, but found `{header}`"
+                ),
+            )
+        })?;
+        let contradictory = match state.trim_end() {
+            "consistent" => false,
+            "contradictory" => true,
+            state => {
+                return Err(error(
+                    1,
+                    header.len() - state.len() + 1,
+                    format!("expected `consistent` or `contradictory`, but found `{state}`"),
+                ))
+            }
+        };
+        let side = |side: &str| {
+            side.parse()
+                .ok()
+                .filter(|side| (MIN_SIZE..=MAX_SIZE).contains(side))
+        };
+        let (decided, (width, height)) = counts
+            .split_once(" of ")
+            .and_then(|(decided, cells)| {
+                let size = match cells.split_once('×') {
+                    Some((width, height)) => (side(width)?, side(height)?),
+                    None => {
+                        let total = cells.parse::<i32>().ok()?;
+                        let size = (MIN_SIZE..=MAX_SIZE).find(|size| size * size >= total)?;
+                        Some((size, size)).filter(|_| size * size == total)?
+                    }
+                };
+                Some((decided.parse::<usize>().ok()?, size))
+            })
+            .ok_or_else(|| {
+                error(
+                    1,
+                    1,
+                    format!(
+                        "expected `N of W×H decided`, or `N of T decided` with `T` the number of cells in a square grid, but found `{counts}`"
+                    ),
+                )
+            })?;
+
+        let mut guess = Self::new(width, height);
+        for (y, line) in lines.enumerate() {
+            if y >= height as usize {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                return Err(error(y + 2, 1, format!("the grid has only {height} rows")));
+            }
+            for (x, c) in line.chars().enumerate() {
+                if x >= width as usize {
+                    return Err(error(
+                        y + 2,
+                        x + 1,
+                        format!("the grid has only {width} columns"),
+                    ));
+                }
+                let (x, y) = (x as i32, y as i32);
+                match c {
+                    '#' => guess.alive.set(x, y),
+                    '.' => guess.dead.set(x, y),
+                    '?' => {}
+                    '+' => guess.try_alive.set(x, y),
+                    '-' => guess.try_dead.set(x, y),
+                    c => {
+                        return Err(error(
+                            y as usize + 2,
+                            x as usize + 1,
+                            format!("expected one of `#.?+-`, but found `{c}`"),
+                        ))
+                    }
+                }
+                if matches!(c, '#' | '.') {
+                    guess.decided_in_row[y as usize] += 1;
+                }
+            }
+        }
+        if guess.decided_count() != decided {
+            return Err(error(
+                1,
+                1,
+                format!(
+                    "the header says {decided} cells are decided, but {} are",
+                    guess.decided_count()
+                ),
+            ));
+        }
+        guess.alive_neighbours = guess.alive.neighbour_counts(guess.neighbourhood);
+        guess.dead_neighbours = guess.dead.neighbour_counts(guess.neighbourhood);
+        if contradictory {
+            guess.contradiction = Some(PARSED_CONTRADICTION);
+        }
+        Ok(guess)
+    }
+
+    /// How much propagation deciding `(x, y)` has recently caused: the number of cells deduced directly after
+    /// the searcher branched on it, accumulated over the current subtree and halved each time the search
+    /// backtracks out of a guess. Always zero unless the searcher's [`CellOrder`](crate::order::CellOrder)
+    /// asks for impact to be tracked.
+    pub fn impact(&self, x: i32, y: i32) -> i32 {
+        self.impact.as_ref().map_or(0, |impact| impact.get(x, y))
+    }
+
+    /// The [`Guess::impact`] of every cell.
+    pub fn impact_grid(&self) -> Grid {
+        self.impact.as_ref().map_or_else(
+            || Grid::new(self.width(), self.height()),
+            CountGrid::to_grid,
+        )
+    }
+
+    pub(crate) fn track_impact(&mut self) {
+        let (width, height) = (self.width(), self.height());
+        self.impact
+            .get_or_insert_with(|| CountGrid::new(width, height));
+    }
+
+    /// Records that branching on `(x, y)` deduced `deductions` further cells, if impact is tracked.
+    pub(crate) fn record_impact(&mut self, x: i32, y: i32, deductions: usize) {
+        if let Some(impact) = &mut self.impact {
+            impact.saturating_add(x, y, deductions.try_into().unwrap_or(i32::MAX));
+        }
+    }
+
+    /// The cells decided in this guess but not in `before`, which it refines.
+    pub(crate) fn newly_decided(&self, before: &Guess) -> BoolGrid {
+        let mut decided = self.alive.clone();
+        decided |= &self.dead;
+        decided -= &before.alive;
+        decided -= &before.dead;
+        decided
+    }
+
+    /// Takes on the impact learned in `other` as it is, if both track impact.
+    pub(crate) fn adopt_impact(&mut self, other: &Guess) {
+        if let (Some(impact), Some(learned)) = (&mut self.impact, &other.impact) {
+            impact.clone_from(learned);
+        }
+    }
+
+    /// Takes on the impact learned in `descendant`, a refinement of this guess that is being abandoned, halving it.
+    pub(crate) fn inherit_impact(&mut self, descendant: &Guess) {
+        let (width, height) = (self.width(), self.height());
+        if let (Some(impact), Some(learned)) = (&mut self.impact, &descendant.impact) {
+            for y in 0..height {
+                for x in 0..width {
+                    impact.set(x, y, learned.get(x, y) / 2);
+                }
+            }
+        }
+    }
+
+    /// # Safety
+    /// `x` and `y` must be between `0` and the width and height of the guess.
+    pub(crate) unsafe fn guessed_alive(&self, x: i32, y: i32) -> bool {
+        self.alive.test_unchecked(x, y)
+    }
+
+    /// # Safety
+    /// `x` and `y` must be between `0` and the width and height of the guess.
+    pub(crate) unsafe fn guessed_dead(&self, x: i32, y: i32) -> bool {
+        self.dead.test_unchecked(x, y)
     }
 
     /// Adjust this guess with the additional information that the given cell is alive.
-    /// Safety: `x` and `y` must be between `0` and `SIZE`.
-    pub fn guess_alive(&mut self, next: &Grid, x: i32, y: i32) {
-        let mut queue = Vec::with_capacity((SIZE * SIZE) as usize);
-        if let Ok(()) = self.guess_alive_with_queue(x, y, &mut queue) {
+    /// `x` and `y` must be between `0` and the width and height of the guess.
+    ///
+    /// # Panics
+    /// If `next` is a different size from the guess.
+    pub(crate) fn guess_alive(&mut self, next: &Grid, x: i32, y: i32) {
+        self.assert_next_size(next);
+        #[cfg(feature = "provenance")]
+        {
+            self.provenance.clear();
+            self.record_step(next, (x, y), Some(true), "guessed", (x, y));
+        }
+        let mut queue = Vec::with_capacity(self.cell_count());
+        let undecided = unsafe { !self.guessed_alive(x, y) && !self.guessed_dead(x, y) };
+        if let Ok(()) = self.guess_alive_with_queue(next, x, y, &mut queue) {
+            if undecided {
+                unsafe {
+                    self.decisions.set_unchecked(x, y);
+                }
+            }
             let _ = self.propagate_constraints(next, queue);
         }
+        // O(width · height).
+        checked_assert!(
+            self.check_invariants().is_ok(),
+            "after guessing ({x}, {y}) was alive: {}\n{}",
+            self.check_invariants().unwrap_err(),
+            self.render()
+        );
     }
 
     /// Adjust this guess with the additional information that the given cell is dead.
-    /// Safety: `x` and `y` must be between `0` and `SIZE`.
-    pub fn guess_dead(&mut self, next: &Grid, x: i32, y: i32) {
-        let mut queue = Vec::with_capacity((SIZE * SIZE) as usize);
-        if let Ok(()) = self.guess_dead_with_queue(x, y, &mut queue) {
+    /// `x` and `y` must be between `0` and the width and height of the guess.
+    ///
+    /// # Panics
+    /// If `next` is a different size from the guess.
+    pub(crate) fn guess_dead(&mut self, next: &Grid, x: i32, y: i32) {
+        self.assert_next_size(next);
+        #[cfg(feature = "provenance")]
+        {
+            self.provenance.clear();
+            self.record_step(next, (x, y), Some(false), "guessed", (x, y));
+        }
+        let mut queue = Vec::with_capacity(self.cell_count());
+        let undecided = unsafe { !self.guessed_alive(x, y) && !self.guessed_dead(x, y) };
+        if let Ok(()) = self.guess_dead_with_queue(next, x, y, &mut queue) {
+            if undecided {
+                unsafe {
+                    self.decisions.set_unchecked(x, y);
+                }
+            }
+            let _ = self.propagate_constraints(next, queue);
+        }
+        // O(width · height).
+        checked_assert!(
+            self.check_invariants().is_ok(),
+            "after guessing ({x}, {y}) was dead: {}\n{}",
+            self.check_invariants().unwrap_err(),
+            self.render()
+        );
+    }
+
+    /// Shaves this guess to a fixed point, by probing both values of each undecided cell with full propagation.
+    ///
+    /// If one value leads to a contradiction, the cell takes the other. If neither does, every cell that both
+    /// probes decided the same way is decided that way now, since it holds whatever the probed cell turns out to be.
+    /// This costs two propagations per undecided cell per pass, so it is far too slow to run after every guess.
+    pub(crate) fn shave(&mut self, next: &Grid) {
+        let mut changed = true;
+        while changed && self.contradiction.is_none() {
+            changed = false;
+            for (x, y) in self.unknown_cells().collect::<Vec<_>>() {
+                // Earlier probes in this pass may have decided the cell already.
+                if unsafe { self.guessed_alive(x, y) || self.guessed_dead(x, y) } {
+                    continue;
+                }
+                let mut alive = self.clone();
+                alive.guess_alive(next, x, y);
+                let mut dead = self.clone();
+                dead.guess_dead(next, x, y);
+                match (alive.found_contradiction(), dead.found_contradiction()) {
+                    (true, true) => {
+                        #[cfg(feature = "provenance")]
+                        self.provenance.clear();
+                        let _ =
+                            self.fail(next, "both values of a cell lead to contradictions", x, y);
+                        return;
+                    }
+                    (true, false) => {
+                        trace!("shaving: ({x}, {y}) cannot be alive");
+                        *self = dead;
+                        self.decisions.clear(x, y);
+                        changed = true;
+                    }
+                    (false, true) => {
+                        trace!("shaving: ({x}, {y}) cannot be dead");
+                        *self = alive;
+                        self.decisions.clear(x, y);
+                        changed = true;
+                    }
+                    (false, false) => {
+                        for (x2, y2) in self.unknown_cells().collect::<Vec<_>>() {
+                            // Both coordinates are on the guess.
+                            let (in_alive, in_dead) = unsafe {
+                                (
+                                    (alive.guessed_alive(x2, y2), alive.guessed_dead(x2, y2)),
+                                    (dead.guessed_alive(x2, y2), dead.guessed_dead(x2, y2)),
+                                )
+                            };
+                            if in_alive != in_dead || in_alive == (false, false) {
+                                continue;
+                            }
+                            trace!("shaving: ({x2}, {y2}) is decided whatever ({x}, {y}) is");
+                            if in_alive.0 {
+                                self.guess_alive(next, x2, y2);
+                            } else {
+                                self.guess_dead(next, x2, y2);
+                            }
+                            self.decisions.clear(x2, y2);
+                            changed = true;
+                            if self.found_contradiction() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Asserts every cell decided in `other` into this guess, with propagation, as a deduction rather than a decision.
+    ///
+    /// This is only sound if everything `other` decided follows from what this guess assumes,
+    /// as it does when both are root guesses for the same target and mask, so that `other` holds only implications.
+    /// If the two disagree, this guess becomes contradictory.
+    pub(crate) fn merge_root_implications(&mut self, next: &Grid, other: &Guess) {
+        for (alive, cells) in [(true, &other.alive), (false, &other.dead)] {
+            for (x, y) in cells.iter() {
+                if self.contradiction.is_some() {
+                    return;
+                }
+                // Both coordinates are on the guess.
+                let undecided = unsafe { !self.guessed_alive(x, y) && !self.guessed_dead(x, y) };
+                // If the cell is already decided, this either does nothing or finds the conflict.
+                if alive {
+                    self.guess_alive(next, x, y);
+                } else {
+                    self.guess_dead(next, x, y);
+                }
+                if undecided {
+                    self.decisions.clear(x, y);
+                }
+            }
+        }
+    }
+
+    /// Runs the second, block-level tier of propagation to a fixed point, interleaved with the per-cell tier.
+    ///
+    /// For each 2×2 block of cells with undecided cells, every joint assignment of those cells is checked against
+    /// the sixteen cells whose neighbourhoods overlap the block. Cells with the same value in every consistent
+    /// assignment are decided, and a block with no consistent assignment is a contradiction.
+    /// This catches joint constraints that the per-cell rules miss, at the cost of up to
+    /// `16 · 16` constraint checks per block.
+    pub(crate) fn propagate_blocks(&mut self, next: &Grid) {
+        self.assert_next_size(next);
+        while self.contradiction.is_none() {
+            let mut queue = Vec::new();
+            if self.propagate_block_pass(next, &mut queue).is_err() || queue.is_empty() {
+                break;
+            }
             let _ = self.propagate_constraints(next, queue);
         }
+        // O(width · height).
+        checked_assert!(
+            self.check_invariants().is_ok(),
+            "after block propagation: {}\n{}",
+            self.check_invariants().unwrap_err(),
+            self.render()
+        );
+    }
+
+    /// Checks every 2×2 block once, deciding the cells that are forced, and queueing their neighbours.
+    fn propagate_block_pass(&mut self, next: &Grid, queue: &mut Vec<(i32, i32)>) -> Result<(), ()> {
+        const BLOCK: [(i32, i32); 4] = [(0, 0), (1, 0), (0, 1), (1, 1)];
+        let (width, height) = (self.width(), self.height());
+        // On a plane, the blocks that would wrap around an edge are not blocks at all.
+        let (last_x, last_y) = match self.topology {
+            Topology::Torus => (width, height),
+            Topology::Plane => (width - 1, height - 1),
+        };
+        for y in 0..last_y {
+            for x in 0..last_x {
+                let cells = BLOCK.map(|(dx, dy)| ((x + dx) % width, (y + dy) % height));
+                // The block cells that are undecided, as indices into `BLOCK`.
+                let mut undecided = [0; 4];
+                let mut count = 0;
+                for (i, &(x, y)) in cells.iter().enumerate() {
+                    if unsafe { !self.guessed_alive(x, y) && !self.guessed_dead(x, y) } {
+                        undecided[count] = i;
+                        count += 1;
+                    }
+                }
+                if count == 0 {
+                    continue;
+                }
+                let undecided = &undecided[..count];
+
+                // Bit `i` of an assignment is whether `undecided[i]` is alive.
+                let mut always_alive = u32::MAX;
+                let mut ever_alive = 0;
+                for assignment in 0u32..1 << count {
+                    if self.block_assignment_consistent(next, x, y, undecided, assignment) {
+                        always_alive &= assignment;
+                        ever_alive |= assignment;
+                    }
+                }
+                if ever_alive == 0 && always_alive == u32::MAX {
+                    return self.fail(next, BLOCK_INCONSISTENT, x, y);
+                }
+
+                for (bit, &i) in undecided.iter().enumerate() {
+                    let (x2, y2) = cells[i];
+                    if always_alive & 1 << bit != 0 {
+                        self.deduce_alive(next, x2, y2, BLOCK_FORCED, (x, y), queue)?;
+                    } else if ever_alive & 1 << bit == 0 {
+                        self.deduce_dead(next, x2, y2, BLOCK_FORCED, (x, y), queue)?;
+                    }
+                }
+            }
+        }
+        Ok(())
     }
 
-    fn fail(&mut self) -> Result<(), ()> {
-        self.found_contradiction = true;
+    /// Whether some completion of this guess, with the `undecided` cells of the 2×2 block at `(x, y)` set by
+    /// `assignment`, could step to `next` on each of the sixteen cells whose neighbourhoods overlap the block.
+    fn block_assignment_consistent(
+        &self,
+        next: &Grid,
+        x: i32,
+        y: i32,
+        undecided: &[usize],
+        assignment: u32,
+    ) -> bool {
+        // Offsets of the block's cells from its top left corner, indexed as in `propagate_block_pass`.
+        let block_offset = |i: usize| ((i % 2) as i32, (i / 2) as i32);
+        let (width, height) = (self.width(), self.height());
+        for dy in -1..=2 {
+            for dx in -1..=2 {
+                let (cx, cy) = ((x + dx + width) % width, (y + dy + height) % height);
+                if self.topology == Topology::Plane && (cx, cy) != (x + dx, y + dy) {
+                    // Past the edge of a plane, where nothing is stepped.
+                    continue;
+                }
+                let mut alive = unsafe { self.alive_neighbours.get_unchecked(cx, cy) };
+                let mut dead = unsafe { self.dead_neighbours.get_unchecked(cx, cy) };
+                let mut can_live = unsafe { !self.guessed_dead(cx, cy) };
+                let mut can_die = unsafe { !self.guessed_alive(cx, cy) };
+                for (bit, &i) in undecided.iter().enumerate() {
+                    let cell_alive = assignment & 1 << bit != 0;
+                    let (bx, by) = block_offset(i);
+                    if (bx, by) == (dx, dy) {
+                        can_live = cell_alive;
+                        can_die = !cell_alive;
+                    } else if self.neighbourhood.contains(dx - bx, dy - by) {
+                        if cell_alive {
+                            alive += 1;
+                        } else {
+                            dead += 1;
+                        }
+                    }
+                }
+                // The constraint cell has between `alive` and `max` alive neighbours.
+                let max = self.neighbourhood.size() - dead;
+                let possible = |n: i32| alive <= n && n <= max;
+                let consistent = if unsafe { next.get(cx, cy) }.value > 0 {
+                    possible(3) || (can_live && possible(2))
+                } else if can_die {
+                    !(alive == 3 && max == 3)
+                } else {
+                    // A live cell dies with any number of neighbours other than two or three.
+                    alive < 2 || max > 3
+                };
+                if !consistent {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// The cells whose constraints `(x, y)` takes part in, which are also the cells its own constraint ranges over.
+    /// Every neighbour iteration in propagation goes through this, so that it agrees with the neighbour counts.
+    #[inline]
+    fn neighbours(&self, x: i32, y: i32) -> impl Iterator<Item = (i32, i32)> {
+        let topology = self.topology;
+        self.neighbourhood
+            .positions(x, y, self.width(), self.height())
+            .filter(move |&neighbour| topology.adjacent((x, y), neighbour))
+    }
+
+    /// Records that the constraint of `(x, y)` cannot be satisfied, by the given rule.
+    fn fail(&mut self, next: &Grid, rule: &'static str, x: i32, y: i32) -> Result<(), ()> {
+        trace!("contradiction at ({x}, {y}): {rule}");
+        #[cfg(feature = "provenance")]
+        self.record_step(next, (x, y), None, rule, (x, y));
+        #[cfg(not(feature = "provenance"))]
+        let _ = next;
+        self.contradiction = Some(rule);
+        self.contradiction_cell = Some((x, y));
         Err(())
     }
 
+    /// Logs a step of reasoning, reading the counts of `centre` as they are now.
+    #[cfg(feature = "provenance")]
+    fn record_step(
+        &mut self,
+        next: &Grid,
+        cell: (i32, i32),
+        decided: Option<bool>,
+        rule: &'static str,
+        centre: (i32, i32),
+    ) {
+        let (x, y) = centre;
+        // The rules read the block's sixteen constraint cells and their neighbours,
+        // and a conflict only reads the conflicting cell.
+        let reads = match rule {
+            BLOCK_FORCED | BLOCK_INCONSISTENT => (-2, 3),
+            "guessed" | CONFLICT_ALIVE | CONFLICT_DEAD => (0, 0),
+            _ => (-1, 1),
+        };
+        self.provenance.record(TraceStep {
+            cell,
+            decided,
+            rule,
+            centre,
+            alive_neighbours: self.alive_neighbours.get(x, y),
+            dead_neighbours: self.dead_neighbours.get(x, y),
+            next_alive: next.get_wrapped(x, y).value > 0,
+            reads,
+        });
+    }
+
     fn guess_alive_with_queue(
         &mut self,
+        next: &Grid,
         x: i32,
         y: i32,
         queue: &mut Vec<(i32, i32)>,
     ) -> Result<(), ()> {
-        if unsafe { self.dead.get(x, y) }.value > 0 {
-            self.fail()?
+        if unsafe { self.dead.test_unchecked(x, y) } {
+            self.fail(next, CONFLICT_ALIVE, x, y)?
         }
 
-        if unsafe { self.alive.get(x, y) }.value > 0 {
+        if unsafe { self.alive.test_unchecked(x, y) } {
             return Ok(());
         }
 
         unsafe {
-            self.alive.set(x, y, Cell::one());
+            self.alive.set_unchecked(x, y);
         }
+        self.decided_in_row[y as usize] += 1;
+
+        // The cell's own constraint can now say more about its neighbours.
+        queue.push((x, y));
 
-        // Now preserve the invariant that `min_neighbours` and `max_neighbours` are correct.
-        // For each proper neighbour of the cell, increase `min_neighbours` by one.
-        for (x2, y2) in Grid::neighbour_positions(x, y) {
+        // Now preserve the invariant that `alive_neighbours` is correct.
+        for (x2, y2) in self.neighbours(x, y) {
             unsafe {
-                self.min_neighbours.set_add(x2, y2, Cell::one());
+                self.alive_neighbours.increment_unchecked(x2, y2);
             }
             // It's faster without this check!
             // if !queue.contains(&(x2, y2)) {
@@ -137,54 +1076,194 @@ impl Guess {
             // }
         }
 
+        // Only now that every count is up to date is it safe to deduce more.
+        self.force_sparse(next, x, y, queue)?;
+        for (x2, y2) in self.neighbours(x, y) {
+            self.force_crowded(next, x2, y2, queue)?;
+        }
+
         Ok(())
     }
 
     fn guess_dead_with_queue(
         &mut self,
+        next: &Grid,
         x: i32,
         y: i32,
         queue: &mut Vec<(i32, i32)>,
     ) -> Result<(), ()> {
-        if unsafe { self.alive.get(x, y) }.value > 0 {
-            self.fail()?
+        if unsafe { self.alive.test_unchecked(x, y) } {
+            self.fail(next, CONFLICT_DEAD, x, y)?
         }
 
-        if unsafe { self.dead.get(x, y) }.value > 0 {
+        if unsafe { self.dead.test_unchecked(x, y) } {
             return Ok(());
         }
 
         unsafe {
-            self.dead.set(x, y, Cell::one());
+            self.dead.set_unchecked(x, y);
         }
+        self.decided_in_row[y as usize] += 1;
 
-        // Now preserve the invariant that `min_neighbours` and `max_neighbours` are correct.
-        // For each proper neighbour of the cell, decrease `max_neighbours` by one.
-        for (x2, y2) in Grid::neighbour_positions(x, y) {
+        // The cell's own constraint can now say more about its neighbours.
+        queue.push((x, y));
+
+        // Now preserve the invariant that `dead_neighbours` is correct.
+        for (x2, y2) in self.neighbours(x, y) {
             unsafe {
-                self.max_neighbours.set_add(x2, y2, Cell::neg_one());
+                self.dead_neighbours.increment_unchecked(x2, y2);
             }
             // if !queue.contains(&(x2, y2)) {
             queue.push((x2, y2));
             // }
         }
 
+        // Only now that every count is up to date is it safe to deduce more.
+        self.force_sparse(next, x, y, queue)?;
+        for (x2, y2) in self.neighbours(x, y) {
+            self.force_sparse(next, x2, y2, queue)?;
+        }
+
         Ok(())
     }
 
+    /// Applies the rules for a cell that is alive on the next frame as soon as its neighbours could be too many,
+    /// rather than waiting for it to come off the queue: it can have at most three alive neighbours.
+    /// Call this when `(x, y)` gains an alive neighbour.
+    fn force_crowded(
+        &mut self,
+        next: &Grid,
+        x: i32,
+        y: i32,
+        queue: &mut Vec<(i32, i32)>,
+    ) -> Result<(), ()> {
+        if unsafe { next.get(x, y) }.value == 0 {
+            return Ok(());
+        }
+        let alive = unsafe { self.alive_neighbours.get_unchecked(x, y) };
+        let dead = unsafe { self.dead_neighbours.get_unchecked(x, y) };
+        match alive.cmp(&3) {
+            Ordering::Less => Ok(()),
+            Ordering::Equal if alive + dead < self.neighbourhood.size() => self
+                .guess_neighbours_dead_with_queue(
+                    next,
+                    x,
+                    y,
+                    "at least three neighbours and alive next frame",
+                    queue,
+                ),
+            Ordering::Equal => Ok(()),
+            Ordering::Greater => self.fail(
+                next,
+                "more than three neighbours but alive next frame",
+                x,
+                y,
+            ),
+        }
+    }
+
+    /// Applies the rules for a cell that is alive on the next frame as soon as its neighbours could be too few,
+    /// rather than waiting for it to come off the queue: a birth needs three alive neighbours, and a survival two.
+    /// Call this when `(x, y)` is decided, or gains a dead neighbour.
+    fn force_sparse(
+        &mut self,
+        next: &Grid,
+        x: i32,
+        y: i32,
+        queue: &mut Vec<(i32, i32)>,
+    ) -> Result<(), ()> {
+        if unsafe { next.get(x, y) }.value == 0 {
+            return Ok(());
+        }
+        let (required, rule) = if unsafe { self.guessed_dead(x, y) } {
+            (3, "neighbour of a cell that is born next frame")
+        } else if unsafe { self.guessed_alive(x, y) } {
+            (2, "neighbour of a cell that survives to the next frame")
+        } else {
+            return Ok(());
+        };
+        let alive = unsafe { self.alive_neighbours.get_unchecked(x, y) };
+        let dead = unsafe { self.dead_neighbours.get_unchecked(x, y) };
+        match (self.neighbourhood.size() - dead).cmp(&required) {
+            Ordering::Less => self.fail(
+                next,
+                "too few possible neighbours to be alive next frame",
+                x,
+                y,
+            ),
+            Ordering::Equal if alive < required => {
+                self.guess_neighbours_alive_with_queue(next, x, y, rule, queue)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Deduces that the cell is alive because of the given rule.
+    /// `centre` is the cell whose constraint the rule applied to.
+    fn deduce_alive(
+        &mut self,
+        next: &Grid,
+        x: i32,
+        y: i32,
+        rule: &'static str,
+        centre: (i32, i32),
+        queue: &mut Vec<(i32, i32)>,
+    ) -> Result<(), ()> {
+        trace!("({x}, {y}) was alive: {rule}");
+        #[cfg(feature = "provenance")]
+        if unsafe { !self.guessed_alive(x, y) } {
+            self.record_step(next, (x, y), Some(true), rule, centre);
+        }
+        #[cfg(not(feature = "provenance"))]
+        let _ = centre;
+        let result = self.guess_alive_with_queue(next, x, y, queue);
+        if result.is_err() {
+            // Blame the deduction rather than the cell it conflicted with.
+            self.contradiction = Some(rule);
+        }
+        result
+    }
+
+    /// Deduces that the cell is dead because of the given rule.
+    /// `centre` is the cell whose constraint the rule applied to.
+    fn deduce_dead(
+        &mut self,
+        next: &Grid,
+        x: i32,
+        y: i32,
+        rule: &'static str,
+        centre: (i32, i32),
+        queue: &mut Vec<(i32, i32)>,
+    ) -> Result<(), ()> {
+        trace!("({x}, {y}) was dead: {rule}");
+        #[cfg(feature = "provenance")]
+        if unsafe { !self.guessed_dead(x, y) } {
+            self.record_step(next, (x, y), Some(false), rule, centre);
+        }
+        #[cfg(not(feature = "provenance"))]
+        let _ = centre;
+        let result = self.guess_dead_with_queue(next, x, y, queue);
+        if result.is_err() {
+            self.contradiction = Some(rule);
+        }
+        result
+    }
+
     fn guess_neighbours_alive_with_queue(
         &mut self,
+        next: &Grid,
         x: i32,
         y: i32,
+        rule: &'static str,
         queue: &mut Vec<(i32, i32)>,
     ) -> Result<(), ()> {
-        for (x2, y2) in Grid::neighbour_positions(x, y) {
+        for (x2, y2) in self.neighbours(x, y) {
             // If the cell is not already marked as dead or alive...
-            if unsafe { self.dead.get(x2, y2) }.value == 0
-                && unsafe { self.alive.get(x2, y2) }.value == 0
+            if unsafe { !self.dead.test_unchecked(x2, y2) }
+                && unsafe { !self.alive.test_unchecked(x2, y2) }
             {
                 // ...guess that it is alive.
-                self.guess_alive_with_queue(x2, y2, queue)?;
+                self.deduce_alive(next, x2, y2, rule, (x, y), queue)?;
             }
         }
         Ok(())
@@ -192,101 +1271,159 @@ impl Guess {
 
     fn guess_neighbours_dead_with_queue(
         &mut self,
+        next: &Grid,
         x: i32,
         y: i32,
+        rule: &'static str,
         queue: &mut Vec<(i32, i32)>,
     ) -> Result<(), ()> {
-        for (x2, y2) in Grid::neighbour_positions(x, y) {
+        for (x2, y2) in self.neighbours(x, y) {
             // If the cell is not already marked as dead or alive...
-            if unsafe { self.dead.get(x2, y2) }.value == 0
-                && unsafe { self.alive.get(x2, y2) }.value == 0
+            if unsafe { !self.dead.test_unchecked(x2, y2) }
+                && unsafe { !self.alive.test_unchecked(x2, y2) }
             {
                 // ...guess that it is dead.
-                self.guess_dead_with_queue(x2, y2, queue)?;
+                self.deduce_dead(next, x2, y2, rule, (x, y), queue)?;
             }
         }
         Ok(())
     }
 
-    /// Given information about minimum and maximum neighbours, and the next frame of the grid,
+    /// Given the neighbour counts of each cell, and the next frame of the grid,
     /// work out some more information about the previous frame.
     /// The queue is the list of cells whose neighbour count has just been updated.
     /// Safety: this queue should only contain properly wrapped positions.
     fn propagate_constraints(&mut self, next: &Grid, mut queue: Vec<(i32, i32)>) -> Result<(), ()> {
         while let Some((x, y)) = queue.pop() {
-            let min = unsafe { self.min_neighbours.get(x, y) }.value;
-            let max = unsafe { self.max_neighbours.get(x, y) }.value;
+            // O(1). The accessors below check this too, but this failure is clearer.
+            checked_assert!(
+                (0..self.width()).contains(&x) && (0..self.height()).contains(&y),
+                "unwrapped position ({x}, {y}) in the propagation queue"
+            );
+            let alive = unsafe { self.alive_neighbours.get_unchecked(x, y) };
+            let dead = unsafe { self.dead_neighbours.get_unchecked(x, y) };
+            let undecided = self.neighbourhood.size() - alive - dead;
             let next_state = unsafe { next.get(x, y) }.value;
 
-            if min == max {
-                // Naive approach.
-                match min {
+            if undecided == 0 {
+                // Every neighbour is decided, so we know the neighbour count exactly.
+                match alive {
                     3 => {
                         // The cell must be alive next frame.
                         if next_state == 0 {
-                            return self.fail();
+                            return self.fail(
+                                next,
+                                "exactly three neighbours but dead next frame",
+                                x,
+                                y,
+                            );
                         }
                     }
                     2 => {
                         if next_state > 0 {
                             // The cell is alive next frame.
                             // So it must be alive on the previous frame.
-                            self.guess_alive_with_queue(x, y, &mut queue)?;
+                            self.deduce_alive(
+                                next,
+                                x,
+                                y,
+                                "exactly two neighbours and alive next frame",
+                                (x, y),
+                                &mut queue,
+                            )?;
                         } else {
                             // The cell is dead next frame.
                             // So it must be dead on the previous frame.
-                            self.guess_dead_with_queue(x, y, &mut queue)?;
+                            self.deduce_dead(
+                                next,
+                                x,
+                                y,
+                                "exactly two neighbours and dead next frame",
+                                (x, y),
+                                &mut queue,
+                            )?;
                         }
                     }
                     _ => {
                         // The cell must be dead next frame.
                         if next_state > 0 {
-                            return self.fail();
+                            return self.fail(
+                                next,
+                                "neither two nor three neighbours but alive next frame",
+                                x,
+                                y,
+                            );
                         }
                     }
                 }
                 continue;
             }
 
-            // Min/max calculations.
-            if max < 2 || min > 3 {
+            // The cell has between `alive` and `alive + undecided` neighbours.
+            if alive + undecided < 2 || alive > 3 {
                 // This cell *must* be dead on the next frame.
                 // The value of this cell in the previous frame is arbitrary.
                 if next_state > 0 {
-                    return self.fail();
+                    return self.fail(
+                        next,
+                        "too few or too many neighbours but alive next frame",
+                        x,
+                        y,
+                    );
                 }
-            } else if max == 2 {
+            } else if alive + undecided == 2 {
                 if next_state > 0 {
                     // This cell can only be alive on the next frame if it is alive on the previous frame.
-                    self.guess_alive_with_queue(x, y, &mut queue)?;
+                    let rule = "at most two neighbours and alive next frame";
+                    self.deduce_alive(next, x, y, rule, (x, y), &mut queue)?;
                     // Also, in this case, all of its remaining neighbours are forced to be alive on the previous frame.
                     // This way we enforce the maximum.
-                    self.guess_neighbours_alive_with_queue(x, y, &mut queue)?;
-                } else if min == 2 {
+                    self.guess_neighbours_alive_with_queue(next, x, y, rule, &mut queue)?;
+                } else if alive == 2 {
                     // The cell is dead on the next frame and has exactly 2 neighbours.
                     // The only way this can happen is if it is dead on the previous frame.
-                    self.guess_dead_with_queue(x, y, &mut queue)?;
+                    self.deduce_dead(
+                        next,
+                        x,
+                        y,
+                        "exactly two neighbours and dead next frame",
+                        (x, y),
+                        &mut queue,
+                    )?;
                 } else {
                     // This cell is dead on the next frame and has 0, 1, or 2 neighbours.
                     // We can't make a deduction now.
                 }
-            } else if min == 3 {
+            } else if alive == 3 {
                 if next_state > 0 {
                     // This cell can only be alive on the next frame if it has exactly three living neighbours.
                     // Force the remaining undecided neighbours to be dead.
                     // We can't tell if this cell is alive or dead on the previous frame.
-                    self.guess_neighbours_dead_with_queue(x, y, &mut queue)?;
-                } else if max == 4 {
-                    // This cell is dead on the next frame and has at least three neighbours.
-                    // The only way this can happen is if it in fact has four neighbours.
-                    // Given `max == 4`, there must be exactly one undecided neighbour left.
-                    for (x2, y2) in Grid::neighbour_positions(x, y) {
+                    self.guess_neighbours_dead_with_queue(
+                        next,
+                        x,
+                        y,
+                        "at least three neighbours and alive next frame",
+                        &mut queue,
+                    )?;
+                } else if undecided == 1 {
+                    // This cell is dead on the next frame and has three or four neighbours.
+                    // The only way this can happen is if it in fact has four neighbours,
+                    // so its one undecided neighbour is alive.
+                    for (x2, y2) in self.neighbours(x, y) {
                         // If the cell is not already marked as dead or alive...
-                        if unsafe { self.dead.get(x2, y2) }.value == 0
-                            && unsafe { self.alive.get(x2, y2) }.value == 0
+                        if unsafe { !self.dead.test_unchecked(x2, y2) }
+                            && unsafe { !self.alive.test_unchecked(x2, y2) }
                         {
                             // ...guess that it is alive.
-                            self.guess_alive_with_queue(x2, y2, &mut queue)?;
+                            self.deduce_alive(
+ next,
+                                x2,
+                                y2,
+                                "last undecided neighbour of a cell with three or four neighbours that is dead next frame",
+                                (x, y),
+                                &mut queue,
+                            )?;
                             // We already know that there is exactly one neighbour.
                             break;
                         }
@@ -296,38 +1433,39 @@ impl Guess {
 
             // Previous state calculations.
             // Helpful for adding constraints to cells that live on the next frame.
-            let previously_dead = unsafe { self.dead.get(x, y) }.value;
-            let previously_alive = unsafe { self.alive.get(x, y) }.value;
+            let previously_dead = unsafe { self.dead.test_unchecked(x, y) };
+            let previously_alive = unsafe { self.alive.test_unchecked(x, y) };
 
-            match (next_state > 0, previously_dead > 0, previously_alive > 0) {
+            match (next_state > 0, previously_dead, previously_alive) {
                 (true, true, _) => {
                     // This cell comes alive on the next frame.
                     // It can only do this if it has exactly three neighbours.
-                    if min == 3 {
-                        self.guess_neighbours_dead_with_queue(x, y, &mut queue)?;
-                    } else if max == 3 {
-                        self.guess_neighbours_alive_with_queue(x, y, &mut queue)?;
-                    } else if min == 2 {
+                    let rule = "neighbour of a cell that is born next frame";
+                    if alive == 3 {
+                        self.guess_neighbours_dead_with_queue(next, x, y, rule, &mut queue)?;
+                    } else if alive + undecided == 3 {
+                        self.guess_neighbours_alive_with_queue(next, x, y, rule, &mut queue)?;
+                    } else if alive == 2 {
                         // We'd like to try individually setting the neighbours of this cell to be alive.
-                        for (x2, y2) in Grid::neighbour_positions(x, y) {
+                        for (x2, y2) in self.neighbours(x, y) {
                             // If the cell is not already marked as dead or alive...
-                            if unsafe { self.dead.get(x2, y2) }.value == 0
-                                && unsafe { self.alive.get(x2, y2) }.value == 0
+                            if unsafe { !self.dead.test_unchecked(x2, y2) }
+                                && unsafe { !self.alive.test_unchecked(x2, y2) }
                             {
                                 // ...express our desire to test the case where it is alive.
                                 unsafe {
-                                    self.try_alive.set(x2, y2, Cell::one());
+                                    self.try_alive.set_unchecked(x2, y2);
                                 }
                             }
                         }
-                    } else if max == 4 {
+                    } else if alive + undecided == 4 {
                         // We'd like to try individually setting the neighbours of this cell to be dead.
-                        for (x2, y2) in Grid::neighbour_positions(x, y) {
-                            if unsafe { self.dead.get(x2, y2) }.value == 0
-                                && unsafe { self.alive.get(x2, y2) }.value == 0
+                        for (x2, y2) in self.neighbours(x, y) {
+                            if unsafe { !self.dead.test_unchecked(x2, y2) }
+                                && unsafe { !self.alive.test_unchecked(x2, y2) }
                             {
                                 unsafe {
-                                    self.try_dead.set(x2, y2, Cell::one());
+                                    self.try_dead.set_unchecked(x2, y2);
                                 }
                             }
                         }
@@ -336,10 +1474,11 @@ impl Guess {
                 (true, _, true) => {
                     // This cell remains alive.
                     // It can only do this if it has exactly two or three neighbours.
-                    if min == 3 {
-                        self.guess_neighbours_dead_with_queue(x, y, &mut queue)?;
-                    } else if max == 2 {
-                        self.guess_neighbours_alive_with_queue(x, y, &mut queue)?;
+                    let rule = "neighbour of a cell that survives to the next frame";
+                    if alive == 3 {
+                        self.guess_neighbours_dead_with_queue(next, x, y, rule, &mut queue)?;
+                    } else if alive + undecided == 2 {
+                        self.guess_neighbours_alive_with_queue(next, x, y, rule, &mut queue)?;
                     }
                 }
                 (false, true, _) => {
@@ -359,6 +1498,142 @@ impl Guess {
     }
 
     pub fn found_contradiction(&self) -> bool {
-        self.found_contradiction
+        self.contradiction.is_some()
+    }
+
+    /// The rule that found this guess to be contradictory, if it is.
+    pub fn contradiction(&self) -> Option<&'static str> {
+        self.contradiction
+    }
+
+    /// The cell whose constraint this guess could not satisfy, if it is contradictory.
+    /// A deduction that led to the contradiction may be blamed for it in [`Guess::contradiction`],
+    /// but this is still the cell where it was detected.
+    pub fn contradiction_cell(&self) -> Option<(i32, i32)> {
+        self.contradiction_cell
+    }
+
+    /// If the last guess led to a contradiction, the steps of propagation it depended on, in order:
+    /// the guess itself if it mattered, each deduction that fed into the contradiction, and the contradiction.
+    #[cfg(feature = "provenance")]
+    pub fn contradiction_trace(&self) -> Option<Vec<TraceStep>> {
+        self.contradiction?;
+        self.provenance.trace(self.width(), self.height())
+    }
+
+    /// Renders [`Guess::contradiction_trace`] as a numbered list, or `None` if there is no contradiction.
+    #[cfg(feature = "provenance")]
+    pub fn render_trace(&self) -> Option<String> {
+        Some(render_trace(&self.contradiction_trace()?))
+    }
+
+    /// Checks that no cell is both alive and dead, and that the neighbour and row counts match the decided cells.
+    /// These hold after every public operation, even once a contradiction has been found.
+    /// This is `O(width · height)`.
+    #[cfg(any(feature = "testing", feature = "checked"))]
+    pub(crate) fn check_invariants(&self) -> Result<(), String> {
+        let alive_neighbours = self.alive().neighbours_in(self.neighbourhood);
+        let dead_neighbours = self.dead().neighbours_in(self.neighbourhood);
+        let (width, height) = (self.width(), self.height());
+        for y in 0..height {
+            for x in 0..width {
+                // Both coordinates are on the guess.
+                let (alive, dead, alive_count, dead_count) = unsafe {
+                    (
+                        self.alive.test_unchecked(x, y),
+                        self.dead.test_unchecked(x, y),
+                        self.alive_neighbours.get_unchecked(x, y),
+                        self.dead_neighbours.get_unchecked(x, y),
+                    )
+                };
+                if alive && dead {
+                    return Err(format!("({x}, {y}) was guessed both alive and dead"));
+                }
+                if !alive && !dead && unsafe { self.decisions.test_unchecked(x, y) } {
+                    return Err(format!("({x}, {y}) is an undecided decision"));
+                }
+                let expected_alive = unsafe { alive_neighbours.get(x, y) }.value;
+                if alive_count != expected_alive {
+                    return Err(format!(
+                        "({x}, {y}) has {expected_alive} alive neighbours but a count of {alive_count}"
+                    ));
+                }
+                // The neighbours past the edges of a plane are always dead.
+                let outside = self.neighbourhood.size() - self.neighbours(x, y).count() as i32;
+                let expected_dead = unsafe { dead_neighbours.get(x, y) }.value + outside;
+                if dead_count != expected_dead {
+                    return Err(format!(
+                        "({x}, {y}) has {expected_dead} dead neighbours but a count of {dead_count}"
+                    ));
+                }
+            }
+            let decided = (0..width)
+                .filter(|&x| unsafe {
+                    self.alive.test_unchecked(x, y) || self.dead.test_unchecked(x, y)
+                })
+                .count();
+            if decided != self.decided_in_row[y as usize] as usize {
+                return Err(format!(
+                    "row {y} has {decided} decided cells but a count of {}",
+                    self.decided_in_row[y as usize]
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A compact encoding, one character per cell, that [`Guess::parse_compact`] reads back.
+/// A header line gives the number of decided cells out of the total, or out of the width and height
+/// when the guess is not square, and whether the guess is contradictory,
+/// then each row has `#` for alive cells, `.` for dead ones and `?` for undecided ones.
+/// The alternate form, `{:#}`, also marks undecided cells the search will try first:
+/// `-` for those it will try dead, and otherwise `+` for those it will try alive.
+///
+/// ```
+/// use undeath::Guess;
+///
+/// let encoded = Guess::default().to_string();
+/// assert_eq!(encoded.lines().next(), Some("0 of 64 decided, consistent"));
+/// assert_eq!(encoded.lines().nth(1), Some("????????"));
+/// ```
+impl Display for Guess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} of ", self.decided_count())?;
+        if self.width() == self.height() {
+            write!(f, "{}", self.cell_count())?;
+        } else {
+            write!(f, "{}×{}", self.width(), self.height())?;
+        }
+        write!(
+            f,
+            " decided, {}",
+            if self.found_contradiction() {
+                "contradictory"
+            } else {
+                "consistent"
+            }
+        )?;
+        for y in 0..self.height() {
+            writeln!(f)?;
+            for x in 0..self.width() {
+                // Both coordinates are on the guess.
+                let c = unsafe {
+                    if self.alive.test_unchecked(x, y) {
+                        '#'
+                    } else if self.dead.test_unchecked(x, y) {
+                        '.'
+                    } else if f.alternate() && self.try_dead.test_unchecked(x, y) {
+                        '-'
+                    } else if f.alternate() && self.try_alive.test_unchecked(x, y) {
+                        '+'
+                    } else {
+                        '?'
+                    }
+                };
+                write!(f, "{c}")?;
+            }
+        }
+        Ok(())
     }
 }
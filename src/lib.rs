@@ -0,0 +1,61 @@
+//! Conway's game of 𝔲𝔫𝔡𝔢𝔞𝔱𝔥: find previous states of a Game of Life board.
+//!
+//! The simplest entry points are [`solve`] and [`solve_all`].
+//! For finer control, [`Searcher`] enumerates the possible predecessors
+//! of a target [`Grid`] a bounded number of iterations at a time,
+//! and [`Predecessors`] wraps it as an iterator.
+//!
+//! The items re-exported at the crate root, and in [`prelude`], are the stable public API;
+//! see `CHANGELOG.md` for what that promises.
+
+pub mod apgcode;
+pub mod bruteforce;
+pub mod cadence;
+mod checked;
+pub mod decompose;
+pub mod effort;
+mod error;
+pub mod evolution;
+pub mod grid;
+pub mod guess;
+mod local;
+pub mod order;
+pub mod parallel;
+#[cfg(feature = "provenance")]
+pub mod provenance;
+pub mod replay;
+pub mod rle;
+pub mod rng;
+pub mod search_log;
+pub mod searcher;
+#[cfg(feature = "serde")]
+mod serialization;
+mod solve;
+pub mod string;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod trace;
+pub mod typed_grid;
+pub mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use error::Error;
+pub use grid::{Cell, Grid};
+pub use guess::Guess;
+pub use searcher::{Predecessors, SearchResult, SearchStats, Searcher};
+pub use solve::{
+    solve, solve_all, solve_all_with, solve_with, Options, PredecessorOutcome, SolverSession,
+};
+
+/// The items most programs need, for glob importing.
+///
+/// ```
+/// use undeath::prelude::*;
+///
+/// assert!(solve(&Grid::default())?.is_some());
+/// # Ok::<(), Error>(())
+/// ```
+pub mod prelude {
+    pub use crate::{solve, solve_all, Cell, Error, Grid, Options, SearchResult, Searcher};
+}
@@ -0,0 +1,269 @@
+//! Stochastic local search for predecessors, in the style of WalkSAT.
+//!
+//! Rather than deciding cells one at a time, this keeps a complete previous frame,
+//! and repeatedly flips a cell near a target cell that it steps to the wrong value.
+//! It can find predecessors of dense targets that make systematic search flail,
+//! but it can never prove that there are none.
+
+use log::{debug, trace};
+
+use crate::{
+    checked::checked_assert,
+    grid::NeighbourField,
+    rng::{RandomSource, Rng},
+    typed_grid::BoolGrid,
+    Cell, Error, Grid, Guess, SearchResult,
+};
+
+/// The state of a local search: the current previous frame, and which target cells it gets wrong.
+pub(crate) struct LocalSearch {
+    next: Grid,
+    /// The cells that may be flipped.
+    /// The others were decided in the root guess, and keep that value.
+    free: BoolGrid,
+    /// The cells the root guess decided to be alive.
+    fixed_alive: Grid,
+    field: NeighbourField,
+    /// The target cells that `field` steps to the wrong value.
+    violated: BoolGrid,
+    /// How many cells are in `violated`.
+    violations: u32,
+    rng: Rng,
+    /// The probability of flipping a random candidate rather than the best one.
+    noise: f64,
+    /// How many flips to make before starting again from a new random frame.
+    restart_after: usize,
+    /// How many flips have been made since the last restart.
+    flips: usize,
+}
+
+/// Whether a cell with the given value and amount of neighbours is alive on the next frame.
+fn survives(alive: bool, neighbours: i32) -> bool {
+    neighbours == 3 || alive && neighbours == 2
+}
+
+impl LocalSearch {
+    /// Searches for predecessors of `next` that agree with every cell decided in `root`,
+    /// which must be the same size.
+    pub(crate) fn new(
+        next: &Grid,
+        root: &Guess,
+        rng: Rng,
+        noise_percent: u32,
+        restart_after: usize,
+    ) -> Self {
+        let mut free = BoolGrid::new(root.width(), root.height());
+        for (x, y) in root.unknown_cells() {
+            free.set(x, y);
+        }
+        let fixed_alive = root.alive();
+        let mut search = Self {
+            next: next.clone(),
+            free,
+            field: NeighbourField::new(fixed_alive.clone()),
+            fixed_alive,
+            violated: BoolGrid::new(root.width(), root.height()),
+            violations: 0,
+            rng,
+            noise: noise_percent as f64 / 100.0,
+            restart_after,
+            flips: 0,
+        };
+        search.restart();
+        search
+    }
+
+    /// Starts again from a random frame, keeping the cells the root guess decided.
+    pub(crate) fn restart(&mut self) {
+        let mut grid = self.fixed_alive.clone();
+        let (width, height) = (grid.width(), grid.height());
+        for y in 0..height {
+            for x in 0..width {
+                if self.free.test(x, y) && self.rng.gen_bool(0.5) {
+                    grid.set_wrapped(x, y, Cell::one());
+                }
+            }
+        }
+        self.field = NeighbourField::new(grid);
+        self.violated = BoolGrid::new(width, height);
+        self.violations = 0;
+        for y in 0..height {
+            for x in 0..width {
+                self.update_violation(x, y);
+            }
+        }
+        self.flips = 0;
+    }
+
+    /// Whether the target cell at `(x, y)` is stepped to the wrong value by the current frame.
+    fn is_violated(&self, x: i32, y: i32) -> bool {
+        let alive = self.field.get(x, y).value > 0;
+        let neighbours = self.field.counts().get_wrapped(x, y).value;
+        survives(alive, neighbours) != (self.next.get_wrapped(x, y).value > 0)
+    }
+
+    fn update_violation(&mut self, x: i32, y: i32) {
+        match (self.is_violated(x, y), self.violated.test(x, y)) {
+            (true, false) => {
+                self.violated.set(x, y);
+                self.violations += 1;
+            }
+            (false, true) => {
+                self.violated.clear(x, y);
+                self.violations -= 1;
+            }
+            _ => {}
+        }
+    }
+
+    /// `(x, y)` and its neighbours: the target cells whose violations flipping `(x, y)` can change.
+    fn neighbourhood(&self, x: i32, y: i32) -> impl Iterator<Item = (i32, i32)> {
+        std::iter::once((x, y)).chain(self.next.neighbour_positions(x, y))
+    }
+
+    /// How many more target cells would be violated after flipping `(x, y)`, which may be negative.
+    fn flip_delta(&self, x: i32, y: i32) -> i32 {
+        let change = if self.field.get(x, y).value > 0 {
+            -1
+        } else {
+            1
+        };
+        self.neighbourhood(x, y)
+            .map(|(nx, ny)| {
+                let mut alive = self.field.get(nx, ny).value > 0;
+                let mut neighbours = self.field.counts().get_wrapped(nx, ny).value;
+                if (nx, ny) == (x, y) {
+                    alive = !alive;
+                } else {
+                    neighbours += change;
+                }
+                let violated =
+                    survives(alive, neighbours) != (self.next.get_wrapped(nx, ny).value > 0);
+                violated as i32 - self.violated.test(nx, ny) as i32
+            })
+            .sum()
+    }
+
+    fn flip(&mut self, x: i32, y: i32) {
+        self.field.toggle(x, y);
+        for (nx, ny) in self.neighbourhood(x, y) {
+            self.update_violation(nx, ny);
+        }
+        // O(width × height).
+        checked_assert!(
+            (0..self.next.height()).all(|y| (0..self.next.width())
+                .all(|x| self.violated.test(x, y) == self.is_violated(x, y)))
+                && self.violated.count() == self.violations as usize,
+            "violations drifted after flipping ({x}, {y})"
+        );
+    }
+
+    /// Picks a uniformly random violated target cell. There must be one.
+    fn random_violation(&mut self) -> (i32, i32) {
+        let index = self.rng.gen_range(0..self.violations as u64);
+        // The count matches the set, so there is a violation at every index below it.
+        self.violated.iter().nth(index as usize).unwrap()
+    }
+
+    /// Makes one flip near a violated target cell: a random free cell with probability `noise`,
+    /// and otherwise the free cell whose flip leaves the fewest violations, breaking ties at random.
+    /// Does nothing if no cell near the chosen violation is free.
+    fn step(&mut self) {
+        let (x, y) = self.random_violation();
+        let candidates = self
+            .neighbourhood(x, y)
+            .filter(|&(cx, cy)| self.free.test(cx, cy))
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return;
+        }
+        let chosen = if self.rng.gen_bool(self.noise) {
+            candidates[self.rng.gen_range(0..candidates.len() as u64) as usize]
+        } else {
+            let mut best = candidates[0];
+            let mut best_delta = i32::MAX;
+            let mut ties = 0;
+            for &(cx, cy) in &candidates {
+                let delta = self.flip_delta(cx, cy);
+                if delta < best_delta {
+                    best = (cx, cy);
+                    best_delta = delta;
+                    ties = 1;
+                } else if delta == best_delta {
+                    // Keeps each of the tied candidates with equal probability.
+                    ties += 1;
+                    if self.rng.gen_range(0..ties) == 0 {
+                        best = (cx, cy);
+                    }
+                }
+            }
+            best
+        };
+        self.flip(chosen.0, chosen.1);
+    }
+
+    /// The current frame.
+    pub(crate) fn frame(&self) -> &Grid {
+        self.field.grid()
+    }
+
+    /// The target cells the current frame steps to the wrong value.
+    pub(crate) fn violated(&self) -> &BoolGrid {
+        &self.violated
+    }
+
+    /// How many target cells the current frame steps to the wrong value.
+    pub(crate) fn violations(&self) -> u32 {
+        self.violations
+    }
+
+    /// Flips cells for at most `max_iterations` iterations, one flip each,
+    /// until at most `max_violations` target cells are violated. Returns how many iterations it took.
+    pub(crate) fn flip_until(&mut self, max_violations: u32, max_iterations: usize) -> usize {
+        let mut iterations = 0;
+        while iterations < max_iterations && self.violations() > max_violations {
+            if self.flips >= self.restart_after {
+                debug!(
+                    "restarting local search with {} violations left",
+                    self.violations()
+                );
+                self.restart();
+                continue;
+            }
+            self.step();
+            self.flips += 1;
+            iterations += 1;
+            trace!(
+                "local search has {} violations after {} flips",
+                self.violations(),
+                self.flips
+            );
+        }
+        iterations
+    }
+
+    /// Checks that the current frame, which violates nothing, steps to the target, and starts somewhere new,
+    /// rather than finding the same predecessor again straight away.
+    pub(crate) fn take_predecessor(&mut self) -> Result<Grid, Error> {
+        let grid = self.field.grid().clone();
+        let mut stepped = grid.clone();
+        stepped.step();
+        if stepped != self.next {
+            return Err(Error::Solver(
+                "local search found a frame that does not step to the target".to_owned(),
+            ));
+        }
+        self.restart();
+        Ok(grid)
+    }
+
+    /// Flips cells for at most `max_iterations` iterations, one flip each, until no target cell is violated.
+    /// Never returns [`SearchResult::Unsatisfiable`], since local search proves nothing.
+    pub(crate) fn search(&mut self, max_iterations: usize) -> Result<SearchResult, Error> {
+        let iterations = self.flip_until(0, max_iterations);
+        if self.violations != 0 {
+            return Ok(SearchResult::Working(iterations));
+        }
+        Ok(SearchResult::Found(self.take_predecessor()?, iterations))
+    }
+}
@@ -1,76 +1,1527 @@
-#![feature(maybe_uninit_uninit_array, maybe_uninit_array_assume_init)]
-
 use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc, Mutex,
     },
-    time::SystemTime,
+    time::{Duration, Instant, SystemTime},
 };
 
 use chrono::{DateTime, Local};
-use grid::Grid;
-use rand::seq::SliceRandom;
+use clap::{CommandFactory, Parser, Subcommand};
+use log::{info, LevelFilter, Log, Metadata, Record};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
-use searcher::{SearchResult, Searcher};
-use string::large_number;
+use terminal_size::Width;
+use undeath::{
+    bruteforce,
+    evolution::EvolutionReport,
+    grid::{Anchor, Grid, LoadError, ParseGridError, Topology},
+    parallel::{enumerate_streaming, Dedup, Discovery, ParallelOptions},
+    replay::Replay,
+    rle::{self, Rle, RleError},
+    rng::{RandomSource, Rng},
+    search_log::{LogEvent, SearchLog},
+    searcher::{SearchResult, SearchStats, Searcher, Shaving, Strategy},
+    string::{
+        hconcat_all, highlight_diff_styled, human_duration, large_number, layout_blocks,
+        parse_duration, progress_bar, rate, sparkline, vconcat_all, ColumnAlignment, DiffStyle,
+        Table,
+    },
+    trace::{read_binary, TraceEvent},
+    verify::{verify_chain, verify_generations},
+    Error, Options, PredecessorOutcome, SolverSession,
+};
+
+/// Find a long chain of predecessors of a Game of Life pattern.
+#[derive(Parser)]
+struct Cli {
+    /// The most detailed log messages to print to stderr: off, error, warn, info, debug, or trace.
+    #[arg(long, default_value = "warn")]
+    log_level: LevelFilter,
+    /// The seed for all randomness, so that a run can be repeated exactly. Defaults to one based on the time.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Also propagate constraints over 2×2 blocks of cells. This prunes more, but is slower per guess.
+    #[arg(long)]
+    block_propagation: bool,
+    /// When to shave guesses, deciding whatever follows from both values of each undecided cell:
+    /// off, root, or periodic:K to also shave every K guesses deep.
+    #[arg(long, default_value = "off")]
+    shave: Shaving,
+    /// Stop the chain search once it has run for this long, such as `90s`, `10m` or `1h 30m`,
+    /// at the end of the macrostep it is in. A bare number is a number of seconds.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    timeout: Option<Duration>,
+    /// Before searching, check every K×K window of the target by brute force,
+    /// and give up straight away if one has no predecessor.
+    #[arg(long, value_name = "K", value_parser = clap::value_parser!(u64).range(1..=bruteforce::MAX_WINDOW as u64))]
+    check_windows: Option<u64>,
+    /// After each summary, print a heat map of how many contradictions the search detected at each cell,
+    /// which shows where it is struggling.
+    #[arg(long)]
+    heatmap: bool,
+    /// How to explore the tree of guesses: dfs, best-first (or best-first:N to keep at most N guesses open),
+    /// which can find each predecessor sooner at the cost of memory, beam:W, which keeps only the best W
+    /// guesses at each depth and may miss predecessors, local (or local:F to restart every F flips),
+    /// a randomized local search that suits dense targets but may find the same predecessor repeatedly,
+    /// or hybrid (or hybrid:K:R), which hands local search's frames with at most K wrong cells to dfs,
+    /// leaving cells within R of them undecided.
+    #[arg(long, default_value = "dfs")]
+    strategy: Strategy,
+    /// Append milestones of the search to this file as JSON lines, with timestamps: each search starting,
+    /// solutions, a progress snapshot every macrostep, and the outcome if the run ends.
+    /// Starts and solutions are only logged for depth-first search.
+    #[arg(long, value_name = "PATH")]
+    log_file: Option<PathBuf>,
+    /// Once the log file reaches this many megabytes, move it to PATH.1 and start a new one.
+    #[arg(long, default_value = "64", value_name = "MB")]
+    log_file_max_mb: u64,
+    /// Instead of searching for a chain, read targets from stdin and write one line to stdout for each,
+    /// saying whether it has a predecessor, in the order they were read.
+    /// A target that cannot be read gets an error line, and the rest are still solved.
+    #[arg(long)]
+    batch: bool,
+    /// How `--batch` targets are written: plain for the `.gol` format, with a blank line after each target,
+    /// so rows must not be empty, or rle for one run-length encoded pattern per line, without its header.
+    #[arg(long, default_value = "plain")]
+    batch_format: BatchFormat,
+    /// How `--batch` results are written: json for one JSON object per line, or compact for a status,
+    /// which is found, none, unknown or error, followed by the predecessor's RLE or the error.
+    #[arg(long, default_value = "json")]
+    batch_output: BatchOutput,
+    /// How many iterations `--batch` may spend on each target before reporting it as unknown.
+    #[arg(long, default_value = "10000000")]
+    max_iterations: usize,
+    /// Use a pattern built into the binary instead of reading one from a file, for the chain search,
+    /// `enumerate` and `info`. `list-demos` lists them.
+    #[arg(long, global = true, value_name = "NAME")]
+    demo: Option<Demo>,
+    /// Fold a pattern that is too large for the grid, or for its RLE header, back onto the grid
+    /// instead of failing, for the chain search, `enumerate` and `info`. The folded cells may overlap.
+    #[arg(long, global = true)]
+    allow_wrap: bool,
+    /// Read the pattern with at least this many dead cells around it on its grid, failing if there is no room,
+    /// for the chain search, `enumerate` and `info`.
+    #[arg(long, global = true, value_name = "N", conflicts_with_all = ["center", "allow_wrap"])]
+    margin: Option<u32>,
+    /// Move the pattern to the middle of its grid, for the chain search, `enumerate` and `info`.
+    #[arg(long, global = true, conflicts_with = "allow_wrap")]
+    center: bool,
+    /// Log the reasoning behind each contradiction found within this many guesses of the root, at debug level.
+    #[cfg(feature = "provenance")]
+    #[arg(long)]
+    trace_contradictions: Option<usize>,
+    /// What to do instead of searching for a chain.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the predecessors of a pattern, searching depth-first on several threads.
+    Enumerate {
+        /// The pattern to find the predecessors of.
+        #[arg(default_value = DEFAULT_PATTERN)]
+        pattern: PathBuf,
+        /// How many threads to search on. Defaults to one per core.
+        #[arg(long)]
+        threads: Option<usize>,
+        /// Stop after finding this many distinct predecessors.
+        #[arg(long)]
+        count: Option<usize>,
+        /// Which predecessors count as the same: exact, or canonical to also merge translations of each other.
+        #[arg(long, default_value = "exact")]
+        dedup: Dedup,
+        /// Where predecessors may have alive cells: all, or auto for the cells next to the pattern's alive cells.
+        #[arg(long, default_value = "all")]
+        region: Region,
+        /// How to print each predecessor: render for a drawing, coords for one `x y` line per alive cell
+        /// in row-major order, rle-inline for run-length encoding, or ndjson for one JSON object per line,
+        /// written as soon as each is found, then a summary object. Predecessors are separated by blank lines.
+        /// With any format but render, the summary goes to stderr, so stdout holds only the predecessors.
+        #[arg(long, default_value = "render")]
+        output_format: OutputFormat,
+    },
+    /// Describe how a pattern evolves forwards: how long it takes to settle into a cycle, its period, and its population.
+    Info {
+        /// The pattern to describe.
+        #[arg(default_value = DEFAULT_PATTERN)]
+        pattern: PathBuf,
+        /// How many generations ahead to look for a cycle before giving up.
+        #[arg(long, default_value = "10000")]
+        max_steps: usize,
+        /// Remember every generation in a hash map, which finds the cycle in fewer steps but uses more memory.
+        #[arg(long)]
+        hashed: bool,
+        /// Also search for a predecessor, reporting whether one was found, there is proven to be none, or neither is known.
+        #[arg(long)]
+        predecessor_check: bool,
+        /// How many iterations the predecessor check may take before giving up.
+        #[arg(long, default_value = "100000")]
+        predecessor_budget: usize,
+        /// Print one JSON object instead of a table.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Compare two patterns cell by cell, exiting with status 0 if they are the same and 1 if not.
+    Diff {
+        /// The pattern to compare, or with `--as-previous`, the one to step first.
+        a: PathBuf,
+        /// The pattern to compare it with.
+        b: PathBuf,
+        /// Step A once before comparing, to check whether it is a predecessor of B.
+        #[arg(long)]
+        as_previous: bool,
+        /// Move both patterns to the same place before comparing, so that only their shapes are compared:
+        /// top-left to put their bounding boxes at the origin, or center to centre them on the grid.
+        #[arg(long)]
+        align: Option<Align>,
+    },
+    /// Check a claim that one pattern evolves into another, without trusting the solver,
+    /// exiting with status 0 if it holds and 1 if not.
+    Verify {
+        /// The earlier pattern, or a directory of `.gol` files forming a chain, each a predecessor of the next
+        /// in the order of their names.
+        prev: PathBuf,
+        /// The pattern PREV should evolve into. Leave this out when PREV is a directory.
+        next: Option<PathBuf>,
+        /// How many generations PREV should take to become NEXT.
+        #[arg(long, default_value = "1")]
+        generations: usize,
+        /// The rule to step patterns with. Only Life, B3/S23, is supported.
+        #[arg(long, default_value = "B3/S23")]
+        rule: LifeRule,
+    },
+    /// Rewrite patterns in a canonical form, so that translations, rotations and reflections of one pattern
+    /// are written identically, and point out files that are the same pattern as an earlier one.
+    /// Each file's fingerprint before and after is reported.
+    Canon {
+        /// The patterns to rewrite.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Write each canonical form back over its file, rather than to stdout.
+        #[arg(long)]
+        in_place: bool,
+        /// How to write canonical forms: plain for the `.gol` format, or rle for run-length encoding.
+        #[arg(long, default_value = "plain")]
+        format: PatternFormat,
+        /// Only report, without writing anything, and exit with status 1 if any file is not already canonical.
+        #[arg(long, conflicts_with = "in_place")]
+        check: bool,
+    },
+    /// Watch a search recorded as a binary trace, redrawing the guess it was looking at after each of its moves.
+    /// When stdout is not a terminal, only the guess after the last move is printed.
+    /// Then each predecessor the search found is printed, with the move that found it.
+    Replay {
+        /// The binary trace to replay, as written by `trace::BinaryTrace`.
+        trace: PathBuf,
+        /// How fast to play the trace, as a multiple of ten moves a second, such as `10x`.
+        #[arg(long, default_value = "1x")]
+        speed: Speed,
+        /// Print the guess just after this move, counting the start of the search as move 0, instead of animating.
+        #[arg(long, value_name = "EVENT")]
+        at: Option<usize>,
+    },
+    /// List the patterns built into the binary, which `--demo NAME` uses instead of a file.
+    ListDemos,
+}
+
+/// A pattern built into the binary, so that the tool works from any directory.
+#[derive(Clone, Copy)]
+enum Demo {
+    Glider,
+    RPentomino,
+}
+
+impl Demo {
+    const ALL: [Demo; 2] = [Demo::Glider, Demo::RPentomino];
+
+    fn name(self) -> &'static str {
+        match self {
+            Demo::Glider => "glider",
+            Demo::RPentomino => "r-pentomino",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Demo::Glider => {
+                "the smallest spaceship, moving one cell diagonally every four generations"
+            }
+            Demo::RPentomino => {
+                "five cells that take over a thousand generations to settle on an infinite plane"
+            }
+        }
+    }
+
+    fn grid(self) -> Grid {
+        Grid::from_text(match self {
+            Demo::Glider => include_str!("../glider.gol"),
+            Demo::RPentomino => include_str!("../rpentomino.gol"),
+        })
+    }
+}
+
+impl FromStr for Demo {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Demo::ALL
+            .into_iter()
+            .find(|demo| demo.name() == s)
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "expected `glider` or `r-pentomino`, but found `{s}`"
+                ))
+            })
+    }
+}
+
+/// Where the `enumerate` subcommand looks for predecessors.
+#[derive(Clone, Copy)]
+enum Region {
+    /// Anywhere on the grid.
+    All,
+    /// Only next to the target's alive cells, as [`Grid::suggested_mask`] says.
+    Auto,
+}
+
+impl FromStr for Region {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Region::All),
+            "auto" => Ok(Region::Auto),
+            _ => Err(Error::InvalidInput(format!(
+                "expected `all` or `auto`, but found `{s}`"
+            ))),
+        }
+    }
+}
+
+/// How the `enumerate` subcommand prints each predecessor.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// As [`Grid::render`] draws it.
+    Render,
+    /// One `x y` line for each alive cell, for other programs to read.
+    Coords,
+    /// As [`Grid::to_rle`] encodes it, after a comment saying where it came from.
+    RleInline,
+    /// One JSON object per line, written as soon as each predecessor is found, then a summary object.
+    Ndjson,
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "render" => Ok(OutputFormat::Render),
+            "coords" => Ok(OutputFormat::Coords),
+            "rle-inline" => Ok(OutputFormat::RleInline),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            _ => Err(Error::InvalidInput(format!(
+                "expected `render`, `coords`, `rle-inline` or `ndjson`, but found `{s}`"
+            ))),
+        }
+    }
+}
+
+/// How `--batch` targets are read from stdin.
+#[derive(Clone, Copy)]
+enum BatchFormat {
+    /// `.gol` text, with each target ended by a blank line.
+    Plain,
+    /// One RLE body per line.
+    Rle,
+}
+
+impl FromStr for BatchFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(BatchFormat::Plain),
+            "rle" => Ok(BatchFormat::Rle),
+            _ => Err(Error::InvalidInput(format!(
+                "expected `plain` or `rle`, but found `{s}`"
+            ))),
+        }
+    }
+}
 
-pub mod grid;
-pub mod guess;
-pub mod searcher;
-pub mod string;
+/// How `--batch` results are written to stdout.
+#[derive(Clone, Copy)]
+enum BatchOutput {
+    /// One JSON object per target.
+    Json,
+    /// A status word per target, then the predecessor's RLE body or the error message.
+    Compact,
+}
+
+impl FromStr for BatchOutput {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(BatchOutput::Json),
+            "compact" => Ok(BatchOutput::Compact),
+            _ => Err(Error::InvalidInput(format!(
+                "expected `json` or `compact`, but found `{s}`"
+            ))),
+        }
+    }
+}
+
+/// Where the `diff` subcommand moves both patterns to before comparing them.
+#[derive(Clone, Copy)]
+enum Align {
+    TopLeft,
+    Center,
+}
+
+impl FromStr for Align {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top-left" => Ok(Align::TopLeft),
+            "center" => Ok(Align::Center),
+            _ => Err(Error::InvalidInput(format!(
+                "expected `top-left` or `center`, but found `{s}`"
+            ))),
+        }
+    }
+}
+
+/// How the `canon` subcommand writes patterns.
+#[derive(Clone, Copy)]
+enum PatternFormat {
+    /// As [`Grid::to_text`] writes it.
+    Plain,
+    /// As [`Grid::to_rle`] encodes it.
+    Rle,
+}
+
+impl FromStr for PatternFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(PatternFormat::Plain),
+            "rle" => Ok(PatternFormat::Rle),
+            _ => Err(Error::InvalidInput(format!(
+                "expected `plain` or `rle`, but found `{s}`"
+            ))),
+        }
+    }
+}
+
+/// The rule named by `verify --rule`, which can only be Life's.
+#[derive(Clone, Copy)]
+struct LifeRule;
+
+impl FromStr for LifeRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "B3/S23" | "23/3" | "LIFE" => Ok(LifeRule),
+            _ => Err(Error::InvalidInput(format!(
+                "only Life, B3/S23, is supported, but found `{s}`"
+            ))),
+        }
+    }
+}
+
+/// How fast the `replay` subcommand plays a trace, as a multiple of [`Speed::BASE_RATE`].
+#[derive(Clone, Copy)]
+struct Speed(f64);
 
-pub struct Sequence {
+impl Speed {
+    /// How many moves a second are played at `1x`.
+    const BASE_RATE: f64 = 10.0;
+
+    /// How long to show each move for.
+    fn frame_time(self) -> Duration {
+        Duration::from_secs_f64(1.0 / (Self::BASE_RATE * self.0))
+    }
+}
+
+impl FromStr for Speed {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_suffix('x').unwrap_or(s).parse::<f64>() {
+            Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(Speed(speed)),
+            _ => Err(Error::InvalidInput(format!(
+                "expected a positive speed such as `10x`, but found `{s}`"
+            ))),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Formats `grid`, ending with a newline.
+    fn format(self, grid: &Grid) -> String {
+        match self {
+            OutputFormat::Render => grid.render() + "\n",
+            OutputFormat::Coords => grid
+                .alive_cells()
+                .map(|(x, y)| format!("{x} {y}\n"))
+                .collect(),
+            OutputFormat::RleInline => Rle::solution(grid.clone()).to_string(),
+            OutputFormat::Ndjson => unreachable!("NDJSON is written as each predecessor is found"),
+        }
+    }
+}
+
+/// The NDJSON line for a predecessor found by `enumerate`.
+fn ndjson_predecessor(discovery: &Discovery) -> String {
+    format!(
+        "{{\"event\":\"predecessor\",\"index\":{},\"population\":{},\"rle\":\"{}\",\"iterations\":{},\"elapsed_ms\":{}}}",
+        discovery.index,
+        discovery.predecessor.alive_cells().count(),
+        // RLE has no characters that need escaping apart from its line breaks.
+        discovery.predecessor.to_rle().replace('\n', "\\n"),
+        discovery.iterations,
+        discovery.elapsed.as_millis()
+    )
+}
+
+/// Writes log messages to stderr.
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+struct Sequence {
     grids: Vec<Grid>,
     searcher: Searcher,
 }
 
-fn main() {
+fn main() -> ExitCode {
+    // With no arguments at all, the chain search starts from `glider.gol` in the current directory.
+    // Without one, explain how to use the tool rather than failing to read it.
+    if std::env::args_os().len() == 1 && !Path::new(DEFAULT_PATTERN).exists() {
+        // Printing help only fails if stdout is closed, and then there is no one to tell.
+        let _ = Cli::command().print_help();
+        println!(
+            "\nThere is no {DEFAULT_PATTERN} here to search from. \
+             Try `undeath --demo glider`, or `undeath list-demos` for the built-in patterns."
+        );
+        // `EX_USAGE` from `sysexits.h`.
+        return ExitCode::from(64);
+    }
+    let cli = Cli::parse();
+    // This only fails if a logger has already been set, and nothing else sets one.
+    log::set_logger(&StderrLogger).expect("the logger is only set once");
+    log::set_max_level(cli.log_level);
+
+    let seed = cli.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+    });
+    let result = match &cli.command {
+        Some(Command::Enumerate {
+            pattern,
+            threads,
+            count,
+            dedup,
+            region,
+            output_format,
+        }) => load_pattern(pattern, &cli).and_then(|target| {
+            run_enumerate(
+                target,
+                *threads,
+                *count,
+                *dedup,
+                *region,
+                *output_format,
+                cli.heatmap,
+            )
+        }),
+        Some(Command::Info {
+            pattern,
+            max_steps,
+            hashed,
+            predecessor_check,
+            predecessor_budget,
+            json,
+        }) => load_pattern(pattern, &cli).and_then(|grid| {
+            run_info(
+                grid,
+                *max_steps,
+                *hashed,
+                predecessor_check.then_some(*predecessor_budget),
+                *json,
+            )
+        }),
+        _ if cli.demo.is_some() && (cli.command.is_some() || cli.batch) => {
+            Err(Error::InvalidInput(
+                "`--demo` only replaces the pattern of the chain search, `enumerate` and `info`"
+                    .to_owned(),
+            ))
+        }
+        Some(Command::Diff {
+            a,
+            b,
+            as_previous,
+            align,
+        }) => match run_diff(a, b, *as_previous, *align) {
+            // Like `diff` and `cmp`, patterns that differ are not an error, but are reported in the exit status.
+            Ok(false) => return ExitCode::from(1),
+            result => result.map(|_| ()),
+        },
+        Some(Command::Verify {
+            prev,
+            next,
+            generations,
+            rule: LifeRule,
+        }) => match run_verify(prev, next.as_ref(), *generations) {
+            Ok(false) => return ExitCode::from(1),
+            result => result.map(|_| ()),
+        },
+        Some(Command::Canon {
+            files,
+            in_place,
+            format,
+            check,
+        }) => match run_canon(files, *in_place, *format, *check) {
+            Ok(false) => return ExitCode::from(1),
+            result => result.map(|_| ()),
+        },
+        Some(Command::ListDemos) => {
+            run_list_demos();
+            Ok(())
+        }
+        Some(Command::Replay { trace, speed, at }) => run_replay(trace, *speed, *at),
+        None if cli.batch => run_batch(Rng::new(seed), &cli),
+        None => run(Rng::new(seed), &cli),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            // Exit codes follow the conventions of `sysexits.h`.
+            ExitCode::from(match error {
+                Error::Io { .. } => 74,
+                Error::Parse { .. } | Error::InvalidInput(_) => 65,
+                Error::Solver(_) => 70,
+                Error::BudgetExhausted { .. } => 75,
+                _ => 1,
+            })
+        }
+    }
+}
+
+/// The pattern the chain search starts from, and `enumerate` and `info` describe, unless told otherwise.
+const DEFAULT_PATTERN: &str = "glider.gol";
+
+/// Reads the pattern at `path`, or uses the built-in `--demo` pattern if there is one.
+/// The pattern gets `--margin` cells of room around it, is moved to the middle of its grid with `--center`,
+/// or is folded onto the grid if it is too large and `--allow-wrap` is set.
+/// If the file does not exist, the error suggests `--demo`, and if the pattern is too large,
+/// the ways to place a pattern, then `--allow-wrap`.
+fn load_pattern(path: &Path, cli: &Cli) -> Result<Grid, Error> {
+    if let Some(demo) = cli.demo {
+        return Ok(demo.grid());
+    }
+    let grid = if let Some(margin) = cli.margin {
+        // The margin goes around the pattern on the grid the file gives, so read it once to find that grid.
+        Grid::from_file(path)
+            .and_then(|grid| Grid::from_file_with_margin(path, margin, grid.width(), grid.height()))
+    } else if cli.center {
+        Grid::from_file_centered(path).map(|(grid, (x, y))| {
+            info!("moved the pattern by ({x}, {y}) to centre it");
+            grid
+        })
+    } else {
+        Grid::from_file_with_wrap(path, cli.allow_wrap)
+    };
+    grid.map_err(|error| match error {
+        LoadError::Io { path, source } if source.kind() == std::io::ErrorKind::NotFound => Error::io(
+            path,
+            std::io::Error::new(
+                source.kind(),
+                format!("{source}; to use a built-in pattern instead, pass `--demo NAME`, as listed by `undeath list-demos`"),
+            ),
+        ),
+        error @ (LoadError::Parse {
+            source: ParseGridError::LineTooLong { .. } | ParseGridError::TooManyRows { .. },
+            ..
+        }
+        | LoadError::Rle {
+            source: RleError::TooLarge { .. } | RleError::OutOfBounds { .. },
+            ..
+        }) => {
+            let hint = "a pattern that fits can be given room with `--margin N` or moved to the middle of its grid \
+                        with `--center`; to fold this one onto the grid instead, pass `--allow-wrap`";
+            match error.into() {
+                Error::Parse {
+                    line,
+                    column,
+                    message,
+                } => Error::Parse {
+                    line,
+                    column,
+                    message: format!("{message}; {hint}"),
+                },
+                Error::InvalidInput(message) => Error::InvalidInput(format!("{message}; {hint}")),
+                error => error,
+            }
+        }
+        error => error.into(),
+    })
+}
+
+/// Prints the name and a description of each built-in pattern.
+fn run_list_demos() {
+    let mut table = Table::new();
+    for demo in Demo::ALL {
+        table.add_row([demo.name(), demo.description()]);
+    }
+    println!("{}", table.render());
+}
+
+fn run_enumerate(
+    target: Grid,
+    threads: Option<usize>,
+    count: Option<usize>,
+    dedup: Dedup,
+    region: Region,
+    output_format: OutputFormat,
+    heatmap: bool,
+) -> Result<(), Error> {
+    let defaults = ParallelOptions::default();
+    let options = ParallelOptions {
+        threads: threads.unwrap_or(defaults.threads),
+        limit: count,
+        dedup,
+        mask: match region {
+            Region::All => None,
+            Region::Auto => Some(target.suggested_mask()),
+        },
+        ..defaults
+    };
+    let start_time = Instant::now();
+    // Canonical predecessors step to a translation of the target, so they are checked against its canonical form.
+    let canonical_target = target.canonical();
+    let mut stdout = std::io::stdout();
+    let mut streaming_error = None;
+    let enumeration = enumerate_streaming(&target, &options, |discovery| {
+        if output_format != OutputFormat::Ndjson || streaming_error.is_some() {
+            return;
+        }
+        // Each line is checked before it is written, since other programs act on it straight away.
+        let mut stepped = discovery.predecessor.clone();
+        stepped.step();
+        let valid = match dedup {
+            Dedup::Exact => stepped == target,
+            Dedup::Canonical => stepped.canonical() == canonical_target,
+        };
+        if !valid {
+            streaming_error = Some(Error::Solver(format!(
+                "found an invalid predecessor:\n{}",
+                discovery.predecessor.render()
+            )));
+            return;
+        }
+        streaming_error = writeln!(stdout, "{}", ndjson_predecessor(&discovery))
+            .and_then(|()| stdout.flush())
+            .map_err(|e| Error::io("stdout", e))
+            .err();
+    })?;
+    if let Some(error) = streaming_error {
+        return Err(error);
+    }
+    if output_format == OutputFormat::Ndjson {
+        println!(
+            "{{\"event\":\"summary\",\"count\":{},\"duplicates\":{},\"exhausted\":{},\"iterations\":{},\"elapsed_ms\":{}}}",
+            enumeration.predecessors.len(),
+            enumeration.duplicates,
+            enumeration.exhausted,
+            enumeration.iterations,
+            start_time.elapsed().as_millis()
+        );
+    } else {
+        for (index, predecessor) in enumeration.predecessors.iter().enumerate() {
+            if index > 0 && output_format != OutputFormat::Render {
+                println!();
+            }
+            print!("{}", output_format.format(predecessor));
+        }
+    }
+    // Only drawings share stdout with the summary; other formats keep it for other programs to read.
+    let summary = |text: String| {
+        if output_format == OutputFormat::Render {
+            print!("{text}");
+        } else {
+            eprint!("{text}");
+        }
+    };
+    summary(format!(
+        "{} distinct predecessors, and {} duplicates, in {} iterations and {}.\n",
+        large_number(enumeration.predecessors.len()),
+        large_number(enumeration.duplicates),
+        large_number(enumeration.iterations),
+        human_duration(start_time.elapsed())
+    ));
+    summary(
+        match (enumeration.exhausted, region) {
+            (true, Region::All) => "That is every predecessor.\n",
+            (true, Region::Auto) => "That is every predecessor within the region.\n",
+            (false, _) => "The search stopped early, so there may be more.\n",
+        }
+        .to_owned(),
+    );
+    if heatmap {
+        summary(format!(
+            "Contradictions at each cell:\n{}",
+            enumeration.heatmap.render_heatmap()
+        ));
+    }
+    Ok(())
+}
+
+/// How a pattern behaves as it evolves, as the `info` subcommand reports it.
+enum Behaviour {
+    /// It dies out in this many generations, which is zero if it is already empty.
+    DiesOut(usize),
+    StillLife,
+    /// It returns to itself after this many generations.
+    Oscillator(usize),
+    /// It returns to a translation of itself after this many generations.
+    Spaceship(usize),
+    /// After `transient` generations, it repeats every `period` generations.
+    Settles {
+        transient: usize,
+        period: usize,
+    },
+    /// No cycle was found within the step limit.
+    Unknown,
+}
+
+impl Behaviour {
+    fn of(grid: &Grid, report: &EvolutionReport) -> Self {
+        if let Some(generation) = report.died_at {
+            return Behaviour::DiesOut(generation);
+        }
+        match (report.transient_len, report.period) {
+            (Some(0), Some(1)) => Behaviour::StillLife,
+            (Some(0), Some(period)) => {
+                // On the torus, a spaceship comes back exactly once it has gone all the way round,
+                // but comes back translated much sooner.
+                let canonical = grid.canonical();
+                let mut current = grid.clone();
+                for generation in 1..period {
+                    current.step();
+                    if current.canonical() == canonical {
+                        return Behaviour::Spaceship(generation);
+                    }
+                }
+                Behaviour::Oscillator(period)
+            }
+            (Some(transient), Some(period)) => Behaviour::Settles { transient, period },
+            _ => Behaviour::Unknown,
+        }
+    }
+
+    /// The kind of behaviour, as a short name.
+    fn name(&self) -> &'static str {
+        match self {
+            Behaviour::DiesOut(_) => "dies out",
+            Behaviour::StillLife => "still life",
+            Behaviour::Oscillator(_) => "oscillator",
+            Behaviour::Spaceship(_) => "spaceship",
+            Behaviour::Settles { .. } => "settles",
+            Behaviour::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for Behaviour {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Behaviour::DiesOut(0) => write!(f, "empty"),
+            Behaviour::DiesOut(generation) => write!(f, "dies out at generation {generation}"),
+            Behaviour::StillLife => write!(f, "still life"),
+            Behaviour::Oscillator(period) => write!(f, "oscillator with period {period}"),
+            Behaviour::Spaceship(period) => write!(f, "spaceship with period {period}"),
+            Behaviour::Settles { transient, period } => write!(
+                f,
+                "settles after {transient} generations into a cycle of period {period}"
+            ),
+            Behaviour::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+fn run_info(
+    grid: Grid,
+    max_steps: usize,
+    hashed: bool,
+    predecessor_budget: Option<usize>,
+    json: bool,
+) -> Result<(), Error> {
+    let report = if hashed {
+        grid.evolution_report_hashed(max_steps)
+    } else {
+        grid.evolution_report(max_steps)
+    };
+    let stats = grid.stats();
+    let symmetries = grid.symmetries();
+    let behaviour = Behaviour::of(&grid, &report);
+    let predecessor = predecessor_budget
+        .map(|max_iterations| {
+            match SolverSession::new().solve(&grid, &Options { max_iterations }) {
+                Ok(Some(parent)) => Ok(PredecessorOutcome::Found(parent)),
+                Ok(None) => Ok(PredecessorOutcome::ProvenNone),
+                Err(Error::BudgetExhausted { .. }) => Ok(PredecessorOutcome::Unknown),
+                Err(error) => Err(error),
+            }
+        })
+        .transpose()?;
+    let predecessor = predecessor.map(|outcome| match outcome {
+        PredecessorOutcome::Found(_) => "found",
+        PredecessorOutcome::ProvenNone => "proven none",
+        PredecessorOutcome::Unknown => "unknown",
+    });
+    let (width, height) = stats.bounding_box;
+
+    if json {
+        let or_null = |value: Option<String>| value.unwrap_or_else(|| "null".to_owned());
+        let mut fields = vec![
+            format!("\"width\":{},\"height\":{}", grid.width(), grid.height()),
+            format!("\"population\":{}", stats.population),
+            format!("\"density\":{}", stats.density),
+            format!(
+                "\"centroid\":{}",
+                or_null(stats.centroid.map(|(x, y)| format!("[{x},{y}]")))
+            ),
+            format!(
+                "\"spread\":{}",
+                or_null(stats.spread.map(|spread| spread.to_string()))
+            ),
+            format!("\"bounding_box\":[{width},{height}]"),
+            format!("\"components\":{}", stats.components),
+            format!(
+                "\"symmetries\":[{}]",
+                symmetries
+                    .iter()
+                    .map(|symmetry| format!("\"{symmetry}\""))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            format!(
+                "\"transient\":{}",
+                or_null(report.transient_len.map(|transient| transient.to_string()))
+            ),
+            format!(
+                "\"period\":{}",
+                or_null(report.period.map(|period| period.to_string()))
+            ),
+            format!("\"min_population\":{}", report.min_population),
+            format!("\"max_population\":{}", report.max_population),
+            format!(
+                "\"died_at\":{}",
+                or_null(report.died_at.map(|generation| generation.to_string()))
+            ),
+            format!("\"behaviour\":\"{}\"", behaviour.name()),
+        ];
+        if let Behaviour::Oscillator(period) | Behaviour::Spaceship(period) = behaviour {
+            fields.push(format!("\"behaviour_period\":{period}"));
+        }
+        if let Some(predecessor) = predecessor {
+            fields.push(format!("\"predecessor\":\"{predecessor}\""));
+        }
+        println!("{{{}}}", fields.join(","));
+        return Ok(());
+    }
+
+    let or_unknown = |value: Option<usize>| value.map_or("unknown".to_string(), large_number);
+    let mut table = Table::new();
+    table
+        .align(1, ColumnAlignment::Right)
+        .add_row(["Grid", &format!("{}×{}", grid.width(), grid.height())])
+        .add_row(["Population", &large_number(stats.population)])
+        .add_row(["Density", &format!("{:.1}%", stats.density * 100.0)])
+        .add_row([
+            "Centroid",
+            &stats
+                .centroid
+                .map_or("none".to_string(), |(x, y)| format!("({x:.2}, {y:.2})")),
+        ])
+        .add_row([
+            "Spread",
+            &stats
+                .spread
+                .map_or("none".to_string(), |spread| format!("{spread:.2}")),
+        ])
+        .add_row(["Bounding box", &format!("{width}×{height}")])
+        .add_row(["Components", &large_number(stats.components)])
+        .add_row([
+            "Symmetries",
+            &if symmetries.is_empty() {
+                "none".to_string()
+            } else {
+                symmetries
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+        ])
+        .add_row(["Behaviour", &behaviour.to_string()])
+        .add_row(["Transient", &or_unknown(report.transient_len)])
+        .add_row(["Period", &or_unknown(report.period)])
+        .add_row(["Smallest population", &large_number(report.min_population)])
+        .add_row(["Largest population", &large_number(report.max_population)])
+        .add_row([
+            "Died at",
+            &match (report.died_at, report.period) {
+                (Some(generation), _) => large_number(generation),
+                (None, Some(_)) => "never".to_string(),
+                (None, None) => "unknown".to_string(),
+            },
+        ]);
+    if let Some(predecessor) = predecessor {
+        table.add_row(["Predecessor", predecessor]);
+    }
+    println!("{}", table.render());
+    if report.period.is_none() {
+        println!("No cycle was found within {max_steps} generations, so the populations only cover those.");
+    }
+    Ok(())
+}
+
+/// Compares the patterns in `a` and `b`, printing both and where they differ, and returns whether they are the same.
+fn run_diff(
+    a: &PathBuf,
+    b: &PathBuf,
+    as_previous: bool,
+    align: Option<Align>,
+) -> Result<bool, Error> {
+    let mut first = Grid::from_file(a)?;
+    let mut second = Grid::from_file(b)?;
+    if !first.same_size(&second) {
+        return Err(Error::InvalidInput(format!(
+            "{} is {}×{}, but {} is {}×{}",
+            a.display(),
+            first.width(),
+            first.height(),
+            b.display(),
+            second.width(),
+            second.height()
+        )));
+    }
+    if as_previous {
+        first.step();
+    }
+    if let Some(align) = align {
+        let anchor = match align {
+            Align::TopLeft => Anchor::TopLeft,
+            Align::Center => Anchor::Center,
+        };
+        for grid in [&mut first, &mut second] {
+            // Nothing can be cut off when the rectangle is the whole grid.
+            *grid = grid
+                .reembed(grid.width(), grid.height(), anchor)
+                .expect("the whole grid holds any pattern");
+        }
+    }
+    let first_name = if as_previous {
+        format!("{} stepped once", a.display())
+    } else {
+        a.display().to_string()
+    };
+    println!("{first_name}, then {}:", b.display());
+    println!(
+        "{}",
+        hconcat_all(&[&first.render(), &second.render()], "   ")
+    );
+    let style = if std::io::stdout().is_terminal() {
+        DiffStyle::Ansi
+    } else {
+        DiffStyle::Brackets
+    };
+    let highlighted = highlight_diff_styled(&first.render(), &second.render(), style);
+    // The last line counts differing characters, but cells are what matter here.
+    let (highlighted, _) = highlighted
+        .rsplit_once('\n')
+        .expect("a rendered grid has several lines");
+    let mismatched = first.xor(&second).alive_cells().collect::<Vec<_>>();
+    if mismatched.is_empty() {
+        if as_previous {
+            println!("{} is a predecessor of {}.", a.display(), b.display());
+        } else {
+            println!("The patterns are the same.");
+        }
+        return Ok(true);
+    }
+    println!("{}, with the cells that differ highlighted:", b.display());
+    println!("{highlighted}");
+    println!(
+        "{} cells differ: {}.",
+        large_number(mismatched.len()),
+        mismatched
+            .iter()
+            .map(|(x, y)| format!("({x}, {y})"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(false)
+}
+
+/// Checks that `prev` becomes `next` after `generations` steps, or if `prev` is a directory,
+/// that the chain of patterns in it is valid, and returns whether the claim holds.
+fn run_verify(prev: &PathBuf, next: Option<&PathBuf>, generations: usize) -> Result<bool, Error> {
+    let result = if prev.is_dir() {
+        if next.is_some() {
+            return Err(Error::InvalidInput(
+                "a chain is checked on its own, without a next pattern".to_owned(),
+            ));
+        }
+        let mut paths = std::fs::read_dir(prev)
+            .map_err(|e| Error::io(prev, e))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::io(prev, e))?;
+        paths.retain(|path| path.extension().is_some_and(|extension| extension == "gol"));
+        paths.sort();
+        if paths.len() < 2 {
+            return Err(Error::InvalidInput(format!(
+                "a chain needs at least two `.gol` files, but {} has {}",
+                prev.display(),
+                paths.len()
+            )));
+        }
+        let frames = paths
+            .iter()
+            .map(Grid::from_file)
+            .collect::<Result<Vec<_>, _>>()?;
+        for (index, path) in paths.iter().enumerate() {
+            println!("Frame {index}: {}", path.display());
+        }
+        verify_chain(&frames)
+    } else {
+        let Some(next) = next else {
+            return Err(Error::InvalidInput(
+                "a pattern needs a next pattern to be checked against".to_owned(),
+            ));
+        };
+        verify_generations(
+            &Grid::from_file(prev)?,
+            &Grid::from_file(next)?,
+            generations,
+        )
+    };
+    match result {
+        Ok(()) => {
+            println!("The claim holds.");
+            Ok(true)
+        }
+        Err(error) => {
+            println!("The claim is false: {error}");
+            Ok(false)
+        }
+    }
+}
+
+/// A short hash of the size, topology and alive cells of `grid`, to tell patterns apart at a glance.
+fn fingerprint(grid: &Grid) -> String {
+    // FNV-1a, which is stable across runs and platforms, unlike the standard library's hasher.
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    let topology = match grid.topology() {
+        Topology::Torus => 0,
+        Topology::Plane => 1,
+    };
+    let header = [grid.width(), grid.height(), topology];
+    for value in header
+        .into_iter()
+        .chain(grid.alive_cells().flat_map(|(x, y)| [x, y]))
+    {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+/// Rewrites each of `files` in its canonical form, and returns whether every file was already canonical.
+fn run_canon(
+    files: &[PathBuf],
+    in_place: bool,
+    format: PatternFormat,
+    check: bool,
+) -> Result<bool, Error> {
+    // Patterns written to stdout keep it to themselves, so the report goes to stderr.
+    let to_stdout = !in_place && !check;
+    let report = |line: String| {
+        if to_stdout {
+            eprintln!("{line}");
+        } else {
+            println!("{line}");
+        }
+    };
+    let mut seen = HashMap::<Grid, &PathBuf>::new();
+    let mut all_canonical = true;
+    for (index, path) in files.iter().enumerate() {
+        let original = std::fs::read(path).map_err(|e| Error::io(path, e))?;
+        let grid = Grid::from_bytes(&original)?;
+        let (canonical, _) = grid.canonical_symmetric().trimmed();
+        let written = match format {
+            PatternFormat::Plain => canonical.to_text(),
+            PatternFormat::Rle => canonical.to_rle(),
+        };
+        let already = original == written.as_bytes();
+        all_canonical &= already;
+        let mut line = format!(
+            "{}: {} → {}",
+            path.display(),
+            fingerprint(&grid),
+            fingerprint(&canonical)
+        );
+        if already {
+            line.push_str(", already canonical");
+        }
+        match seen.get(&canonical) {
+            Some(earlier) => line.push_str(&format!(", the same pattern as {}", earlier.display())),
+            None => {
+                seen.insert(canonical, path);
+            }
+        }
+        report(line);
+        if in_place {
+            if !already {
+                std::fs::write(path, &written).map_err(|e| Error::io(path, e))?;
+            }
+        } else if !check {
+            if index > 0 {
+                println!();
+            }
+            print!("{written}");
+        }
+    }
+    Ok(!check || all_canonical)
+}
+
+/// The outcome of solving one `--batch` target, written as one line in `format`.
+fn batch_record(
+    index: usize,
+    result: Result<Option<Grid>, Error>,
+    decisions: u64,
+    format: BatchOutput,
+) -> String {
+    let (status, detail) = match &result {
+        Ok(Some(predecessor)) => {
+            // Batch records are one line each, and the grid's size is already known, so the header is left out.
+            // The whole grid is written, rather than the bounding box, to keep the predecessor where it is.
+            let body = rle::encode_body(
+                predecessor,
+                (0, 0),
+                (predecessor.width(), predecessor.height()),
+            )
+            .replace('\n', "");
+            ("found", Some(body))
+        }
+        Ok(None) => ("none", None),
+        Err(Error::BudgetExhausted { .. }) => ("unknown", None),
+        Err(error) => ("error", Some(error.to_string().replace('\n', " "))),
+    };
+    match format {
+        BatchOutput::Compact => match detail {
+            Some(detail) => format!("{status} {detail}"),
+            None => status.to_owned(),
+        },
+        BatchOutput::Json => {
+            let detail = match (&result, detail) {
+                (Ok(_), Some(rle)) => format!(",\"rle\":\"{rle}\""),
+                (_, Some(message)) => format!(
+                    ",\"message\":\"{}\"",
+                    message.replace('\\', "\\\\").replace('"', "\\\"")
+                ),
+                (_, None) => String::new(),
+            };
+            format!(
+                "{{\"index\":{index},\"status\":\"{status}\"{detail},\"decisions\":{decisions}}}"
+            )
+        }
+    }
+}
+
+fn run_batch(rng: Rng, cli: &Cli) -> Result<(), Error> {
+    let searcher = Searcher::new(Grid::default())
+        .block_propagation(cli.block_propagation)
+        .shave(cli.shave)
+        .strategy(cli.strategy)
+        .rng(rng.derive_stream("search"));
+    let mut session = SolverSession::with_searcher(searcher);
+    let options = Options {
+        max_iterations: cli.max_iterations,
+    };
+    let mut stdout = std::io::stdout();
+    let mut index = 0;
+    let mut solve = |target: Result<Grid, Error>| {
+        let result = target.and_then(|target| session.solve(&target, &options));
+        let decisions = match &result {
+            Err(Error::Parse { .. }) => 0,
+            _ => session.stats().decisions,
+        };
+        let record = batch_record(index, result, decisions, cli.batch_output);
+        index += 1;
+        // Each record is flushed as soon as it is written, so that other programs can act on it straight away.
+        writeln!(stdout, "{record}")
+            .and_then(|()| stdout.flush())
+            .map_err(|e| Error::io("stdout", e))
+    };
+    let mut plain = Vec::new();
+    for (number, line) in std::io::stdin().lock().split(b'\n').enumerate() {
+        let line = line.map_err(|e| Error::io("stdin", e))?;
+        let blank = line.iter().all(u8::is_ascii_whitespace);
+        match cli.batch_format {
+            BatchFormat::Plain if blank => {
+                if !plain.is_empty() {
+                    solve(Grid::from_bytes(&plain))?;
+                    plain.clear();
+                }
+            }
+            BatchFormat::Plain => {
+                plain.extend_from_slice(&line);
+                plain.push(b'\n');
+            }
+            BatchFormat::Rle => {
+                // Headers and comments are skipped, since every target is on the same grid.
+                let body = String::from_utf8_lossy(&line);
+                let body = body.trim();
+                if !blank && !body.starts_with('#') && !body.starts_with('x') {
+                    let target =
+                        Rle::parse(body)
+                            .map(|rle| rle.grid)
+                            .map_err(|error| match error {
+                                Error::Parse {
+                                    column, message, ..
+                                } => Error::Parse {
+                                    line: number + 1,
+                                    column,
+                                    message,
+                                },
+                                error => error,
+                            });
+                    solve(target)?;
+                }
+            }
+        }
+    }
+    if !plain.is_empty() {
+        solve(Grid::from_bytes(&plain))?;
+    }
+    Ok(())
+}
+
+/// A line describing the move at `index` in a trace of `len` events.
+fn describe_move(index: usize, len: usize, event: &TraceEvent) -> String {
+    let description = match *event {
+        TraceEvent::Start { .. } => "the search starts".to_owned(),
+        TraceEvent::Decide {
+            depth,
+            x,
+            y,
+            alive,
+            contradiction,
+            ..
+        } => format!(
+            "at depth {depth}, guess ({x}, {y}) is {}{}",
+            if alive { "alive" } else { "dead" },
+            if contradiction {
+                ", which is a contradiction"
+            } else {
+                ""
+            }
+        ),
+        TraceEvent::Backtrack { depth, .. } => format!("backtrack to depth {depth}"),
+        TraceEvent::Solution { depth, .. } => format!("a predecessor is found at depth {depth}"),
+    };
+    format!("Move {index} of {}: {description}.", len - 1)
+}
+
+fn run_replay(trace: &PathBuf, speed: Speed, at: Option<usize>) -> Result<(), Error> {
+    let file = File::open(trace).map_err(|e| Error::io(trace, e))?;
+    let replay = Replay::new(read_binary(BufReader::new(file))?)?;
+    let events = replay.events();
+    if let Some(index) = at {
+        let state = replay.state_at(index)?;
+        println!("{}", describe_move(index, events.len(), &events[index]));
+        println!("{}", state.render());
+        return Ok(());
+    }
+    let animate = std::io::stdout().is_terminal();
+    let mut stdout = std::io::stdout();
+    let mut frame = String::new();
+    let mut last = None;
+    let mut solutions = Vec::new();
+    for (index, state) in replay.states().enumerate() {
+        let state = state?;
+        if let TraceEvent::Solution { .. } = events[index] {
+            solutions.push((index, state.alive()));
+        }
+        if animate {
+            frame.clear();
+            // Move to the top left and clear the screen, then draw over it.
+            frame.push_str("\x1b[H\x1b[2J");
+            frame.push_str(&describe_move(index, events.len(), &events[index]));
+            frame.push('\n');
+            state.render_into(&mut frame);
+            frame.push('\n');
+            stdout
+                .write_all(frame.as_bytes())
+                .and_then(|()| stdout.flush())
+                .map_err(|e| Error::io("stdout", e))?;
+            std::thread::sleep(speed.frame_time());
+        }
+        last = Some(state);
+    }
+    // A replay always has its start.
+    let last = last.expect("a trace begins with the start of a search");
+    if !animate {
+        let index = events.len() - 1;
+        println!("{}", describe_move(index, events.len(), &events[index]));
+        println!("{}", last.render());
+    }
+    println!(
+        "The recorded search made {} moves and found {} predecessors.",
+        large_number(events.len() - 1),
+        large_number(solutions.len())
+    );
+    for (index, solution) in solutions {
+        println!("Found at move {index}:\n{}", solution.render());
+    }
+    Ok(())
+}
+
+fn run(rng: Rng, cli: &Cli) -> Result<(), Error> {
+    let Some(path) = &cli.log_file else {
+        return search_chains(rng, cli, None);
+    };
+    let log = SearchLog::open(path, cli.log_file_max_mb.saturating_mul(1 << 20))?;
+    let result = search_chains(rng, cli, Some(&log));
+    let outcome = match &result {
+        Ok(()) => "finished".to_owned(),
+        Err(error) => format!("error: {error}"),
+    };
+    log.log(&LogEvent::Finish { outcome: &outcome })?;
+    result
+}
+
+fn search_chains(rng: Rng, cli: &Cli, log: Option<&SearchLog>) -> Result<(), Error> {
+    let search_rng = rng.derive_stream("search");
+    // How long each attempt searches for in each macrostep, however expensive its iterations are.
+    let micro_step = Duration::from_millis(100);
+    let new_searcher = |grid| {
+        let searcher = Searcher::new(grid)
+            .block_propagation(cli.block_propagation)
+            .shave(cli.shave)
+            .strategy(cli.strategy)
+            .rng(search_rng.clone())
+            .progress_cadence(micro_step);
+        // The window size was checked when parsing arguments, but not whether the target can have its windows checked.
+        let searcher = match cli.check_windows {
+            Some(window) => searcher.check_windows(window as usize).map_err(|error| {
+                Error::InvalidInput(format!("cannot pass `--check-windows` here: {error}"))
+            })?,
+            None => searcher,
+        };
+        let searcher = match log {
+            Some(log) => searcher.trace_to(log.clone()),
+            None => searcher,
+        };
+        #[cfg(feature = "provenance")]
+        let searcher = match cli.trace_contradictions {
+            Some(max_depth) => searcher.trace_contradictions(max_depth),
+            None => searcher,
+        };
+        Ok::<_, Error>(searcher)
+    };
+
+    // This only fails if the global thread pool has already been built, and nothing else builds it.
     rayon::ThreadPoolBuilder::new()
         .num_threads(6)
         .build_global()
-        .unwrap();
+        .expect("the global thread pool is only built once");
 
-    let start = Grid::from_file("glider.gol");
+    let start = load_pattern(Path::new(DEFAULT_PATTERN), cli)?;
     let mut attempts = vec![Sequence {
         grids: vec![start.clone()],
-        searcher: Searcher::new(start),
+        searcher: new_searcher(start)?,
     }];
 
     let mut macro_step = 0;
     let total_iterations: Arc<AtomicUsize> = Default::default();
-    let micro_step_size = 100_000;
     let max_attempts = 100;
 
     let prefix = DateTime::<Local>::from(SystemTime::now())
         .format("out-%Y-%m-%d-%H-%M-%S")
         .to_string();
-    std::fs::create_dir(&prefix).unwrap();
+    std::fs::create_dir(&prefix).map_err(|e| Error::io(&prefix, e))?;
+
+    // Chains are laid out to fit the terminal, so that the dumped output can be read with `cat`.
+    let output_width = terminal_size::terminal_size()
+        .map(|(Width(width), _)| width as usize)
+        .unwrap_or(100);
 
     let terminated_attempts = Arc::new(Mutex::new(Vec::new()));
     let terminated_attempts2 = Arc::clone(&terminated_attempts);
 
-    let mut rng = rand::thread_rng();
+    println!("Seed {}.", rng.seed());
+    let mut rng = rng.derive_stream("attempt order");
+    let start_time = Instant::now();
+    // The depth of the search for the longest chain's next step, after each macrostep.
+    let mut depths = Vec::new();
     loop {
-        attempts.shuffle(&mut rng);
+        rng.shuffle(&mut attempts);
         // Search for the attempts with the smallest amount of alive cells first.
+        // Every sequence starts with at least one grid.
         attempts.sort_by_cached_key(|x| {
             x.grids.last().unwrap().alive_cells().count() as i32 - x.grids.len() as i32
         });
         attempts = attempts
             .into_par_iter()
             .take(max_attempts)
-            .flat_map(|mut current_attempt| {
-                match current_attempt.searcher.search(micro_step_size) {
+            .map(|mut current_attempt| {
+                Ok(match current_attempt.searcher.search_slice()? {
                     SearchResult::Found(grid, iterations) => {
                         total_iterations.fetch_add(iterations, Ordering::SeqCst);
+                        info!(
+                            "extending a chain of length {} with a new attempt",
+                            current_attempt.grids.len()
+                        );
                         let mut new_grids = current_attempt.grids.clone();
                         new_grids.push(grid.clone());
                         vec![
                             current_attempt,
                             Sequence {
                                 grids: new_grids,
-                                searcher: Searcher::new(grid),
+                                searcher: new_searcher(grid)?,
                             },
                         ]
                     }
@@ -83,44 +1534,132 @@ fn main() {
                         // );
                         vec![current_attempt]
                     }
-                    SearchResult::Unsatisfiable => {
+                    // Beam search gives up for good, so its attempts end too.
+                    SearchResult::Unsatisfiable | SearchResult::Inconclusive => {
+                        info!(
+                            "terminating an attempt with a chain of length {}",
+                            current_attempt.grids.len()
+                        );
+                        // The lock is only poisoned if another thread panicked while holding it.
                         terminated_attempts2.lock().unwrap().push(current_attempt);
                         Vec::new()
                     }
-                }
+                    // Any other outcome is inconclusive, so keep searching.
+                    _ => vec![current_attempt],
+                })
             })
+            .collect::<Result<Vec<_>, Error>>()?
+            .into_iter()
+            .flatten()
             .collect::<Vec<_>>();
 
         macro_step += 1;
 
-        println!("---");
-        println!("Macrostep #{macro_step:06}.");
-        println!(
-            "{} iterations.",
-            large_number(total_iterations.load(Ordering::SeqCst))
-        );
-        println!("{} running attempts.", large_number(attempts.len()));
-        println!(
-            "{} terminated attempts.",
-            terminated_attempts.lock().unwrap().len()
-        );
+        let iterations = total_iterations.load(Ordering::SeqCst);
+        // As above, the lock can only be poisoned by a panic elsewhere.
         let guard = terminated_attempts.lock().unwrap();
+        // The initial sequence is always either still running or terminated.
         let best_sequence = attempts
             .iter()
             .chain(guard.iter())
             .max_by_key(|x| x.grids.len())
             .unwrap();
-        println!("Longest chain is length {}.", best_sequence.grids.len());
+        // Chains are stored from the target backwards, so reverse them to check them step by step.
+        let chain = best_sequence
+            .grids
+            .iter()
+            .rev()
+            .cloned()
+            .collect::<Vec<_>>();
+        verify_chain(&chain).map_err(|e| Error::Solver(format!("invalid chain: {e}")))?;
+        let depth = best_sequence.searcher.depth();
+        depths.push(depth as f64);
+        println!("---");
+        println!(
+            "Macrostep #{macro_step:06} ({}): next step {}, {depth} guesses deep.",
+            human_duration(start_time.elapsed()),
+            progress_bar(best_sequence.searcher.stats().progress_estimate, 20)
+        );
+        let mut stats = SearchStats::default();
+        for sequence in attempts.iter().chain(guard.iter()) {
+            stats += sequence.searcher.stats();
+        }
+        if let Some(log) = log {
+            log.log(&LogEvent::Progress {
+                iterations,
+                stats: &stats,
+            })?;
+        }
+        println!(
+            "{}",
+            Table::new()
+                .align(1, ColumnAlignment::Right)
+                .add_row(["Iterations", &large_number(iterations)])
+                .add_row(["Rate", &rate(iterations, start_time.elapsed())])
+                .add_row(["Running attempts", &large_number(attempts.len())])
+                .add_row(["Terminated attempts", &large_number(guard.len())])
+                .add_row(["Longest chain", &best_sequence.grids.len().to_string()])
+                .add_row([
+                    "Deductions per decision",
+                    &format!("{:.2}", stats.deductions_per_decision())
+                ])
+                .add_row(["Contradictions", &large_number(stats.contradictions)])
+                .add_row(["Search depth", &sparkline(&depths, 40)])
+                .render()
+        );
+        if cli.heatmap {
+            println!(
+                "Contradictions at each cell, in the search for the longest chain's next step:"
+            );
+            print!(
+                "{}",
+                best_sequence
+                    .searcher
+                    .contradiction_heatmap()
+                    .render_heatmap()
+            );
+        }
+        if cli.log_level >= LevelFilter::Debug {
+            println!("Where the search for the longest chain's next step decided cells:");
+            print!("{}", best_sequence.searcher.effort_report().render());
+        }
+        let path = format!("{prefix}/{macro_step:06}.txt");
         std::fs::write(
-            format!("{prefix}/{macro_step:06}.txt"),
-            best_sequence
-                .grids
-                .iter()
-                .map(|x| x.render())
-                .collect::<Vec<_>>()
-                .join("\n\n\n"),
+            &path,
+            layout_blocks(
+                &best_sequence
+                    .grids
+                    .iter()
+                    .enumerate()
+                    .map(|(i, x)| {
+                        vconcat_all(&[
+                            &format!("Generation -{i}"),
+                            &x.render(),
+                            &format!("{} alive cells.", x.alive_cells().count()),
+                        ])
+                    })
+                    .collect::<Vec<_>>(),
+                output_width,
+                "   ",
+            ),
         )
-        .unwrap();
+        .map_err(|e| Error::io(&path, e))?;
+        // The earliest generation, which can be read back as the target of a further search.
+        best_sequence
+            .grids
+            .last()
+            .expect("every chain holds its target")
+            .to_file(format!("{prefix}/{macro_step:06}.gol"))?;
         drop(guard);
+        if cli
+            .timeout
+            .is_some_and(|timeout| start_time.elapsed() >= timeout)
+        {
+            println!(
+                "Stopping after {}, as `--timeout` asked.",
+                human_duration(start_time.elapsed())
+            );
+            return Ok(());
+        }
     }
 }
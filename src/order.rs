@@ -0,0 +1,67 @@
+//! Strategies for choosing which undecided cell a [`Searcher`](crate::Searcher) branches on next.
+//!
+//! The choice never affects which predecessors are found, only how quickly and in what order.
+
+use crate::guess::Guess;
+
+/// Chooses the cell to branch on from the undecided cells of a guess.
+pub trait CellOrder {
+    /// Picks one of `candidates` to branch on, or `None` if there are none.
+    ///
+    /// The candidates are every undecided cell of `guess`, in the searcher's default order of preference:
+    /// first the cells the guess suggests trying, then those furthest from the target's alive cells.
+    /// A cell may appear more than once.
+    fn choose(
+        &mut self,
+        guess: &Guess,
+        candidates: &mut dyn Iterator<Item = (i32, i32)>,
+    ) -> Option<(i32, i32)>;
+
+    /// Whether this order reads [`Guess::impact`], which the searcher only tracks if asked to.
+    fn tracks_impact(&self) -> bool {
+        false
+    }
+}
+
+/// Branches on the first candidate. This is the searcher's default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FirstCandidate;
+
+impl CellOrder for FirstCandidate {
+    fn choose(
+        &mut self,
+        _guess: &Guess,
+        candidates: &mut dyn Iterator<Item = (i32, i32)>,
+    ) -> Option<(i32, i32)> {
+        candidates.next()
+    }
+}
+
+/// Branches on the candidate with the highest [`Guess::impact`],
+/// that is, the cell whose decision has recently caused the most propagation.
+/// Ties go to the earlier candidate, so this behaves like [`FirstCandidate`] until impact has been measured.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HighestImpact;
+
+impl CellOrder for HighestImpact {
+    fn choose(
+        &mut self,
+        guess: &Guess,
+        candidates: &mut dyn Iterator<Item = (i32, i32)>,
+    ) -> Option<(i32, i32)> {
+        let mut best = None;
+        let mut best_impact = i32::MIN;
+        for (x, y) in candidates {
+            let impact = guess.impact(x, y);
+            if impact > best_impact {
+                best = Some((x, y));
+                best_impact = impact;
+            }
+        }
+        best
+    }
+
+    fn tracks_impact(&self) -> bool {
+        true
+    }
+}
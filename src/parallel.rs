@@ -0,0 +1,392 @@
+//! Enumerating predecessors on several threads at once.
+//!
+//! The root guess is split into subtrees by deciding its first few candidate cells in every possible way,
+//! and worker threads search the subtrees depth-first, taking a new one whenever they finish one.
+//! Every predecessor lies in exactly one subtree. Workers send what they find over a channel to the calling thread,
+//! which drops duplicates, stops every worker once enough predecessors have been found,
+//! and only reports the search space exhausted once every subtree has been searched to the end.
+
+use std::{
+    collections::BTreeSet,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{debug, info};
+
+use crate::{
+    cadence::{Cadence, SystemClock},
+    searcher::{SearchResult, SearchStats, Searcher},
+    Error, Grid, Guess,
+};
+
+/// Which predecessors [`enumerate`] treats as the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dedup {
+    /// Only identical grids.
+    #[default]
+    Exact,
+    /// Grids with the same [`Grid::canonical`] form, that is, translations of each other around the torus.
+    /// The one kept is the canonical form itself.
+    Canonical,
+}
+
+impl FromStr for Dedup {
+    type Err = Error;
+
+    /// Parses `exact` or `canonical`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(Dedup::Exact),
+            "canonical" => Ok(Dedup::Canonical),
+            _ => Err(Error::InvalidInput(format!(
+                "expected `exact` or `canonical`, but found `{s}`"
+            ))),
+        }
+    }
+}
+
+/// How [`enumerate`] divides up and limits its work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParallelOptions {
+    /// How many worker threads to search on.
+    pub threads: usize,
+    /// If set, stop once this many distinct predecessors have been found.
+    pub limit: Option<usize>,
+    pub dedup: Dedup,
+    /// How many cells to decide at the root in every possible way, making up to `2^split_depth` subtrees.
+    /// More subtrees than threads keeps every thread busy when some subtrees are much larger than others.
+    pub split_depth: usize,
+    /// How many iterations a worker searches between checks for whether it should stop.
+    pub slice: usize,
+    /// If set, workers instead size their slices to check about this often, however expensive iterations are,
+    /// as a [`Cadence`] does.
+    pub cadence: Option<Duration>,
+    /// If set, only find predecessors whose alive cells all lie in this mask, as in [`Searcher::with_mask`].
+    pub mask: Option<Grid>,
+}
+
+impl Default for ParallelOptions {
+    fn default() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            limit: None,
+            dedup: Dedup::Exact,
+            split_depth: 6,
+            slice: 10_000,
+            cadence: None,
+            mask: None,
+        }
+    }
+}
+
+/// What [`enumerate`] found.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Enumeration {
+    /// The distinct predecessors found, in ascending order, so that the result does not depend on scheduling.
+    /// If the search stopped at the limit, which predecessors these are may still depend on it.
+    pub predecessors: Vec<Grid>,
+    /// How many predecessors were dropped as duplicates of ones already found.
+    pub duplicates: usize,
+    /// Whether every subtree was searched to the end, so that `predecessors` is complete.
+    pub exhausted: bool,
+    /// The total search iterations over every worker.
+    pub iterations: usize,
+    /// The decisions and deductions of every worker, summed.
+    pub stats: SearchStats,
+    /// How many contradictions every worker detected at each cell, summed.
+    /// See [`Searcher::contradiction_heatmap`].
+    pub heatmap: Grid,
+}
+
+/// A distinct predecessor, passed to the callback of [`enumerate_streaming`] as soon as it is found.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct Discovery<'a> {
+    /// How many distinct predecessors were found before this one.
+    /// Duplicates are dropped before they are counted, so these run from zero without gaps.
+    pub index: usize,
+    /// The predecessor, in the form [`ParallelOptions::dedup`] keeps.
+    pub predecessor: &'a Grid,
+    /// The search iterations over every worker so far, as of the last time each reported.
+    pub iterations: usize,
+    /// The time since the enumeration started.
+    pub elapsed: Duration,
+}
+
+/// What a worker tells the collector.
+enum Message {
+    Found(Box<Grid>),
+    /// A subtree was searched to the end.
+    Finished,
+    /// A worker has stopped, with the totals over every subtree it searched.
+    Done {
+        iterations: usize,
+        stats: SearchStats,
+        heatmap: Box<Grid>,
+    },
+    Failed(Error),
+}
+
+/// The guesses that decide the first `depth` candidate cells of `searcher`'s root in every consistent way.
+fn split(searcher: &Searcher, next: &Grid, depth: usize) -> Vec<Guess> {
+    let Some(root) = searcher.root() else {
+        return Vec::new();
+    };
+    let mut cells = Vec::new();
+    for cell in searcher.candidates() {
+        if cells.len() == depth {
+            break;
+        }
+        if !cells.contains(&cell) {
+            cells.push(cell);
+        }
+    }
+    let mut guesses = vec![root.clone()];
+    for (x, y) in cells {
+        guesses = guesses
+            .into_iter()
+            .flat_map(|guess| {
+                let mut alive = guess.clone();
+                alive.guess_alive(next, x, y);
+                let mut dead = guess;
+                dead.guess_dead(next, x, y);
+                [alive, dead]
+            })
+            // A cell that propagation already decided contradicts the other value, so no subtree is searched twice.
+            .filter(|guess| !guess.found_contradiction())
+            .collect();
+    }
+    guesses
+}
+
+/// Searches the subtrees in `queue` until there are none left or `stop` is set, reporting to `sender`,
+/// and adding the iterations of each slice to `searched` as it goes.
+fn work(
+    next: &Grid,
+    queue: &Mutex<Vec<Guess>>,
+    stop: &AtomicBool,
+    searched: &AtomicUsize,
+    slice: usize,
+    cadence: Option<Duration>,
+    sender: mpsc::Sender<Message>,
+) {
+    let mut cadence = cadence.map(|target| Cadence::new(target, SystemClock::default()));
+    let mut iterations = 0;
+    let mut stats = SearchStats::default();
+    let mut heatmap = Grid::new(next.width(), next.height());
+    'subtrees: while !stop.load(Ordering::Relaxed) {
+        // A worker that panicked holding the lock leaves the queue intact, so carry on with it.
+        let Some(root) = queue.lock().unwrap_or_else(|e| e.into_inner()).pop() else {
+            break;
+        };
+        let mut searcher = Searcher::with_root(next.clone(), root);
+        loop {
+            if stop.load(Ordering::Relaxed) {
+                stats += searcher.stats();
+                heatmap += &searcher.contradiction_heatmap();
+                break 'subtrees;
+            }
+            // One cadence is kept across subtrees, since the cost of iterations carries over between them.
+            let started = cadence.as_ref().map(Cadence::start);
+            let result = searcher.search(cadence.as_ref().map_or(slice, Cadence::slice));
+            if let (
+                Some(cadence),
+                Some(started),
+                Ok(SearchResult::Found(_, spent) | SearchResult::Working(spent)),
+            ) = (&mut cadence, started, &result)
+            {
+                cadence.finish(*spent, started);
+            }
+            let message = match result {
+                Ok(SearchResult::Found(grid, spent)) => {
+                    iterations += spent;
+                    searched.fetch_add(spent, Ordering::Relaxed);
+                    Message::Found(Box::new(grid))
+                }
+                Ok(SearchResult::Working(spent)) => {
+                    iterations += spent;
+                    searched.fetch_add(spent, Ordering::Relaxed);
+                    continue;
+                }
+                Ok(SearchResult::Unsatisfiable) => {
+                    stats += searcher.stats();
+                    heatmap += &searcher.contradiction_heatmap();
+                    Message::Finished
+                }
+                Ok(_) => Message::Failed(Error::Solver(
+                    "depth-first search gave up before exhausting a subtree".to_owned(),
+                )),
+                Err(error) => Message::Failed(error),
+            };
+            let finished = !matches!(message, Message::Found(_));
+            // The collector only hangs up once it has stopped every worker.
+            if sender.send(message).is_err() || finished {
+                break;
+            }
+        }
+    }
+    let _ = sender.send(Message::Done {
+        iterations,
+        stats,
+        heatmap: Box::new(heatmap),
+    });
+}
+
+/// Finds the predecessors of `next` on [`ParallelOptions::threads`] threads, dropping duplicates as
+/// [`ParallelOptions::dedup`] says, until every one has been found or there are [`ParallelOptions::limit`] of them.
+///
+/// ```
+/// use undeath::parallel::{enumerate, ParallelOptions};
+/// use undeath::{Cell, Grid};
+///
+/// // A blinker, with predecessors confined to the 4×4 window around it.
+/// let blinker = Grid::from_text("\n\n\n..###");
+/// let mut mask = Grid::default();
+/// for y in 2..6 {
+///     for x in 1..5 {
+///         mask.set_wrapped(x, y, Cell::one());
+///     }
+/// }
+/// let options = ParallelOptions { threads: 2, mask: Some(mask), ..ParallelOptions::default() };
+/// let enumeration = enumerate(&blinker, &options)?;
+/// assert!(enumeration.exhausted);
+/// for mut predecessor in enumeration.predecessors {
+///     predecessor.step();
+///     assert!(predecessor == blinker);
+/// }
+/// # Ok::<(), undeath::Error>(())
+/// ```
+pub fn enumerate(next: &Grid, options: &ParallelOptions) -> Result<Enumeration, Error> {
+    enumerate_streaming(next, options, |_| {})
+}
+
+/// Like [`enumerate`], but also calls `on_found` on the calling thread with each distinct predecessor
+/// as soon as it is found, so that long enumerations can report results as they go.
+/// Predecessors arrive in the order they are found, which depends on scheduling,
+/// and only distinct ones up to [`ParallelOptions::limit`] are passed on.
+///
+/// ```
+/// use undeath::parallel::{enumerate_streaming, ParallelOptions};
+/// use undeath::Grid;
+///
+/// let block = Grid::from_text("##\n##");
+/// let options = ParallelOptions { threads: 2, limit: Some(3), ..ParallelOptions::default() };
+/// let mut indices = Vec::new();
+/// let enumeration = enumerate_streaming(&block, &options, |discovery| indices.push(discovery.index))?;
+/// assert_eq!(indices, [0, 1, 2]);
+/// assert_eq!(enumeration.predecessors.len(), 3);
+/// # Ok::<(), undeath::Error>(())
+/// ```
+pub fn enumerate_streaming(
+    next: &Grid,
+    options: &ParallelOptions,
+    mut on_found: impl FnMut(Discovery<'_>),
+) -> Result<Enumeration, Error> {
+    let start = Instant::now();
+    let searcher = match &options.mask {
+        Some(mask) => Searcher::with_mask(next.clone(), mask),
+        None => Searcher::new(next.clone()),
+    };
+    let subtrees = split(&searcher, next, options.split_depth);
+    let total = subtrees.len();
+    info!(
+        "enumerating {total} subtrees on {} threads",
+        options.threads
+    );
+    let queue = Mutex::new(subtrees);
+    let stop = AtomicBool::new(false);
+    let searched = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    let mut seen = BTreeSet::new();
+    let mut enumeration = Enumeration {
+        predecessors: Vec::new(),
+        duplicates: 0,
+        exhausted: false,
+        iterations: 0,
+        stats: SearchStats::default(),
+        heatmap: Grid::new(next.width(), next.height()),
+    };
+    let mut finished = 0;
+    let mut failure = None;
+    thread::scope(|scope| {
+        for _ in 0..options.threads.max(1) {
+            let sender = sender.clone();
+            scope.spawn(|| {
+                work(
+                    next,
+                    &queue,
+                    &stop,
+                    &searched,
+                    options.slice,
+                    options.cadence,
+                    sender,
+                )
+            });
+        }
+        // Only the workers hold senders now, so the channel closes once every one of them is done.
+        drop(sender);
+        for message in receiver {
+            match message {
+                Message::Found(grid) => {
+                    if options.limit.is_some_and(|limit| seen.len() >= limit) {
+                        // Found after the limit was reached, before the worker saw the signal to stop.
+                        continue;
+                    }
+                    let grid = match options.dedup {
+                        Dedup::Exact => *grid,
+                        Dedup::Canonical => grid.canonical(),
+                    };
+                    if seen.contains(&grid) {
+                        enumeration.duplicates += 1;
+                        continue;
+                    }
+                    on_found(Discovery {
+                        index: seen.len(),
+                        predecessor: &grid,
+                        iterations: searched.load(Ordering::Relaxed),
+                        elapsed: start.elapsed(),
+                    });
+                    seen.insert(grid);
+                    if options.limit.is_some_and(|limit| seen.len() >= limit) {
+                        debug!("found {} predecessors, so stopping", seen.len());
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                }
+                Message::Finished => finished += 1,
+                Message::Done {
+                    iterations,
+                    stats,
+                    heatmap,
+                } => {
+                    enumeration.iterations += iterations;
+                    enumeration.stats += stats;
+                    enumeration.heatmap += &heatmap;
+                }
+                Message::Failed(error) => {
+                    stop.store(true, Ordering::Relaxed);
+                    failure.get_or_insert(error);
+                }
+            }
+        }
+    });
+    if let Some(error) = failure {
+        return Err(error);
+    }
+    enumeration.exhausted = finished == total;
+    enumeration.predecessors = seen.into_iter().collect();
+    info!(
+        "found {} predecessors, {} duplicates, in {} of {total} subtrees searched to the end",
+        enumeration.predecessors.len(),
+        enumeration.duplicates,
+        finished
+    );
+    Ok(enumeration)
+}
@@ -0,0 +1,113 @@
+//! Records the reasoning behind each propagation, so that a contradiction can be explained step by step.
+//!
+//! This is only compiled with the `provenance` feature, since the bookkeeping slows down every guess.
+
+use std::fmt::Write;
+
+use crate::typed_grid::BoolGrid;
+
+/// One step of the reasoning that led to a contradiction: a guess, a deduction, or the contradiction itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TraceStep {
+    /// The cell that was decided, or where the contradiction was found.
+    pub cell: (i32, i32),
+    /// The value the cell was decided to have on the previous frame, alive or dead,
+    /// or `None` if this step found the contradiction.
+    pub decided: Option<bool>,
+    /// The rule that fired, or `"guessed"` for the guess that started the propagation.
+    pub rule: &'static str,
+    /// The cell whose constraint fired. For the 2×2 block rules, this is the top left corner of the block.
+    pub centre: (i32, i32),
+    /// How many neighbours of `centre` were decided alive when the rule fired.
+    pub alive_neighbours: i32,
+    /// How many neighbours of `centre` were decided dead when the rule fired.
+    pub dead_neighbours: i32,
+    /// Whether `centre` is alive on the next frame.
+    pub next_alive: bool,
+    /// The offsets from `centre`, in each axis, of the cells whose values the rule read.
+    pub(crate) reads: (i32, i32),
+}
+
+/// The steps taken since the last guess, in order.
+#[derive(Clone, Default)]
+pub(crate) struct Provenance {
+    steps: Vec<TraceStep>,
+}
+
+impl Provenance {
+    /// Forgets the steps of earlier guesses.
+    pub(crate) fn clear(&mut self) {
+        self.steps.clear();
+    }
+
+    pub(crate) fn record(&mut self, step: TraceStep) {
+        self.steps.push(step);
+    }
+
+    /// The steps that the contradiction found by the last step depends on, in order,
+    /// or `None` if the last step did not find a contradiction.
+    ///
+    /// Walks backwards from the contradiction, keeping each step that decided a cell some later kept step read.
+    /// Cells decided before the guess that started this propagation are taken as given.
+    /// The steps are for a `width`×`height` grid.
+    pub(crate) fn trace(&self, width: i32, height: i32) -> Option<Vec<TraceStep>> {
+        let (last, earlier) = self.steps.split_last()?;
+        last.decided.is_none().then_some(())?;
+        let mut read = BoolGrid::new(width, height);
+        add_reads(last, &mut read);
+        let mut trace = vec![last.clone()];
+        for step in earlier.iter().rev() {
+            let (x, y) = step.cell;
+            if read.test(x, y) {
+                add_reads(step, &mut read);
+                trace.push(step.clone());
+            }
+        }
+        trace.reverse();
+        Some(trace)
+    }
+}
+
+/// Adds the cells a step read to `read`.
+fn add_reads(step: &TraceStep, read: &mut BoolGrid) {
+    let (lo, hi) = step.reads;
+    for dy in lo..=hi {
+        for dx in lo..=hi {
+            // The offsets are at most a few cells, far less than the size of any grid.
+            read.set(step.centre.0 + dx, step.centre.1 + dy);
+        }
+    }
+}
+
+/// Renders a trace as a numbered list, one step per line, with each step's rule and the counts it saw indented below.
+pub(crate) fn render_trace(trace: &[TraceStep]) -> String {
+    let mut output = String::new();
+    for (i, step) in trace.iter().enumerate() {
+        let (x, y) = step.cell;
+        // Writing to a `String` cannot fail.
+        let _ = match step.decided {
+            Some(alive) => {
+                let value = if alive { "alive" } else { "dead" };
+                if step.rule == "guessed" {
+                    writeln!(output, "{}. ({x}, {y}) guessed {value}", i + 1)
+                } else {
+                    writeln!(output, "{}. ({x}, {y}) deduced {value}", i + 1)
+                }
+            }
+            None => writeln!(output, "{}. contradiction at ({x}, {y})", i + 1),
+        };
+        if step.rule != "guessed" {
+            let (cx, cy) = step.centre;
+            let _ = writeln!(output, "   {}", step.rule);
+            let _ = writeln!(
+                output,
+                "   at ({cx}, {cy}): {} alive and {} dead neighbours, {} next frame",
+                step.alive_neighbours,
+                step.dead_neighbours,
+                if step.next_alive { "alive" } else { "dead" }
+            );
+        }
+    }
+    output
+}
@@ -0,0 +1,244 @@
+//! Stepping through a recorded search, to see the guess it was looking at after any of its moves.
+//!
+//! Propagation is deterministic, so the decisions in a trace are enough to rebuild every guess:
+//! [`Replay`] applies them to the root guess, exactly as the searcher did, and checks that each
+//! contradiction recorded in the trace happens again.
+
+use crate::{
+    searcher::{refine, Shaving},
+    trace::TraceEvent,
+    Error, Grid, Guess,
+};
+
+/// A recorded search, ready to be replayed.
+///
+/// ```
+/// use std::sync::mpsc;
+/// use undeath::{replay::Replay, Grid, SearchResult, Searcher};
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let blinker = Grid::from_text("\n\n\n..###");
+/// let mut searcher = Searcher::new(blinker).trace_to(sender);
+/// let predecessor = loop {
+///     match searcher.search(10_000)? {
+///         SearchResult::Found(predecessor, _) => break predecessor,
+///         SearchResult::Working(_) => continue,
+///         _ => panic!("a blinker has a predecessor"),
+///     }
+/// };
+/// drop(searcher);
+///
+/// let replay = Replay::new(receiver.iter().collect())?;
+/// assert!(replay.solutions()? == vec![predecessor]);
+/// println!("{}", replay.render_at(replay.len() / 2)?);
+/// # Ok::<(), undeath::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct Replay {
+    events: Vec<TraceEvent>,
+    next: Grid,
+    root: Guess,
+    block_propagation: bool,
+    shaving: Shaving,
+}
+
+/// The guesses of a replay in progress: the stack of guesses from the root, and the one the last move looked at.
+struct State {
+    stack: Vec<Guess>,
+    current: Guess,
+}
+
+impl Replay {
+    /// Prepares to replay `events`, which must begin with a [`TraceEvent::Start`].
+    pub fn new(events: Vec<TraceEvent>) -> Result<Self, Error> {
+        let Some(TraceEvent::Start {
+            next,
+            root_alive,
+            root_dead,
+            block_propagation,
+            shave_period,
+        }) = events.first()
+        else {
+            return Err(Error::InvalidInput(
+                "a trace must begin with the start of a search".to_owned(),
+            ));
+        };
+        let root = Guess::from_partial(next, root_alive, root_dead);
+        if root.found_contradiction() {
+            return Err(Error::InvalidInput(
+                "the root guess of the trace is contradictory".to_owned(),
+            ));
+        }
+        Ok(Self {
+            next: next.clone(),
+            root,
+            block_propagation: *block_propagation,
+            shaving: match *shave_period {
+                0 => Shaving::Off,
+                period => Shaving::Periodic(period),
+            },
+            events,
+        })
+    }
+
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// How many events there are, including the start.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether there are no events but the start.
+    pub fn is_empty(&self) -> bool {
+        self.events.len() <= 1
+    }
+
+    /// The target of the recorded search.
+    pub fn next(&self) -> &Grid {
+        &self.next
+    }
+
+    /// Applies the event at `index` to `state`.
+    fn apply(&self, state: &mut State, index: usize) -> Result<(), Error> {
+        let mismatch = |message: String| {
+            Error::Solver(format!(
+                "the trace does not replay at event {index}: {message}"
+            ))
+        };
+        let truncate = |state: &mut State, depth: usize| {
+            if depth >= state.stack.len() {
+                return Err(mismatch(format!(
+                    "there is no guess at depth {depth} to return to"
+                )));
+            }
+            state.stack.truncate(depth + 1);
+            state.current = state.stack[depth].clone();
+            Ok(())
+        };
+        match self.events[index] {
+            TraceEvent::Start { .. } => {
+                state.stack = vec![self.root.clone()];
+                state.current = self.root.clone();
+            }
+            TraceEvent::Decide {
+                depth,
+                x,
+                y,
+                alive,
+                contradiction,
+                ..
+            } => {
+                truncate(state, depth)?;
+                let refined = refine(
+                    &self.next,
+                    &state.stack[depth],
+                    depth,
+                    (x, y),
+                    alive,
+                    self.block_propagation,
+                    self.shaving,
+                );
+                if refined.found_contradiction() != contradiction {
+                    return Err(mismatch(format!(
+                        "guessing ({x}, {y}) was {} was{} a contradiction when recorded",
+                        if alive { "alive" } else { "dead" },
+                        if contradiction { "" } else { " not" }
+                    )));
+                }
+                if !contradiction {
+                    state.stack.push(refined.clone());
+                }
+                state.current = refined;
+            }
+            TraceEvent::Backtrack { depth, .. } => truncate(state, depth)?,
+            TraceEvent::Solution { depth, .. } => {
+                truncate(state, depth)?;
+                if state.current.unknown_cells().next().is_some() {
+                    return Err(mismatch("the solution has undecided cells".to_owned()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The guess the search was looking at just after the event at `index`:
+    /// for a decision, the guess it made, even if that was a contradiction,
+    /// and for a backtrack or a solution, the guess it went back to or found.
+    pub fn state_at(&self, index: usize) -> Result<Guess, Error> {
+        if index >= self.events.len() {
+            return Err(Error::InvalidInput(format!(
+                "the trace has {} events, so there is no event {index}",
+                self.events.len()
+            )));
+        }
+        let mut state = State {
+            stack: Vec::new(),
+            current: self.root.clone(),
+        };
+        for i in 0..=index {
+            self.apply(&mut state, i)?;
+        }
+        Ok(state.current)
+    }
+
+    /// Renders [`Replay::state_at`], as [`Guess::render`] does.
+    pub fn render_at(&self, index: usize) -> Result<String, Error> {
+        Ok(self.state_at(index)?.render())
+    }
+
+    /// The guess after each event in turn, as [`Replay::state_at`] gives it,
+    /// replaying the whole trace once rather than from the start for each event.
+    /// Stops after the first event that does not replay.
+    pub fn states(&self) -> States<'_> {
+        States {
+            replay: self,
+            state: State {
+                stack: Vec::new(),
+                current: self.root.clone(),
+            },
+            index: 0,
+            failed: false,
+        }
+    }
+
+    /// Every predecessor the recorded search found, in the order it found them.
+    pub fn solutions(&self) -> Result<Vec<Grid>, Error> {
+        let mut solutions = Vec::new();
+        for (event, state) in self.events.iter().zip(self.states()) {
+            let state = state?;
+            if let TraceEvent::Solution { .. } = event {
+                solutions.push(state.alive());
+            }
+        }
+        Ok(solutions)
+    }
+}
+
+/// The guesses of a [`Replay`] after each of its events, from [`Replay::states`].
+pub struct States<'a> {
+    replay: &'a Replay,
+    state: State,
+    index: usize,
+    failed: bool,
+}
+
+impl Iterator for States<'_> {
+    type Item = Result<Guess, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.index >= self.replay.events.len() {
+            return None;
+        }
+        let result = self.replay.apply(&mut self.state, self.index);
+        self.index += 1;
+        Some(match result {
+            Ok(()) => Ok(self.state.current.clone()),
+            Err(error) => {
+                self.failed = true;
+                Err(error)
+            }
+        })
+    }
+}
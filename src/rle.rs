@@ -0,0 +1,438 @@
+//! Reading and writing patterns in the run-length encoded format that Golly and LifeWiki use,
+//! including the `#CXRLE` line Golly adds and any comment lines.
+//!
+//! A pattern is a header line such as `x = 3, y = 3, rule = B3/S23`, then rows of runs:
+//! `b` for dead cells, `o` for alive ones, `$` between rows, each optionally preceded by a count,
+//! and `!` at the end. Lines starting with `#` before the header are comments.
+//!
+//! Golly writes where the pattern was and which generation it had reached as `#CXRLE Pos=X,Y Gen=N`.
+//! The position is that of the pattern's top left corner in Golly's coordinates, whose origin is
+//! taken here to be the cell `(width / 2, height / 2)`, near the centre of the grid, so that patterns
+//! Golly centres on its origin are centred on the grid too. The pattern wraps around the torus if it
+//! is placed across an edge. Without a position, the pattern's top left corner is the grid's.
+
+use std::fmt::Write;
+
+use crate::{
+    grid::{Cell, Grid, MAX_SIZE, MIN_SIZE, SIZE},
+    Error,
+};
+
+/// The comment [`Rle::solution`] adds, to say where a pattern came from.
+pub const GENERATED_COMMENT: &str = "#C generated by undeath";
+
+/// Why an RLE pattern could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RleError {
+    /// The text is not valid RLE. Lines and columns are counted from one.
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The header gives a rule other than Life's.
+    UnsupportedRule { rule: String },
+    /// The header gives a pattern larger than the largest grid, [`MAX_SIZE`]×[`MAX_SIZE`].
+    TooLarge { width: i64, height: i64 },
+    /// An alive cell lies outside the `width`×`height` the header gives, or outside a [`SIZE`]×[`SIZE`] grid
+    /// if there is no header. The line and column are those of the first such cell,
+    /// and `pattern` is the width and height of the whole pattern, from its top left corner.
+    OutOfBounds {
+        line: usize,
+        column: usize,
+        width: i64,
+        height: i64,
+        pattern: (i64, i64),
+    },
+}
+
+impl std::fmt::Display for RleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RleError::Syntax {
+                line,
+                column,
+                message,
+            } => write!(f, "parse error at line {line}, column {column}: {message}"),
+            RleError::UnsupportedRule { rule } => {
+                write!(f, "only Life, B3/S23, is supported, but the rule is `{rule}`")
+            }
+            RleError::TooLarge { width, height } => write!(
+                f,
+                "the pattern is {width}×{height}, which does not fit on the largest grid, {MAX_SIZE}×{MAX_SIZE}"
+            ),
+            RleError::OutOfBounds {
+                line,
+                column,
+                width,
+                height,
+                pattern: (pattern_width, pattern_height),
+            } => write!(
+                f,
+                "the pattern is {pattern_width}×{pattern_height}, which does not fit in {width}×{height}, \
+                 from line {line}, column {column}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RleError {}
+
+/// Errors with a position in the text are parse errors, and the others invalid input.
+impl From<RleError> for Error {
+    fn from(error: RleError) -> Self {
+        match error {
+            RleError::Syntax {
+                line,
+                column,
+                message,
+            } => Error::Parse {
+                line,
+                column,
+                message,
+            },
+            RleError::OutOfBounds {
+                line,
+                column,
+                width,
+                height,
+                pattern: (pattern_width, pattern_height),
+            } => Error::Parse {
+                line,
+                column,
+                message: format!(
+                    "the pattern is {pattern_width}×{pattern_height}, which does not fit in {width}×{height}"
+                ),
+            },
+            error => Error::InvalidInput(error.to_string()),
+        }
+    }
+}
+
+/// A pattern read from RLE, with what Golly and others wrote alongside it.
+///
+/// ```
+/// use undeath::{rle::Rle, Grid};
+///
+/// let rle = Rle::parse("#N Glider\n#CXRLE Pos=-4,-4 Gen=12\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n")?;
+/// assert!(rle.grid == Grid::from_text(".#\n..#\n###"));
+/// assert_eq!(rle.generation, Some(12));
+/// assert_eq!(rle.comments, ["#N Glider"]);
+/// # Ok::<(), undeath::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Rle {
+    /// The pattern, placed on the grid as its position says.
+    pub grid: Grid,
+    /// The position of the pattern's top left corner in Golly's coordinates, from `#CXRLE Pos=X,Y`.
+    /// When writing, this is replaced by where the pattern now is.
+    pub position: Option<(i64, i64)>,
+    /// The generation the pattern had reached, from `#CXRLE Gen=N`.
+    pub generation: Option<u64>,
+    /// Every `#` line apart from `#CXRLE`, verbatim and in order, such as `#C` comments and `#N` names.
+    pub comments: Vec<String>,
+}
+
+impl Rle {
+    /// A solution found by this crate, with a [`GENERATED_COMMENT`] saying so.
+    pub fn solution(grid: Grid) -> Self {
+        Self {
+            grid,
+            comments: vec![GENERATED_COMMENT.to_owned()],
+            ..Self::default()
+        }
+    }
+
+    /// Parses an RLE pattern. The header line may be left out, and whitespace and line breaks
+    /// within the rows are ignored, as is anything after the `!`.
+    ///
+    /// The grid is [`SIZE`]×[`SIZE`] if the width and height in the header fit on it, and otherwise is that width
+    /// and height, but no smaller than [`MIN_SIZE`] on either side.
+    /// Fails if the pattern is larger than its header says or than [`MAX_SIZE`], or if the rule is not Life's.
+    /// [`Grid::from_rle`] reads just the grid, with a more detailed [`RleError`].
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        Ok(Self::read(text, false)?)
+    }
+
+    /// [`Rle::parse`], failing with an [`RleError`].
+    ///
+    /// If `wrap` is set, a pattern larger than its header or than [`MAX_SIZE`] is not an error:
+    /// the grid is at most [`MAX_SIZE`] on each side, and the cells past its edges wrap around it.
+    pub(crate) fn read(text: &str, wrap: bool) -> Result<Self, RleError> {
+        let mut rle = Rle::default();
+        let mut lines = text.lines().enumerate().peekable();
+        // Comments come first.
+        while let Some((index, line)) = lines.next_if(|(_, line)| {
+            let line = line.trim_start();
+            line.starts_with('#') || line.is_empty()
+        }) {
+            let line = line.trim_end();
+            if let Some(fields) = line.strip_prefix("#CXRLE") {
+                rle.parse_cxrle(fields, index + 1)?;
+            } else if !line.is_empty() {
+                rle.comments.push(line.to_owned());
+            }
+        }
+        let mut size = (SIZE as i64, SIZE as i64);
+        if let Some(&(index, line)) = lines.peek() {
+            if line.trim_start().starts_with('x') {
+                size = parse_header(line, index + 1)?;
+                lines.next();
+            }
+        }
+        let (width, height) = size;
+        if !wrap && (width > MAX_SIZE as i64 || height > MAX_SIZE as i64) {
+            return Err(RleError::TooLarge { width, height });
+        }
+        let (width, height) = (width.min(MAX_SIZE as i64), height.min(MAX_SIZE as i64));
+        let grid_size = if width <= SIZE as i64 && height <= SIZE as i64 {
+            (SIZE as i64, SIZE as i64)
+        } else {
+            (width.max(MIN_SIZE as i64), height.max(MIN_SIZE as i64))
+        };
+        rle.grid = Grid::new(grid_size.0 as i32, grid_size.1 as i32);
+        let origin = match rle.position {
+            Some((x, y)) => (x + grid_size.0 / 2, y + grid_size.1 / 2),
+            None => (0, 0),
+        };
+
+        let (mut x, mut y) = (0i64, 0i64);
+        let mut run = 0i64;
+        // Where the first cell outside the bounds is, and how far the pattern reaches.
+        let mut outside = None;
+        let mut extent = (0i64, 0i64);
+        for (index, line) in lines {
+            for (column, c) in line.chars().enumerate() {
+                let error = |message: &str| RleError::Syntax {
+                    line: index + 1,
+                    column: column + 1,
+                    message: message.to_owned(),
+                };
+                let length = run.max(1);
+                match c {
+                    '0'..='9' => {
+                        run = run
+                            .checked_mul(10)
+                            .and_then(|run| run.checked_add(c as i64 - '0' as i64))
+                            .filter(|&run| run <= grid_size.0 * grid_size.1)
+                            .ok_or_else(|| error("the run is longer than the grid"))?;
+                        continue;
+                    }
+                    'b' | '.' => x += length,
+                    'o' | 'A' => {
+                        extent = (extent.0.max(x + length), extent.1.max(y + 1));
+                        if !wrap && (x + length > width || y >= height) {
+                            // Keep reading, to report how large the whole pattern is.
+                            outside.get_or_insert((index + 1, column + 1));
+                        }
+                        for dx in 0..length {
+                            rle.grid.set_wrapped(
+                                (origin.0 + x + dx).rem_euclid(grid_size.0) as i32,
+                                (origin.1 + y).rem_euclid(grid_size.1) as i32,
+                                Cell::one(),
+                            );
+                        }
+                        x += length;
+                    }
+                    '$' => {
+                        x = 0;
+                        y += length;
+                    }
+                    '!' => {
+                        return match outside {
+                            Some((line, column)) => Err(RleError::OutOfBounds {
+                                line,
+                                column,
+                                width,
+                                height,
+                                pattern: extent,
+                            }),
+                            None => Ok(rle),
+                        }
+                    }
+                    c if c.is_whitespace() => {}
+                    c => return Err(error(&format!("unexpected `{c}` in RLE"))),
+                }
+                run = 0;
+            }
+        }
+        Err(RleError::Syntax {
+            line: text.lines().count().max(1),
+            column: text.lines().last().map_or(0, |line| line.chars().count()) + 1,
+            message: "the RLE does not end with `!`".to_owned(),
+        })
+    }
+
+    /// Reads the `Pos` and `Gen` fields of a `#CXRLE` line, ignoring any others.
+    fn parse_cxrle(&mut self, fields: &str, line: usize) -> Result<(), RleError> {
+        let error = |message: String| RleError::Syntax {
+            line,
+            column: 1,
+            message,
+        };
+        for field in fields.split_whitespace() {
+            if let Some(position) = field.strip_prefix("Pos=") {
+                let parsed = position
+                    .split_once(',')
+                    .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)));
+                self.position =
+                    Some(parsed.ok_or_else(|| error(format!("invalid position `{position}`")))?);
+            } else if let Some(generation) = field.strip_prefix("Gen=") {
+                self.generation = Some(
+                    generation
+                        .parse()
+                        .map_err(|_| error(format!("invalid generation `{generation}`")))?,
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads a header line such as `x = 3, y = 3, rule = B3/S23`, returning the width and height.
+fn parse_header(line: &str, number: usize) -> Result<(i64, i64), RleError> {
+    let error = |message: String| RleError::Syntax {
+        line: number,
+        column: 1,
+        message,
+    };
+    let (mut width, mut height) = (None, None);
+    for field in line.split(',') {
+        let Some((key, value)) = field.split_once('=') else {
+            return Err(error(format!(
+                "expected `key = value`, but found `{field}`"
+            )));
+        };
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = value.parse().ok(),
+            "y" => height = value.parse().ok(),
+            "rule" if !["B3/S23", "23/3"].contains(&value.to_ascii_uppercase().as_str()) => {
+                return Err(RleError::UnsupportedRule {
+                    rule: value.to_owned(),
+                });
+            }
+            _ => {}
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => Err(error("the header needs a width and a height".to_owned())),
+    }
+}
+
+/// The longest line Golly writes in the rows of an RLE pattern.
+const LINE_LENGTH: usize = 70;
+
+/// The rows of the `width`×`height` rectangle of `grid` whose top left corner is `(x, y)`, wrapping around the torus,
+/// run-length encoded and ending with `!`. Dead cells at the end of a row, and empty rows at the end,
+/// are left out. Lines are broken between runs so that none is longer than 70 characters, as Golly writes them.
+///
+/// ```
+/// use undeath::{rle::encode_body, Grid};
+///
+/// let glider = Grid::from_text("\n..#\n...#\n.###");
+/// assert_eq!(encode_body(&glider, (0, 0), (8, 8)), "$2bo$3bo$b3o!");
+/// assert_eq!(encode_body(&glider, (1, 1), (3, 3)), "bo$2bo$3o!");
+/// ```
+pub fn encode_body(grid: &Grid, (x, y): (i32, i32), (width, height): (i32, i32)) -> String {
+    /// Appends a run, first starting a new line if the current one, which starts at `line_start`, has no room for it.
+    fn push_run(out: &mut String, line_start: &mut usize, length: usize, tag: char) {
+        let digits = if length > 1 {
+            length.ilog10() as usize + 1
+        } else {
+            0
+        };
+        if out.len() - *line_start + digits + 1 > LINE_LENGTH {
+            out.push('\n');
+            *line_start = out.len();
+        }
+        if length > 1 {
+            write!(out, "{length}").unwrap();
+        }
+        out.push(tag);
+    }
+
+    let mut out = String::new();
+    let mut line_start = 0;
+    // Empty rows, and dead cells at the end of a row, are only written once something follows them.
+    let mut rows_ended = 0;
+    for row_y in y..y + height {
+        let row = (x..x + width)
+            .map(|cell_x| {
+                grid.get_wrapped(
+                    cell_x.rem_euclid(grid.width()),
+                    row_y.rem_euclid(grid.height()),
+                )
+            })
+            .collect::<Vec<_>>();
+        let Some(last) = row.iter().rposition(|cell| cell.value > 0) else {
+            rows_ended += 1;
+            continue;
+        };
+        if rows_ended > 0 {
+            push_run(&mut out, &mut line_start, rows_ended, '$');
+        }
+        let mut cells = row[..=last].iter().map(|cell| cell.value > 0).peekable();
+        while let Some(alive) = cells.next() {
+            let mut length = 1;
+            while cells.next_if_eq(&alive).is_some() {
+                length += 1;
+            }
+            push_run(
+                &mut out,
+                &mut line_start,
+                length,
+                if alive { 'o' } else { 'b' },
+            );
+        }
+        rows_ended = 1;
+    }
+    push_run(&mut out, &mut line_start, 1, '!');
+    out
+}
+
+/// Writes the comments, then a `#CXRLE` line if there was a position or generation, then the pattern.
+/// Only the pattern's bounding box is written, as [`Grid::to_rle`] writes it,
+/// with the position of its top left corner if there was a position.
+///
+/// ```
+/// use undeath::rle::Rle;
+///
+/// let text = "#CXRLE Pos=-4,-3 Gen=7\n#C A block.\nx = 2, y = 2, rule = B3/S23\n2o$2o!\n";
+/// assert_eq!(Rle::parse(text)?.to_string(), text);
+/// # Ok::<(), undeath::Error>(())
+/// ```
+impl std::fmt::Display for Rle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bounding_box = self.position.and(self.grid.bounding_box());
+        let position = match bounding_box {
+            Some((x, y, _, _)) => {
+                let (centre_x, centre_y) = (self.grid.width() / 2, self.grid.height() / 2);
+                Some(((x - centre_x) as i64, (y - centre_y) as i64))
+            }
+            None => self.position,
+        };
+        if position.is_some() || self.generation.is_some() {
+            f.write_str("#CXRLE")?;
+            if let Some((x, y)) = position {
+                write!(f, " Pos={x},{y}")?;
+            }
+            if let Some(generation) = self.generation {
+                write!(f, " Gen={generation}")?;
+            }
+            writeln!(f)?;
+        }
+        for comment in &self.comments {
+            writeln!(f, "{comment}")?;
+        }
+        match bounding_box {
+            Some((x, y, width, height)) => {
+                writeln!(f, "x = {width}, y = {height}, rule = B3/S23")?;
+                writeln!(f, "{}", encode_body(&self.grid, (x, y), (width, height)))
+            }
+            None => f.write_str(&self.grid.to_rle()),
+        }
+    }
+}
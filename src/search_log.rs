@@ -0,0 +1,171 @@
+//! A lightweight log of how a long search is going, for reading after the fact.
+//!
+//! Where a [`trace`](crate::trace) records every decision, a [`SearchLog`] records only the milestones:
+//! each search starting or restarting, solutions, progress snapshots and the final outcome,
+//! as one JSON object per line with a timestamp.
+//! Each line is written to the file as soon as it is recorded, so the log survives the program dying.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    searcher::SearchStats,
+    trace::{json_grid, TraceEvent, TraceSink},
+    Error, Grid,
+};
+
+/// A milestone of a search, recorded by a [`SearchLog`].
+#[derive(Debug, Clone, Copy)]
+pub enum LogEvent<'a> {
+    /// The first search started, looking for predecessors of `next`.
+    Start { next: &'a Grid },
+    /// Another search started after the first, looking for predecessors of `next`.
+    Restart { next: &'a Grid },
+    /// A search found a predecessor, at this iteration of that search and this depth of guesses.
+    Solution { iteration: u64, depth: usize },
+    /// A snapshot of the work done so far.
+    Progress {
+        iterations: usize,
+        stats: &'a SearchStats,
+    },
+    /// The searching is over, for the reason given.
+    Finish { outcome: &'a str },
+}
+
+/// Appends [`LogEvent`]s to a file as JSON lines, starting afresh at a size limit.
+///
+/// Clones share the same file, so one log can be given to many searchers with [`Searcher::trace_to`](crate::Searcher::trace_to),
+/// which records each search's start and solutions.
+/// Only the first start is logged as a start; every later one is a restart.
+///
+/// When the file grows past the limit, it is renamed with `.1` appended, replacing any older one,
+/// and a new file is started, so at most about twice the limit is kept.
+#[derive(Clone)]
+pub struct SearchLog {
+    file: Arc<Mutex<LogFile>>,
+}
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    /// The size of the current file.
+    written: u64,
+    max_bytes: u64,
+    started: bool,
+}
+
+impl SearchLog {
+    /// Opens `path` to append to, creating it if needed, and starts afresh once it reaches `max_bytes`.
+    pub fn open(path: impl AsRef<Path>, max_bytes: u64) -> Result<Self, Error> {
+        let path = path.as_ref().to_owned();
+        let file = Self::append_to(&path)?;
+        let written = file.metadata().map_err(|e| Error::io(&path, e))?.len();
+        Ok(Self {
+            file: Arc::new(Mutex::new(LogFile {
+                path,
+                file,
+                written,
+                max_bytes,
+                started: false,
+            })),
+        })
+    }
+
+    fn append_to(path: &Path) -> Result<File, Error> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| Error::io(path, e))
+    }
+
+    /// Writes `event` to the log, with the time it was recorded.
+    pub fn log(&self, event: &LogEvent) -> Result<(), Error> {
+        // A panic elsewhere while writing leaves at worst a partial line, so carry on.
+        let mut log = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let event = match *event {
+            LogEvent::Start { next } | LogEvent::Restart { next } if log.started => {
+                LogEvent::Restart { next }
+            }
+            event => event,
+        };
+        if let LogEvent::Start { .. } = event {
+            log.started = true;
+        }
+        let time_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis());
+        let fields = match event {
+            LogEvent::Start { next } => format!("\"event\":\"start\",\"next\":{}", json_grid(next)),
+            LogEvent::Restart { next } => {
+                format!("\"event\":\"restart\",\"next\":{}", json_grid(next))
+            }
+            LogEvent::Solution { iteration, depth } => {
+                format!("\"event\":\"solution\",\"iteration\":{iteration},\"depth\":{depth}")
+            }
+            LogEvent::Progress { iterations, stats } => format!(
+                "\"event\":\"progress\",\"iterations\":{iterations},\"decisions\":{},\
+                 \"deductions\":{},\"contradictions\":{}",
+                stats.decisions, stats.deductions, stats.contradictions
+            ),
+            LogEvent::Finish { outcome } => {
+                format!("\"event\":\"finish\",\"outcome\":{}", json_string(outcome))
+            }
+        };
+        let line = format!("{{\"time_ms\":{time_ms},{fields}}}\n");
+        if log.written > 0 && log.written + line.len() as u64 > log.max_bytes {
+            log.rotate()?;
+        }
+        log.file
+            .write_all(line.as_bytes())
+            .map_err(|e| Error::io(&log.path, e))?;
+        log.written += line.len() as u64;
+        Ok(())
+    }
+}
+
+/// `text` as a JSON string literal.
+fn json_string(text: &str) -> String {
+    let mut literal = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            c if c.is_control() => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+impl LogFile {
+    /// Moves the current file to its `.1` name, and starts a new one.
+    fn rotate(&mut self) -> Result<(), Error> {
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(".1");
+        std::fs::rename(&self.path, &rotated).map_err(|e| Error::io(&self.path, e))?;
+        self.file = SearchLog::append_to(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+/// Logs the start and solutions of each search, and ignores its decisions and backtracks.
+impl TraceSink for SearchLog {
+    fn record(&mut self, event: &TraceEvent) -> Result<(), Error> {
+        match event {
+            TraceEvent::Start { next, .. } => self.log(&LogEvent::Start { next }),
+            &TraceEvent::Solution { iteration, depth } => {
+                self.log(&LogEvent::Solution { iteration, depth })
+            }
+            TraceEvent::Decide { .. } | TraceEvent::Backtrack { .. } => Ok(()),
+        }
+    }
+}
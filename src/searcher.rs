@@ -1,18 +1,459 @@
+use std::{
+    cmp::Ordering, collections::BinaryHeap, fmt::Write, iter::FusedIterator, ops::AddAssign,
+    str::FromStr, time::Duration,
+};
+
+use log::{debug, info};
+
 use crate::{
-    grid::{Grid, SIZE},
+    bruteforce,
+    cadence::{Cadence, SystemClock},
+    effort::EffortReport,
+    grid::{Cell, Grid, Neighbourhood, Topology},
     guess::Guess,
-    string::hconcat,
+    local::LocalSearch,
+    order::{CellOrder, FirstCandidate},
+    rng::Rng,
+    string::hconcat_all,
+    trace::{TraceEvent, TraceSink},
+    typed_grid::BoolGrid,
+    verify::{render_mismatch, verify_predecessor},
+    Error,
 };
 
+/// The cells a guess decided besides the one guessed, for [`SearchStats`] and the [`EffortReport`].
+struct Deductions {
+    /// How many cells were decided, which is the size of `cells`, counted without walking it.
+    count: usize,
+    cells: BoolGrid,
+    /// The cell guessed.
+    guessed: (i32, i32),
+    /// How many guesses below the root the refined guess is.
+    depth: usize,
+}
+
+/// A resumable depth-first search for the predecessors of a grid.
+///
+/// Call [`Searcher::search`] repeatedly, each time with a bound on the amount of work to do,
+/// to enumerate every predecessor exactly once.
+///
+/// ```
+/// use undeath::{Cell, Grid, SearchResult, Searcher};
+///
+/// let mut blinker = Grid::default();
+/// for x in 2..5 {
+///     blinker.set_wrapped(x, 3, Cell::one());
+/// }
+///
+/// let mut searcher = Searcher::new(blinker.clone());
+/// let parent = loop {
+///     match searcher.search(10_000)? {
+///         SearchResult::Found(parent, _) => break parent,
+///         SearchResult::Working(_) => continue,
+///         _ => panic!("a blinker has a predecessor"),
+///     }
+/// };
+/// let mut stepped = parent.clone();
+/// stepped.step();
+/// assert!(stepped == blinker);
+/// # Ok::<(), undeath::Error>(())
+/// ```
 pub struct Searcher {
     next: Grid,
     guess_stack: Vec<Guess>,
     action_stack: Vec<Action>,
+    /// Every cell not in a frozen row, in the order to branch on them.
     all_cells: Vec<(i32, i32)>,
+    /// A bitmask of the rows removed from `all_cells`, because they are decided in every guess the search has left.
+    /// Only the first 64 rows are ever frozen.
+    frozen_rows: u64,
     alive_cells: Vec<(i32, i32)>,
+    /// If set, the cells predecessors may have alive, as given to [`Searcher::with_mask`].
+    mask: Option<Grid>,
+    /// Which cells count as neighbours, under Life's rule.
+    neighbourhood: Neighbourhood,
+    /// Chooses the cell to branch on.
+    order: Box<dyn CellOrder + Send>,
+    /// When to shave guesses.
+    shaving: Shaving,
+    /// Whether to run the block-level tier of propagation after each guess.
+    block_propagation: bool,
+    /// If set, contradictions found within this many guesses of the root are checked by brute force.
+    validation_depth: Option<usize>,
+    /// If set, contradictions found within this many guesses of the root have their reasoning logged.
+    #[cfg(feature = "provenance")]
+    trace_depth: Option<usize>,
+    stats: SearchStats,
+    /// How many contradictions were detected at each constraint cell.
+    heatmap: Grid,
+    /// Where cells were decided, and how.
+    effort: EffortReport,
+    /// How to explore the tree of guesses.
+    strategy: Strategy,
+    /// In best-first and beam search, the guesses waiting to be branched on, once the search has started.
+    /// In beam search, these are the guesses left at the current level.
+    open: Option<BinaryHeap<OpenGuess>>,
+    /// In beam search, the refinements of the guesses branched on at the current level.
+    next_level: Vec<OpenGuess>,
+    /// Whether beam search has discarded a guess, so that exhausting the rest proves nothing.
+    pruned: bool,
+    /// How many guesses have ever been opened, to break ties between them.
+    opened: u64,
+    /// How many guesses were made to reach the bottom of `guess_stack`,
+    /// which is an open guess being searched depth-first in best-first search, and otherwise the root.
+    base_depth: usize,
+    /// What randomized strategies draw from.
+    rng: Rng,
+    /// In local and hybrid search, the current frame, once the search has started.
+    local: Option<LocalSearch>,
+    /// In hybrid search, the frame local search handed off to depth-first search, once it has.
+    handoff: Option<Handoff>,
+    /// Sizes the batches [`Searcher::search_slice`] searches, once it has been called or configured.
+    cadence: Option<Cadence>,
+    /// Where to record the moves of depth-first search, if anywhere.
+    trace: Option<Tracer>,
+}
+
+/// A [`TraceSink`], and what the searcher needs to feed it.
+struct Tracer {
+    sink: Box<dyn TraceSink + Send>,
+    /// Whether the [`TraceEvent::Start`] event has been recorded.
+    started: bool,
+    /// How many steps depth-first search has taken, over every call to [`Searcher::search`].
+    iteration: u64,
+}
+
+/// Records the event `event` makes from the current iteration, if tracing a depth-first search.
+/// This takes the fields it needs rather than the searcher, so that the current guess can stay borrowed.
+fn emit(
+    trace: &mut Option<Tracer>,
+    strategy: Strategy,
+    event: impl FnOnce(u64) -> TraceEvent,
+) -> Result<(), Error> {
+    match trace {
+        Some(trace) if strategy == Strategy::DepthFirst => {
+            trace.sink.record(&event(trace.iteration))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A frame that local search got close to a predecessor, for depth-first search to finish.
+struct Handoff {
+    /// The root guess the frame agrees with.
+    root: Guess,
+    frame: Grid,
+    /// The target cells the frame steps to the wrong value.
+    violated: BoolGrid,
+    /// How far, in either axis, from a violated target cell the frame's cells are left undecided.
+    radius: i32,
+    /// The radius to widen to if depth-first search finds nothing more, before going back to local search.
+    max_radius: i32,
+    /// How many iterations of depth-first search this handoff has had.
+    iterations: usize,
+}
+
+impl Handoff {
+    /// The distance between two cells on a `width`×`height` torus, as a king moves.
+    fn distance((x1, y1): (i32, i32), (x2, y2): (i32, i32), width: i32, height: i32) -> i32 {
+        let dx = (x1 - x2).rem_euclid(width);
+        let dy = (y1 - y2).rem_euclid(height);
+        dx.min(width - dx).max(dy.min(height - dy))
+    }
+
+    /// The root guess, with every cell of the frame further than `radius` from all violations decided to match it.
+    fn guess(&self, next: &Grid) -> Guess {
+        let violations = self.violated.iter().collect::<Vec<_>>();
+        let mut alive = self.root.alive();
+        let mut dead = self.root.dead();
+        let (width, height) = (next.width(), next.height());
+        for y in 0..height {
+            for x in 0..width {
+                if violations.iter().any(|&violation| {
+                    Self::distance((x, y), violation, width, height) <= self.radius
+                }) {
+                    continue;
+                }
+                if self.frame.get_wrapped(x, y).value > 0 {
+                    alive.set_wrapped(x, y, Cell::one());
+                } else {
+                    dead.set_wrapped(x, y, Cell::one());
+                }
+            }
+        }
+        Guess::from_partial(next, &alive, &dead)
+    }
+
+    /// Whether every cell is within `radius` of a violation, so that exhausting the handoff exhausts the root.
+    fn covers_everything(&self) -> bool {
+        self.radius >= self.frame.width().max(self.frame.height()) / 2
+    }
+}
+
+/// Running totals of how a [`Searcher`] has decided cells so far.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[non_exhaustive]
+pub struct SearchStats {
+    /// How many guesses the searcher has made, including those that led to a contradiction.
+    pub decisions: u64,
+    /// How many cells those guesses decided by propagation, beyond the guessed cells themselves.
+    pub deductions: u64,
+    /// How many of those guesses led to a contradiction.
+    pub contradictions: u64,
+    /// A crude estimate of how much of the search space depth-first search has ruled out, from zero to one.
+    ///
+    /// The guesses on the searcher's stack are read as a path through a binary tree, with the first guess
+    /// at each cell as `0` and the second as `1`, and the estimate is the fraction of the tree to the left
+    /// of that path, as if every subtree were the same size. **They are not.** Propagation prunes some
+    /// subtrees almost at once and others hardly at all, so the estimate is nonlinear in time: it can sit
+    /// near zero for most of a search and then jump, or race to 0.99 and stay there. Use it to tell whether
+    /// a search is moving, not to predict when it will end.
+    ///
+    /// It never decreases within a run, and is exactly `1.0` once the search space is exhausted.
+    /// Restarts, as after [`Searcher::merge_root`], keep the estimate where it was.
+    /// Only depth-first search moves it before then; other strategies report zero until they exhaust.
+    pub progress_estimate: f64,
+}
+
+impl SearchStats {
+    /// How many cells propagation decided per guess, on average, or zero if there have been no guesses.
+    pub fn deductions_per_decision(&self) -> f64 {
+        if self.decisions == 0 {
+            0.0
+        } else {
+            self.deductions as f64 / self.decisions as f64
+        }
+    }
+}
+
+impl AddAssign for SearchStats {
+    fn add_assign(&mut self, other: Self) {
+        self.decisions += other.decisions;
+        self.deductions += other.deductions;
+        self.contradictions += other.contradictions;
+        // Estimates of different searches don't add up, so keep the furthest along.
+        self.progress_estimate = self.progress_estimate.max(other.progress_estimate);
+    }
+}
+
+/// When a [`Searcher`] shaves its guesses: for each undecided cell, it tries both values with full propagation,
+/// and keeps whatever follows from both. This prunes more than propagation alone, but is expensive.
+///
+/// ```
+/// use undeath::searcher::Shaving;
+///
+/// assert_eq!("root".parse::<Shaving>()?, Shaving::Root);
+/// assert_eq!("periodic:4".parse::<Shaving>()?, Shaving::Periodic(4));
+/// assert!("periodic:0".parse::<Shaving>().is_err());
+/// # Ok::<(), undeath::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Shaving {
+    /// Never shave.
+    #[default]
+    Off,
+    /// Shave once, before searching.
+    Root,
+    /// Shave before searching, and after every guess at a depth that is a multiple of this.
+    Periodic(usize),
+}
+
+impl FromStr for Shaving {
+    type Err = Error;
+
+    /// Parses `off`, `root`, or `periodic:K` for a positive `K`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Shaving::Off),
+            "root" => Ok(Shaving::Root),
+            _ => match s.strip_prefix("periodic:").map(str::parse) {
+                Some(Ok(period)) if period > 0 => Ok(Shaving::Periodic(period)),
+                _ => Err(Error::InvalidInput(format!(
+                    "expected `off`, `root`, or `periodic:K` for a positive K, but found `{s}`"
+                ))),
+            },
+        }
+    }
+}
+
+/// How a [`Searcher`] explores the tree of guesses.
+///
+/// Depth-first and best-first search find every predecessor exactly once. Beam search finds some of them,
+/// and local and hybrid search may find the same one many times.
+///
+/// ```
+/// use undeath::searcher::Strategy;
+///
+/// assert_eq!("dfs".parse::<Strategy>()?, Strategy::DepthFirst);
+/// assert_eq!(
+///     "best-first".parse::<Strategy>()?,
+///     Strategy::BestFirst { max_open: Strategy::DEFAULT_MAX_OPEN }
+/// );
+/// assert_eq!("best-first:64".parse::<Strategy>()?, Strategy::BestFirst { max_open: 64 });
+/// assert_eq!("beam:8".parse::<Strategy>()?, Strategy::Beam { width: 8 });
+/// assert_eq!(
+///     "local:500".parse::<Strategy>()?,
+///     Strategy::Local { noise_percent: Strategy::DEFAULT_NOISE_PERCENT, restart_after: 500 }
+/// );
+/// assert_eq!("hybrid:6:2".parse::<Strategy>()?, Strategy::Hybrid { max_violations: 6, radius: 2 });
+/// # Ok::<(), undeath::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Always refine the most recent guess. This needs memory only for one path through the tree,
+    /// so it is the strategy to use for enumerating every predecessor.
+    #[default]
+    DepthFirst,
+    /// Always branch on the open guess that has decided the most cells beyond the guesses made to reach it,
+    /// which can find a first predecessor sooner by not committing to early guesses.
+    /// At most `max_open` guesses are kept open; once that many are, the best is searched depth-first instead.
+    BestFirst { max_open: usize },
+    /// Branch on every guess at one depth before going deeper, keeping only the best `width` refinements
+    /// of each level, scored as in best-first search. This needs little memory and never backtracks,
+    /// but the guesses it discards may be the only ones that lead to predecessors,
+    /// so running out of guesses is [`SearchResult::Inconclusive`] rather than a proof.
+    Beam { width: usize },
+    /// Don't search the tree of guesses at all: start from a random frame that agrees with the root guess,
+    /// and repeatedly flip a cell near a target cell it steps to the wrong value, until there are none.
+    /// With probability `noise_percent`%, a random nearby cell is flipped; otherwise, the one that leaves the fewest
+    /// wrong cells. After `restart_after` flips, it starts again from a new random frame.
+    /// This can find predecessors of dense targets that make systematic search flail,
+    /// but never proves anything, so it only reports [`SearchResult::Unsatisfiable`] if the root guess does.
+    /// It draws from the generator set with [`Searcher::rng`], and ignores the cell order.
+    Local {
+        noise_percent: u32,
+        restart_after: usize,
+    },
+    /// Run local search, with the default noise and restarts, until at most `max_violations` target cells
+    /// are stepped to the wrong value, then hand off to depth-first search: cells of the frame further than
+    /// `radius` from every violation are decided as they are, and the rest are searched.
+    /// If that finds nothing more, the radius grows by one and depth-first search starts again.
+    /// If that finds nothing more either, or either search runs for as many iterations as local search makes flips
+    /// between restarts, local search restarts from a new frame.
+    /// Like local search, this only proves anything if the radius covers the whole grid,
+    /// and may find the same predecessor many times.
+    Hybrid { max_violations: u32, radius: i32 },
+}
+
+impl Strategy {
+    /// How many guesses best-first search keeps open unless told otherwise.
+    pub const DEFAULT_MAX_OPEN: usize = 4096;
+    /// How often local search flips a random cell rather than the best one unless told otherwise, in percent.
+    pub const DEFAULT_NOISE_PERCENT: u32 = 20;
+    /// How many flips local search makes before restarting unless told otherwise.
+    pub const DEFAULT_RESTART_AFTER: usize = 10_000;
+    /// How few violations hybrid search waits for before handing off to depth-first search unless told otherwise.
+    pub const DEFAULT_MAX_VIOLATIONS: u32 = 2;
+    /// How far from each violation hybrid search leaves cells undecided unless told otherwise.
+    pub const DEFAULT_RADIUS: i32 = 1;
+}
+
+impl FromStr for Strategy {
+    type Err = Error;
+
+    /// Parses `dfs`, `best-first`, `best-first:N` for a positive `N` guesses kept open,
+    /// `beam:W` for a positive width `W`, `local` or `local:F` to restart local search every positive `F` flips,
+    /// or `hybrid` or `hybrid:K:R` to hand off at a positive `K` violations with radius `R`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let positive = |prefix| match s.strip_prefix(prefix).map(str::parse) {
+            Some(Ok(n)) if n > 0 => Some(n),
+            _ => None,
+        };
+        match s {
+            "dfs" => Ok(Strategy::DepthFirst),
+            "best-first" => Ok(Strategy::BestFirst {
+                max_open: Strategy::DEFAULT_MAX_OPEN,
+            }),
+            "local" => Ok(Strategy::Local {
+                noise_percent: Strategy::DEFAULT_NOISE_PERCENT,
+                restart_after: Strategy::DEFAULT_RESTART_AFTER,
+            }),
+            "hybrid" => Ok(Strategy::Hybrid {
+                max_violations: Strategy::DEFAULT_MAX_VIOLATIONS,
+                radius: Strategy::DEFAULT_RADIUS,
+            }),
+            _ => {
+                if let Some(max_open) = positive("best-first:") {
+                    Ok(Strategy::BestFirst { max_open })
+                } else if let Some(width) = positive("beam:") {
+                    Ok(Strategy::Beam { width })
+                } else if let Some(restart_after) = positive("local:") {
+                    Ok(Strategy::Local {
+                        noise_percent: Strategy::DEFAULT_NOISE_PERCENT,
+                        restart_after,
+                    })
+                } else if let Some(Ok(hybrid)) = s.strip_prefix("hybrid:").map(parse_hybrid) {
+                    Ok(hybrid)
+                } else {
+                    Err(Error::InvalidInput(format!(
+                        "expected `dfs`, `best-first`, `best-first:N`, `beam:W`, `local`, `local:F`, \
+                         `hybrid`, or `hybrid:K:R` for positive N, W, F and K, but found `{s}`"
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// Parses the `K:R` of `hybrid:K:R`.
+fn parse_hybrid(knobs: &str) -> Result<Strategy, ()> {
+    let (max_violations, radius) = knobs.split_once(':').ok_or(())?;
+    let max_violations = max_violations.parse().map_err(|_| ())?;
+    let radius = radius.parse().map_err(|_| ())?;
+    if max_violations == 0 || radius < 0 {
+        return Err(());
+    }
+    Ok(Strategy::Hybrid {
+        max_violations,
+        radius,
+    })
+}
+
+/// A guess waiting to be branched on in best-first or beam search. The greatest is branched on first.
+struct OpenGuess {
+    /// How many cells the guess has decided beyond the guesses made to reach it.
+    score: i64,
+    /// When the guess was opened, so that ties go to the most recent, as they would in depth-first search.
+    opened: u64,
+    /// How many guesses were made to reach this one.
+    depth: usize,
+    guess: Guess,
+}
+
+impl OpenGuess {
+    /// Scores `guess`, reached by `depth` guesses, as the number of cells it has decided beyond those guesses.
+    fn new(guess: Guess, depth: usize, opened: u64) -> Self {
+        Self {
+            score: guess.decided_count() as i64 - depth as i64,
+            opened,
+            depth,
+            guess,
+        }
+    }
+}
+
+impl PartialEq for OpenGuess {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenGuess {}
+
+impl PartialOrd for OpenGuess {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenGuess {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.score, self.opened).cmp(&(other.score, other.opened))
+    }
 }
 
 #[derive(Clone, Copy)]
+#[allow(clippy::enum_variant_names)]
 enum Action {
     MakeGuess,
     /// If the bool is true, the first guess is that the cell is alive.
@@ -21,168 +462,1380 @@ enum Action {
     SecondGuess(i32, i32, bool),
 }
 
+/// The outcome of one call to [`Searcher::search`].
+/// The iteration counts are the number of iterations performed during that call.
+///
+/// ```
+/// use undeath::{Grid, SearchResult, Searcher};
+///
+/// // The empty grid is its own predecessor.
+/// let mut searcher = Searcher::new(Grid::default());
+/// assert!(matches!(searcher.search(1_000_000)?, SearchResult::Found(_, _)));
+/// # Ok::<(), undeath::Error>(())
+/// ```
+#[non_exhaustive]
+// Results are returned once per call to `search`, so boxing the grid would gain nothing.
+#[allow(clippy::large_enum_variant)]
 pub enum SearchResult {
+    /// A new predecessor was found. Searching again will look for the next one.
     Found(Grid, usize),
+    /// The iteration limit was reached. Searching again will resume where this call stopped.
     Working(usize),
+    /// Every predecessor has already been found.
     Unsatisfiable,
+    /// The search gave up without considering every possibility, as [`Strategy::Beam`] does when
+    /// every guess it kept turns out to be contradictory. Unlike [`SearchResult::Unsatisfiable`], this proves nothing:
+    /// there may be predecessors it did not find. Searching again gives up again.
+    Inconclusive,
 }
 
 impl Searcher {
+    /// Searches for the predecessors of `next`, which are grids of the same size and [`Topology`].
+    /// On a [`Topology::Plane`], predecessors cannot rely on cells wrapping around the edges.
+    /// Local and hybrid search, [`Searcher::check_windows`] and [`Searcher::trace_to`] then fail with
+    /// [`Error::InvalidInput`], as they do outside the Moore neighbourhood.
+    ///
+    /// ```
+    /// use undeath::{Cell, Grid, SearchResult, Searcher};
+    ///
+    /// let mut glider = Grid::new(20, 6);
+    /// for (x, y) in [(11, 1), (12, 2), (10, 3), (11, 3), (12, 3)] {
+    ///     glider.set_wrapped(x, y, Cell::one());
+    /// }
+    /// let SearchResult::Found(parent, _) = Searcher::new(glider.clone()).search(1_000_000)? else {
+    ///     panic!("a glider has a predecessor");
+    /// };
+    /// assert_eq!((parent.width(), parent.height()), (20, 6));
+    /// # Ok::<(), undeath::Error>(())
+    /// ```
     pub fn new(next: Grid) -> Self {
-        let alive_cells = next.alive_cells().collect::<Vec<_>>();
-        let mut all_cells = (0..SIZE)
-            .flat_map(|x| (0..SIZE).map(move |y| (x, y)))
-            .collect::<Vec<_>>();
-        // Try cells furthest from active ones first.
-        all_cells.sort_by_key(|(x, y)| {
-            -alive_cells
-                .iter()
-                .map(|(ax, ay)| ((ax - x + SIZE) % SIZE).abs() + ((ay - y + SIZE) % SIZE).abs())
-                .min()
-                .unwrap_or(-1000)
+        let (width, height) = (next.width(), next.height());
+        let mut searcher = Self {
+            next: Grid::new(width, height),
+            guess_stack: Vec::new(),
+            action_stack: Vec::new(),
+            all_cells: Vec::new(),
+            frozen_rows: 0,
+            alive_cells: Vec::new(),
+            mask: None,
+            neighbourhood: Neighbourhood::Moore,
+            order: Box::new(FirstCandidate),
+            shaving: Shaving::Off,
+            block_propagation: false,
+            validation_depth: None,
+            #[cfg(feature = "provenance")]
+            trace_depth: None,
+            stats: SearchStats::default(),
+            heatmap: Grid::new(width, height),
+            effort: EffortReport::new(width, height),
+            strategy: Strategy::DepthFirst,
+            open: None,
+            next_level: Vec::new(),
+            pruned: false,
+            opened: 0,
+            base_depth: 0,
+            rng: Rng::new(0),
+            local: None,
+            handoff: None,
+            cadence: None,
+            trace: None,
+        };
+        searcher.reset(next);
+        searcher
+    }
+
+    /// Starts searching for the predecessors of `next` from scratch, as if this searcher had just been made for it,
+    /// but keeping its configuration and the buffers it has already allocated.
+    /// Unlike the builder methods, this does not shave the root or check its windows.
+    pub(crate) fn reset(&mut self, next: Grid) {
+        self.alive_cells.clear();
+        self.alive_cells.extend(next.alive_cells());
+        branching_order(
+            &self.alive_cells,
+            next.width(),
+            next.height(),
+            &mut self.all_cells,
+        );
+        self.next = next;
+        self.mask = None;
+        self.restart_root();
+        self.frozen_rows = 0;
+        self.stats = SearchStats::default();
+        self.heatmap.reset(self.next.width(), self.next.height());
+        self.effort.reset(self.next.width(), self.next.height());
+        self.open = None;
+        self.next_level.clear();
+        self.pruned = false;
+        self.opened = 0;
+        self.base_depth = 0;
+        self.local = None;
+        self.handoff = None;
+        if let Some(trace) = self.trace.as_mut() {
+            trace.started = false;
+            trace.iteration = 0;
+        }
+    }
+
+    /// Starts searching for the predecessors of `next` instead, keeping this searcher's configuration and buffers,
+    /// which is cheaper than making a new searcher for each of many related targets.
+    ///
+    /// What is kept:
+    /// - every setting made with the builder methods, such as the cell order, strategy, neighbourhood,
+    ///   shaving, trace sink and progress cadence, and the state of the random generator;
+    /// - the memory of the guess and action stacks and the branching order, so that searching a target
+    ///   of the same size, with no more alive cells than the last, allocates nothing up front.
+    ///
+    /// What is not:
+    /// - the guesses, so the search starts again from the root, which is not shaved or checked against windows;
+    /// - the mask, so predecessors may have alive cells anywhere;
+    /// - the branching order, which is worked out again from the alive cells of `next`, and the frozen rows;
+    /// - the [`Searcher::stats`], and the state of best-first, beam, local and hybrid search.
+    ///
+    /// If `keep_activity` is set, what the searcher learned about where the search struggles is kept too:
+    /// the [`Searcher::contradiction_heatmap`] and [`Searcher::effort_report`], and, if the cell order tracks [`Guess::impact`], the impact
+    /// of the guess being refined, which includes what every guess before it learned. That suits targets that differ in only a few cells.
+    /// Otherwise, or if `next` is a different size from the last target, they are all cleared as in a new searcher.
+    ///
+    /// ```
+    /// use undeath::{Grid, SearchResult, Searcher};
+    ///
+    /// let mut searcher = Searcher::new(Grid::from_text("\n\n\n..###"));
+    /// assert!(matches!(searcher.search(1_000_000)?, SearchResult::Found(..)));
+    /// let block = Grid::from_text("\n\n..##\n..##");
+    /// searcher.reset_with_target(block.clone(), false);
+    /// let SearchResult::Found(mut parent, _) = searcher.search(1_000_000)? else {
+    ///     panic!("a block is a still life");
+    /// };
+    /// parent.step();
+    /// assert!(parent == block);
+    /// # Ok::<(), undeath::Error>(())
+    /// ```
+    pub fn reset_with_target(&mut self, next: Grid, keep_activity: bool) {
+        if !keep_activity || !next.same_size(&self.next) {
+            self.reset(next);
+            return;
+        }
+        let heatmap = std::mem::take(&mut self.heatmap);
+        let effort = std::mem::take(&mut self.effort);
+        // Popping the guess keeps the stack's memory, unlike taking the stack.
+        let current = self.guess_stack.pop();
+        self.reset(next);
+        self.heatmap = heatmap;
+        self.effort = effort;
+        if let (Some(current), Some(root)) = (current, self.guess_stack.first_mut()) {
+            root.adopt_impact(&current);
+        }
+    }
+
+    /// Starts again from the root guess, which decides only that the cells outside the mask, if there is one, are dead.
+    fn restart_root(&mut self) {
+        let (width, height) = (self.next.width(), self.next.height());
+        // Clearing an old guess in place reuses its memory.
+        self.guess_stack.truncate(1);
+        let mut root = match self.guess_stack.pop() {
+            Some(mut root) => {
+                root.reset(self.neighbourhood, self.next.topology(), width, height);
+                root
+            }
+            None => Guess::empty_on(self.neighbourhood, self.next.topology(), width, height),
+        };
+        if self.order.tracks_impact() {
+            root.track_impact();
+        }
+        if let Some(mask) = &self.mask {
+            for y in 0..height {
+                for x in 0..width {
+                    if mask.get_wrapped(x, y).value <= 0 {
+                        root.guess_dead(&self.next, x, y);
+                    }
+                }
+            }
+        }
+        self.action_stack.clear();
+        // If the root is contradictory, no grid confined to the mask can work, so there is nothing left to search.
+        if !root.found_contradiction() {
+            self.guess_stack.push(root);
+            self.action_stack.push(Action::MakeGuess);
+        }
+    }
+
+    /// Like [`Searcher::new`], but only finds the predecessors consistent with `root`, which must not be contradictory.
+    pub(crate) fn with_root(next: Grid, root: Guess) -> Self {
+        let mut searcher = Self::new(next);
+        searcher.guess_stack = vec![root];
+        searcher
+    }
+
+    /// Like [`Searcher::new`], but only finds predecessors whose alive cells all lie in `mask`:
+    /// every cell where `mask` is not positive is assumed to be dead from the start.
+    ///
+    /// # Panics
+    /// If `mask` is a different size from `next`. [`Searcher::try_with_mask`] fails instead.
+    pub fn with_mask(next: Grid, mask: &Grid) -> Self {
+        next.assert_same_size(mask);
+        let mut searcher = Self::new(next);
+        searcher.mask = Some(mask.clone());
+        searcher.restart_root();
+        searcher
+    }
+
+    /// Like [`Searcher::with_mask`], but fails with [`Error::InvalidInput`] if `mask` is a different size from `next`,
+    /// as it may be when both are read from files.
+    ///
+    /// ```
+    /// use undeath::{Error, Grid, Searcher};
+    ///
+    /// assert!(Searcher::try_with_mask(Grid::default(), &Grid::default()).is_ok());
+    /// assert!(matches!(
+    ///     Searcher::try_with_mask(Grid::default(), &Grid::new(16, 16)),
+    ///     Err(Error::InvalidInput(_))
+    /// ));
+    /// ```
+    pub fn try_with_mask(next: Grid, mask: &Grid) -> Result<Self, Error> {
+        next.check_same_size(mask)?;
+        Ok(Self::with_mask(next, mask))
+    }
+
+    /// Finds predecessors under Life's rule applied to the neighbours in `neighbourhood`,
+    /// rather than in the Moore neighbourhood. This starts the search again from the root guess,
+    /// so call it before any other builder method.
+    ///
+    /// Only the Moore neighbourhood supports local and hybrid search, [`Searcher::check_windows`],
+    /// and [`Searcher::trace_to`], which fail with [`Error::InvalidInput`] otherwise.
+    /// [`Searcher::validate_contradictions`] checks nothing in other neighbourhoods.
+    ///
+    /// ```
+    /// use undeath::{grid::Neighbourhood, Grid, SearchResult, Searcher};
+    ///
+    /// let target = Grid::from_text("\n\n..##\n..##");
+    /// let mut searcher = Searcher::new(target.clone()).neighbourhood(Neighbourhood::VonNeumann);
+    /// let SearchResult::Found(mut parent, _) = searcher.search(1_000_000)? else {
+    ///     panic!("a block is still life in either neighbourhood");
+    /// };
+    /// parent.step_in(Neighbourhood::VonNeumann);
+    /// assert!(parent == target);
+    /// # Ok::<(), undeath::Error>(())
+    /// ```
+    pub fn neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self.restart_root();
+        self
+    }
+
+    /// Whether this searches in the Moore neighbourhood on a torus, which the brute-force checks,
+    /// local search and traces all assume.
+    fn is_moore_torus(&self) -> bool {
+        self.neighbourhood == Neighbourhood::Moore && self.next.topology() == Topology::Torus
+    }
+
+    /// Enables or disables a second tier of constraint propagation, which reasons about 2×2 blocks of cells jointly.
+    /// It prunes more of the search tree, but makes each guess more expensive; it is off by default.
+    pub fn block_propagation(mut self, enabled: bool) -> Self {
+        self.block_propagation = enabled;
+        self
+    }
+
+    /// Sets when to shave guesses. Anything but [`Shaving::Off`] shaves the initial guess immediately,
+    /// so call this before searching.
+    pub fn shave(mut self, shaving: Shaving) -> Self {
+        self.shaving = shaving;
+        if shaving == Shaving::Off {
+            return self;
+        }
+        if let Some(root) = self.guess_stack.first_mut() {
+            root.shave(&self.next);
+            if root.found_contradiction() {
+                // As in `with_mask`, there is nothing left to search.
+                self.guess_stack.clear();
+                self.action_stack.clear();
+            }
+        }
+        self
+    }
+
+    /// Checks every `window`×`window` window of the target with [`bruteforce::unsatisfiable_window`],
+    /// given the cells the initial guess decides. If some window has no predecessor, neither does the target,
+    /// so the search reports [`SearchResult::Unsatisfiable`] without branching at all. Call this before searching.
+    ///
+    /// Returns [`Error::InvalidInput`] unless `window` is between 1 and [`bruteforce::MAX_WINDOW`],
+    /// or if the searcher is not in the Moore neighbourhood or its target is not on a torus.
+    pub fn check_windows(mut self, window: usize) -> Result<Self, Error> {
+        if !self.is_moore_torus() {
+            return Err(Error::InvalidInput(
+                "windows can only be checked in the Moore neighbourhood on a torus".to_owned(),
+            ));
+        }
+        if !(1..=bruteforce::MAX_WINDOW).contains(&window) {
+            return Err(Error::InvalidInput(format!(
+                "windows must be between 1 and {} cells wide, but {window} were asked for",
+                bruteforce::MAX_WINDOW
+            )));
+        }
+        let Some(root) = self.guess_stack.first() else {
+            return Ok(self);
+        };
+        let alive = root.alive();
+        let free = alive.or(&root.dead()).not();
+        if let Some((x, y)) = bruteforce::unsatisfiable_window(&self.next, &alive, &free, window)? {
+            info!("the {window}×{window} window at ({x}, {y}) has no predecessor, so there are no predecessors");
+            // As in `with_mask`, there is nothing left to search.
+            self.guess_stack.clear();
+            self.action_stack.clear();
+        }
+        Ok(self)
+    }
+
+    /// Sets the strategy for choosing which cell to branch on next. The default is [`FirstCandidate`].
+    pub fn cell_order(mut self, order: impl CellOrder + Send + 'static) -> Self {
+        if order.tracks_impact() {
+            for guess in &mut self.guess_stack {
+                guess.track_impact();
+            }
+        }
+        self.order = Box::new(order);
+        self
+    }
+
+    /// Sets how to explore the tree of guesses. The default is [`Strategy::DepthFirst`].
+    /// Call this before searching.
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the generator that randomized strategies, such as [`Strategy::Local`], draw from.
+    /// The default is seeded with zero. Call this before searching.
+    pub fn rng(mut self, rng: Rng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Sets how long each call to [`Searcher::search_slice`] should take, timed by the system clock.
+    /// The default is [`Cadence::DEFAULT_TARGET`].
+    pub fn progress_cadence(self, target: Duration) -> Self {
+        self.cadence(Cadence::new(target, SystemClock::default()))
+    }
+
+    /// Sets what sizes the batches [`Searcher::search_slice`] searches, for example to time them with another [`Clock`](crate::cadence::Clock).
+    pub fn cadence(mut self, cadence: Cadence) -> Self {
+        self.cadence = Some(cadence);
+        self
+    }
+
+    /// Records every decision, backtrack and solution of depth-first search to `sink`, for [`Replay`](crate::replay::Replay).
+    /// Only [`Strategy::DepthFirst`] is traced: with other strategies, nothing is recorded.
+    pub fn trace_to(mut self, sink: impl TraceSink + Send + 'static) -> Self {
+        self.trace = Some(Tracer {
+            sink: Box::new(sink),
+            started: false,
+            iteration: 0,
         });
+        self
+    }
 
-        Self {
-            next,
-            guess_stack: vec![Guess::default()],
-            action_stack: vec![Action::MakeGuess],
-            all_cells,
-            alive_cells,
+    /// Records the start of the search, if tracing and it has not been recorded already.
+    fn start_trace(&mut self) -> Result<(), Error> {
+        let moore_torus = self.is_moore_torus();
+        let Some(trace) = &mut self.trace else {
+            return Ok(());
+        };
+        if trace.started || self.strategy != Strategy::DepthFirst {
+            return Ok(());
+        }
+        if !moore_torus {
+            // Replaying a trace assumes the Moore neighbourhood on a torus.
+            return Err(Error::InvalidInput(
+                "only searches in the Moore neighbourhood on a torus can be traced".to_owned(),
+            ));
+        }
+        trace.started = true;
+        let Some(root) = self.guess_stack.first() else {
+            return Ok(());
+        };
+        let event = TraceEvent::Start {
+            next: self.next.clone(),
+            root_alive: root.alive(),
+            root_dead: root.dead(),
+            block_propagation: self.block_propagation,
+            shave_period: match self.shaving {
+                Shaving::Periodic(period) => period,
+                _ => 0,
+            },
+        };
+        trace.sink.record(&event)
+    }
+
+    /// Refines `guess`, reached by `depth` guesses, with the information that `(x, y)` is `alive`, or dead if not.
+    /// Also returns which other cells this decided.
+    fn apply_guess(
+        &self,
+        guess: &Guess,
+        depth: usize,
+        x: i32,
+        y: i32,
+        alive: bool,
+    ) -> (Guess, Deductions) {
+        let mut new_guess = refine(
+            &self.next,
+            guess,
+            depth,
+            (x, y),
+            alive,
+            self.block_propagation,
+            self.shaving,
+        );
+        let deductions = new_guess
+            .decided_count()
+            .saturating_sub(guess.decided_count() + 1);
+        new_guess.record_impact(x, y, deductions);
+        let mut cells = new_guess.newly_decided(guess);
+        cells.clear(x, y);
+        let deductions = Deductions {
+            count: deductions,
+            cells,
+            guessed: (x, y),
+            depth: depth + 1,
+        };
+        (new_guess, deductions)
+    }
+
+    /// Counts a guess that made the deductions `deductions` and led to `new_guess`,
+    /// and where it failed if it was contradictory.
+    fn record_decision(&mut self, new_guess: &Guess, deductions: Deductions) {
+        self.stats.decisions += 1;
+        self.stats.deductions += deductions.count as u64;
+        let (x, y) = deductions.guessed;
+        self.effort
+            .record_decision(x, y, deductions.depth, &deductions.cells);
+        if let Some((x, y)) = new_guess.contradiction_cell() {
+            self.stats.contradictions += 1;
+            let count = self.heatmap.get_wrapped(x, y).value;
+            self.heatmap.set_wrapped(x, y, Cell { value: count + 1 });
+        }
+    }
+
+    /// Moves [`SearchStats::progress_estimate`] up to where the depth-first path now is, if it is further along.
+    /// The action stack holds one pending guess per depth, so a second guess at depth `d` means the subtree
+    /// of its first guess, `2^-(d + 1)` of the tree, is done.
+    fn record_progress(&mut self) {
+        if self.strategy != Strategy::DepthFirst {
+            return;
+        }
+        let estimate = self
+            .action_stack
+            .iter()
+            .enumerate()
+            .filter(|(_, action)| matches!(action, Action::SecondGuess(_, _, _)))
+            .map(|(depth, _)| 0.5f64.powi(depth as i32 + 1))
+            .sum::<f64>();
+        // Rounding could make a deeper path look slightly smaller, so never go backwards.
+        self.stats.progress_estimate = self.stats.progress_estimate.max(estimate.min(1.0));
+    }
+
+    /// Records that the search space has been exhausted.
+    fn record_exhausted(&mut self) {
+        self.stats.progress_estimate = 1.0;
+    }
+
+    /// How the search has decided cells so far.
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    /// How many contradictions were detected at each cell's constraint so far,
+    /// which shows where the search is struggling. The counts sum to [`SearchStats::contradictions`].
+    pub fn contradiction_heatmap(&self) -> Grid {
+        self.heatmap.clone()
+    }
+
+    /// Where cells were decided so far, by branching or propagation, and how deep,
+    /// which shows whether the cell order is spending its guesses where the contradictions are.
+    pub fn effort_report(&self) -> &EffortReport {
+        &self.effort
+    }
+
+    /// A stable text summary of where the search is, for golden tests that pin down how a heuristic searches
+    /// and not only what it finds. Two searchers configured alike, with the same seed, give the same snapshots
+    /// after the same iterations, on any platform.
+    ///
+    /// The first line is the depth-first decision path from the root to the current guess: `#(x,y)` for a cell
+    /// guessed alive and `.(x,y)` for one guessed dead, with a `'` after a cell's second value.
+    /// It is empty for other strategies.
+    /// Then come the decided cells of the current guess and the [`Searcher::stats`].
+    ///
+    /// ```
+    /// use undeath::{Grid, Searcher};
+    ///
+    /// let mut searcher = Searcher::new(Grid::from_text("\n\n\n..###"));
+    /// assert!(searcher.behavior_snapshot().starts_with("path:\ndecided: 0 of 64\ndecisions: 0\n"));
+    /// searcher.search(3)?;
+    /// let snapshot = searcher.behavior_snapshot();
+    /// assert!(snapshot.starts_with("path: .(5,4)\ndecided: 1 of 64\n"), "{snapshot}");
+    /// # Ok::<(), undeath::Error>(())
+    /// ```
+    pub fn behavior_snapshot(&self) -> String {
+        let mut path = String::from("path:");
+        // The top of the stack is what to try next, not a decision the current guess depends on.
+        let applied = &self.action_stack[..self.action_stack.len().saturating_sub(1)];
+        for action in applied {
+            match *action {
+                Action::MakeGuess => {}
+                Action::FirstGuess(x, y, alive) => {
+                    let state = if alive { '#' } else { '.' };
+                    write!(path, " {state}({x},{y})").unwrap();
+                }
+                Action::SecondGuess(x, y, alive) => {
+                    let state = if alive { '.' } else { '#' };
+                    write!(path, " {state}({x},{y})'").unwrap();
+                }
+            }
+        }
+        let stats = self.stats;
+        format!(
+            "{path}\ndecided: {} of {}\ndecisions: {}\ndeductions: {}\ncontradictions: {}\nprogress estimate: {}\n",
+            self.current().map_or(0, Guess::decided_count),
+            self.next.width() * self.next.height(),
+            stats.decisions,
+            stats.deductions,
+            stats.contradictions,
+            stats.progress_estimate,
+        )
+    }
+
+    /// Checks every contradiction found within `max_depth` guesses of the root against the brute-force oracle,
+    /// panicking with a description of the guess if the contradiction was wrong,
+    /// that is, if the guess could in fact have been completed to a predecessor.
+    ///
+    /// This is only feasible when few enough cells are undecided, so in practice it needs a small
+    /// [`Searcher::with_mask`]. Contradictions with more than [`bruteforce::MAX_FREE_CELLS`] undecided cells
+    /// are not checked.
+    pub fn validate_contradictions(mut self, max_depth: usize) -> Self {
+        self.validation_depth = Some(max_depth);
+        self
+    }
+
+    /// Logs the reasoning behind each contradiction found within `max_depth` guesses of the root,
+    /// as a [`Guess::render_trace`] at debug level. Deeper contradictions are usually too numerous to read.
+    #[cfg(feature = "provenance")]
+    pub fn trace_contradictions(mut self, max_depth: usize) -> Self {
+        self.trace_depth = Some(max_depth);
+        self
+    }
+
+    /// If tracing is enabled, logs why `contradiction`, found by refining a guess reached by `depth` guesses,
+    /// is contradictory.
+    #[cfg(feature = "provenance")]
+    fn trace_contradiction(&self, contradiction: &Guess, depth: usize) {
+        match self.trace_depth {
+            Some(max_depth) if depth <= max_depth => {}
+            _ => return,
+        }
+        if let Some(trace) = contradiction.render_trace() {
+            debug!("contradiction at depth {depth}:\n{trace}");
+        }
+    }
+
+    /// If validation is enabled, checks that guessing `(x, y)` to be `alive` really contradicts `guess`,
+    /// which was reached by `depth` guesses.
+    fn validate_contradiction(
+        &self,
+        guess: &Guess,
+        depth: usize,
+        contradiction: &Guess,
+        x: i32,
+        y: i32,
+        alive: bool,
+    ) {
+        match self.validation_depth {
+            Some(max_depth) if depth <= max_depth => {}
+            _ => return,
+        }
+        // The brute-force oracle only knows the Moore neighbourhood.
+        if self.neighbourhood != Neighbourhood::Moore {
+            return;
+        }
+
+        let mut fixed = guess.alive();
+        let mut free = fixed.or(&guess.dead()).not();
+        free.set_wrapped(x, y, Cell::zero());
+        if alive {
+            fixed.set_wrapped(x, y, Cell::one());
+        }
+        if free.alive_cells().count() > bruteforce::MAX_FREE_CELLS {
+            return;
+        }
+
+        // The amount of free cells was checked above, so this cannot fail.
+        if let Some(witness) = bruteforce::completion(&self.next, &fixed, &free).unwrap() {
+            panic!(
+                "guessing ({x}, {y}) was {} was wrongly found to be a contradiction by the rule {:?}\n\
+                 guess before, target, and a predecessor that extends it:\n{}",
+                if alive { "alive" } else { "dead" },
+                contradiction.contradiction().unwrap_or("unknown"),
+                hconcat_all(
+                    &[&guess.render(), &self.next.render(), &witness.render()],
+                    "   "
+                ),
+            );
+        }
+    }
+
+    /// The guess the search started from, holding only what follows from the target and mask
+    /// (and from shaving, if enabled), or `None` if the search space has been exhausted.
+    /// A best-first search branches on its root straight away, so this is also `None` once one has started.
+    pub fn root(&self) -> Option<&Guess> {
+        if self.open.is_some() || self.handoff.is_some() {
+            return None;
+        }
+        self.guess_stack.first()
+    }
+
+    /// Folds what another searcher for the same target and mask has deduced at its root into this one's root,
+    /// so that searchers working on parts of one problem can share what each has proved.
+    /// Returns whether this searcher's root learned anything.
+    ///
+    /// If the two roots conflict, the problem has no solution, and the next search returns
+    /// [`SearchResult::Unsatisfiable`]. Otherwise, if the root learned anything, the search restarts from the
+    /// merged root. That discards its progress, so predecessors it has already found may be found again.
+    /// As there is no [`Searcher::root`] once a best-first search has started, this then does nothing.
+    ///
+    /// # Panics
+    /// If `root` is a different size from the target.
+    pub fn merge_root(&mut self, root: &Guess) -> bool {
+        assert!(
+            root.width() == self.next.width() && root.height() == self.next.height(),
+            "cannot merge a root for a grid of another size"
+        );
+        let Some(own) = self.root() else {
+            return false;
+        };
+        let mut merged = own.clone();
+        merged.merge_root_implications(&self.next, root);
+        if self.block_propagation && !merged.found_contradiction() {
+            merged.propagate_blocks(&self.next);
+        }
+        if merged.found_contradiction() {
+            info!("merged root implications conflict, so there are no predecessors");
+            self.guess_stack.clear();
+            self.action_stack.clear();
+            return true;
+        }
+        if merged.decided_count() == own.decided_count() {
+            return false;
+        }
+        debug!(
+            "restarting from a merged root with {} more decided cells",
+            merged.decided_count() - own.decided_count()
+        );
+        self.guess_stack = vec![merged];
+        self.action_stack = vec![Action::MakeGuess];
+        // Local search starts again from frames that agree with the new root.
+        self.local = None;
+        // Frozen rows were decided in the guesses just discarded, not necessarily in the root.
+        branching_order(
+            &self.alive_cells,
+            self.next.width(),
+            self.next.height(),
+            &mut self.all_cells,
+        );
+        self.frozen_rows = 0;
+        true
+    }
+
+    /// The guess currently being refined, or `None` if the search space has been exhausted.
+    /// In best-first search, this is the open guess that will be branched on next.
+    pub fn current_guess(&self) -> Option<Guess> {
+        self.current().cloned()
+    }
+
+    /// How many cells depth-first search has guessed on its current path: the guesses on its action stack.
+    /// This is zero before the search starts and once it has finished.
+    pub fn depth(&self) -> usize {
+        self.action_stack
+            .iter()
+            .filter(|action| !matches!(action, Action::MakeGuess))
+            .count()
+    }
+
+    fn current(&self) -> Option<&Guess> {
+        self.guess_stack
+            .last()
+            .or_else(|| Some(&self.open.as_ref()?.peek()?.guess))
+            .or_else(|| Some(&self.next_level.iter().max()?.guess))
+    }
+
+    /// The undecided cells of the current guess, in the order the searcher's [`CellOrder`] chooses from.
+    /// A cell may appear more than once.
+    pub fn candidates(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.current()
+            .into_iter()
+            .flat_map(|guess| candidates(&self.all_cells, guess))
+    }
+
+    /// Removes the rows that are fully decided in `guess` from the branching order,
+    /// if no pending alternative guess could undecide them.
+    fn freeze_rows(&mut self) {
+        let Some(guess) = self.guess_stack.last() else {
+            return;
+        };
+        let decided = (0..guess.height().min(64))
+            .filter(|&y| guess.row_decided(y))
+            .fold(0u64, |rows, y| rows | 1 << y);
+        if decided & !self.frozen_rows == 0 {
+            return;
+        }
+        // Backtracking only ever returns to a pending first guess.
+        // Without one, every guess the search has left refines this one.
+        if self
+            .action_stack
+            .iter()
+            .any(|action| matches!(action, Action::FirstGuess(_, _, _)))
+        {
+            return;
         }
+        debug!("freezing rows {:#x}", decided & !self.frozen_rows);
+        self.frozen_rows |= decided;
+        self.all_cells
+            .retain(|&(_, y)| y >= 64 || decided & 1 << y == 0);
     }
 
-    pub fn current_guess(&self) -> Guess {
-        self.guess_stack.last().unwrap().clone()
+    /// Runs the search for about as long as the [`Searcher::progress_cadence`], however expensive iterations are,
+    /// so that callers can report progress or check whether to stop at a steady rate.
+    /// The number of iterations is adjusted from how fast recent calls went.
+    pub fn search_slice(&mut self) -> Result<SearchResult, Error> {
+        let cadence = self.cadence.get_or_insert_with(Cadence::default);
+        let started = cadence.start();
+        let slice = cadence.slice();
+        let result = self.search(slice)?;
+        let iterations = match result {
+            SearchResult::Found(_, iterations) | SearchResult::Working(iterations) => iterations,
+            SearchResult::Unsatisfiable | SearchResult::Inconclusive => return Ok(result),
+        };
+        if let Some(cadence) = &mut self.cadence {
+            cadence.finish(iterations, started);
+        }
+        Ok(result)
     }
 
-    pub fn search(&mut self, max_iterations: usize) -> SearchResult {
+    /// Runs the search for at most `max_iterations` iterations.
+    /// An error is only returned if the searcher's internal state is inconsistent, which indicates a bug.
+    pub fn search(&mut self, max_iterations: usize) -> Result<SearchResult, Error> {
+        if matches!(
+            self.strategy,
+            Strategy::Local { .. } | Strategy::Hybrid { .. }
+        ) && !self.is_moore_torus()
+        {
+            return Err(Error::InvalidInput(
+                "local and hybrid search only support the Moore neighbourhood on a torus"
+                    .to_owned(),
+            ));
+        }
+        if let Strategy::Local {
+            noise_percent,
+            restart_after,
+        } = self.strategy
+        {
+            let Some(root) = self.guess_stack.first() else {
+                info!("the root guess is contradictory, so there are no predecessors");
+                self.record_exhausted();
+                return Ok(SearchResult::Unsatisfiable);
+            };
+            let local = self.local.get_or_insert_with(|| {
+                LocalSearch::new(
+                    &self.next,
+                    root,
+                    self.rng.clone(),
+                    noise_percent,
+                    restart_after,
+                )
+            });
+            return local.search(max_iterations);
+        }
         let mut iterations = 0;
-        while let Some(action) = self.action_stack.last().copied() {
-            let guess = self.guess_stack.last().unwrap();
+        match self.strategy {
+            Strategy::Hybrid {
+                max_violations,
+                radius,
+            } if self.handoff.is_none() => {
+                let Some(root) = self.guess_stack.first() else {
+                    info!("the root guess is contradictory, so there are no predecessors");
+                    self.record_exhausted();
+                    return Ok(SearchResult::Unsatisfiable);
+                };
+                let local = self.local.get_or_insert_with(|| {
+                    LocalSearch::new(
+                        &self.next,
+                        root,
+                        self.rng.clone(),
+                        Strategy::DEFAULT_NOISE_PERCENT,
+                        Strategy::DEFAULT_RESTART_AFTER,
+                    )
+                });
+                iterations = local.flip_until(max_violations, max_iterations);
+                if local.violations() == 0 {
+                    return Ok(SearchResult::Found(local.take_predecessor()?, iterations));
+                }
+                if local.violations() > max_violations {
+                    return Ok(SearchResult::Working(iterations));
+                }
+                info!(
+                    "local search got within {} violations, so handing off to depth-first search",
+                    local.violations()
+                );
+                self.handoff = Some(Handoff {
+                    root: root.clone(),
+                    frame: local.frame().clone(),
+                    violated: local.violated().clone(),
+                    radius,
+                    max_radius: radius + 1,
+                    iterations: 0,
+                });
+                self.start_handoff();
+                if iterations >= max_iterations {
+                    return Ok(SearchResult::Working(iterations));
+                }
+            }
+            Strategy::BestFirst { .. } | Strategy::Beam { .. } => self.start_open(),
+            _ => {}
+        }
+        self.start_trace()?;
+        loop {
+            let found = if !self.action_stack.is_empty() {
+                self.step_depth_first()?
+            } else if let Some(open) = self.open.as_mut().and_then(BinaryHeap::pop) {
+                self.step_open(open)?
+            } else if self.next_beam_level() || self.widen_handoff() {
+                continue;
+            } else if self.return_to_local() {
+                return Ok(SearchResult::Working(iterations));
+            } else {
+                break;
+            };
 
             iterations += 1;
+            if let Some(trace) = &mut self.trace {
+                trace.iteration += 1;
+            }
 
-            match action {
-                Action::MakeGuess => {
-                    self.action_stack.pop();
-                    // Make a guess.
-                    // Pick a cell that has not yet been guessed.
-
-                    let mut try_dead = guess.try_dead();
-                    try_dead -= &guess.alive();
-                    try_dead -= &guess.dead();
-
-                    let mut try_alive = guess.try_alive();
-                    try_alive -= &guess.alive();
-                    try_alive -= &guess.dead();
-
-                    match try_dead
-                        .alive_cells()
-                        .chain(try_alive.alive_cells())
-                        .chain(self.all_cells.iter().copied().filter(|(x, y)| unsafe {
-                            !guess.guessed_alive(*x, *y) && !guess.guessed_dead(*x, *y)
-                        }))
-                        .next()
-                    {
-                        Some((x, y)) => {
-                            self.action_stack.push(Action::FirstGuess(
-                                x,
-                                y,
-                                self.alive_cells.contains(&(x, y)),
-                            ));
-                        }
-                        None => {
-                            // There were no cells left to guess.
-                            let mut next_grid = guess.alive();
-                            next_grid.step();
-                            if next_grid != self.next {
-                                panic!(
-                                    "grids did not match:\n{}",
-                                    hconcat(
-                                        &hconcat(&guess.render(), &self.next.render(), "   "),
-                                        &next_grid.render(),
-                                        "   "
-                                    )
-                                );
-                            }
-                            // We have a valid solution.
-                            // We'll pretend to the rest of the execution procedure that this solution was invalid,
-                            // so that it can keep searching.
-                            let alive = guess.alive();
-                            self.guess_stack.pop();
-                            while let Some(action) = self.action_stack.pop() {
-                                match action {
-                                    Action::MakeGuess => todo!(),
-                                    Action::FirstGuess(x, y, alive) => {
-                                        // The last time we made a first guess,
-                                        // instead do the second guess.
-                                        self.action_stack.push(Action::SecondGuess(x, y, alive));
-                                        break;
-                                    }
-                                    Action::SecondGuess(_, _, _) => {
-                                        // Pop out of this inner loop too.
-                                        self.guess_stack.pop();
-                                    }
-                                }
-                            }
-                            return SearchResult::Found(alive, iterations);
-                        }
-                    };
+            if let Some(predecessor) = found {
+                info!("found a predecessor after {iterations} iterations");
+                return Ok(SearchResult::Found(predecessor, iterations));
+            }
+            if let Some(handoff) = self.handoff.as_mut() {
+                handoff.iterations += 1;
+                if handoff.iterations >= Strategy::DEFAULT_RESTART_AFTER && self.return_to_local() {
+                    return Ok(SearchResult::Working(iterations));
                 }
-                Action::FirstGuess(x, y, alive) => {
-                    let mut new_guess = guess.clone();
-                    if alive {
-                        new_guess.guess_alive(&self.next, x, y);
-                    } else {
-                        new_guess.guess_dead(&self.next, x, y);
+            }
+            if iterations >= max_iterations {
+                return Ok(SearchResult::Working(iterations));
+            }
+        }
+
+        if self.pruned {
+            info!("every guess the beam kept was contradictory");
+            return Ok(SearchResult::Inconclusive);
+        }
+        info!("search space exhausted");
+        self.record_exhausted();
+        Ok(SearchResult::Unsatisfiable)
+    }
+
+    /// Performs the next action of depth-first search, returning the predecessor it found, if any.
+    fn step_depth_first(&mut self) -> Result<Option<Grid>, Error> {
+        let Some(action) = self.action_stack.last().copied() else {
+            return Ok(None);
+        };
+        if let Action::MakeGuess = action {
+            self.freeze_rows();
+        }
+        let Some(guess) = self.guess_stack.last() else {
+            return Err(Error::Solver(
+                "there are actions left to perform but no guess to apply them to".to_owned(),
+            ));
+        };
+        let depth = self.base_depth + self.guess_stack.len() - 1;
+
+        match action {
+            Action::MakeGuess => {
+                self.action_stack.pop();
+                // Make a guess.
+                // Pick a cell that has not yet been guessed.
+                let candidate = self
+                    .order
+                    .choose(guess, &mut candidates(&self.all_cells, guess));
+                match candidate {
+                    Some((x, y)) => {
+                        self.action_stack.push(Action::FirstGuess(
+                            x,
+                            y,
+                            self.alive_cells.contains(&(x, y)),
+                        ));
                     }
-                    if new_guess.found_contradiction() {
-                        // Instead, guess this cell was dead.
-                        self.action_stack.pop();
-                        self.action_stack.push(Action::SecondGuess(x, y, alive));
-                    } else {
-                        self.guess_stack.push(new_guess);
-                        self.action_stack.push(Action::MakeGuess);
+                    None => {
+                        // There were no cells left to guess.
+                        let alive = self.check_predecessor(guess)?;
+                        let depth = self.guess_stack.len() - 1;
+                        emit(&mut self.trace, self.strategy, |iteration| {
+                            TraceEvent::Solution { iteration, depth }
+                        })?;
+                        // We have a valid solution.
+                        // We'll pretend to the rest of the execution procedure that this solution was invalid,
+                        // so that it can keep searching.
+                        self.pop_guess();
+                        self.backtrack()?;
+                        return Ok(Some(alive));
                     }
-                }
-                Action::SecondGuess(x, y, alive) => {
-                    let mut new_guess = guess.clone();
-                    if alive {
-                        new_guess.guess_dead(&self.next, x, y);
-                    } else {
-                        new_guess.guess_alive(&self.next, x, y);
+                };
+            }
+            Action::FirstGuess(x, y, alive) => {
+                debug!(
+                    "guessing ({x}, {y}) was {}",
+                    if alive { "alive" } else { "dead" }
+                );
+                let (new_guess, deductions) = self.apply_guess(guess, depth, x, y, alive);
+                let stack_depth = self.guess_stack.len() - 1;
+                emit(&mut self.trace, self.strategy, |iteration| {
+                    TraceEvent::Decide {
+                        iteration,
+                        depth: stack_depth,
+                        x,
+                        y,
+                        alive,
+                        contradiction: new_guess.found_contradiction(),
                     }
-                    if new_guess.found_contradiction() {
-                        // This cell can neither be dead nor alive.
-                        // So `guess` is inconsistent.
-                        // Pop out of this implicit loop.
-                        while let Some(action) = self.action_stack.pop() {
-                            match action {
-                                Action::MakeGuess => unimplemented!(),
-                                Action::FirstGuess(x, y, alive) => {
-                                    // The last time we made a first guess,
-                                    // instead do the second guess.
-                                    self.action_stack.push(Action::SecondGuess(x, y, alive));
-                                    break;
-                                }
-                                Action::SecondGuess(_, _, _) => {
-                                    // Pop out of this inner loop too.
-                                    self.guess_stack.pop();
-                                }
-                            }
-                        }
-                    } else {
-                        self.guess_stack.push(new_guess);
-                        self.action_stack.push(Action::MakeGuess);
+                })?;
+                if new_guess.found_contradiction() {
+                    self.validate_contradiction(guess, depth, &new_guess, x, y, alive);
+                    #[cfg(feature = "provenance")]
+                    self.trace_contradiction(&new_guess, depth);
+                    self.record_decision(&new_guess, deductions);
+                    if let Some(guess) = self.guess_stack.last_mut() {
+                        guess.inherit_impact(&new_guess);
                     }
+                    // Instead, guess this cell was dead.
+                    self.action_stack.pop();
+                    self.action_stack.push(Action::SecondGuess(x, y, alive));
+                    self.record_progress();
+                } else {
+                    self.record_decision(&new_guess, deductions);
+                    self.guess_stack.push(new_guess);
+                    self.action_stack.push(Action::MakeGuess);
                 }
             }
+            Action::SecondGuess(x, y, alive) => {
+                debug!(
+                    "guessing ({x}, {y}) was {} instead",
+                    if alive { "dead" } else { "alive" }
+                );
+                let (new_guess, deductions) = self.apply_guess(guess, depth, x, y, !alive);
+                let stack_depth = self.guess_stack.len() - 1;
+                emit(&mut self.trace, self.strategy, |iteration| {
+                    TraceEvent::Decide {
+                        iteration,
+                        depth: stack_depth,
+                        x,
+                        y,
+                        alive: !alive,
+                        contradiction: new_guess.found_contradiction(),
+                    }
+                })?;
+                if new_guess.found_contradiction() {
+                    self.validate_contradiction(guess, depth, &new_guess, x, y, !alive);
+                    #[cfg(feature = "provenance")]
+                    self.trace_contradiction(&new_guess, depth);
+                    self.record_decision(&new_guess, deductions);
+                    // This cell can neither be dead nor alive.
+                    // So `guess` is inconsistent.
+                    // Pop out of this implicit loop.
+                    self.backtrack()?;
+                } else {
+                    self.record_decision(&new_guess, deductions);
+                    self.guess_stack.push(new_guess);
+                    self.action_stack.push(Action::MakeGuess);
+                }
+            }
+        }
+        Ok(None)
+    }
 
-            if iterations >= max_iterations {
-                return SearchResult::Working(iterations);
+    /// Moves the root guess to the open list, if best-first or beam search has not started yet.
+    fn start_open(&mut self) {
+        if self.open.is_some() {
+            return;
+        }
+        self.open = Some(BinaryHeap::new());
+        self.action_stack.clear();
+        if let Some(root) = self.guess_stack.pop() {
+            self.open_guess(root, 0);
+        }
+    }
+
+    /// Opens `guess`, reached by `depth` guesses: in best-first search, straight away,
+    /// and in beam search, as a candidate for the next level.
+    fn open_guess(&mut self, guess: Guess, depth: usize) {
+        let open = OpenGuess::new(guess, depth, self.opened);
+        self.opened += 1;
+        if let Strategy::Beam { .. } = self.strategy {
+            self.next_level.push(open);
+        } else if let Some(heap) = self.open.as_mut() {
+            heap.push(open);
+        }
+    }
+
+    /// Starts depth-first search from the guess the handoff leaves undecided around its violations.
+    fn start_handoff(&mut self) {
+        let Some(handoff) = &self.handoff else {
+            return;
+        };
+        let guess = handoff.guess(&self.next);
+        if guess.found_contradiction() {
+            debug!(
+                "the handoff with radius {} is contradictory",
+                handoff.radius
+            );
+            self.guess_stack.clear();
+            self.action_stack.clear();
+        } else {
+            debug!(
+                "searching the {} cells the handoff with radius {} leaves undecided",
+                guess.unknown_cells().count(),
+                handoff.radius
+            );
+            self.guess_stack = vec![guess];
+            self.action_stack = vec![Action::MakeGuess];
+        }
+        branching_order(
+            &self.alive_cells,
+            self.next.width(),
+            self.next.height(),
+            &mut self.all_cells,
+        );
+        self.frozen_rows = 0;
+        self.base_depth = 0;
+    }
+
+    /// In hybrid search, once depth-first search from the handoff is exhausted, leaves more of the frame undecided
+    /// and starts again. Returns whether there was more to leave undecided.
+    fn widen_handoff(&mut self) -> bool {
+        let Some(handoff) = self.handoff.as_mut() else {
+            return false;
+        };
+        if handoff.covers_everything() || handoff.radius >= handoff.max_radius {
+            return false;
+        }
+        handoff.radius += 1;
+        info!(
+            "nothing more within the handoff, so widening its radius to {}",
+            handoff.radius
+        );
+        self.start_handoff();
+        true
+    }
+
+    /// In hybrid search, abandons the handoff and restarts local search from a new frame,
+    /// unless the handoff leaves every cell undecided, when searching it is as good as searching the root.
+    /// Returns whether it did.
+    fn return_to_local(&mut self) -> bool {
+        let Some(handoff) = self.handoff.take_if(|handoff| !handoff.covers_everything()) else {
+            return false;
+        };
+        debug!("going back to local search from a new frame");
+        self.guess_stack = vec![handoff.root];
+        self.action_stack = vec![Action::MakeGuess];
+        branching_order(
+            &self.alive_cells,
+            self.next.width(),
+            self.next.height(),
+            &mut self.all_cells,
+        );
+        self.frozen_rows = 0;
+        if let Some(local) = self.local.as_mut() {
+            local.restart();
+        }
+        true
+    }
+
+    /// In beam search, once every guess at the current level has been branched on,
+    /// opens the best of their refinements as the next level. Returns whether there were any.
+    fn next_beam_level(&mut self) -> bool {
+        let Strategy::Beam { width } = self.strategy else {
+            return false;
+        };
+        if self.next_level.is_empty() {
+            return false;
+        }
+        let mut level = std::mem::take(&mut self.next_level);
+        if level.len() > width {
+            level.sort_unstable_by(|a, b| b.cmp(a));
+            level.truncate(width);
+            self.pruned = true;
+        }
+        debug!("the beam moves on to {} guesses", level.len());
+        self.open = Some(level.into());
+        true
+    }
+
+    /// Branches on `best`, the best guess just taken from the open list, opening both of its refinements
+    /// that do not contradict themselves, and returning it if it is a predecessor.
+    /// In best-first search, if the open list is full, searches `best` depth-first instead.
+    fn step_open(&mut self, best: OpenGuess) -> Result<Option<Grid>, Error> {
+        // Rows frozen while searching an open guess depth-first are not necessarily decided in the others.
+        if self.frozen_rows != 0 {
+            branching_order(
+                &self.alive_cells,
+                self.next.width(),
+                self.next.height(),
+                &mut self.all_cells,
+            );
+            self.frozen_rows = 0;
+        }
+        let OpenGuess { depth, guess, .. } = best;
+        if let Strategy::BestFirst { max_open } = self.strategy {
+            // `best` has just been taken off, so this is how many guesses were open.
+            if self.open.as_ref().map_or(0, BinaryHeap::len) + 1 >= max_open {
+                debug!("the open list is full, so searching its best guess depth-first");
+                self.base_depth = depth;
+                self.guess_stack = vec![guess];
+                self.action_stack = vec![Action::MakeGuess];
+                return Ok(None);
             }
         }
 
-        SearchResult::Unsatisfiable
+        let candidate = self
+            .order
+            .choose(&guess, &mut candidates(&self.all_cells, &guess));
+        let Some((x, y)) = candidate else {
+            return self.check_predecessor(&guess).map(Some);
+        };
+        // Ties go to the guess opened last, so open the value depth-first search would try first last.
+        let first = self.alive_cells.contains(&(x, y));
+        for alive in [!first, first] {
+            let (new_guess, deductions) = self.apply_guess(&guess, depth, x, y, alive);
+            self.record_decision(&new_guess, deductions);
+            if new_guess.found_contradiction() {
+                self.validate_contradiction(&guess, depth, &new_guess, x, y, alive);
+                #[cfg(feature = "provenance")]
+                self.trace_contradiction(&new_guess, depth);
+            } else {
+                self.open_guess(new_guess, depth + 1);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks that a guess with no undecided cells is a predecessor, and returns it.
+    fn check_predecessor(&self, guess: &Guess) -> Result<Grid, Error> {
+        let alive = guess.alive();
+        if self.neighbourhood != Neighbourhood::Moore {
+            let mut stepped = alive.clone();
+            stepped.step_in(self.neighbourhood);
+            if stepped != self.next {
+                return Err(Error::Solver(format!(
+                    "a fully decided guess is not a predecessor:\n{}\nthe target, then the guess stepped:\n{}",
+                    guess.render(),
+                    render_mismatch(&self.next, &stepped)
+                )));
+            }
+            return Ok(alive);
+        }
+        let everything = Grid::new(self.next.width(), self.next.height()).not();
+        verify_predecessor(&alive, &self.next, &everything).map_err(|error| {
+            Error::Solver(format!(
+                "a fully decided guess is not a predecessor:\n{}\n{error}",
+                guess.render()
+            ))
+        })?;
+        Ok(alive)
+    }
+
+    /// Unwinds the action stack to the most recent first guess, and replaces it with the second guess.
+    /// The guesses made since that first guess are discarded.
+    fn backtrack(&mut self) -> Result<(), Error> {
+        debug!("backtracking");
+        while let Some(action) = self.action_stack.pop() {
+            match action {
+                Action::MakeGuess => {
+                    // `MakeGuess` is popped as soon as it is handled, so it is never below a pending guess.
+                    return Err(Error::Solver(
+                        "found an unhandled `MakeGuess` while backtracking".to_owned(),
+                    ));
+                }
+                Action::FirstGuess(x, y, alive) => {
+                    // The last time we made a first guess,
+                    // instead do the second guess.
+                    self.action_stack.push(Action::SecondGuess(x, y, alive));
+                    break;
+                }
+                Action::SecondGuess(_, _, _) => {
+                    // Pop out of this inner loop too.
+                    self.pop_guess();
+                }
+            }
+        }
+        if let Some(depth) = self.guess_stack.len().checked_sub(1) {
+            emit(&mut self.trace, self.strategy, |iteration| {
+                TraceEvent::Backtrack { iteration, depth }
+            })?;
+        }
+        self.record_progress();
+        Ok(())
+    }
+
+    /// Discards the most recent guess, passing what it learned about impact back to its parent.
+    fn pop_guess(&mut self) {
+        if let Some(abandoned) = self.guess_stack.pop() {
+            if let Some(parent) = self.guess_stack.last_mut() {
+                parent.inherit_impact(&abandoned);
+            }
+        }
+    }
+}
+
+/// Refines `guess`, reached by `depth` guesses, with the information that `(x, y)` is `alive`, or dead if not,
+/// propagating as a searcher with the given settings does.
+pub(crate) fn refine(
+    next: &Grid,
+    guess: &Guess,
+    depth: usize,
+    (x, y): (i32, i32),
+    alive: bool,
+    block_propagation: bool,
+    shaving: Shaving,
+) -> Guess {
+    let mut new_guess = guess.clone();
+    if alive {
+        new_guess.guess_alive(next, x, y);
+    } else {
+        new_guess.guess_dead(next, x, y);
+    }
+    if block_propagation && !new_guess.found_contradiction() {
+        new_guess.propagate_blocks(next);
+    }
+    if let Shaving::Periodic(period) = shaving {
+        let depth = depth + 1;
+        if period > 0 && depth.is_multiple_of(period) && !new_guess.found_contradiction() {
+            new_guess.shave(next);
+        }
+    }
+    new_guess
+}
+
+/// Fills `all_cells` with every cell of a `width`×`height` grid, in the order to branch on them:
+/// those furthest from the target's alive cells first.
+fn branching_order(
+    alive_cells: &[(i32, i32)],
+    width: i32,
+    height: i32,
+    all_cells: &mut Vec<(i32, i32)>,
+) {
+    let distance = |x: i32, y: i32| {
+        alive_cells
+            .iter()
+            .map(|(ax, ay)| ((ax - x + width) % width).abs() + ((ay - y + height) % height).abs())
+            .min()
+            .unwrap_or(-1000)
+    };
+    // Each cell's distance is worked out once, rather than at every comparison of the sort,
+    // by sorting pairs of the negated distance and the cell's index in place of the cells themselves.
+    all_cells.clear();
+    all_cells.extend(
+        (0..width)
+            .flat_map(|x| (0..height).map(move |y| (x, y)))
+            .map(|(x, y)| (-distance(x, y), x + width * y)),
+    );
+    all_cells.sort_by_key(|&(key, _)| key);
+    for cell in all_cells.iter_mut() {
+        *cell = (cell.1 % width, cell.1 / width);
+    }
+}
+
+/// The undecided cells of `guess`: first those it suggests trying dead, then those it suggests trying alive,
+/// then the rest in the order of `all_cells`.
+fn candidates<'a>(
+    all_cells: &'a [(i32, i32)],
+    guess: &'a Guess,
+) -> impl Iterator<Item = (i32, i32)> + 'a {
+    // Every cell here lies in the grid.
+    let undecided =
+        |&(x, y): &(i32, i32)| unsafe { !guess.guessed_alive(x, y) && !guess.guessed_dead(x, y) };
+    guess
+        .try_dead()
+        .iter()
+        .chain(guess.try_alive().iter())
+        .chain(all_cells.iter().copied())
+        .filter(undecided)
+}
+
+impl IntoIterator for Searcher {
+    type Item = Result<Grid, Error>;
+    type IntoIter = Predecessors;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Predecessors::new(self)
+    }
+}
+
+/// An iterator over the predecessors of a grid, in the order the searcher finds them.
+///
+/// Each item may take at most a fixed number of search iterations to find, set with [`Predecessors::budget`].
+/// If that budget runs out before the next predecessor is found, the iterator yields
+/// [`Error::BudgetExhausted`] rather than searching forever, and then ends.
+/// The iterator also ends once every predecessor has been found, or once a beam search has given up.
+///
+/// ```
+/// use undeath::Grid;
+///
+/// // The sparsest of the first ten predecessors of the empty grid is the empty grid itself.
+/// let sparsest = Grid::default()
+///     .predecessors()
+///     .take(10)
+///     .collect::<Result<Vec<_>, _>>()?
+///     .into_iter()
+///     .min_by_key(|parent| parent.alive_cells().count());
+/// assert!(sparsest == Some(Grid::default()));
+/// # Ok::<(), undeath::Error>(())
+/// ```
+pub struct Predecessors {
+    searcher: Searcher,
+    budget: usize,
+    finished: bool,
+}
+
+impl Predecessors {
+    pub fn new(searcher: Searcher) -> Self {
+        Self {
+            searcher,
+            budget: crate::Options::default().max_iterations,
+            finished: false,
+        }
+    }
+
+    /// Sets the maximum number of search iterations spent looking for each predecessor.
+    pub fn budget(mut self, iterations: usize) -> Self {
+        self.budget = iterations;
+        self
     }
 }
+
+impl Iterator for Predecessors {
+    type Item = Result<Grid, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let result = match self.searcher.search(self.budget) {
+            Ok(SearchResult::Found(grid, _)) => return Some(Ok(grid)),
+            Ok(SearchResult::Working(iterations)) => {
+                Some(Err(Error::BudgetExhausted { iterations }))
+            }
+            Ok(SearchResult::Unsatisfiable | SearchResult::Inconclusive) => None,
+            Err(error) => Some(Err(error)),
+        };
+        self.finished = true;
+        result
+    }
+}
+
+impl FusedIterator for Predecessors {}
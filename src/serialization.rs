@@ -0,0 +1,115 @@
+//! `Serialize` and `Deserialize` for [`Grid`] and [`Cell`], with the `serde` feature.
+//!
+//! A grid is a sequence of rows, top to bottom, each a string of `.` for dead cells and `#` for alive ones,
+//! as [`Grid::to_gol_string`] writes them but without any `!size` line, so that it stays readable in formats such as JSON.
+//! Every row is as wide as the grid, so the grid keeps its size, but not its [`Topology`](crate::grid::Topology):
+//! a deserialised grid is on a torus. Deserialising checks the rows as [`str::parse`] would,
+//! failing with a [`ParseGridError`], and also that they are all as long as each other,
+//! hold only `.` and `#`, and make a grid at least [`MIN_SIZE`] on each side.
+//! A cell is its value, an integer.
+//!
+//! ```
+//! use undeath::Grid;
+//!
+//! let blinker = Grid::from_text("\n.###");
+//! let json = serde_json::to_string(&blinker).unwrap();
+//! assert!(json.starts_with(r#"["........",".###....","#));
+//! assert!(serde_json::from_str::<Grid>(&json).unwrap() == blinker);
+//! let error = serde_json::from_str::<Grid>(r#"["...", ".#..", "..."]"#).unwrap_err();
+//! assert!(error.to_string().contains("line 2 is 4 cells long, but the first is 3"));
+//! ```
+
+use serde::{de, ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::grid::{Cell, Grid, ParseGridError, MAX_SIZE, MIN_SIZE};
+
+impl Serialize for Cell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.value)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cell {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i32::deserialize(deserializer).map(|value| Cell { value })
+    }
+}
+
+impl Serialize for Grid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut rows = serializer.serialize_seq(Some(self.height() as usize))?;
+        for row in self.gol_rows() {
+            rows.serialize_element(&row)?;
+        }
+        rows.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<String>::deserialize(deserializer)?;
+        from_rows(&rows).map_err(de::Error::custom)
+    }
+}
+
+/// Reads rows of `.` and `#` onto a grid exactly their size.
+fn from_rows(rows: &[String]) -> Result<Grid, ParseGridError> {
+    let Some(first) = rows.first() else {
+        return Err(ParseGridError::Empty);
+    };
+    let pattern = (
+        rows.iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0),
+        rows.len(),
+    );
+    if rows.len() > MAX_SIZE as usize {
+        return Err(ParseGridError::TooManyRows {
+            rows: rows.len(),
+            pattern,
+        });
+    }
+    let width = first.chars().count();
+    let mut alive = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+        let mut length = 0;
+        for (x, character) in row.chars().enumerate() {
+            match character {
+                '.' => {}
+                '#' => alive.push((x, y)),
+                character => {
+                    return Err(ParseGridError::UnexpectedCharacter {
+                        line: y + 1,
+                        column: x + 1,
+                        character,
+                    })
+                }
+            }
+            length += 1;
+        }
+        if length > MAX_SIZE as usize {
+            return Err(ParseGridError::LineTooLong {
+                line: y + 1,
+                length,
+                pattern,
+            });
+        }
+        if length != width {
+            return Err(ParseGridError::UnevenRow {
+                line: y + 1,
+                length,
+                width,
+            });
+        }
+    }
+    let height = rows.len();
+    if width < MIN_SIZE as usize || height < MIN_SIZE as usize {
+        return Err(ParseGridError::TooSmall { width, height });
+    }
+    let mut grid = Grid::new(width as i32, height as i32);
+    for (x, y) in alive {
+        grid.set_wrapped(x as i32, y as i32, Cell::one());
+    }
+    Ok(grid)
+}
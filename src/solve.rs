@@ -0,0 +1,195 @@
+//! The front door of the crate: find predecessors of a grid without driving a [`Searcher`] by hand.
+
+use crate::{
+    grid::Grid,
+    searcher::{SearchResult, SearchStats, Searcher},
+    Error,
+};
+
+/// Limits on how much work [`solve_with`] and [`solve_all_with`] may do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The maximum number of search iterations, in total, before giving up with
+    /// [`Error::BudgetExhausted`].
+    pub max_iterations: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            max_iterations: 10_000_000,
+        }
+    }
+}
+
+/// Finds a predecessor of `next`, that is, a grid that becomes `next` after one step.
+///
+/// Returns `Ok(None)` only if the search space was exhausted, which proves that `next` has no predecessor.
+/// If the default budget of [`Options`] runs out first, this returns [`Error::BudgetExhausted`] instead:
+/// running out of time says nothing about whether a predecessor exists.
+///
+/// ```
+/// use undeath::grid::{Cell, Grid};
+///
+/// // A horizontal blinker.
+/// let mut blinker = Grid::default();
+/// for x in 2..5 {
+///     blinker.set_wrapped(x, 3, Cell::one());
+/// }
+///
+/// let parent = undeath::solve(&blinker)?.expect("a blinker has a predecessor");
+/// let mut stepped = parent.clone();
+/// stepped.step();
+/// assert!(stepped == blinker);
+/// # Ok::<(), undeath::Error>(())
+/// ```
+pub fn solve(next: &Grid) -> Result<Option<Grid>, Error> {
+    solve_with(next, &Options::default())
+}
+
+/// Like [`solve`], but with custom limits.
+pub fn solve_with(next: &Grid, options: &Options) -> Result<Option<Grid>, Error> {
+    Ok(solve_all_with(next, 1, options)?.pop())
+}
+
+/// Finds up to `limit` distinct predecessors of `next`.
+///
+/// Fewer than `limit` predecessors are returned only if the search space was exhausted,
+/// so the result is then the complete list of predecessors.
+/// As with [`solve`], running out of budget first is an error, even if some predecessors were found.
+///
+/// ```
+/// use undeath::grid::{Cell, Grid};
+///
+/// let mut blinker = Grid::default();
+/// for x in 2..5 {
+///     blinker.set_wrapped(x, 3, Cell::one());
+/// }
+///
+/// let parents = undeath::solve_all(&blinker, 3)?;
+/// assert_eq!(parents.len(), 3);
+/// for parent in parents {
+///     let mut stepped = parent.clone();
+///     stepped.step();
+///     assert!(stepped == blinker);
+/// }
+/// # Ok::<(), undeath::Error>(())
+/// ```
+pub fn solve_all(next: &Grid, limit: usize) -> Result<Vec<Grid>, Error> {
+    solve_all_with(next, limit, &Options::default())
+}
+
+/// Like [`solve_all`], but with custom limits.
+pub fn solve_all_with(next: &Grid, limit: usize, options: &Options) -> Result<Vec<Grid>, Error> {
+    SolverSession::new().solve_all(next, limit, options)
+}
+
+/// What [`Grid::predecessor`] and [`Grid::predecessors_up_to`] learnt, keeping a proof that there is
+/// no predecessor apart from simply not finding one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PredecessorOutcome<T = Grid> {
+    /// Predecessors were found.
+    Found(T),
+    /// The search space was exhausted without finding a predecessor, so there is none.
+    ProvenNone,
+    /// The search stopped before it could tell, because its budget ran out.
+    /// A search that fails is an error instead.
+    Unknown,
+}
+
+impl<T> PredecessorOutcome<T> {
+    /// The predecessors found, if any, forgetting why there were none.
+    pub fn found(self) -> Option<T> {
+        match self {
+            PredecessorOutcome::Found(found) => Some(found),
+            _ => None,
+        }
+    }
+}
+
+/// Solves many targets one after another, reusing the searcher's buffers between them
+/// rather than allocating afresh for each target, as [`solve_with`] does.
+///
+/// ```
+/// use undeath::{Grid, Options, SolverSession};
+///
+/// let mut session = SolverSession::new();
+/// for text in ["\n\n\n..###", ".#\n..#\n###", "##\n##"] {
+///     let target = Grid::from_text(text);
+///     let mut parent = session
+///         .solve(&target, &Options::default())?
+///         .expect("each of these has a predecessor");
+///     parent.step();
+///     assert!(parent == target);
+/// }
+/// # Ok::<(), undeath::Error>(())
+/// ```
+pub struct SolverSession {
+    searcher: Searcher,
+}
+
+impl SolverSession {
+    /// A session that has not solved anything yet.
+    pub fn new() -> Self {
+        Self {
+            searcher: Searcher::new(Grid::default()),
+        }
+    }
+
+    /// A session that searches as `searcher` is configured to, with its strategy, propagation, shaving and random source.
+    /// Whatever `searcher` was searching for is forgotten, as are its mask and any windows it was checked with.
+    pub fn with_searcher(searcher: Searcher) -> Self {
+        Self { searcher }
+    }
+
+    /// Like [`solve_with`], reusing this session's buffers.
+    pub fn solve(&mut self, next: &Grid, options: &Options) -> Result<Option<Grid>, Error> {
+        Ok(self.solve_all(next, 1, options)?.pop())
+    }
+
+    /// Like [`solve_all_with`], reusing this session's buffers.
+    pub fn solve_all(
+        &mut self,
+        next: &Grid,
+        limit: usize,
+        options: &Options,
+    ) -> Result<Vec<Grid>, Error> {
+        self.searcher.reset(next.clone());
+        let mut solutions = Vec::new();
+        let mut remaining = options.max_iterations;
+        while solutions.len() < limit {
+            if remaining == 0 {
+                return Err(Error::BudgetExhausted {
+                    iterations: options.max_iterations,
+                });
+            }
+            match self.searcher.search(remaining)? {
+                SearchResult::Found(grid, iterations) => {
+                    remaining = remaining.saturating_sub(iterations);
+                    solutions.push(grid);
+                }
+                SearchResult::Working(iterations) => {
+                    remaining = remaining.saturating_sub(iterations);
+                }
+                SearchResult::Unsatisfiable => break,
+                SearchResult::Inconclusive => {
+                    return Err(Error::Solver(
+                        "depth-first search gave up before exhausting the search space".to_owned(),
+                    ))
+                }
+            }
+        }
+        Ok(solutions)
+    }
+
+    /// How the search for the most recent target decided cells.
+    pub fn stats(&self) -> SearchStats {
+        self.searcher.stats()
+    }
+}
+
+impl Default for SolverSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,23 +1,885 @@
+use std::{fmt::Display, time::Duration};
+
+/// How to line up blocks of different heights when joining them side by side.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    /// The first lines of each block are level; shorter blocks are padded at the bottom.
+    #[default]
+    Top,
+    /// The last lines of each block are level; shorter blocks are padded at the top.
+    Bottom,
+}
+
+/// Places `right` to the right of `left`, separated by `gap`.
+/// Equivalent to [`hconcat_aligned`] with [`Alignment::Top`].
 pub fn hconcat(left: &str, right: &str, gap: &str) -> String {
-    left.lines()
-        .zip(right.lines())
-        .map(|(l, r)| format!("{l}{gap}{r}"))
+    hconcat_aligned(left, right, gap, Alignment::Top)
+}
+
+/// Places `right` to the right of `left`, separated by `gap`.
+/// Every line of `left` is padded to its widest line so that `right` forms a straight column,
+/// and the shorter block is padded with blank lines according to `alignment`.
+pub fn hconcat_aligned(left: &str, right: &str, gap: &str, alignment: Alignment) -> String {
+    hconcat_all_aligned(&[left, right], gap, alignment)
+}
+
+/// Places all of the given blocks side by side, separated by `gap`.
+/// Equivalent to [`hconcat_all_aligned`] with [`Alignment::Top`].
+pub fn hconcat_all(blocks: &[&str], gap: &str) -> String {
+    hconcat_all_aligned(blocks, gap, Alignment::Top)
+}
+
+/// Places all of the given blocks side by side, separated by `gap`.
+/// Each block is split into lines once, and the output is then assembled row by row.
+pub fn hconcat_all_aligned(blocks: &[&str], gap: &str, alignment: Alignment) -> String {
+    let blocks = blocks
+        .iter()
+        .map(|block| block.lines().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let widths = blocks
+        .iter()
+        .map(|lines| {
+            lines
+                .iter()
+                .map(|line| visible_width(line))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    let height = blocks.iter().map(|lines| lines.len()).max().unwrap_or(0);
+
+    (0..height)
+        .map(|row| {
+            let mut output = String::new();
+            for (i, lines) in blocks.iter().enumerate() {
+                let line = line_at(lines, row, height, alignment);
+                output += line;
+                // The last block needs no padding, since nothing follows it.
+                if i + 1 < blocks.len() {
+                    output += &" ".repeat(widths[i] - visible_width(line));
+                    output += gap;
+                }
+            }
+            output
+        })
         .collect::<Vec<_>>()
         .join("\n")
 }
 
-pub fn large_number(number: usize) -> String {
+/// Looks up the line of `block` that should appear on the given row of a join `height` lines tall.
+fn line_at<'a>(block: &[&'a str], row: usize, height: usize, alignment: Alignment) -> &'a str {
+    let offset = match alignment {
+        Alignment::Top => 0,
+        Alignment::Bottom => height - block.len(),
+    };
+    row.checked_sub(offset)
+        .and_then(|i| block.get(i))
+        .copied()
+        .unwrap_or("")
+}
+
+/// Lays out blocks left to right, starting a new row of blocks whenever the next block
+/// would take the row past `max_width` columns. Rows are separated by a blank line.
+/// A block that is wider than `max_width` on its own is placed in a row by itself.
+pub fn layout_blocks(blocks: &[String], max_width: usize, gap: &str) -> String {
+    let gap_width = visible_width(gap);
+    let mut rows: Vec<Vec<&str>> = Vec::new();
+    let mut row_width = 0;
+    for block in blocks {
+        let width = block.lines().map(visible_width).max().unwrap_or(0);
+        match rows.last_mut() {
+            Some(row) if row_width + gap_width + width <= max_width => {
+                row.push(block);
+                row_width += gap_width + width;
+            }
+            _ => {
+                rows.push(vec![block]);
+                row_width = width;
+            }
+        }
+    }
+
+    rows.iter()
+        .map(|row| hconcat_all(row, gap))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Stacks `top` above `bottom`, with `gap_lines` blank lines in between.
+/// Every line is padded with spaces to the width of the widest line,
+/// so the result is a rectangular block that can be passed to [`hconcat`].
+pub fn vconcat(top: &str, bottom: &str, gap_lines: usize) -> String {
+    vconcat_with_gap(&[top, bottom], gap_lines)
+}
+
+/// Stacks all of the given blocks on top of each other, padding them to a common width.
+pub fn vconcat_all(blocks: &[&str]) -> String {
+    vconcat_with_gap(blocks, 0)
+}
+
+fn vconcat_with_gap(blocks: &[&str], gap_lines: usize) -> String {
+    let width = blocks
+        .iter()
+        .flat_map(|block| block.lines())
+        .map(visible_width)
+        .max()
+        .unwrap_or(0);
+    let blank = " ".repeat(width);
+
+    let mut lines = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        if i > 0 {
+            lines.extend(std::iter::repeat_n(blank.clone(), gap_lines));
+        }
+        lines.extend(block.lines().map(|line| {
+            let padding = width - visible_width(line);
+            format!("{line}{}", " ".repeat(padding))
+        }));
+    }
+    lines.join("\n")
+}
+
+/// The number of terminal columns taken up by `text`.
+/// ANSI CSI escape sequences (`ESC [ ... letter`) take up no space.
+pub fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            skip_escape(&mut chars);
+        } else {
+            width += char_width(c);
+        }
+    }
+    width
+}
+
+/// Removes all ANSI CSI escape sequences from `text`, for output that should not be coloured.
+pub fn strip_ansi(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            skip_escape(&mut chars);
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
+
+/// Consumes the rest of an escape sequence whose `ESC` has just been read.
+fn skip_escape(chars: &mut std::str::Chars) {
+    if chars.clone().next() == Some('[') {
+        chars.next();
+        for c in chars.by_ref() {
+            if c.is_ascii_alphabetic() {
+                break;
+            }
+        }
+    }
+}
+
+/// The number of terminal columns taken up by a single character.
+/// This is a small lookup covering the characters we actually render, not a full Unicode width table.
+fn char_width(c: char) -> usize {
+    match c {
+        // Box drawing (`─│┌┐└┘`) and block elements (`█▒▁▇`) are a single column,
+        // despite taking up three bytes.
+        '\u{2500}'..='\u{259F}' => 1,
+        // Control characters, combining marks, and zero-width spaces and joiners.
+        '\u{0}'..='\u{1F}'
+        | '\u{7F}'
+        | '\u{300}'..='\u{36F}'
+        | '\u{200B}'..='\u{200F}'
+        | '\u{FE00}'..='\u{FE0F}' => 0,
+        // East Asian wide characters and emoji.
+        '\u{1100}'..='\u{115F}'
+        | '\u{2E80}'..='\u{A4CF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF00}'..='\u{FF60}'
+        | '\u{FFE0}'..='\u{FFE6}'
+        | '\u{1F300}'..='\u{1FAFF}'
+        | '\u{20000}'..='\u{3FFFD}' => 2,
+        _ => 1,
+    }
+}
+
+/// How [`highlight_diff_styled`] marks the characters that differ.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStyle {
+    /// Differing runs are shown in red reverse video.
+    #[default]
+    Ansi,
+    /// Differing runs are wrapped in square brackets, for output that should not be coloured.
+    Brackets,
+}
+
+/// Renders `actual` with every character that differs from `expected` highlighted,
+/// followed by a line counting the differing positions.
+/// Equivalent to [`highlight_diff_styled`] with [`DiffStyle::Ansi`].
+pub fn highlight_diff(expected: &str, actual: &str) -> String {
+    highlight_diff_styled(expected, actual, DiffStyle::Ansi)
+}
+
+/// Renders `actual` with every character that differs from `expected` highlighted,
+/// followed by a line counting the differing positions.
+/// Blocks of different sizes are padded with spaces before comparison,
+/// and any escape sequences in the inputs are ignored.
+pub fn highlight_diff_styled(expected: &str, actual: &str, style: DiffStyle) -> String {
+    let (open, close) = match style {
+        DiffStyle::Ansi => ("\x1b[7;31m", "\x1b[0m"),
+        DiffStyle::Brackets => ("[", "]"),
+    };
+
+    let (expected, actual) = diff_cells(expected, actual);
+    let mut differences = 0;
+    let mut lines = Vec::new();
+    for (expected_line, actual_line) in expected.iter().zip(&actual) {
+        let mut line = String::new();
+        let mut highlighting = false;
+        for (e, a) in expected_line.iter().zip(actual_line) {
+            let differs = e != a;
+            if differs {
+                differences += 1;
+            }
+            if differs != highlighting {
+                line += if differs { open } else { close };
+                highlighting = differs;
+            }
+            line.push(*a);
+        }
+        if highlighting {
+            line += close;
+        }
+        lines.push(line);
+    }
+
+    lines.push(format!("{differences} differing positions."));
+    lines.join("\n")
+}
+
+/// Counts the character positions at which two blocks differ, padding them to the same size first.
+pub fn count_differences(expected: &str, actual: &str) -> usize {
+    let (expected, actual) = diff_cells(expected, actual);
+    expected
+        .iter()
+        .zip(&actual)
+        .map(|(e, a)| e.iter().zip(a).filter(|(e, a)| e != a).count())
+        .sum()
+}
+
+/// Splits both blocks into rectangular grids of characters of the same dimensions.
+fn diff_cells(expected: &str, actual: &str) -> (Vec<Vec<char>>, Vec<Vec<char>>) {
+    let split = |block: &str| {
+        strip_ansi(block)
+            .lines()
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+    let mut expected = split(expected);
+    let mut actual = split(actual);
+
+    let height = expected.len().max(actual.len());
+    let width = expected
+        .iter()
+        .chain(&actual)
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+    for block in [&mut expected, &mut actual] {
+        block.resize(height, Vec::new());
+        for line in block {
+            line.resize(width, ' ');
+        }
+    }
+    (expected, actual)
+}
+
+/// How the cells of a [`Table`] column are padded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnAlignment {
+    #[default]
+    Left,
+    Right,
+}
+
+/// A table of text, rendered with every column padded to a common width.
+#[derive(Debug, Clone)]
+pub struct Table {
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    alignments: Vec<ColumnAlignment>,
+    gap: String,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self {
+            header: None,
+            rows: Vec::new(),
+            alignments: Vec::new(),
+            gap: "  ".to_owned(),
+        }
+    }
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the header row, which is rendered above a separator line.
+    pub fn header(&mut self, cells: impl IntoIterator<Item = impl Into<String>>) -> &mut Self {
+        self.header = Some(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the alignment of the given column. Columns are left-aligned by default.
+    pub fn align(&mut self, column: usize, alignment: ColumnAlignment) -> &mut Self {
+        if self.alignments.len() <= column {
+            self.alignments.resize(column + 1, ColumnAlignment::Left);
+        }
+        self.alignments[column] = alignment;
+        self
+    }
+
+    /// Sets the text placed between adjacent columns. Defaults to two spaces.
+    pub fn gap(&mut self, gap: impl Into<String>) -> &mut Self {
+        self.gap = gap.into();
+        self
+    }
+
+    /// Adds a row to the bottom of the table.
+    /// Rows may have different lengths; missing cells are left blank.
+    pub fn add_row(&mut self, cells: impl IntoIterator<Item = impl Into<String>>) -> &mut Self {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let all_rows = || self.header.iter().chain(self.rows.iter());
+        let columns = all_rows().map(|row| row.len()).max().unwrap_or(0);
+        let widths = (0..columns)
+            .map(|column| {
+                all_rows()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| visible_width(cell))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+
+        let mut lines = Vec::new();
+        if let Some(header) = &self.header {
+            lines.push(self.render_row(header, &widths));
+            lines.push(
+                widths
+                    .iter()
+                    .map(|width| "─".repeat(*width))
+                    .collect::<Vec<_>>()
+                    .join(&"─".repeat(visible_width(&self.gap))),
+            );
+        }
+        lines.extend(self.rows.iter().map(|row| self.render_row(row, &widths)));
+        lines.join("\n")
+    }
+
+    fn render_row(&self, row: &[String], widths: &[usize]) -> String {
+        let mut output = String::new();
+        for (column, width) in widths.iter().enumerate() {
+            let cell = row.get(column).map(String::as_str).unwrap_or("");
+            let padding = " ".repeat(width - visible_width(cell));
+            if column > 0 {
+                output += &self.gap;
+            }
+            match self.alignments.get(column).copied().unwrap_or_default() {
+                ColumnAlignment::Left => {
+                    output += cell;
+                    output += &padding;
+                }
+                ColumnAlignment::Right => {
+                    output += &padding;
+                    output += cell;
+                }
+            }
+        }
+        // Avoid trailing whitespace from padding at the end of the line.
+        output.truncate(output.trim_end().len());
+        output
+    }
+}
+
+/// Renders a progress bar such as `[#####.....] 52%`, with `width` cells between the brackets.
+/// The fraction is clamped to lie between zero and one.
+pub fn progress_bar(fraction: f64, width: usize) -> String {
+    let fraction = if fraction.is_nan() {
+        0.0
+    } else {
+        fraction.clamp(0.0, 1.0)
+    };
+    let filled = ((fraction * width as f64) as usize).min(width);
+    format!(
+        "[{}{}] {}%",
+        "#".repeat(filled),
+        ".".repeat(width - filled),
+        (fraction * 100.0) as usize
+    )
+}
+
+/// Renders a sparkline such as `▁▃▅▇▆▂`, scaled so that the smallest value is the lowest bar
+/// and the largest value is the highest. If there are more than `width` values,
+/// consecutive values are averaged so that the sparkline is at most `width` characters wide.
+/// Non-finite values are drawn as the lowest bar.
+pub fn sparkline(values: &[f64], width: usize) -> String {
+    sparkline_with(values, width, &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'])
+}
+
+/// Like [`sparkline`], but using only ASCII characters, for terminals that cannot display block elements.
+pub fn sparkline_ascii(values: &[f64], width: usize) -> String {
+    sparkline_with(values, width, &['_', '.', '-', ':', '=', '+', '*', '#'])
+}
+
+fn sparkline_with(values: &[f64], width: usize, glyphs: &[char]) -> String {
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+
+    // Average the values into at most `width` buckets.
+    let buckets = values.len().min(width);
+    let samples = (0..buckets)
+        .map(|i| {
+            let bucket = &values[i * values.len() / buckets..(i + 1) * values.len() / buckets];
+            bucket.iter().sum::<f64>() / bucket.len() as f64
+        })
+        .collect::<Vec<_>>();
+
+    let finite = || samples.iter().copied().filter(|x| x.is_finite());
+    let min = finite().fold(f64::INFINITY, f64::min);
+    let max = finite().fold(f64::NEG_INFINITY, f64::max);
+    let top = glyphs.len() - 1;
+    samples
+        .iter()
+        .map(|sample| {
+            let level = if !sample.is_finite() {
+                0
+            } else if max > min {
+                (((sample - min) / (max - min)) * top as f64).round() as usize
+            } else {
+                // A flat line is drawn halfway up.
+                top / 2
+            };
+            glyphs[level.min(top)]
+        })
+        .collect()
+}
+
+/// Unsigned integer types that can be formatted by [`large_number`] and [`large_number_si`].
+pub trait Unsigned: Copy {
+    fn widen(self) -> u128;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Unsigned for $t {
+                #[inline]
+                fn widen(self) -> u128 {
+                    self as u128
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+
+/// Formats a number for humans, e.g. `12 345` or `4.567 million`.
+/// Digits beyond the third significant group are truncated, not rounded.
+pub fn large_number(number: impl Unsigned) -> String {
+    let number = number.widen();
     if number < 1_000 {
         number.to_string()
     } else if number < 1_000_000 {
         format!("{} {:03}", number / 1_000, number % 1_000)
     } else if number < 1_000_000_000 {
-        format!("{}.{:03} million", number / 1_000_000, (number % 1_000_000) / 1_000)
-    } else {
+        format!(
+            "{}.{:03} million",
+            number / 1_000_000,
+            (number % 1_000_000) / 1_000
+        )
+    } else if number < 1_000_000_000_000 {
         format!(
             "{}.{:03} billion",
             number / 1_000_000_000,
             (number % 1_000_000_000) / 1_000_000
         )
+    } else {
+        format!(
+            "{}.{:03} trillion",
+            number / 1_000_000_000_000,
+            (number % 1_000_000_000_000) / 1_000_000_000
+        )
+    }
+}
+
+/// Formats a number compactly with an SI suffix and three significant figures, e.g. `12.3k` or `4.56M`.
+/// Like [`large_number`], excess digits are truncated, so `999 999` is `999k` rather than `1.00M`.
+pub fn large_number_si(number: impl Unsigned) -> String {
+    const SUFFIXES: [&str; 8] = ["k", "M", "G", "T", "P", "E", "Z", "Y"];
+
+    let number = number.widen();
+    if number < 1_000 {
+        return number.to_string();
+    }
+
+    // Find the largest suffix that keeps the leading part at least one.
+    let mut exponent = 0;
+    let mut unit = 1_000u128;
+    while exponent + 1 < SUFFIXES.len() && number / unit >= 1_000 {
+        exponent += 1;
+        unit *= 1_000;
+    }
+    let suffix = SUFFIXES[exponent];
+
+    // The value in units of one hundredth of the suffix.
+    let hundredths = number / (unit / 100);
+    if hundredths >= 10_000 {
+        format!("{}{suffix}", hundredths / 100)
+    } else if hundredths >= 1_000 {
+        format!("{}.{}{suffix}", hundredths / 100, (hundredths % 100) / 10)
+    } else {
+        format!("{}.{:02}{suffix}", hundredths / 100, hundredths % 100)
+    }
+}
+
+/// Formats the rate at which `count` events happened over `elapsed`, e.g. `12.3k/s`.
+pub fn rate(count: impl Unsigned, elapsed: Duration) -> String {
+    let millis = elapsed.as_millis();
+    if millis == 0 {
+        return "-/s".to_owned();
+    }
+    format!(
+        "{}/s",
+        large_number_si(count.widen().saturating_mul(1_000) / millis)
+    )
+}
+
+/// Formats a duration for humans, e.g. `873ms`, `12.4s`, `3m 07s`, or `1h 02m`.
+/// Precision decreases as the duration grows; excess precision is truncated.
+pub fn human_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    let secs = duration.as_secs();
+    if millis < 1_000 {
+        format!("{millis}ms")
+    } else if millis < 10_000 {
+        format!("{}.{:02}s", secs, (millis % 1_000) / 10)
+    } else if secs < 60 {
+        format!("{}.{}s", secs, (millis % 1_000) / 100)
+    } else if secs < 60 * 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / (60 * 60), (secs / 60) % 60)
+    }
+}
+
+/// The error returned by [`parse_duration`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDurationError {
+    input: String,
+}
+
+impl Display for ParseDurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid duration `{}`: expected a number with an optional ms, s, m, or h suffix, or several with suffixes such as `3m 07s`",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseDurationError {}
+
+/// Parses a duration such as `500ms`, `30s`, `2.5m`, or `1h`.
+/// A bare number is interpreted as a number of seconds.
+/// Several durations separated by spaces, each with a suffix, are added up,
+/// so that everything [`human_duration`] writes, such as `3m 07s`, can be read back.
+pub fn parse_duration(input: &str) -> Result<Duration, ParseDurationError> {
+    let error = || ParseDurationError {
+        input: input.to_owned(),
+    };
+    if let Some(duration) = parse_duration_part(input.trim()) {
+        return Ok(duration);
+    }
+    let parts = input.split_whitespace().collect::<Vec<_>>();
+    if parts.len() < 2 {
+        return Err(error());
+    }
+    parts
+        .iter()
+        .try_fold(Duration::ZERO, |total, part| {
+            // Each part needs its own suffix, so that `2 3` is not read as five seconds.
+            if !part.ends_with(|c: char| c.is_ascii_alphabetic()) {
+                return None;
+            }
+            total.checked_add(parse_duration_part(part)?)
+        })
+        .ok_or_else(error)
+}
+
+/// Parses a single number with an optional suffix, for [`parse_duration`].
+fn parse_duration_part(trimmed: &str) -> Option<Duration> {
+    // `ms` must be checked before `m` and `s`.
+    let (number, scale) = if let Some(number) = trimmed.strip_suffix("ms") {
+        (number, 0.001)
+    } else if let Some(number) = trimmed.strip_suffix('s') {
+        (number, 1.0)
+    } else if let Some(number) = trimmed.strip_suffix('m') {
+        (number, 60.0)
+    } else if let Some(number) = trimmed.strip_suffix('h') {
+        (number, 60.0 * 60.0)
+    } else {
+        (trimmed, 1.0)
+    };
+
+    number
+        .trim_end()
+        .parse::<f64>()
+        .ok()
+        .and_then(|number| Duration::try_from_secs_f64(number * scale).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_of_uneven_heights_are_aligned() {
+        let (left, right) = ("a\nbb\nccc", "X");
+        assert_eq!(
+            hconcat_aligned(left, right, " | ", Alignment::Top),
+            "a   | X\nbb  | \nccc | "
+        );
+        assert_eq!(
+            hconcat_aligned(left, right, " | ", Alignment::Bottom),
+            "a   | \nbb  | \nccc | X"
+        );
+        // When the right block is the taller one, the left is padded with blank columns.
+        assert_eq!(
+            hconcat_aligned("ab", "1\n2\n3", "", Alignment::Bottom),
+            "  1\n  2\nab3"
+        );
+        assert_eq!(hconcat("ab", "1\n2", ""), "ab1\n  2");
+    }
+
+    #[test]
+    fn blocks_of_uneven_widths_form_straight_columns() {
+        assert_eq!(
+            hconcat_aligned("#\n###\n##", "x\ny\nz", " ", Alignment::Top),
+            "#   x\n### y\n##  z"
+        );
+        // Widths are measured in columns, ignoring colours and counting wide characters twice.
+        assert_eq!(
+            hconcat_aligned("世\n\x1b[1ma\x1b[0m", "R\nR", "", Alignment::Top),
+            "世R\n\x1b[1ma\x1b[0m R"
+        );
+    }
+
+    #[test]
+    fn empty_blocks_take_no_room() {
+        assert_eq!(hconcat_aligned("", "", " ", Alignment::Top), "");
+        assert_eq!(hconcat_aligned("", "x\ny", "|", Alignment::Top), "|x\n|y");
+        assert_eq!(
+            hconcat_aligned("x\ny", "", "|", Alignment::Bottom),
+            "x|\ny|"
+        );
+    }
+
+    #[test]
+    fn progress_bars_fit_any_width() {
+        assert_eq!(progress_bar(0.5, 0), "[] 50%");
+        assert_eq!(progress_bar(1.0, 0), "[] 100%");
+        assert_eq!(progress_bar(0.5, 1), "[.] 50%");
+        assert_eq!(progress_bar(1.0, 1), "[#] 100%");
+        assert_eq!(progress_bar(0.52, 10), "[#####.....] 52%");
+        // Cells and the percentage are only filled once they are reached.
+        assert_eq!(progress_bar(0.999, 10), "[#########.] 99%");
+    }
+
+    #[test]
+    fn progress_bars_clamp_the_fraction() {
+        assert_eq!(progress_bar(-0.3, 4), "[....] 0%");
+        assert_eq!(progress_bar(f64::NEG_INFINITY, 4), "[....] 0%");
+        assert_eq!(progress_bar(1.7, 4), "[####] 100%");
+        assert_eq!(progress_bar(f64::INFINITY, 4), "[####] 100%");
+        assert_eq!(progress_bar(f64::NAN, 4), "[....] 0%");
+        assert_eq!(progress_bar(-1.0, 0), "[] 0%");
+        assert_eq!(progress_bar(2.0, 1), "[#] 100%");
+    }
+
+    #[test]
+    fn large_numbers_change_form_at_each_thousand() {
+        assert_eq!(large_number(999u32), "999");
+        assert_eq!(large_number(1_000u32), "1 000");
+        assert_eq!(large_number(1_005u32), "1 005");
+        assert_eq!(large_number(999_999u32), "999 999");
+        assert_eq!(large_number(1_000_000u32), "1.000 million");
+        assert_eq!(large_number(999_999_999u32), "999.999 million");
+        assert_eq!(large_number(1_000_000_000u64), "1.000 billion");
+        assert_eq!(large_number(u64::MAX), "18446744.073 trillion");
+    }
+
+    #[test]
+    fn large_numbers_with_si_suffixes_keep_three_figures() {
+        assert_eq!(large_number_si(999u32), "999");
+        assert_eq!(large_number_si(1_000u32), "1.00k");
+        assert_eq!(large_number_si(12_345u32), "12.3k");
+        assert_eq!(large_number_si(999_999u32), "999k");
+        assert_eq!(large_number_si(1_000_000u32), "1.00M");
+        assert_eq!(large_number_si(u64::MAX), "18.4E");
+        // Past the last suffix, the leading part just grows.
+        assert_eq!(large_number_si(u128::MAX), "340282366920938Y");
+    }
+
+    #[test]
+    fn many_blocks_join_side_by_side() {
+        assert_eq!(hconcat_all(&["a", "b\nb", "c"], "|"), "a|b|c\n |b|");
+        assert_eq!(
+            hconcat_all_aligned(&["a", "b\nb", "c"], "|", Alignment::Bottom),
+            " |b|\na|b|c"
+        );
+        assert_eq!(hconcat_all(&["only\none"], "|"), "only\none");
+        assert_eq!(hconcat_all(&[], "|"), "");
+    }
+
+    #[test]
+    fn four_blocks_wrap_onto_rows() {
+        let blocks = ["aaa\naaa", "bbb\nbbb", "ccc\nccc", "ddd\nddd"].map(String::from);
+        // Two blocks and the gap between them fit in eight columns, but not three.
+        assert_eq!(
+            layout_blocks(&blocks, 8, " "),
+            "aaa bbb\naaa bbb\n\nccc ddd\nccc ddd"
+        );
+        assert_eq!(
+            layout_blocks(&blocks, 11, " "),
+            "aaa bbb ccc\naaa bbb ccc\n\nddd\nddd"
+        );
+        assert_eq!(
+            layout_blocks(&blocks, 100, " "),
+            "aaa bbb ccc ddd\naaa bbb ccc ddd"
+        );
+        // Each block is on its own when even two do not fit.
+        assert_eq!(layout_blocks(&blocks[..2], 6, " "), "aaa\naaa\n\nbbb\nbbb");
+    }
+
+    #[test]
+    fn oversized_blocks_get_rows_of_their_own() {
+        let blocks = ["a", "wider than the limit", "b", "c"].map(String::from);
+        assert_eq!(
+            layout_blocks(&blocks, 5, " "),
+            "a\n\nwider than the limit\n\nb c"
+        );
+        assert_eq!(layout_blocks(&[], 5, " "), "");
+    }
+
+    #[test]
+    fn stacked_blocks_are_padded_to_one_width() {
+        assert_eq!(vconcat("ab", "c", 1), "ab\n  \nc ");
+        assert_eq!(vconcat("ab", "c", 0), "ab\nc ");
+        assert_eq!(vconcat_all(&["x", "yyy\nz"]), "x  \nyyy\nz  ");
+        assert_eq!(
+            vconcat_all(&["\x1b[1m世\x1b[0m", "abc"]),
+            "\x1b[1m世\x1b[0m \nabc"
+        );
+        assert_eq!(vconcat_all(&[]), "");
+        // The result is rectangular, so it can be joined beside another block.
+        assert_eq!(hconcat(&vconcat("ab", "c", 0), "1\n2", "|"), "ab|1\nc |2");
+    }
+
+    #[test]
+    fn colours_take_up_no_width() {
+        for plain in [
+            "",
+            "generation 12",
+            "██  ██",
+            "┌──┐",
+            "e\u{301}",
+            "世界",
+            "🙂 ok",
+            "한글 and text",
+        ] {
+            let coloured = format!("\x1b[1;31m{plain}\x1b[0m");
+            assert_eq!(visible_width(&coloured), visible_width(plain), "{plain:?}");
+            assert_eq!(strip_ansi(&coloured), plain);
+            assert_eq!(strip_ansi(plain), plain);
+        }
+        // Escapes in the middle of a line, and next to wide characters.
+        let mixed = "a\x1b[7m世\x1b[27mb\x1b[38;5;208m🙂\x1b[m";
+        assert_eq!(strip_ansi(mixed), "a世b🙂");
+        assert_eq!(visible_width(mixed), 6);
+    }
+
+    #[test]
+    fn wide_and_zero_width_characters_are_measured_by_columns() {
+        assert_eq!(visible_width("abc"), 3);
+        // Drawn as one column each, although each takes three bytes.
+        assert_eq!(visible_width("█▒▁─│┌"), 6);
+        assert_eq!(visible_width("世界"), 4);
+        assert_eq!(visible_width("ｆｕｌｌ"), 8);
+        assert_eq!(visible_width("🙂"), 2);
+        // A combining accent, a zero-width space and a variation selector add nothing.
+        assert_eq!(visible_width("e\u{301}"), 1);
+        assert_eq!(visible_width("a\u{200B}b"), 2);
+        assert_eq!(visible_width("✔\u{FE0F}"), 1);
+        // Only CSI sequences are escapes; a lone ESC is dropped and what follows it counts.
+        assert_eq!(visible_width("\x1bxy"), 2);
+        assert_eq!(strip_ansi("\x1bxy"), "xy");
+    }
+
+    #[test]
+    fn durations_read_back_as_they_were_written() {
+        for millis in [
+            0, 1, 873, 1_000, 1_230, 9_990, 12_400, 59_900, 60_000, 187_000, 3_599_000, 3_600_000,
+            3_720_000, 90_000_000,
+        ] {
+            let written = human_duration(Duration::from_millis(millis));
+            let read = parse_duration(&written)
+                .unwrap_or_else(|error| panic!("{written} should parse: {error}"));
+            assert_eq!(read, Duration::from_millis(millis), "{written}");
+            assert_eq!(human_duration(read), written);
+        }
+        // What is written is truncated, so reading it back gives the duration rounded down.
+        let written = human_duration(Duration::from_millis(12_489));
+        assert_eq!(written, "12.4s");
+        assert_eq!(parse_duration(&written), Ok(Duration::from_millis(12_400)));
+    }
+
+    #[test]
+    fn durations_take_any_suffix_or_none() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration(" 30s "), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("2.5m"), Ok(Duration::from_secs(150)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3_600)));
+        assert_eq!(parse_duration("1h 02m"), Ok(Duration::from_secs(3_720)));
+        // Without a suffix, or with the suffix apart from the number, it is seconds.
+        assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_duration("2 s"), Ok(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn malformed_durations_are_rejected() {
+        for input in [
+            "", "   ", "s", "ms", "5x", "2 3", "1h 5", "-5s", "-1", "1e30h", "NaNs", "infs",
+        ] {
+            let error = parse_duration(input).expect_err(input);
+            assert!(error
+                .to_string()
+                .starts_with(&format!("invalid duration `{input}`")));
+        }
+        // Parts that each fit, but not added together.
+        let largest = Duration::MAX.as_secs() / 3_600;
+        assert!(parse_duration(&format!("{largest}h {largest}h")).is_err());
     }
 }
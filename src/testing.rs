@@ -0,0 +1,152 @@
+//! [Proptest](https://docs.rs/proptest) strategies and helpers for testing code that uses this crate.
+//! Only available with the `testing` feature.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use undeath::testing;
+//!
+//! proptest!(|(grid in testing::grid(0.3))| {
+//!     prop_assert!(grid.alive_cells().count() <= 64);
+//! });
+//! ```
+
+use proptest::prelude::*;
+
+use crate::{
+    grid::{Cell, Grid, Topology, MIN_SIZE, SIZE},
+    guess::Guess,
+};
+
+/// A cell position on a [`SIZE`]×[`SIZE`] grid, in `0..SIZE` along each axis.
+pub fn coordinate() -> impl Strategy<Value = (i32, i32)> {
+    (0..SIZE, 0..SIZE)
+}
+
+/// A [`SIZE`]×[`SIZE`] grid in which each cell is alive with probability `density`.
+pub fn grid(density: f64) -> impl Strategy<Value = Grid> {
+    grid_in(0, 0, SIZE, SIZE, density)
+}
+
+/// A [`SIZE`]×[`SIZE`] grid in which each cell of the `width` by `height` rectangle with top left corner `(x, y)`
+/// is alive with probability `density`, and every other cell is dead.
+pub fn grid_in(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    density: f64,
+) -> impl Strategy<Value = Grid> {
+    proptest::collection::vec(proptest::bool::weighted(density), (width * height) as usize)
+        .prop_map(move |cells| {
+            let mut grid = Grid::default();
+            for (i, alive) in cells.into_iter().enumerate() {
+                if alive {
+                    let i = i as i32;
+                    grid.set_wrapped(x + i % width, y + i / width, Cell::one());
+                }
+            }
+            grid
+        })
+}
+
+/// A grid from [`MIN_SIZE`] to `max_side` cells on each side, on a torus or a plane,
+/// in which each cell is alive with probability `density`.
+pub fn grid_of_any_size(max_side: i32, density: f64) -> impl Strategy<Value = Grid> {
+    (
+        MIN_SIZE..=max_side,
+        MIN_SIZE..=max_side,
+        prop_oneof![Just(Topology::Torus), Just(Topology::Plane)],
+    )
+        .prop_flat_map(move |(width, height, topology)| {
+            proptest::collection::vec(proptest::bool::weighted(density), (width * height) as usize)
+                .prop_map(move |cells| {
+                    let mut grid = Grid::new(width, height).with_topology(topology);
+                    for (i, alive) in cells.into_iter().enumerate() {
+                        if alive {
+                            let i = i as i32;
+                            grid.set_wrapped(i % width, i / width, Cell::one());
+                        }
+                    }
+                    grid
+                })
+        })
+}
+
+/// A sequence of up to `max_len` guesses to pass to [`apply_guesses`]:
+/// a position, and whether the cell there is guessed to be alive.
+pub fn guesses(max_len: usize) -> impl Strategy<Value = Vec<(i32, i32, bool)>> {
+    proptest::collection::vec((0..SIZE, 0..SIZE, any::<bool>()), 0..=max_len)
+}
+
+/// Applies a scripted sequence of guesses, each with its constraint propagation, to an empty guess for `next`.
+/// Coordinates are wrapped into the grid. Guesses made after a contradiction are still applied.
+pub fn apply_guesses(next: &Grid, guesses: &[(i32, i32, bool)]) -> Guess {
+    let mut guess = Guess::new(next.width(), next.height());
+    for &(x, y, alive) in guesses {
+        let (x, y) = (x.rem_euclid(next.width()), y.rem_euclid(next.height()));
+        if alive {
+            guess.guess_alive(next, x, y);
+        } else {
+            guess.guess_dead(next, x, y);
+        }
+    }
+    guess
+}
+
+/// Checks the internal consistency of a guess, describing the first problem found.
+pub fn check_guess(guess: &Guess) -> Result<(), String> {
+    guess.check_invariants()
+}
+
+/// Shaves a guess for `next`, as [`Searcher::shave`](crate::Searcher::shave) does.
+pub fn shave(guess: &mut Guess, next: &Grid) {
+    guess.shave(next);
+}
+
+/// Merges the decided cells of `other` into `guess` for `next`, as [`Searcher::merge_root`](crate::Searcher::merge_root) does.
+pub fn merge_root_implications(guess: &mut Guess, next: &Grid, other: &Guess) {
+    guess.merge_root_implications(next, other);
+}
+
+/// The cells a guess suggests trying alive, and those it suggests trying dead, when the search next branches.
+pub fn suggestions(guess: &Guess) -> (Grid, Grid) {
+    (guess.try_alive().to_grid(), guess.try_dead().to_grid())
+}
+
+/// Counts the neighbours of `grid` into `out` as [`Grid::neighbours_into`] used to,
+/// summing each row over a window of three into `windows` and then adding up three windows for each cell.
+/// This is the separable version the single pass in [`Grid::neighbours_into`] replaced, to compare them against.
+///
+/// # Panics
+/// If `out` is a different size from `grid`.
+pub fn separable_neighbours_into(grid: &Grid, out: &mut Grid, windows: &mut Vec<i32>) {
+    grid.separable_neighbours_into(out, windows);
+}
+
+/// Moves every cell of `grid` by `(dx, dy)`, wrapping around the torus.
+pub fn translate(grid: &Grid, dx: i32, dy: i32) -> Grid {
+    let (width, height) = (grid.width(), grid.height());
+    let mut result = Grid::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            result.set_wrapped(
+                (x + dx).rem_euclid(width),
+                (y + dy).rem_euclid(height),
+                grid.get_wrapped(x, y),
+            );
+        }
+    }
+    result
+}
+
+/// Rotates `grid` a quarter turn clockwise, which swaps its width and height.
+pub fn rotate(grid: &Grid) -> Grid {
+    let (width, height) = (grid.width(), grid.height());
+    let mut result = Grid::new(height, width);
+    for y in 0..height {
+        for x in 0..width {
+            result.set_wrapped(height - 1 - y, x, grid.get_wrapped(x, y));
+        }
+    }
+    result
+}
@@ -0,0 +1,430 @@
+//! Recording every move of a depth-first search, to debug heuristics or to show how a search went.
+//!
+//! A [`Searcher`](crate::Searcher) given a [`TraceSink`] with [`Searcher::trace_to`](crate::Searcher::trace_to)
+//! reports each decision, backtrack and solution to it as a [`TraceEvent`].
+//! [`BinaryTrace`] writes them compactly, a few bytes each, and [`read_binary`] reads them back
+//! for [`Replay`](crate::replay::Replay); [`JsonTrace`] writes one JSON object per line, for other tools.
+
+use std::{
+    io::{Read, Write},
+    sync::mpsc,
+};
+
+use crate::{
+    grid::{Grid, MAX_SIZE, MIN_SIZE, SIZE},
+    Cell, Error,
+};
+
+/// One move of a depth-first search.
+///
+/// Depths count the guesses made since the root: the root guess is at depth zero.
+/// Iterations count the search's steps from zero, over every call to [`Searcher::search`](crate::Searcher::search),
+/// and each event has the index of the step that caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+// Only the start carries grids, and there is one of those per search, so boxing them would gain nothing.
+#[allow(clippy::large_enum_variant)]
+pub enum TraceEvent {
+    /// The search started from `root_alive` and `root_dead`, the cells the root guess decided.
+    /// Always the first event, and only the first.
+    Start {
+        next: Grid,
+        root_alive: Grid,
+        root_dead: Grid,
+        /// Whether the searcher propagates over 2×2 blocks after each guess.
+        block_propagation: bool,
+        /// If nonzero, the searcher shaves each guess at a depth that is a multiple of this.
+        shave_period: usize,
+    },
+    /// The guess at `depth` was refined with `(x, y)` being alive, or dead if not.
+    /// Unless this was a contradiction, the result is the new guess at `depth + 1`.
+    Decide {
+        iteration: u64,
+        depth: usize,
+        x: i32,
+        y: i32,
+        alive: bool,
+        contradiction: bool,
+    },
+    /// The search went back to the guess at `depth`, discarding every deeper one.
+    Backtrack { iteration: u64, depth: usize },
+    /// The guess at `depth` decides every cell, and is a predecessor.
+    Solution { iteration: u64, depth: usize },
+}
+
+/// Where a [`Searcher`](crate::Searcher) sends its [`TraceEvent`]s.
+pub trait TraceSink {
+    fn record(&mut self, event: &TraceEvent) -> Result<(), Error>;
+}
+
+/// Collects events over a channel, for example to inspect them in the same program.
+impl TraceSink for mpsc::Sender<TraceEvent> {
+    fn record(&mut self, event: &TraceEvent) -> Result<(), Error> {
+        // Nobody is listening any more, which is their business.
+        let _ = self.send(event.clone());
+        Ok(())
+    }
+}
+
+/// The first bytes of a binary trace, ending with the format version.
+/// Version 2 records the width and height of the grid, which version 1 left at [`SIZE`].
+const MAGIC: &[u8; 5] = b"UDTR\x02";
+/// The first bytes of a binary trace in the first version of the format, which [`read_binary`] still reads.
+const MAGIC_V1: &[u8; 5] = b"UDTR\x01";
+
+const START: u8 = 0;
+const DECIDE: u8 = 1;
+const BACKTRACK: u8 = 2;
+const SOLUTION: u8 = 3;
+/// In the tag byte of a decision, whether the cell was guessed alive.
+const ALIVE: u8 = 1 << 2;
+/// In the tag byte of a decision, whether it was a contradiction.
+const CONTRADICTION: u8 = 1 << 3;
+
+/// Appends the positive cells of `grid` as bits, least significant first,
+/// where bit `x + width * y` is `(x, y)`, padded with zeros to a whole byte.
+fn write_grid(out: &mut Vec<u8>, grid: &Grid) {
+    let start = out.len();
+    out.resize(start + grid_bytes(grid.width(), grid.height()), 0);
+    for (x, y) in grid.alive_cells() {
+        let bit = (x + grid.width() * y) as usize;
+        out[start + bit / 8] |= 1 << (bit % 8);
+    }
+}
+
+/// How many bytes [`write_grid`] writes for a `width`×`height` grid.
+fn grid_bytes(width: i32, height: i32) -> usize {
+    ((width * height) as usize).div_ceil(8)
+}
+
+/// Writes `value` seven bits at a time, least significant first, with the top bit of each byte set if more follow.
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Writes [`TraceEvent`]s in a compact binary format, which [`read_binary`] reads.
+/// The start of the search records the width and height of the grid, and a bit for each cell of its grids.
+/// Decisions, backtracks and solutions take a few bytes each.
+/// Wrap files in a [`BufWriter`](std::io::BufWriter), since each event is written separately.
+pub struct BinaryTrace<W> {
+    writer: W,
+    /// The iteration of the last event, which the next is written relative to.
+    iteration: u64,
+    /// How many columns the grid being searched has, which decisions are written relative to.
+    width: i32,
+    wrote_magic: bool,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> BinaryTrace<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            iteration: 0,
+            width: SIZE,
+            wrote_magic: false,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Encodes the step and depth of an event after its tag byte.
+    fn write_position(&mut self, iteration: u64, depth: usize) {
+        write_varint(&mut self.buffer, iteration.saturating_sub(self.iteration));
+        self.iteration = iteration;
+        write_varint(&mut self.buffer, depth as u64);
+    }
+
+    /// The writer, with every event recorded so far written to it.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> TraceSink for BinaryTrace<W> {
+    fn record(&mut self, event: &TraceEvent) -> Result<(), Error> {
+        self.buffer.clear();
+        if !self.wrote_magic {
+            self.buffer.extend_from_slice(MAGIC);
+            self.wrote_magic = true;
+        }
+        match *event {
+            TraceEvent::Start {
+                ref next,
+                ref root_alive,
+                ref root_dead,
+                block_propagation,
+                shave_period,
+            } => {
+                self.buffer.push(START);
+                self.width = next.width();
+                write_varint(&mut self.buffer, next.width() as u64);
+                write_varint(&mut self.buffer, next.height() as u64);
+                for grid in [next, root_alive, root_dead] {
+                    write_grid(&mut self.buffer, grid);
+                }
+                self.buffer.push(block_propagation as u8);
+                write_varint(&mut self.buffer, shave_period as u64);
+            }
+            TraceEvent::Decide {
+                iteration,
+                depth,
+                x,
+                y,
+                alive,
+                contradiction,
+            } => {
+                let mut tag = DECIDE;
+                if alive {
+                    tag |= ALIVE;
+                }
+                if contradiction {
+                    tag |= CONTRADICTION;
+                }
+                self.buffer.push(tag);
+                self.write_position(iteration, depth);
+                write_varint(&mut self.buffer, (x + self.width * y) as u64);
+            }
+            TraceEvent::Backtrack { iteration, depth } => {
+                self.buffer.push(BACKTRACK);
+                self.write_position(iteration, depth);
+            }
+            TraceEvent::Solution { iteration, depth } => {
+                self.buffer.push(SOLUTION);
+                self.write_position(iteration, depth);
+            }
+        }
+        self.writer
+            .write_all(&self.buffer)
+            .map_err(|e| Error::io("the binary trace", e))
+    }
+}
+
+/// The unread part of a binary trace.
+struct Bytes<'a> {
+    rest: &'a [u8],
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidInput(format!("invalid binary trace: {message}"))
+}
+
+impl Bytes<'_> {
+    fn byte(&mut self) -> Result<u8, Error> {
+        let (&first, rest) = self
+            .rest
+            .split_first()
+            .ok_or_else(|| invalid("it ends partway through an event"))?;
+        self.rest = rest;
+        Ok(first)
+    }
+
+    /// Reads a number written by [`write_varint`].
+    fn varint(&mut self) -> Result<u64, Error> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("a number is too long"))
+    }
+
+    /// Reads the width or height of a grid, which must be between [`MIN_SIZE`] and [`MAX_SIZE`].
+    fn side(&mut self) -> Result<i32, Error> {
+        self.varint()
+            .ok()
+            .and_then(|side| i32::try_from(side).ok())
+            .filter(|side| (MIN_SIZE..=MAX_SIZE).contains(side))
+            .ok_or_else(|| invalid("the grid is too small or too large"))
+    }
+
+    /// Reads a `width`×`height` grid written by [`write_grid`].
+    fn grid(&mut self, width: i32, height: i32) -> Result<Grid, Error> {
+        let length = grid_bytes(width, height);
+        if self.rest.len() < length {
+            return Err(invalid("it ends partway through an event"));
+        }
+        let (bytes, rest) = self.rest.split_at(length);
+        self.rest = rest;
+        let mut grid = Grid::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let bit = (x + width * y) as usize;
+                if bytes[bit / 8] >> (bit % 8) & 1 != 0 {
+                    grid.set_wrapped(x, y, Cell::one());
+                }
+            }
+        }
+        Ok(grid)
+    }
+}
+
+/// Reads a binary trace as written by [`BinaryTrace`], or by earlier versions of it, whose grids were all [`SIZE`]×[`SIZE`].
+///
+/// ```
+/// use undeath::{trace::{read_binary, BinaryTrace, TraceEvent, TraceSink}, Grid};
+///
+/// let events = [
+///     TraceEvent::Start {
+///         next: Grid::from_text("\n\n\n..###"),
+///         root_alive: Grid::default(),
+///         root_dead: Grid::default(),
+///         block_propagation: false,
+///         shave_period: 0,
+///     },
+///     TraceEvent::Decide { iteration: 0, depth: 0, x: 3, y: 2, alive: true, contradiction: false },
+///     TraceEvent::Backtrack { iteration: 40, depth: 0 },
+/// ];
+/// let mut sink = BinaryTrace::new(Vec::new());
+/// for event in &events {
+///     sink.record(event)?;
+/// }
+/// let bytes = sink.into_inner();
+/// // The header and the start take 34 bytes, and the others 4 and 3.
+/// assert_eq!(bytes.len(), 41);
+/// assert_eq!(read_binary(bytes.as_slice())?, events);
+/// # Ok::<(), undeath::Error>(())
+/// ```
+pub fn read_binary(mut reader: impl Read) -> Result<Vec<TraceEvent>, Error> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| Error::io("the binary trace", e))?;
+    let (rest, sized) = if let Some(rest) = bytes.strip_prefix(MAGIC.as_slice()) {
+        (rest, true)
+    } else if let Some(rest) = bytes.strip_prefix(MAGIC_V1.as_slice()) {
+        (rest, false)
+    } else {
+        return Err(invalid("it does not start with the expected header"));
+    };
+    let mut bytes = Bytes { rest };
+    let mut events = Vec::new();
+    let mut iteration = 0u64;
+    let (mut width, mut height) = (SIZE, SIZE);
+    while !bytes.rest.is_empty() {
+        let tag = bytes.byte()?;
+        let event = match tag & 0b11 {
+            START if events.is_empty() => {
+                if sized {
+                    width = bytes.side()?;
+                    height = bytes.side()?;
+                }
+                TraceEvent::Start {
+                    next: bytes.grid(width, height)?,
+                    root_alive: bytes.grid(width, height)?,
+                    root_dead: bytes.grid(width, height)?,
+                    block_propagation: bytes.byte()? != 0,
+                    shave_period: bytes.varint()? as usize,
+                }
+            }
+            START => return Err(invalid("it starts more than once")),
+            _ if events.is_empty() => {
+                return Err(invalid("it does not begin with the start of a search"))
+            }
+            kind => {
+                iteration += bytes.varint()?;
+                let depth = bytes.varint()? as usize;
+                match kind {
+                    DECIDE => {
+                        let cell = bytes.varint()?;
+                        if cell >= (width * height) as u64 {
+                            return Err(invalid("a decision is outside the grid"));
+                        }
+                        let cell = cell as i32;
+                        TraceEvent::Decide {
+                            iteration,
+                            depth,
+                            x: cell % width,
+                            y: cell / width,
+                            alive: tag & ALIVE != 0,
+                            contradiction: tag & CONTRADICTION != 0,
+                        }
+                    }
+                    BACKTRACK => TraceEvent::Backtrack { iteration, depth },
+                    _ => TraceEvent::Solution { iteration, depth },
+                }
+            }
+        };
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Writes [`TraceEvent`]s as JSON, one object per line, with an `event` field naming the kind of event.
+/// Grids are written as arrays of rows, with `#` for positive cells and `.` for the others.
+pub struct JsonTrace<W> {
+    writer: W,
+}
+
+impl<W: Write> JsonTrace<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// The writer, with every event recorded so far written to it.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// `grid` as a JSON array of rows, as [`JsonTrace`] writes it.
+pub(crate) fn json_grid(grid: &Grid) -> String {
+    let rows = (0..grid.height())
+        .map(|y| {
+            let row = (0..grid.width())
+                .map(|x| {
+                    if grid.get_wrapped(x, y).value > 0 {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+            format!("\"{row}\"")
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", rows.join(","))
+}
+
+impl<W: Write> TraceSink for JsonTrace<W> {
+    fn record(&mut self, event: &TraceEvent) -> Result<(), Error> {
+        let line = match event {
+            TraceEvent::Start {
+                next,
+                root_alive,
+                root_dead,
+                block_propagation,
+                shave_period,
+            } => format!(
+                "{{\"event\":\"start\",\"next\":{},\"root_alive\":{},\"root_dead\":{},\
+                 \"block_propagation\":{block_propagation},\"shave_period\":{shave_period}}}",
+                json_grid(next),
+                json_grid(root_alive),
+                json_grid(root_dead),
+            ),
+            TraceEvent::Decide {
+                iteration,
+                depth,
+                x,
+                y,
+                alive,
+                contradiction,
+            } => format!(
+                "{{\"event\":\"decide\",\"iteration\":{iteration},\"depth\":{depth},\"x\":{x},\"y\":{y},\
+                 \"alive\":{alive},\"contradiction\":{contradiction}}}"
+            ),
+            TraceEvent::Backtrack { iteration, depth } => format!(
+                "{{\"event\":\"backtrack\",\"iteration\":{iteration},\"depth\":{depth}}}"
+            ),
+            TraceEvent::Solution { iteration, depth } => format!(
+                "{{\"event\":\"solution\",\"iteration\":{iteration},\"depth\":{depth}}}"
+            ),
+        };
+        writeln!(self.writer, "{line}").map_err(|e| Error::io("the JSON trace", e))
+    }
+}
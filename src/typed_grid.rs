@@ -0,0 +1,299 @@
+//! Grids that say what their cells mean.
+//!
+//! A [`Grid`] of [`Cell`]s holds any `i32` in each cell, and is used for patterns, neighbour counts and heat maps alike,
+//! so nothing stops a count from being read as a pattern, or a count from going negative.
+//! The solver's internal state uses these instead:
+//! [`BoolGrid`] holds a set of cells, and [`CountGrid`] holds a count for each cell that never goes below zero.
+//! Converting between them, or to and from a [`Grid`], is always explicit.
+//! Like a [`Grid`], each has a width and a height, and coordinates wrap around at them.
+
+use std::ops::{BitAndAssign, BitOrAssign, Not, SubAssign};
+
+use crate::{
+    checked::checked_assert,
+    grid::{AliveCells, Cell, Grid, Neighbourhood},
+};
+
+/// A set of cells, such as those guessed alive.
+/// The default is an empty set on a [`SIZE`](crate::grid::SIZE)×[`SIZE`](crate::grid::SIZE) grid.
+///
+/// ```
+/// use undeath::{typed_grid::BoolGrid, Grid};
+///
+/// let mut cells = BoolGrid::default();
+/// cells.set(1, 2);
+/// cells.set(-1, 0);
+/// assert!(cells.test(1, 2) && cells.test(7, 0));
+/// cells.clear(1, 2);
+/// assert_eq!(cells.count(), 1);
+/// assert!(cells.to_grid() == Grid::from_text(".......#"));
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct BoolGrid {
+    /// Every cell is zero or one.
+    cells: Grid,
+}
+
+impl BoolGrid {
+    /// An empty set on a `width`×`height` grid.
+    ///
+    /// # Panics
+    /// As [`Grid::new`] does.
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            cells: Grid::new(width, height),
+        }
+    }
+
+    /// The width of the grid the set is on.
+    #[inline]
+    pub fn width(&self) -> i32 {
+        self.cells.width()
+    }
+
+    /// The height of the grid the set is on.
+    #[inline]
+    pub fn height(&self) -> i32 {
+        self.cells.height()
+    }
+
+    /// Empties the set, and moves it to a `width`×`height` grid, reusing its memory where it can.
+    pub(crate) fn reset(&mut self, width: i32, height: i32) {
+        self.cells.reset(width, height);
+    }
+
+    /// The alive cells of `grid`, which are those with a positive value, on a grid of the same size.
+    pub fn from_grid(grid: &Grid) -> Self {
+        let mut cells = Self::new(grid.width(), grid.height());
+        for (x, y) in grid.alive_cells() {
+            cells.set(x, y);
+        }
+        cells
+    }
+
+    /// A grid with the cells in this set alive.
+    pub fn to_grid(&self) -> Grid {
+        self.cells.clone()
+    }
+
+    /// Whether `(x, y)` is in the set.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`.
+    #[inline]
+    pub fn test(&self, x: i32, y: i32) -> bool {
+        self.cells.get_wrapped(x, y).value > 0
+    }
+
+    /// Adds `(x, y)` to the set.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`.
+    #[inline]
+    pub fn set(&mut self, x: i32, y: i32) {
+        self.cells.set_wrapped(x, y, Cell::one());
+    }
+
+    /// Removes `(x, y)` from the set.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`.
+    #[inline]
+    pub fn clear(&mut self, x: i32, y: i32) {
+        self.cells.set_wrapped(x, y, Cell::zero());
+    }
+
+    /// Like [`BoolGrid::test`], without bounds checks, for use in hot loops.
+    ///
+    /// # Safety
+    /// `x` must lie in `0..width` and `y` in `0..height`.
+    #[inline]
+    pub unsafe fn test_unchecked(&self, x: i32, y: i32) -> bool {
+        self.cells.get(x, y).value > 0
+    }
+
+    /// Like [`BoolGrid::set`], without bounds checks, for use in hot loops.
+    ///
+    /// # Safety
+    /// `x` must lie in `0..width` and `y` in `0..height`.
+    #[inline]
+    pub unsafe fn set_unchecked(&mut self, x: i32, y: i32) {
+        self.cells.set(x, y, Cell::one());
+    }
+
+    /// The cells in the set, in row-major order.
+    pub fn iter(&self) -> AliveCells<'_> {
+        self.cells.alive_cells()
+    }
+
+    /// How many cells are in the set.
+    pub fn count(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// How many of each cell's neighbours in `neighbourhood` are in the set.
+    pub fn neighbour_counts(&self, neighbourhood: Neighbourhood) -> CountGrid {
+        CountGrid {
+            counts: self.cells.neighbours_in(neighbourhood),
+        }
+    }
+}
+
+impl std::fmt::Debug for BoolGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BoolGrid").field(&self.cells).finish()
+    }
+}
+
+/// Adds the cells of another set, which must be on a grid of the same size.
+impl BitOrAssign<&BoolGrid> for BoolGrid {
+    fn bitor_assign(&mut self, rhs: &BoolGrid) {
+        self.cells.or_assign(&rhs.cells);
+    }
+}
+
+/// Keeps only the cells also in another set.
+impl BitAndAssign<&BoolGrid> for BoolGrid {
+    fn bitand_assign(&mut self, rhs: &BoolGrid) {
+        self.cells.and_assign(&rhs.cells);
+    }
+}
+
+/// Removes the cells of another set.
+impl SubAssign<&BoolGrid> for BoolGrid {
+    fn sub_assign(&mut self, rhs: &BoolGrid) {
+        self.cells.and_assign(&rhs.cells.not());
+    }
+}
+
+/// Every cell not in the set.
+impl Not for &BoolGrid {
+    type Output = BoolGrid;
+
+    fn not(self) -> BoolGrid {
+        BoolGrid {
+            cells: self.cells.not(),
+        }
+    }
+}
+
+/// A count for each cell, such as how many of its neighbours are guessed alive. Counts are never negative.
+/// The default is all zeros on a [`SIZE`](crate::grid::SIZE)×[`SIZE`](crate::grid::SIZE) grid.
+///
+/// ```
+/// use undeath::typed_grid::CountGrid;
+///
+/// let mut counts = CountGrid::default();
+/// assert_eq!(counts.checked_add(3, 4, 2), Some(2));
+/// // Counts cannot go below zero.
+/// assert_eq!(counts.checked_add(3, 4, -3), None);
+/// assert_eq!(counts.saturating_add(3, 4, -3), 0);
+/// assert_eq!(counts.saturating_add(3, 4, i32::MAX), i32::MAX);
+/// assert!(counts.nonzero().test(3, 4));
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct CountGrid {
+    /// Every cell is at least zero.
+    counts: Grid,
+}
+
+impl CountGrid {
+    /// All zeros on a `width`×`height` grid.
+    ///
+    /// # Panics
+    /// As [`Grid::new`] does.
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            counts: Grid::new(width, height),
+        }
+    }
+
+    /// The width of the grid of counts.
+    #[inline]
+    pub fn width(&self) -> i32 {
+        self.counts.width()
+    }
+
+    /// The height of the grid of counts.
+    #[inline]
+    pub fn height(&self) -> i32 {
+        self.counts.height()
+    }
+
+    /// Sets every count to zero, on a `width`×`height` grid, reusing its memory where it can.
+    pub(crate) fn reset(&mut self, width: i32, height: i32) {
+        self.counts.reset(width, height);
+    }
+
+    /// The counts as a grid, for rendering as a heat map or passing to code that takes grids.
+    pub fn to_grid(&self) -> Grid {
+        self.counts.clone()
+    }
+
+    /// The set of cells whose count is not zero.
+    pub fn nonzero(&self) -> BoolGrid {
+        BoolGrid::from_grid(&self.counts)
+    }
+
+    /// The count at `(x, y)`.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`.
+    #[inline]
+    pub fn get(&self, x: i32, y: i32) -> i32 {
+        self.counts.get_wrapped(x, y).value
+    }
+
+    /// Sets the count at `(x, y)`.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`.
+    ///
+    /// # Panics
+    /// If `count` is negative.
+    pub fn set(&mut self, x: i32, y: i32, count: i32) {
+        assert!(count >= 0, "a count cannot be {count}");
+        self.counts.set_wrapped(x, y, Cell { value: count });
+    }
+
+    /// Adds `delta` to the count at `(x, y)`, returning the new count,
+    /// or leaves it alone and returns `None` if that would make it negative or overflow.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`.
+    pub fn checked_add(&mut self, x: i32, y: i32, delta: i32) -> Option<i32> {
+        let count = self
+            .get(x, y)
+            .checked_add(delta)
+            .filter(|&count| count >= 0)?;
+        self.set(x, y, count);
+        Some(count)
+    }
+
+    /// Adds `delta` to the count at `(x, y)`, stopping at zero and `i32::MAX`, and returns the new count.
+    /// Assumes `x` isn't less than or equal to `-width`, nor `y` to `-height`.
+    pub fn saturating_add(&mut self, x: i32, y: i32, delta: i32) -> i32 {
+        let count = self.get(x, y).saturating_add(delta).max(0);
+        self.set(x, y, count);
+        count
+    }
+
+    /// Like [`CountGrid::get`], without bounds checks, for use in hot loops.
+    ///
+    /// # Safety
+    /// `x` must lie in `0..width` and `y` in `0..height`.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, x: i32, y: i32) -> i32 {
+        self.counts.get(x, y).value
+    }
+
+    /// Adds one to the count at `(x, y)` without bounds checks, for use in hot loops.
+    ///
+    /// # Safety
+    /// `x` must lie in `0..width` and `y` in `0..height`.
+    #[inline]
+    pub unsafe fn increment_unchecked(&mut self, x: i32, y: i32) {
+        // O(1).
+        checked_assert!(
+            self.counts.get(x, y).value < i32::MAX,
+            "the count at ({x}, {y}) overflowed"
+        );
+        self.counts.set_add(x, y, Cell::one());
+    }
+}
+
+impl std::fmt::Debug for CountGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CountGrid").field(&self.counts).finish()
+    }
+}
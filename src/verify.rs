@@ -0,0 +1,198 @@
+//! Independent checks of claims about how grids evolve: chains of predecessors, single predecessors, and periods.
+//!
+//! These only step grids forwards, so they can validate results from the solver or from anywhere else.
+
+use std::fmt::Display;
+
+use crate::{
+    grid::Grid,
+    string::{hconcat_all, highlight_diff},
+};
+
+/// Why a claim checked by this module is false.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChainError {
+    /// Frame `index` does not step to the frame after it.
+    /// Only the cells that are checked are compared; `expected` and `actual` are restricted to them.
+    Mismatch {
+        index: usize,
+        expected: Box<Grid>,
+        actual: Box<Grid>,
+    },
+    /// After `generations` steps, the grid is `actual` rather than the claimed `expected`.
+    Evolution {
+        generations: usize,
+        expected: Box<Grid>,
+        actual: Box<Grid>,
+    },
+    /// The grid returns to itself after `period` steps, fewer than claimed.
+    ShorterPeriod { claimed: usize, period: usize },
+    /// The grid has not returned to itself after the claimed number of steps.
+    NotPeriodic { claimed: usize },
+    /// Frame `index` has the width and height `size`, but the frame after it, or the grid it is checked against, has `other`.
+    SizeMismatch {
+        index: usize,
+        size: (i32, i32),
+        other: (i32, i32),
+    },
+}
+
+impl Display for ChainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChainError::Mismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "frame {index} does not step to frame {} on {} cells; expected, then stepped:\n{}",
+                index + 1,
+                expected.xor(actual).alive_cells().count(),
+                render_mismatch(expected, actual)
+            ),
+            ChainError::Evolution {
+                generations,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "after {generations} generations, the grid differs from the claimed one on {} cells; claimed, then evolved:\n{}",
+                expected.xor(actual).alive_cells().count(),
+                render_mismatch(expected, actual)
+            ),
+            ChainError::ShorterPeriod { claimed, period } => {
+                write!(f, "claimed period {claimed}, but the period is {period}")
+            }
+            ChainError::NotPeriodic { claimed } => {
+                write!(f, "the grid does not repeat after {claimed} steps")
+            }
+            ChainError::SizeMismatch { index, size, other } => write!(
+                f,
+                "frame {index} is {}×{}, but is checked against a {}×{} grid",
+                size.0, size.1, other.0, other.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+/// `expected` and `actual` side by side, then `actual` again with the cells that differ from `expected` highlighted,
+/// for the messages about a grid that is not what it should be.
+pub(crate) fn render_mismatch(expected: &Grid, actual: &Grid) -> String {
+    let (expected, actual) = (expected.render(), actual.render());
+    format!(
+        "{}\n{}",
+        hconcat_all(&[&expected, &actual], "   "),
+        highlight_diff(&expected, &actual)
+    )
+}
+
+/// Checks that each frame steps to the next, reporting the first that does not.
+/// Every frame must be the same size.
+///
+/// ```
+/// use undeath::{verify, Grid};
+///
+/// let horizontal = Grid::from_text("\n\n\n..###");
+/// let vertical = Grid::from_text("\n\n...#\n...#\n...#");
+/// assert!(verify::verify_chain(&[horizontal.clone(), vertical.clone(), horizontal.clone()]).is_ok());
+///
+/// let error = verify::verify_chain(&[horizontal.clone(), vertical, Grid::default()]).unwrap_err();
+/// assert!(matches!(error, verify::ChainError::Mismatch { index: 1, .. }));
+/// ```
+pub fn verify_chain(frames: &[Grid]) -> Result<(), ChainError> {
+    for (index, pair) in frames.windows(2).enumerate() {
+        let everything = Grid::new(pair[1].width(), pair[1].height()).not();
+        check_step(index, &pair[0], &pair[1], &everything)?;
+    }
+    Ok(())
+}
+
+/// Checks that `prev` steps to `next` on every cell where `mask` is positive.
+/// The other cells of `next` are don't-cares.
+pub fn verify_predecessor(prev: &Grid, next: &Grid, mask: &Grid) -> Result<(), ChainError> {
+    check_step(0, prev, next, mask)
+}
+
+/// Checks that `prev` becomes `next` after exactly `generations` steps.
+///
+/// ```
+/// use undeath::{verify, Grid};
+///
+/// let glider = Grid::from_text(".#\n..#\n###");
+/// let moved = Grid::from_text("\n..#\n...#\n.###");
+/// assert!(verify::verify_generations(&glider, &moved, 4).is_ok());
+/// assert!(matches!(
+///     verify::verify_generations(&glider, &moved, 3),
+///     Err(verify::ChainError::Evolution { generations: 3, .. })
+/// ));
+/// ```
+pub fn verify_generations(prev: &Grid, next: &Grid, generations: usize) -> Result<(), ChainError> {
+    check_size(0, prev, next)?;
+    let mut evolved = prev.clone();
+    for _ in 0..generations {
+        evolved.step();
+    }
+    if evolved == *next {
+        Ok(())
+    } else {
+        Err(ChainError::Evolution {
+            generations,
+            expected: Box::new(next.clone()),
+            actual: Box::new(evolved),
+        })
+    }
+}
+
+/// Checks that `grid` returns to itself after exactly `period` steps, and not before.
+/// A still life has period one.
+pub fn verify_period(grid: &Grid, period: usize) -> Result<(), ChainError> {
+    let mut current = grid.clone();
+    for steps in 1..=period {
+        current.step();
+        if current == *grid {
+            return if steps == period {
+                Ok(())
+            } else {
+                Err(ChainError::ShorterPeriod {
+                    claimed: period,
+                    period: steps,
+                })
+            };
+        }
+    }
+    Err(ChainError::NotPeriodic { claimed: period })
+}
+
+/// Fails with [`ChainError::SizeMismatch`] unless `other` is the same size as frame `index`, `grid`.
+fn check_size(index: usize, grid: &Grid, other: &Grid) -> Result<(), ChainError> {
+    if grid.same_size(other) {
+        Ok(())
+    } else {
+        Err(ChainError::SizeMismatch {
+            index,
+            size: (grid.width(), grid.height()),
+            other: (other.width(), other.height()),
+        })
+    }
+}
+
+fn check_step(index: usize, prev: &Grid, next: &Grid, mask: &Grid) -> Result<(), ChainError> {
+    check_size(index, prev, next)?;
+    check_size(index, prev, mask)?;
+    let mut stepped = prev.clone();
+    stepped.step();
+    let (expected, actual) = (next.and(mask), stepped.and(mask));
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(ChainError::Mismatch {
+            index,
+            expected: Box::new(expected),
+            actual: Box::new(actual),
+        })
+    }
+}
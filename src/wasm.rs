@@ -0,0 +1,103 @@
+//! JavaScript bindings, for running the searcher in a browser.
+//!
+//! The search runs in bounded slices, so that a page can call [`Search::step`]
+//! from `requestAnimationFrame` without blocking its main thread.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    grid::Grid,
+    searcher::{SearchResult, Searcher},
+};
+
+#[wasm_bindgen]
+pub struct Search {
+    searcher: Searcher,
+    width: i32,
+    height: i32,
+    solutions: Vec<Grid>,
+    exhausted: bool,
+}
+
+#[wasm_bindgen]
+impl Search {
+    /// Starts searching for predecessors of a pattern in the `.gol` text format.
+    #[wasm_bindgen(constructor)]
+    pub fn new(pattern: &str) -> Search {
+        let grid = Grid::from_text(pattern);
+        Search {
+            width: grid.width(),
+            height: grid.height(),
+            searcher: Searcher::new(grid),
+            solutions: Vec::new(),
+            exhausted: false,
+        }
+    }
+
+    /// The width of the grid, which is large enough for the pattern.
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// The height of the grid, which is large enough for the pattern.
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Runs the search for at most `max_iterations` iterations.
+    /// Returns true if a new solution was found.
+    pub fn step(&mut self, max_iterations: usize) -> Result<bool, JsError> {
+        if self.exhausted {
+            return Ok(false);
+        }
+        match self.searcher.search(max_iterations) {
+            Ok(SearchResult::Found(grid, _)) => {
+                self.solutions.push(grid);
+                Ok(true)
+            }
+            Ok(SearchResult::Working(_)) => Ok(false),
+            // The searcher is depth-first, so it never gives up, but searching again would find nothing either way.
+            Ok(SearchResult::Unsatisfiable | SearchResult::Inconclusive) => {
+                self.exhausted = true;
+                Ok(false)
+            }
+            Err(error) => Err(JsError::new(&error.to_string())),
+        }
+    }
+
+    /// True once every predecessor has been found.
+    pub fn exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// The guess the searcher is currently refining, as one byte per cell in row-major order:
+    /// `0` for dead, `1` for alive, and `2` for undecided.
+    pub fn current_guess(&self) -> Vec<u8> {
+        let Some(guess) = self.searcher.current_guess() else {
+            return Vec::new();
+        };
+        let (alive, dead) = (guess.alive(), guess.dead());
+        let width = self.width;
+        (0..self.height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                if alive.get_wrapped(x, y).value > 0 {
+                    1
+                } else if dead.get_wrapped(x, y).value > 0 {
+                    0
+                } else {
+                    2
+                }
+            })
+            .collect()
+    }
+
+    pub fn solution_count(&self) -> usize {
+        self.solutions.len()
+    }
+
+    /// The solution with the given index, as an RLE pattern that can be pasted into Golly.
+    pub fn solution_rle(&self, index: usize) -> Option<String> {
+        self.solutions.get(index).map(Grid::to_rle)
+    }
+}
@@ -0,0 +1,134 @@
+//! Naming patterns by apgcode, checked against the codes Catagolue gives well-known objects.
+
+use undeath::{
+    grid::{Symmetry, MAX_SIZE},
+    Error, Grid,
+};
+
+/// Well-known objects, with their apgcodes on Catagolue.
+const KNOWN: [(&str, &str); 9] = [
+    ("xs4_33", "##\n##"),
+    ("xs6_696", ".##\n#..#\n.##"),
+    ("xs7_2596", ".##\n#..#\n.#.#\n..#"),
+    ("xs5_253", "##\n#.#\n.#"),
+    ("xs8_6996", ".##\n#..#\n#..#\n.##"),
+    ("xp2_7", "###"),
+    ("xp2_7e", ".###\n###"),
+    ("xq4_153", ".#\n..#\n###"),
+    // Catagolue names spaceships by their phase with the least code, which for this one is not the usual picture.
+    ("xq4_6frc", ".##\n###\n##.#\n.###\n..#"),
+];
+
+/// The part of an apgcode after the underscore.
+fn wechsler(code: &str) -> &str {
+    code.split_once('_').unwrap().1
+}
+
+#[test]
+fn encodes_known_objects_in_every_orientation() {
+    for (code, text) in KNOWN {
+        let grid = Grid::from_text(text);
+        for symmetry in Symmetry::ALL {
+            assert_eq!(
+                grid.transformed(symmetry).to_apgcode(),
+                wechsler(code),
+                "{text}"
+            );
+        }
+    }
+}
+
+#[test]
+fn decodes_known_objects() {
+    for (code, text) in KNOWN {
+        let grid = Grid::from_apgcode(code).unwrap();
+        assert!(
+            grid.canonical_symmetric() == Grid::from_text(text).canonical_symmetric(),
+            "{code}"
+        );
+        assert!(
+            Grid::from_apgcode(wechsler(code)).unwrap() == grid,
+            "{code}"
+        );
+        assert_eq!(grid.to_apgcode(), wechsler(code));
+    }
+}
+
+#[test]
+fn runs_of_empty_columns_and_strips() {
+    // Two cells 45 columns apart have 39 empty columns, a `yz`, then 5, a `y1`, between them.
+    let grid = Grid::from_apgcode("1yzy11zz1").unwrap();
+    assert_eq!((grid.width(), grid.height()), (46, 11));
+    assert_eq!(
+        grid.alive_cells().collect::<Vec<_>>(),
+        [(0, 0), (45, 0), (0, 10)]
+    );
+    assert_eq!(
+        Grid::from_apgcode("1w1x1").unwrap(),
+        Grid::from_text("#..#...#")
+    );
+    assert_eq!(Grid::from_apgcode("xs0_0").unwrap(), Grid::default());
+    assert_eq!(Grid::default().to_apgcode(), "0");
+}
+
+#[test]
+fn rejects_malformed_codes() {
+    for (code, column) in [
+        ("xs4_3#", 6),
+        ("xp2_7y", 6),
+        ("xq4_15A", 7),
+        ("", 1),
+        ("xs4_", 5),
+    ] {
+        match Grid::from_apgcode(code) {
+            Err(Error::Parse {
+                line: 1,
+                column: found,
+                ..
+            }) => assert_eq!(found, column, "{code}"),
+            _ => panic!("expected a parse error for {code:?}"),
+        }
+    }
+    assert!(matches!(
+        Grid::from_apgcode("yl144_1_16_afb5f3db909e60548f086e22ee3353ac"),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn patterns_larger_than_the_largest_grid_are_rejected() {
+    let wide = format!("{}1", "yz".repeat(MAX_SIZE as usize / 39 + 1));
+    assert!(matches!(
+        Grid::from_apgcode(&wide),
+        Err(Error::InvalidInput(_))
+    ));
+    let tall = format!("{}1", "z".repeat(MAX_SIZE as usize / 5 + 1));
+    assert!(matches!(
+        Grid::from_apgcode(&tall),
+        Err(Error::InvalidInput(_))
+    ));
+    // Empty strips and columns past the edge, with no cells in them, are harmless.
+    let trailing = format!("1{}", "z".repeat(MAX_SIZE as usize));
+    assert!(Grid::from_apgcode(&trailing).unwrap() == Grid::from_text("#"));
+}
+
+#[test]
+fn larger_objects_round_trip() {
+    // The pulsar, in the phase Catagolue names it by, is too large for the default grid.
+    let code = "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401";
+    let pulsar = Grid::from_apgcode(code).unwrap();
+    assert_eq!((pulsar.width(), pulsar.height()), (13, 13));
+    // With no empty row or column to spare around it, the torus could cut the pattern elsewhere,
+    // so it needs a larger grid to be seen as Catagolue sees it on the plane.
+    let mut stepped = Grid::new(20, 20);
+    for (x, y) in pulsar.alive_cells() {
+        stepped.set_wrapped(x, y, undeath::Cell::one());
+    }
+    assert_eq!(stepped.to_apgcode(), wechsler(code));
+    let start = stepped.clone();
+    stepped.step();
+    assert!(stepped != start);
+    stepped.step();
+    stepped.step();
+    assert!(stepped == start);
+}
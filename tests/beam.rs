@@ -0,0 +1,80 @@
+//! Beam search keeps the best few guesses at each depth, and gives up rather than proving anything.
+
+use undeath::{searcher::Strategy, Grid, Guess, SearchResult, Searcher};
+
+fn glider() -> Grid {
+    Grid::from_text("\n.#\n..#\n###")
+}
+
+/// Whether every cell `coarse` has decided is decided the same way in `fine`.
+fn refines(fine: &Guess, coarse: &Guess) -> bool {
+    let mut alive = coarse.alive();
+    alive.sub_assign_saturating(&fine.alive());
+    let mut dead = coarse.dead();
+    dead.sub_assign_saturating(&fine.dead());
+    alive == Grid::default() && dead == Grid::default()
+}
+
+/// With width 1, beam search is greedy: each branch keeps only the better refinement,
+/// so the guesses it considers form a single chain, one decision deeper each time, and it never backtracks.
+#[test]
+fn width_one_is_greedy() {
+    let mut searcher = Searcher::new(glider()).strategy(Strategy::Beam { width: 1 });
+    let mut previous = searcher.current_guess().unwrap();
+    loop {
+        let result = searcher.search(1).unwrap();
+        match result {
+            SearchResult::Working(_) => {
+                // If both refinements were contradictory, the beam has died, and searching again gives up.
+                let Some(current) = searcher.current_guess() else {
+                    assert!(matches!(
+                        searcher.search(1).unwrap(),
+                        SearchResult::Inconclusive
+                    ));
+                    return;
+                };
+                assert!(refines(&current, &previous));
+                assert!(current.decided_count() > previous.decided_count());
+                previous = current;
+            }
+            SearchResult::Found(grid, _) => {
+                let mut next = grid;
+                next.step();
+                assert!(next == glider());
+                return;
+            }
+            SearchResult::Inconclusive => return,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn modest_width_finds_a_glider_predecessor() {
+    let mut searcher = Searcher::new(glider()).strategy(Strategy::Beam { width: 32 });
+    match searcher.search(1_000_000).unwrap() {
+        SearchResult::Found(grid, _) => {
+            let mut next = grid;
+            next.step();
+            assert!(next == glider());
+        }
+        _ => panic!("beam search of width 32 should find a glider predecessor"),
+    }
+}
+
+/// Once the beam has dropped a guess, running out of guesses is inconclusive rather than unsatisfiable.
+#[test]
+fn exhausted_beam_is_inconclusive() {
+    let mut searcher = Searcher::new(glider()).strategy(Strategy::Beam { width: 1 });
+    let result = loop {
+        match searcher.search(1_000_000).unwrap() {
+            SearchResult::Found(_, _) => {}
+            result => break result,
+        }
+    };
+    assert!(matches!(result, SearchResult::Inconclusive));
+    assert!(matches!(
+        searcher.search(1_000_000).unwrap(),
+        SearchResult::Inconclusive
+    ));
+}
@@ -0,0 +1,92 @@
+//! Golden tests for how the search proceeds, not only what it finds.
+//!
+//! Each fixture is searched to a few fixed iteration checkpoints, and the [`Searcher::behavior_snapshot`]
+//! at each is compared with a file in `tests/snapshots`. A change to a heuristic that alters the search
+//! shows up as a diff in these files, which is what to review. Regenerate them with
+//!
+//! ```text
+//! UPDATE_SNAPSHOTS=1 cargo test --test behavior
+//! ```
+
+use std::path::PathBuf;
+
+use undeath::{
+    rng::{RandomSource, Rng},
+    Cell, Grid, SearchResult, Searcher,
+};
+
+/// The total iterations after which to take each snapshot.
+const CHECKPOINTS: [usize; 4] = [1, 10, 100, 1_000];
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "could not read {}: {e}\nrun with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        )
+    });
+    assert!(
+        actual == expected,
+        "{name} no longer matches its snapshot\n--- expected\n{expected}\n--- actual\n{actual}\n\
+         run with UPDATE_SNAPSHOTS=1 if the change is intended"
+    );
+}
+
+/// Searches to each checkpoint in turn, recording a snapshot and how many predecessors were found by then.
+fn behavior(mut searcher: Searcher) -> String {
+    let mut output = String::new();
+    let (mut total, mut found) = (0, 0);
+    for checkpoint in CHECKPOINTS {
+        while total < checkpoint {
+            match searcher.search(checkpoint - total).unwrap() {
+                SearchResult::Found(_, iterations) => {
+                    total += iterations;
+                    found += 1;
+                }
+                SearchResult::Working(iterations) => total += iterations,
+                _ => {
+                    output += &format!("stopped after {total} iterations\n");
+                    return output + &searcher.behavior_snapshot();
+                }
+            }
+        }
+        output += &format!("after {total} iterations, {found} found\n");
+        output += &searcher.behavior_snapshot();
+        output += "\n";
+    }
+    output
+}
+
+/// A random 4×4 soup, seeded so that it is always the same, stepped once so that it has a predecessor.
+fn soup() -> Grid {
+    let mut rng = Rng::new(496);
+    let mut soup = Grid::default();
+    for y in 2..6 {
+        for x in 2..6 {
+            if rng.next_u64() & 1 == 1 {
+                soup.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    soup.step();
+    soup
+}
+
+#[test]
+fn glider_gol() {
+    let glider =
+        Grid::from_file(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("glider.gol")).unwrap();
+    assert_snapshot("behavior_glider", &behavior(Searcher::new(glider)));
+}
+
+#[test]
+fn random_soup() {
+    assert_snapshot("behavior_soup", &behavior(Searcher::new(soup())));
+}
@@ -0,0 +1,105 @@
+//! Adaptive slicing sizes batches of iterations from how long recent ones took, as measured by an injected clock.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use undeath::{
+    cadence::{Cadence, Clock},
+    rng::{RandomSource, Rng},
+    Cell, Grid, SearchResult, Searcher,
+};
+
+/// A clock that moves on by a settable step each time it is read, so that every batch appears to take that long.
+#[derive(Clone, Default)]
+struct FakeClock {
+    now: Arc<AtomicU64>,
+    step: Arc<AtomicU64>,
+}
+
+impl FakeClock {
+    fn set_step(&self, step: Duration) {
+        self.step.store(step.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        let step = self.step.load(Ordering::SeqCst);
+        Duration::from_nanos(self.now.fetch_add(step, Ordering::SeqCst) + step)
+    }
+}
+
+const TARGET: Duration = Duration::from_millis(100);
+
+#[test]
+fn slices_follow_the_cost_of_iterations() {
+    let mut cadence = Cadence::new(TARGET, FakeClock::default());
+    assert_eq!(cadence.slice(), Cadence::INITIAL_SLICE);
+    // Cheap iterations: ten microseconds each.
+    for _ in 0..30 {
+        let slice = cadence.slice();
+        cadence.record(slice, Duration::from_micros(10 * slice as u64));
+    }
+    assert!((9_900..=10_000).contains(&cadence.slice()));
+    // A hundred times more expensive.
+    for _ in 0..30 {
+        let slice = cadence.slice();
+        cadence.record(slice, Duration::from_millis(slice as u64));
+    }
+    assert!((99..=101).contains(&cadence.slice()));
+}
+
+#[test]
+fn one_fast_batch_cannot_blow_up_the_next() {
+    let mut cadence = Cadence::new(TARGET, FakeClock::default());
+    cadence.record(Cadence::INITIAL_SLICE, Duration::from_nanos(1));
+    assert_eq!(cadence.slice(), 4 * Cadence::INITIAL_SLICE);
+    cadence.record(cadence.slice(), Duration::ZERO);
+    assert_eq!(cadence.slice(), 8 * Cadence::INITIAL_SLICE);
+}
+
+/// A random half-full soup, stepped once, whose predecessors depth-first search takes millions of iterations to find,
+/// so that every slice runs to the end.
+fn dense_target() -> Grid {
+    let mut rng = Rng::new(1);
+    let mut grid = Grid::default();
+    for y in 0..8 {
+        for x in 0..8 {
+            if rng.gen_bool(0.5) {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    grid.step();
+    grid
+}
+
+fn slices(searcher: &mut Searcher, count: usize) -> Vec<usize> {
+    (0..count)
+        .map(|_| match searcher.search_slice().unwrap() {
+            SearchResult::Working(iterations) => iterations,
+            _ => panic!("the search should still be working"),
+        })
+        .collect()
+}
+
+#[test]
+fn searcher_adapts_when_slices_slow_down() {
+    let clock = FakeClock::default();
+    let mut searcher = Searcher::new(dense_target()).cadence(Cadence::new(TARGET, clock.clone()));
+    // Every batch seems to take a tenth of the target, so slices grow, as fast as they are allowed to.
+    clock.set_step(TARGET / 10);
+    let growing = slices(&mut searcher, 4);
+    assert!(growing.windows(2).all(|pair| pair[1] >= pair[0]));
+    assert!(growing[3] > growing[0]);
+    // Now every batch seems to take ten times the target, so slices shrink.
+    clock.set_step(TARGET * 10);
+    let shrinking = slices(&mut searcher, 4);
+    assert!(shrinking.windows(2).all(|pair| pair[1] <= pair[0]));
+    assert!(shrinking[3] < growing[3]);
+}
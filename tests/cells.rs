@@ -0,0 +1,90 @@
+//! Reading patterns in LifeWiki's plaintext `.cells` format, with their `!` comments.
+
+use undeath::{
+    grid::{LoadError, ParseGridError, SIZE},
+    Error, Grid,
+};
+
+/// The Gosper glider gun as LifeWiki publishes it.
+const GOSPER_GUN: &str = "\
+!Name: Gosper glider gun
+!Author: Bill Gosper
+!The first known gun and the first known finite pattern with unbounded growth.
+!www.conwaylife.com/wiki/index.php?title=Gosper_glider_gun
+........................O
+......................O.O
+............OO......OO............OO
+...........O...O....OO............OO
+OO........O.....O...OO
+OO........O...O.OO....O.O
+..........O.....O.......O
+...........O...O
+............OO
+";
+
+#[test]
+fn comments_are_not_cells() {
+    let text = "!Name: Glider\n!A small spaceship.\n.O\n..O\nOOO\n";
+    let glider = Grid::from_cells(text).unwrap();
+    assert!(glider == Grid::from_text(".#\n..#\n###"));
+    // The `.gol` format reads the comments as alive cells, as it always has.
+    assert!(Grid::from_text(text) != glider);
+}
+
+#[test]
+fn larger_patterns_grow_the_grid() {
+    let gun = Grid::from_cells(GOSPER_GUN).unwrap();
+    assert_eq!((gun.width(), gun.height()), (36, 9));
+    assert_eq!(gun.alive_cells().count(), 36);
+    let small = Grid::from_cells("!Name: Block\r\nOO\r\nOO\r\n").unwrap();
+    assert_eq!((small.width(), small.height()), (SIZE, SIZE));
+    assert_eq!(small.alive_cells().count(), 4);
+}
+
+#[test]
+fn unexpected_characters_are_located() {
+    for (text, location) in [
+        ("!Name: Glider\n.O\n..O\nOO#\n", (4, 3)),
+        (".O\n..o\n", (2, 3)),
+        ("O O\n", (1, 2)),
+        // Comments only start at the beginning of a line.
+        ("OO\n !Name: Block\n", (2, 1)),
+    ] {
+        match Grid::from_cells(text) {
+            Err(Error::Parse { line, column, .. }) => {
+                assert_eq!((line, column), location, "{text}")
+            }
+            _ => panic!("expected a parse error for {text:?}"),
+        }
+    }
+}
+
+#[test]
+fn files_are_read_by_their_extension() {
+    let directory = std::env::temp_dir().join("undeath-cells-files");
+    std::fs::create_dir_all(&directory).unwrap();
+    let gun = Grid::from_cells(GOSPER_GUN).unwrap();
+    for name in ["gun.cells", "gun.CELLS"] {
+        let path = directory.join(name);
+        std::fs::write(&path, GOSPER_GUN).unwrap();
+        assert!(Grid::from_file(&path).unwrap() == gun);
+    }
+    let path = directory.join("broken.cells");
+    std::fs::write(&path, "!Name: Broken\n.O\n.*\n").unwrap();
+    assert!(matches!(
+        Grid::from_file(&path),
+        Err(LoadError::Parse {
+            source: ParseGridError::UnexpectedCharacter {
+                line: 3,
+                column: 2,
+                character: '*'
+            },
+            ..
+        })
+    ));
+    // Other extensions keep the permissive `.gol` format.
+    let path = directory.join("broken.gol");
+    std::fs::write(&path, "!Name: Broken\n.O\n.*\n").unwrap();
+    assert!(Grid::from_file(&path).is_ok());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,34 @@
+//! Runs a real search with every internal check enabled. Only built with `--features checked`.
+
+use undeath::{grid::NeighbourField, Grid, SearchResult, Searcher};
+
+#[test]
+fn glider_search_passes_checks() {
+    let glider = Grid::from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/glider.gol")).unwrap();
+    let mut searcher = Searcher::new(glider.clone());
+    let mut found = 0;
+    let mut iterations = 0;
+    while found < 10 && iterations < 1_000_000 {
+        match searcher.search(10_000).unwrap() {
+            SearchResult::Found(parent, n) => {
+                found += 1;
+                iterations += n;
+                let mut stepped = parent;
+                stepped.step();
+                assert_eq!(stepped, glider);
+            }
+            SearchResult::Working(n) => iterations += n,
+            SearchResult::Unsatisfiable => break,
+            _ => unreachable!(),
+        }
+    }
+    assert_eq!(found, 10);
+}
+
+#[test]
+fn neighbour_field_passes_checks() {
+    let mut field = NeighbourField::new(Grid::default());
+    for i in 0..200 {
+        field.toggle((i * 7) % 8, (i * 3) % 8);
+    }
+}
@@ -0,0 +1,795 @@
+//! The command-line driver's machine-readable output.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    process::{Command, Stdio},
+};
+
+use undeath::{grid::SIZE, trace::BinaryTrace, Cell, Grid, SearchResult, Searcher};
+
+/// Runs `undeath enumerate` on `pattern`, returning stdout.
+fn enumerate(name: &str, pattern: &str, args: &[&str]) -> String {
+    let path = std::env::temp_dir().join(format!("undeath-cli-{name}.gol"));
+    std::fs::write(&path, pattern).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .arg("enumerate")
+        .arg(&path)
+        .args(args)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Decodes the body of an RLE pattern.
+fn grid_from_rle(body: &str) -> Grid {
+    let mut grid = Grid::default();
+    let (mut x, mut y) = (0, 0);
+    let mut run = String::new();
+    for c in body.chars() {
+        if c.is_ascii_digit() {
+            run.push(c);
+            continue;
+        }
+        let length = run.parse().unwrap_or(1);
+        run.clear();
+        match c {
+            'b' => x += length,
+            'o' => {
+                for _ in 0..length {
+                    grid.set_wrapped(x, y, Cell::one());
+                    x += 1;
+                }
+            }
+            '$' => (x, y) = (0, y + length),
+            _ => {}
+        }
+    }
+    grid
+}
+
+/// Counts the alive cells in the body of an RLE pattern.
+fn alive_cells_in_rle(body: &str) -> usize {
+    let mut alive = 0;
+    let mut run = String::new();
+    for c in body.chars() {
+        if c.is_ascii_digit() {
+            run.push(c);
+            continue;
+        }
+        if c == 'o' {
+            alive += run.parse().unwrap_or(1);
+        }
+        run.clear();
+    }
+    alive
+}
+
+#[test]
+fn coords_parse_back_into_predecessors() {
+    let blinker = "\n\n\n..###";
+    let stdout = enumerate(
+        "coords",
+        blinker,
+        &[
+            "--output-format",
+            "coords",
+            "--count",
+            "3",
+            "--region",
+            "auto",
+        ],
+    );
+    let solutions = stdout.split("\n\n").collect::<Vec<_>>();
+    assert_eq!(solutions.len(), 3, "{stdout}");
+    for solution in solutions {
+        let mut grid = Grid::default();
+        for line in solution.lines() {
+            let (x, y) = line.split_once(' ').unwrap();
+            grid.set_wrapped(x.parse().unwrap(), y.parse().unwrap(), Cell::one());
+        }
+        grid.step();
+        assert!(grid == Grid::from_text(blinker), "{solution}");
+    }
+}
+
+#[test]
+fn rle_inline_prints_one_encoding_per_predecessor() {
+    let stdout = enumerate(
+        "rle",
+        "##\n##",
+        &["--output-format", "rle-inline", "--count", "2"],
+    );
+    let solutions = stdout.split("\n\n").collect::<Vec<_>>();
+    assert_eq!(solutions.len(), 2, "{stdout}");
+    for solution in solutions {
+        let lines = solution.lines().collect::<Vec<_>>();
+        assert!(lines.len() >= 3, "{solution}");
+        assert_eq!(lines[0], "#C generated by undeath");
+        assert!(lines[1].starts_with("x = "), "{solution}");
+        assert!(lines[1].ends_with(", rule = B3/S23"), "{solution}");
+        let body = lines[2..].concat();
+        assert!(body.ends_with('!'), "{solution}");
+        assert!(body
+            .chars()
+            .all(|c| c.is_ascii_digit() || "bo$!".contains(c)));
+        assert!(Grid::from_rle(solution).is_ok(), "{solution}");
+    }
+}
+
+#[test]
+fn ndjson_streams_one_object_per_predecessor_then_a_summary() {
+    let blinker = "\n\n\n..###";
+    let stdout = enumerate(
+        "ndjson",
+        blinker,
+        &[
+            "--output-format",
+            "ndjson",
+            "--count",
+            "4",
+            "--region",
+            "auto",
+            "--dedup",
+            "canonical",
+        ],
+    );
+    let objects = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    let (summary, predecessors) = objects.split_last().unwrap();
+    assert_eq!(summary["event"], "summary");
+    assert_eq!(summary["count"], predecessors.len());
+    assert_eq!(predecessors.len(), 4);
+    for (index, predecessor) in predecessors.iter().enumerate() {
+        assert_eq!(predecessor["event"], "predecessor");
+        // Indices count the predecessors written, after duplicates are dropped.
+        assert_eq!(predecessor["index"], index);
+        let rle = predecessor["rle"].as_str().unwrap();
+        assert!(rle.starts_with("x = "), "{rle}");
+        assert_eq!(
+            predecessor["population"],
+            alive_cells_in_rle(&rle.lines().skip(1).collect::<String>())
+        );
+        assert!(
+            predecessor["iterations"].as_u64().unwrap() <= summary["iterations"].as_u64().unwrap()
+        );
+    }
+}
+
+/// Records a search for the first predecessor of a blinker as a binary trace, returning its path and the predecessor.
+fn recorded_trace(name: &str) -> (std::path::PathBuf, Grid) {
+    let path = std::env::temp_dir().join(format!("undeath-cli-{name}.bin"));
+    let sink = BinaryTrace::new(BufWriter::new(File::create(&path).unwrap()));
+    let mut searcher = Searcher::new(Grid::from_text("\n\n\n..###")).trace_to(sink);
+    let predecessor = loop {
+        match searcher.search(1_000).unwrap() {
+            SearchResult::Found(predecessor, _) => break predecessor,
+            SearchResult::Working(_) => {}
+            _ => panic!("a blinker has a predecessor"),
+        }
+    };
+    // Dropping the searcher flushes the trace.
+    drop(searcher);
+    (path, predecessor)
+}
+
+/// Runs `undeath replay` with `args`, returning whether it succeeded, and stdout.
+fn replay(args: &[&std::ffi::OsStr]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .arg("replay")
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+/// Reads back the fully alive cells of the first frame drawn in `text`.
+fn alive_in_frame(text: &str) -> Grid {
+    let mut grid = Grid::default();
+    let rows = text.lines().filter_map(|line| line.split_once('│'));
+    for (y, (_, row)) in rows.enumerate() {
+        let row = row.trim_end_matches('│').chars().collect::<Vec<_>>();
+        for (x, pair) in row.chunks(2).enumerate() {
+            if pair == ['█', '█'] {
+                grid.set_wrapped(x as i32, y as i32, Cell::one());
+            }
+        }
+    }
+    grid
+}
+
+#[test]
+fn replay_ends_at_the_recorded_solution() {
+    let (path, predecessor) = recorded_trace("replay");
+    let (success, stdout) = replay(&[path.as_os_str()]);
+    assert!(success, "{stdout}");
+    // Without a terminal, only the last move is drawn, then the predecessor found.
+    assert_eq!(stdout.matches('┌').count(), 2, "{stdout}");
+    assert!(stdout.contains("found 1 predecessors"), "{stdout}");
+    let (_, found) = stdout.split_once("Found at move ").unwrap();
+    let (index, _) = found.split_once(':').unwrap();
+    assert!(alive_in_frame(found) == predecessor, "{stdout}");
+
+    // Jumping to that move shows the guess the search had just completed.
+    let (success, stdout) = replay(&[path.as_os_str(), "--at".as_ref(), index.as_ref()]);
+    assert!(success, "{stdout}");
+    assert!(stdout.contains("a predecessor is found"), "{stdout}");
+    assert!(alive_in_frame(&stdout) == predecessor, "{stdout}");
+
+    let (success, stdout) = replay(&[path.as_os_str(), "--at".as_ref(), "0".as_ref()]);
+    assert!(success, "{stdout}");
+    assert!(stdout.starts_with("Move 0 of "), "{stdout}");
+    assert!(alive_in_frame(&stdout) == Grid::default(), "{stdout}");
+
+    let (success, _) = replay(&[path.as_os_str(), "--at".as_ref(), "100000".as_ref()]);
+    assert!(!success);
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Runs `undeath --batch` with `args`, piping `input` to it, and returns the lines of stdout.
+fn batch(args: &[&str], input: &[u8]) -> Vec<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .args(["--batch", "--seed", "1"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(str::to_owned)
+        .collect()
+}
+
+#[test]
+fn batch_solves_plain_targets_in_order() {
+    // A full grid has no predecessor, an empty one is its own, and a blinker has many.
+    let full = "########\n".repeat(8);
+    let input = format!("{full}\n........\n\n........\n........\n........\n..###\n");
+    let records = batch(&[], input.as_bytes());
+    assert_eq!(records.len(), 3, "{records:?}");
+    let records = records
+        .iter()
+        .map(|record| serde_json::from_str::<serde_json::Value>(record).unwrap())
+        .collect::<Vec<_>>();
+    for (index, record) in records.iter().enumerate() {
+        assert_eq!(record["index"], index);
+    }
+    assert_eq!(records[0]["status"], "none");
+    assert_eq!(records[1]["status"], "found");
+    assert_eq!(records[1]["rle"], "!");
+    assert_eq!(records[2]["status"], "found");
+    let mut predecessor = grid_from_rle(records[2]["rle"].as_str().unwrap());
+    predecessor.step();
+    assert!(predecessor == Grid::from_text("\n\n\n..###"));
+}
+
+#[test]
+fn batch_reports_malformed_targets_and_carries_on() {
+    let input = "8o$8o$8o$8o$8o$8o$8o$8o!\n3q!\n#C A comment.\n3$2b3o!\n";
+    let records = batch(
+        &["--batch-format", "rle", "--batch-output", "compact"],
+        input.as_bytes(),
+    );
+    assert_eq!(records.len(), 3, "{records:?}");
+    assert_eq!(records[0], "none");
+    assert!(
+        records[1].starts_with("error parse error at line 2"),
+        "{}",
+        records[1]
+    );
+    let (status, body) = records[2].split_once(' ').unwrap();
+    assert_eq!(status, "found");
+    assert!(body.ends_with('!'));
+}
+
+/// Runs `undeath diff` on two patterns, returning the exit code and stdout.
+fn diff(name: &str, a: &str, b: &str, args: &[&str]) -> (Option<i32>, String) {
+    let paths = [("a", a), ("b", b)].map(|(which, pattern)| {
+        let path = std::env::temp_dir().join(format!("undeath-cli-diff-{name}-{which}.gol"));
+        std::fs::write(&path, pattern).unwrap();
+        path
+    });
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .arg("diff")
+        .args(&paths)
+        .args(args)
+        .output()
+        .unwrap();
+    for path in paths {
+        std::fs::remove_file(path).unwrap();
+    }
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn diff_of_identical_patterns_succeeds() {
+    let glider = ".#\n..#\n###";
+    let (code, stdout) = diff("identical", glider, glider, &[]);
+    assert_eq!(code, Some(0), "{stdout}");
+    assert!(stdout.contains("The patterns are the same."), "{stdout}");
+}
+
+#[test]
+fn diff_lists_the_cells_that_differ() {
+    let (code, stdout) = diff("different", "\n\n\n..###", "\n\n..#\n..#\n..#", &[]);
+    assert_eq!(code, Some(1), "{stdout}");
+    assert!(
+        stdout.contains("4 cells differ: (2, 2), (3, 3), (4, 3), (2, 4)."),
+        "{stdout}"
+    );
+    // Without a terminal, differences are bracketed rather than coloured.
+    assert!(stdout.contains('['), "{stdout}");
+    assert!(!stdout.contains('\x1b'), "{stdout}");
+
+    // Aligning compares shapes wherever they are.
+    let (code, stdout) = diff(
+        "aligned",
+        "\n\n\n..###",
+        "\n.....###",
+        &["--align", "center"],
+    );
+    assert_eq!(code, Some(0), "{stdout}");
+}
+
+#[test]
+fn diff_as_previous_checks_for_a_predecessor() {
+    let blinker = "\n\n\n..###";
+    let parent = Grid::from_text(blinker)
+        .predecessor()
+        .unwrap()
+        .found()
+        .unwrap();
+    let parent = (0..SIZE)
+        .map(|y| {
+            (0..SIZE)
+                .map(|x| {
+                    if parent.get_wrapped(x, y).value > 0 {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let (code, stdout) = diff("parent", &parent, blinker, &["--as-previous"]);
+    assert_eq!(code, Some(0), "{stdout}");
+    assert!(stdout.contains("is a predecessor of"), "{stdout}");
+    // A blinker is not its own predecessor, since it alternates.
+    let (code, stdout) = diff("not-parent", blinker, blinker, &["--as-previous"]);
+    assert_eq!(code, Some(1), "{stdout}");
+    assert!(stdout.contains("4 cells differ"), "{stdout}");
+}
+
+/// Runs `undeath verify` with `args`, returning the exit code, stdout and stderr.
+fn verify(args: &[&std::ffi::OsStr]) -> (Option<i32>, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .arg("verify")
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+/// Writes each pattern to its own file, named by its index, in a fresh directory named after `name`,
+/// returning the directory.
+fn pattern_dir(name: &str, patterns: &[&str]) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("undeath-cli-{name}"));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir(&dir).unwrap();
+    for (index, pattern) in patterns.iter().enumerate() {
+        std::fs::write(dir.join(format!("{index:02}.gol")), pattern).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn verify_accepts_a_correct_claim() {
+    // A glider moves one cell diagonally every four generations.
+    let dir = pattern_dir("verify-correct", &[".#\n..#\n###", "\n..#\n...#\n.###"]);
+    let args = [
+        dir.join("00.gol").into_os_string(),
+        dir.join("01.gol").into_os_string(),
+        "--generations".into(),
+        "4".into(),
+    ];
+    let args = args.iter().map(|arg| arg.as_os_str()).collect::<Vec<_>>();
+    let (code, stdout, _) = verify(&args);
+    assert_eq!(code, Some(0), "{stdout}");
+    assert!(stdout.contains("The claim holds."), "{stdout}");
+
+    let (code, stdout, _) = verify(&args[..2]);
+    assert_eq!(code, Some(1), "{stdout}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn verify_rejects_a_claim_off_by_one_cell() {
+    let dir = pattern_dir(
+        "verify-off-by-one",
+        &["\n\n\n..###", "\n\n...#\n...#\n...##"],
+    );
+    let (code, stdout, _) = verify(&[
+        dir.join("00.gol").as_os_str(),
+        dir.join("01.gol").as_os_str(),
+    ]);
+    assert_eq!(code, Some(1), "{stdout}");
+    assert!(
+        stdout.contains("after 1 generations, the grid differs from the claimed one on 1 cells"),
+        "{stdout}"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn verify_refuses_other_rules() {
+    let dir = pattern_dir("verify-rule", &["\n\n\n..###", "\n\n...#\n...#\n...#"]);
+    let (code, _, stderr) = verify(&[
+        dir.join("00.gol").as_os_str(),
+        dir.join("01.gol").as_os_str(),
+        "--rule".as_ref(),
+        "B36/S23".as_ref(),
+    ]);
+    assert_ne!(code, Some(0));
+    assert_ne!(code, Some(1));
+    assert!(
+        stderr.contains("only Life, B3/S23, is supported"),
+        "{stderr}"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn verify_checks_a_chain_in_a_directory() {
+    let horizontal = "\n\n\n..###";
+    let vertical = "\n\n...#\n...#\n...#";
+    let dir = pattern_dir(
+        "verify-chain",
+        &[horizontal, vertical, horizontal, vertical],
+    );
+    let (code, stdout, _) = verify(&[dir.as_os_str()]);
+    assert_eq!(code, Some(0), "{stdout}");
+    assert_eq!(stdout.matches("Frame ").count(), 4, "{stdout}");
+
+    std::fs::write(dir.join("02.gol"), vertical).unwrap();
+    let (code, stdout, _) = verify(&[dir.as_os_str()]);
+    assert_eq!(code, Some(1), "{stdout}");
+    assert!(
+        stdout.contains("frame 1 does not step to frame 2"),
+        "{stdout}"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn canon_writes_orientations_of_a_glider_identically() {
+    let dir = pattern_dir(
+        "canon",
+        &[
+            ".#\n..#\n###",
+            // Moved so that it wraps around the edges.
+            "#.....##\n\n\n\n\n\n.......#\n#",
+            // Turned a quarter clockwise.
+            "\n\n\n....#\n....#.#\n....##",
+            // Reflected left to right.
+            "\n.....#\n....#\n....###",
+        ],
+    );
+    let files = (0..4)
+        .map(|index| dir.join(format!("{index:02}.gol")))
+        .collect::<Vec<_>>();
+    let canon = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_undeath"))
+            .arg("canon")
+            .args(&files)
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    let output = canon(&["--check"]);
+    assert_eq!(output.status.code(), Some(1));
+    let output = canon(&["--in-place"]);
+    assert!(output.status.success());
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(report.lines().count(), 4, "{report}");
+    assert_eq!(report.matches("the same pattern as").count(), 3, "{report}");
+    let contents = files
+        .iter()
+        .map(|file| std::fs::read(file).unwrap())
+        .collect::<Vec<_>>();
+    assert!(contents.iter().all(|content| *content == contents[0]));
+    let canonical = Grid::from_bytes(&contents[0]).unwrap();
+    assert!(
+        canonical.canonical_symmetric() == Grid::from_text(".#\n..#\n###").canonical_symmetric()
+    );
+
+    let output = canon(&["--check"]);
+    assert!(output.status.success());
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(report.matches("already canonical").count(), 4, "{report}");
+
+    // Without --in-place, the patterns go to stdout and the report to stderr.
+    let output = canon(&["--format", "rle"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // Each is cropped to the glider's bounding box.
+    assert_eq!(stdout.matches("x = 3, y = 3").count(), 4, "{stdout}");
+    assert_eq!(String::from_utf8(output.stderr).unwrap().lines().count(), 4);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn canon_fingerprints_tell_apart_distant_cells_sizes_and_topologies() {
+    let row = |x: usize| format!("{}#{}", ".".repeat(x), ".".repeat(299 - x));
+    let dir = pattern_dir(
+        "canon-fingerprints",
+        &[
+            &row(0),
+            // 256 columns along, which only the low byte of each coordinate cannot tell apart.
+            &row(256),
+            &format!("!size 300x3 plane\n{}", row(0)),
+            &format!("!size 300x4\n{}", row(0)),
+        ],
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .arg("canon")
+        .args((0..4).map(|index| dir.join(format!("{index:02}.gol"))))
+        .arg("--check")
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let report = String::from_utf8(output.stdout).unwrap();
+    let before = report
+        .lines()
+        .map(|line| {
+            line.split(": ")
+                .nth(1)
+                .unwrap()
+                .split(" → ")
+                .next()
+                .unwrap()
+        })
+        .collect::<std::collections::BTreeSet<_>>();
+    assert_eq!(before.len(), 4, "{report}");
+}
+
+/// Compares `actual` with `tests/snapshots/{name}.txt`, as the snapshot tests do.
+/// Regenerate the files with `UPDATE_SNAPSHOTS=1 cargo test --test cli`.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        actual == expected,
+        "{name} no longer matches its snapshot\n--- expected\n{expected}\n--- actual\n{actual}"
+    );
+}
+
+/// Runs `undeath info` on a pattern from the corpus, returning stdout.
+fn info(pattern: &str, args: &[&str]) -> String {
+    let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/corpus")
+        .join(format!("{pattern}.gol"));
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .arg("info")
+        .arg(path)
+        .args(["--predecessor-check"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn info_describes_a_blinker_and_a_glider() {
+    for pattern in ["blinker", "glider"] {
+        assert_snapshot(&format!("info_{pattern}"), &info(pattern, &[]));
+    }
+}
+
+#[test]
+fn info_as_json() {
+    let blinker: serde_json::Value = serde_json::from_str(&info("blinker", &["--json"])).unwrap();
+    assert_eq!(blinker["population"], 3);
+    assert_eq!(blinker["bounding_box"], serde_json::json!([3, 1]));
+    assert_eq!(
+        blinker["symmetries"],
+        serde_json::json!(["half turn", "left-right flip", "top-bottom flip"])
+    );
+    assert_eq!(blinker["behaviour"], "oscillator");
+    assert_eq!(blinker["behaviour_period"], 2);
+    assert_eq!(blinker["died_at"], serde_json::Value::Null);
+    assert_eq!(blinker["predecessor"], "found");
+    // Running out of budget is not an error, only an unknown answer.
+    let blinker: serde_json::Value =
+        serde_json::from_str(&info("blinker", &["--json", "--predecessor-budget", "0"])).unwrap();
+    assert_eq!(blinker["predecessor"], "unknown");
+
+    let glider: serde_json::Value = serde_json::from_str(&info("glider", &["--json"])).unwrap();
+    assert_eq!(glider["symmetries"], serde_json::json!([]));
+    assert_eq!(glider["behaviour"], "spaceship");
+    assert_eq!(glider["behaviour_period"], 4);
+    // It only comes back exactly once it has travelled all the way round the torus.
+    assert_eq!(glider["period"], 32);
+}
+
+/// Runs the binary with `args` in a fresh, empty directory named after `name`,
+/// returning the exit code, stdout and stderr.
+fn in_empty_dir(name: &str, args: &[&str]) -> (Option<i32>, String, String) {
+    let dir = pattern_dir(name, &[]);
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .args(args)
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+        stderr,
+    )
+}
+
+#[test]
+fn window_checks_on_a_plane_are_an_error_rather_than_a_crash() {
+    let dir = pattern_dir("plane-windows", &[]);
+    std::fs::write(dir.join("glider.gol"), "!size 8x8 plane\n.#\n..#\n###\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .args(["--check-windows", "3"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(output.status.code(), Some(65), "{stderr}");
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    assert!(stderr.contains("`--check-windows`"), "{stderr}");
+    assert!(stderr.contains("on a torus"), "{stderr}");
+}
+
+#[test]
+fn the_chain_search_stops_when_its_timeout_runs_out() {
+    let started = std::time::Instant::now();
+    let (code, stdout, stderr) =
+        in_empty_dir("timeout", &["--demo", "glider", "--timeout", "300ms"]);
+    assert_eq!(code, Some(0), "{stderr}");
+    assert!(stdout.contains("as `--timeout` asked"), "{stdout}");
+    assert!(started.elapsed() < std::time::Duration::from_secs(30));
+
+    let (code, _, stderr) = in_empty_dir("bad-timeout", &["--demo", "glider", "--timeout", "soon"]);
+    assert_eq!(code, Some(2), "{stderr}");
+    assert!(stderr.contains("`3m 07s`"), "{stderr}");
+}
+
+#[test]
+fn no_arguments_without_a_pattern_prints_usage() {
+    let (code, stdout, _) = in_empty_dir("no-arguments", &[]);
+    assert_eq!(code, Some(64));
+    assert!(stdout.contains("Usage:"), "{stdout}");
+    assert!(stdout.contains("--demo glider"), "{stdout}");
+}
+
+#[test]
+fn a_missing_pattern_suggests_a_demo() {
+    let (code, _, stderr) = in_empty_dir("missing-pattern", &["info"]);
+    assert_eq!(code, Some(74));
+    assert!(stderr.contains("glider.gol"), "{stderr}");
+    assert!(stderr.contains("--demo NAME"), "{stderr}");
+}
+
+#[test]
+fn an_overlong_line_is_reported_rather_than_wrapped() {
+    let dir = pattern_dir("overlong-line", &[&format!("#\n{}#", ".".repeat(2000))]);
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .args(["info", "00.gol"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(output.status.code(), Some(65), "{stderr}");
+    assert!(stderr.contains("line 2, column 1025"), "{stderr}");
+    assert!(stderr.contains("2001 cells long"), "{stderr}");
+    assert!(stderr.contains("the pattern is 2001×2"), "{stderr}");
+    let margin = stderr.find("`--margin N`").expect(&stderr);
+    let center = stderr.find("`--center`").expect(&stderr);
+    let wrap = stderr.find("pass `--allow-wrap`").expect(&stderr);
+    assert!(margin < wrap && center < wrap, "{stderr}");
+    // Asked to, the line wraps around the grid as it used to.
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .args(["info", "00.gol", "--allow-wrap"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(output.status.code(), Some(0), "{stderr}");
+}
+
+#[test]
+fn patterns_can_be_placed_with_a_margin_or_centred() {
+    let dir = pattern_dir("placed-pattern", &["#..\n...\n..."]);
+    let run = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert_eq!(output.status.code(), Some(0), "{stderr}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let plain = run(&["info", "00.gol"]);
+    let margin = run(&["info", "00.gol", "--margin", "3"]);
+    let centred = run(&["info", "00.gol", "--center"]);
+    // The flags only move the pattern, or change the grid around it.
+    assert_ne!(margin, plain);
+    assert_ne!(centred, plain);
+    // A margin the grid has no room for is an error, not a larger grid.
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .args(["info", "00.gol", "--margin", "100"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(output.status.code(), Some(65), "{stderr}");
+    assert!(stderr.contains("more than its 8×8 grid"), "{stderr}");
+    let output = Command::new(env!("CARGO_BIN_EXE_undeath"))
+        .args(["info", "00.gol", "--center", "--allow-wrap"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn demos_work_from_any_directory() {
+    let (code, stdout, _) = in_empty_dir("list-demos", &["list-demos"]);
+    assert_eq!(code, Some(0));
+    let names = stdout
+        .lines()
+        .map(|line| line.split_whitespace().next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["glider", "r-pentomino"]);
+
+    for name in names {
+        let (code, stdout, stderr) =
+            in_empty_dir(&format!("demo-{name}"), &["info", "--demo", name, "--json"]);
+        assert_eq!(code, Some(0), "{stderr}");
+        let info: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(info["population"], 5);
+    }
+}
@@ -0,0 +1,56 @@
+//! Splitting a grid's alive cells into connected objects.
+
+use undeath::{grid::SIZE, Grid};
+
+#[test]
+fn separated_blocks_are_two_components() {
+    let grid = Grid::from_text("##\n##\n\n\n....##\n....##");
+    let components = grid.components();
+    assert_eq!(components.len(), 2);
+    assert_eq!(grid.component_count(), 2);
+    assert_eq!(components[0].cells, [(0, 0), (1, 0), (0, 1), (1, 1)]);
+    assert_eq!(components[0].bounding_box, (0, 0, 2, 2));
+    assert_eq!(components[1].population(), 4);
+    assert_eq!(components[1].bounding_box, (4, 4, 2, 2));
+}
+
+#[test]
+fn diagonal_neighbours_connect() {
+    let grid = Grid::from_text("#\n.#\n..#");
+    assert_eq!(grid.component_count(), 1);
+    assert_eq!(grid.components()[0].bounding_box, (0, 0, 3, 3));
+}
+
+#[test]
+fn block_straddling_the_seam_is_one_component() {
+    // The four corners of the torus are one block.
+    let corners = Grid::from_text("#......#\n\n\n\n\n\n\n#......#");
+    let components = corners.components();
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].population(), 4);
+    assert_eq!(components[0].bounding_box, (SIZE - 1, SIZE - 1, 2, 2));
+    // So is a block across the left and right edges only.
+    let edge = Grid::from_text("\n\n\n#......#\n#......#");
+    assert_eq!(edge.component_count(), 1);
+    assert_eq!(edge.components()[0].bounding_box, (SIZE - 1, 3, 2, 2));
+}
+
+#[test]
+fn row_around_the_torus_covers_every_column() {
+    let grid = Grid::from_text("\n\n########");
+    assert_eq!(grid.components()[0].bounding_box, (0, 2, SIZE, 1));
+}
+
+#[test]
+fn render_colours_each_component() {
+    let grid = Grid::from_text("#.#");
+    let rendered = grid.render_components();
+    assert!(
+        rendered.contains("\x1b[31m██\x1b[0m  \x1b[32m██\x1b[0m"),
+        "{rendered}"
+    );
+    assert_eq!(
+        Grid::default().render_components(),
+        Grid::default().render()
+    );
+}
@@ -0,0 +1,134 @@
+//! Runs the predecessor search on every pattern in `tests/corpus`.
+//!
+//! Each pattern must have a predecessor that is found within its ceiling on search iterations,
+//! recorded in `tests/corpus/manifest.txt`. The ceilings leave room for small changes in search order,
+//! but catch pathological slowdowns. After adding a pattern, or a deliberate change to the search,
+//! regenerate the manifest with
+//!
+//! ```text
+//! UPDATE_CORPUS=1 cargo test --test corpus
+//! ```
+//!
+//! and review the new ceilings before committing them.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use undeath::{searcher::Strategy, Grid, SearchResult, Searcher};
+
+/// No pattern in the corpus should need more than this many iterations.
+const BUDGET: usize = 5_000_000;
+/// The ceilings written by `UPDATE_CORPUS` allow this multiple of the iterations measured.
+const HEADROOM: usize = 2;
+
+fn corpus_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+/// Searches until the first predecessor, returning it and the number of iterations it took.
+fn first_predecessor(target: &Grid, strategy: Strategy) -> (Grid, usize) {
+    let mut searcher = Searcher::new(target.clone()).strategy(strategy);
+    let mut total = 0;
+    loop {
+        match searcher.search(BUDGET - total).unwrap() {
+            SearchResult::Found(grid, iterations) => return (grid, total + iterations),
+            SearchResult::Working(iterations) => {
+                total += iterations;
+                assert!(total < BUDGET, "no predecessor within {BUDGET} iterations");
+            }
+            SearchResult::Unsatisfiable => panic!("the pattern has no predecessor"),
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn read_manifest() -> BTreeMap<String, usize> {
+    let text = std::fs::read_to_string(corpus_dir().join("manifest.txt")).unwrap_or_default();
+    text.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (name, ceiling) = line.split_once(' ').expect("lines are `name ceiling`");
+            (
+                name.to_owned(),
+                ceiling.trim().parse().expect("ceilings are integers"),
+            )
+        })
+        .collect()
+}
+
+fn write_manifest(ceilings: &BTreeMap<String, usize>) {
+    let mut text = String::from(
+        "# The most search iterations each pattern may take to find its first predecessor.\n\
+         # Regenerate with `UPDATE_CORPUS=1 cargo test --test corpus`.\n",
+    );
+    for (name, ceiling) in ceilings {
+        text += &format!("{name} {ceiling}\n");
+    }
+    std::fs::write(corpus_dir().join("manifest.txt"), text).unwrap();
+}
+
+#[test]
+fn corpus() {
+    let update = std::env::var_os("UPDATE_CORPUS").is_some();
+    let manifest = read_manifest();
+    let mut ceilings = BTreeMap::new();
+
+    let mut paths = std::fs::read_dir(corpus_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "gol"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert!(!paths.is_empty(), "the corpus is empty");
+
+    for path in paths {
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let target = Grid::from_file(&path).unwrap();
+        let (predecessor, iterations) = first_predecessor(&target, Strategy::DepthFirst);
+
+        let mut stepped = predecessor.clone();
+        stepped.step();
+        assert!(
+            stepped == target,
+            "{name}: the predecessor found does not step to the pattern"
+        );
+
+        if update {
+            ceilings.insert(name, (iterations * HEADROOM).max(100));
+        } else {
+            let ceiling = *manifest.get(&name).unwrap_or_else(|| {
+                panic!("{name} is missing from the manifest; run with UPDATE_CORPUS=1 to add it")
+            });
+            assert!(
+                iterations <= ceiling,
+                "{name} took {iterations} iterations, over its ceiling of {ceiling}"
+            );
+        }
+    }
+
+    if update {
+        write_manifest(&ceilings);
+    }
+}
+
+/// Best-first search has no ceilings, since it is not the default, but must still find a predecessor of each pattern.
+#[test]
+fn corpus_best_first() {
+    for path in std::fs::read_dir(corpus_dir()).unwrap() {
+        let path = path.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "gol") {
+            continue;
+        }
+        let target = Grid::from_file(&path).unwrap();
+        let strategy = Strategy::BestFirst {
+            max_open: Strategy::DEFAULT_MAX_OPEN,
+        };
+        let (predecessor, _) = first_predecessor(&target, strategy);
+        let mut stepped = predecessor;
+        stepped.step();
+        assert!(
+            stepped == target,
+            "{}: the predecessor found does not step to the pattern",
+            path.display()
+        );
+    }
+}
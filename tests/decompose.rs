@@ -0,0 +1,117 @@
+//! Decomposed problems have exactly the predecessors that brute force finds for the whole mask.
+
+use undeath::{bruteforce, decompose::decompose, Cell, Grid, Options};
+
+/// Two bands two rows high and five wide, three rows apart, so that they never affect the same target cell.
+fn two_bands() -> Grid {
+    let mut mask = Grid::default();
+    for y in 0..2 {
+        for x in 0..5 {
+            mask.set_wrapped(x, y, Cell::one());
+            mask.set_wrapped(x + 2, y + 4, Cell::one());
+        }
+    }
+    mask
+}
+
+/// The step of `top` at the origin together with `bottom` in the second band.
+fn target(top: &str, bottom: &str) -> Grid {
+    let mut grid = Grid::from_text(top);
+    let bottom = Grid::from_text(bottom);
+    for y in 0..2 {
+        for x in 0..5 {
+            if bottom.get_wrapped(x, y).value > 0 {
+                grid.set_wrapped(x + 2, y + 4, Cell::one());
+            }
+        }
+    }
+    grid.step();
+    grid
+}
+
+#[test]
+fn matches_brute_force() {
+    let mask = two_bands();
+    for (top, bottom) in [
+        ("##\n##", "###"),
+        ("###\n#", ".#\n###"),
+        ("##.#\n#.##", "#..#\n.##."),
+    ] {
+        let next = target(top, bottom);
+        let expected = bruteforce::predecessors(&next, &mask).unwrap();
+        let decomposition = decompose(&next, &mask).unwrap();
+        assert_eq!(decomposition.components().len(), 2);
+
+        let counts = decomposition
+            .components()
+            .iter()
+            .map(|component| {
+                bruteforce::predecessors(&component.target, &component.mask)
+                    .unwrap()
+                    .len() as u128
+            })
+            .collect::<Vec<_>>();
+        let count = decomposition.count_with(&Options::default()).unwrap();
+        assert_eq!(count, counts.iter().product());
+        assert_eq!(count, expected.len() as u128);
+
+        let mut predecessors = decomposition
+            .predecessors_with(&Options::default())
+            .unwrap()
+            .collect::<Vec<_>>();
+        predecessors.sort();
+        assert!(predecessors == expected);
+
+        let predecessor = decomposition
+            .solve_with(&Options::default())
+            .unwrap()
+            .unwrap();
+        assert!(expected.contains(&predecessor));
+    }
+}
+
+#[test]
+fn close_groups_stay_together() {
+    let mut mask = Grid::default();
+    for x in 0..5 {
+        mask.set_wrapped(x, 0, Cell::one());
+        mask.set_wrapped(x, 2, Cell::one());
+    }
+    let decomposition = decompose(&Grid::default(), &mask).unwrap();
+    assert_eq!(decomposition.components().len(), 1);
+    assert!(decomposition.components()[0].mask == mask);
+}
+
+#[test]
+fn unreachable_target_cells_must_be_dead() {
+    let mask = two_bands();
+    let mut next = target("##\n##", "###");
+    next.set_wrapped(0, 3, Cell::one());
+    assert!(decompose(&next, &mask).is_none());
+
+    let decomposition = decompose(&Grid::default(), &Grid::default()).unwrap();
+    assert!(decomposition.components().is_empty());
+    let predecessors = decomposition
+        .predecessors_with(&Options::default())
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert!(predecessors == vec![Grid::default()]);
+}
+
+/// A lone alive cell has no predecessor confined to the band around it, so neither does the whole.
+#[test]
+fn one_impossible_component_rules_out_everything() {
+    let mask = two_bands();
+    let mut next = target("##\n##", "");
+    next.set_wrapped(4, 4, Cell::one());
+    let decomposition = decompose(&next, &mask).unwrap();
+    assert_eq!(decomposition.count_with(&Options::default()).unwrap(), 0);
+    assert_eq!(decomposition.solve_with(&Options::default()).unwrap(), None);
+    assert_eq!(
+        decomposition
+            .predecessors_with(&Options::default())
+            .unwrap()
+            .count(),
+        0
+    );
+}
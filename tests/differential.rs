@@ -0,0 +1,128 @@
+//! Checks the searcher against the brute-force oracle on small targets,
+//! where every predecessor can be enumerated directly.
+//!
+//! Any difference means a propagation rule either pruned a real predecessor or let through a wrong one.
+
+use undeath::{
+    bruteforce,
+    order::HighestImpact,
+    searcher::{Shaving, Strategy},
+    Cell, Grid, SearchResult, Searcher,
+};
+
+/// The window that targets and predecessors are confined to.
+const WIDTH: i32 = 5;
+const HEIGHT: i32 = 4;
+/// The top left corner of the window, away from the edges so that nothing wraps around.
+const LEFT: i32 = 1;
+const TOP: i32 = 2;
+
+fn window() -> Grid {
+    let mut mask = Grid::default();
+    for y in TOP..TOP + HEIGHT {
+        for x in LEFT..LEFT + WIDTH {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    mask
+}
+
+/// A way to set up a searcher, such as enabling an optional heuristic.
+type Configure = fn(Searcher) -> Searcher;
+
+/// Every predecessor found by a searcher confined to `mask` and then configured by `configure`, sorted.
+fn search_all(next: &Grid, mask: &Grid, configure: impl Fn(Searcher) -> Searcher) -> Vec<Grid> {
+    let mut searcher = configure(Searcher::with_mask(next.clone(), mask));
+    let mut result = Vec::new();
+    loop {
+        match searcher.search(1_000_000).unwrap() {
+            SearchResult::Found(grid, _) => result.push(grid),
+            SearchResult::Working(_) => {}
+            SearchResult::Unsatisfiable => break,
+            _ => unreachable!(),
+        }
+    }
+    result.sort();
+    result
+}
+
+/// Deterministic targets inside the window: some still lifes and oscillators,
+/// then the images of pseudo-random grids, which always have a predecessor,
+/// then pseudo-random grids themselves, which usually do not.
+fn targets() -> Vec<Grid> {
+    let shift = |text: &str| {
+        let mut grid = Grid::default();
+        for (x, y) in Grid::from_text(text).alive_cells() {
+            grid.set_wrapped(x + LEFT, y + TOP, Cell::one());
+        }
+        grid
+    };
+    let mut targets = vec![
+        Grid::default(),
+        shift(".##\n.##"),
+        shift("\n###"),
+        shift(".#\n#.#\n.#"),
+        shift("#"),
+    ];
+
+    let window = window().alive_cells().collect::<Vec<_>>();
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    for i in 0..12u32 {
+        let mut grid = Grid::default();
+        for &(x, y) in &window {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            if state.is_multiple_of(3) {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+        if i.is_multiple_of(2) {
+            grid.step();
+        }
+        // Stepping can spill outside the window, which would make the target trivially unsatisfiable.
+        if grid.alive_cells().all(|cell| window.contains(&cell)) {
+            targets.push(grid);
+        }
+    }
+    targets
+}
+
+#[test]
+fn searcher_matches_brute_force() {
+    let mask = window();
+    for target in targets() {
+        let expected = bruteforce::predecessors(&target, &mask).unwrap();
+        let configurations: [(&str, Configure); 6] = [
+            ("by default", |searcher| searcher),
+            ("with block propagation", |searcher| {
+                searcher.block_propagation(true)
+            }),
+            ("branching on impact", |searcher| {
+                searcher.cell_order(HighestImpact)
+            }),
+            ("shaving every third guess", |searcher| {
+                searcher.shave(Shaving::Periodic(3))
+            }),
+            ("best first", |searcher| {
+                searcher.strategy(Strategy::BestFirst {
+                    max_open: Strategy::DEFAULT_MAX_OPEN,
+                })
+            }),
+            ("best first with a tiny open list", |searcher| {
+                searcher.strategy(Strategy::BestFirst { max_open: 4 })
+            }),
+        ];
+        for (name, configure) in configurations {
+            let actual = search_all(&target, &mask, configure);
+            assert!(
+                actual == expected,
+                "the searcher found {} predecessors but there are {} of\n{}\n{name}",
+                actual.len(),
+                expected.len(),
+                target.render(),
+            );
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! The per-cell effort report agrees with the search statistics.
+
+use undeath::{
+    grid::SIZE,
+    order::HighestImpact,
+    searcher::{Shaving, Strategy},
+    Grid, SearchResult, Searcher,
+};
+
+fn glider() -> Grid {
+    Grid::from_text(".#\n..#\n###")
+}
+
+/// Searches one iteration at a time, checking the report's totals against the statistics after each.
+fn check_totals(mut searcher: Searcher, iterations: usize) {
+    for _ in 0..iterations {
+        let result = searcher.search(1).unwrap();
+        let (stats, effort) = (searcher.stats(), searcher.effort_report());
+        assert_eq!(effort.total_branched(), stats.decisions);
+        assert_eq!(effort.total_propagated(), stats.deductions);
+        if matches!(
+            result,
+            SearchResult::Unsatisfiable | SearchResult::Inconclusive
+        ) {
+            break;
+        }
+    }
+}
+
+#[test]
+fn totals_match_the_statistics() {
+    check_totals(Searcher::new(glider()), 2_000);
+    check_totals(
+        Searcher::new(glider())
+            .cell_order(HighestImpact)
+            .shave(Shaving::Periodic(3)),
+        2_000,
+    );
+    check_totals(
+        Searcher::new(glider()).strategy(Strategy::BestFirst { max_open: 64 }),
+        2_000,
+    );
+}
+
+#[test]
+fn every_decided_cell_has_a_depth() {
+    let mut searcher = Searcher::new(glider());
+    searcher.search(1_000).unwrap();
+    let effort = searcher.effort_report();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let decided = effort.branched(x, y) + effort.propagated(x, y);
+            match effort.mean_depth(x, y) {
+                Some(depth) => assert!(decided > 0 && depth >= 1.0),
+                None => assert_eq!(decided, 0),
+            }
+        }
+    }
+    assert!(effort.branched_grid() != Grid::default());
+    assert_eq!(effort.render().lines().count(), SIZE as usize + 4);
+}
+
+#[test]
+fn resets_clear_the_report_unless_asked_not_to() {
+    let mut searcher = Searcher::new(glider());
+    searcher.search(1_000).unwrap();
+    let effort = searcher.effort_report().clone();
+    assert!(effort.total_branched() > 0);
+
+    searcher.reset_with_target(glider(), true);
+    assert!(*searcher.effort_report() == effort);
+    searcher.reset_with_target(glider(), false);
+    assert_eq!(searcher.effort_report().total_branched(), 0);
+}
@@ -0,0 +1,67 @@
+//! Heat maps and envelopes of a grid's forward evolution.
+
+use undeath::{grid::SIZE, testing, Grid, SearchResult, Searcher};
+
+fn total(grid: &Grid) -> i32 {
+    (0..SIZE)
+        .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+        .map(|(x, y)| grid.get_wrapped(x, y).value)
+        .sum()
+}
+
+#[test]
+fn blinker_alternates() {
+    let blinker = Grid::from_text("\n\n\n..###");
+    for steps in [1, 2, 7, 10] {
+        let heat = blinker.heatmap(steps);
+        assert_eq!(heat.get_wrapped(3, 3).value, steps as i32);
+        // The horizontal phase comes first.
+        let horizontal = steps.div_ceil(2) as i32;
+        let vertical = steps as i32 / 2;
+        for (x, y, expected) in [
+            (2, 3, horizontal),
+            (4, 3, horizontal),
+            (3, 2, vertical),
+            (3, 4, vertical),
+        ] {
+            assert_eq!(heat.get_wrapped(x, y).value, expected);
+        }
+        assert_eq!(total(&heat), 3 * steps as i32);
+    }
+    assert_eq!(blinker.envelope(2).alive_cells().count(), 5);
+    assert!(blinker.envelope(0) == Grid::default());
+}
+
+#[test]
+fn glider_streaks_diagonally() {
+    let glider = Grid::from_text(".#\n..#\n###");
+    // A glider moves one cell diagonally every four generations, so after 32 it is back where it started.
+    let heat = glider.heatmap(32);
+    assert_eq!(total(&heat), 5 * 32);
+    let envelope = glider.envelope(32);
+    assert!(testing::translate(&envelope, 1, 1) == envelope);
+    // The streak is a diagonal band, which never reaches the cells furthest from it.
+    assert!(envelope.alive_cells().count() < (SIZE * SIZE) as usize);
+    assert!(glider.envelope(4).alive_cells().count() < envelope.alive_cells().count());
+}
+
+#[test]
+fn envelope_masks_an_evolved_soup() {
+    let soup = Grid::from_text("\n\n..##.#\n..#.##\n...##\n..#..#");
+    let mut target = soup.clone();
+    for _ in 0..3 {
+        target.step();
+    }
+    let mask = soup.envelope(3);
+    let mut searcher = Searcher::with_mask(target.clone(), &mask);
+    let mut parent = loop {
+        match searcher.search(10_000).unwrap() {
+            SearchResult::Found(grid, _) => break grid,
+            SearchResult::Working(_) => {}
+            _ => panic!("the soup's own history lies within its envelope"),
+        }
+    };
+    assert!(parent.and(&mask) == parent);
+    parent.step();
+    assert!(parent == target);
+}
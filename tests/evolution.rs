@@ -0,0 +1,96 @@
+//! Transients, periods and populations of forward evolutions, by Brent's algorithm and by hashing.
+
+use undeath::{
+    grid::SIZE,
+    rng::{RandomSource, Rng},
+    Cell, Grid,
+};
+
+fn stepped(grid: &Grid, steps: usize) -> Grid {
+    let mut grid = grid.clone();
+    for _ in 0..steps {
+        grid.step();
+    }
+    grid
+}
+
+#[test]
+fn r_pentomino_dies_on_the_small_torus() {
+    // On the 8×8 torus, the R-pentomino has no room to stabilise, and burns itself out.
+    let r_pentomino = Grid::from_text("\n\n..##\n.##\n..#");
+    for report in [
+        r_pentomino.evolution_report(1_000),
+        r_pentomino.evolution_report_hashed(1_000),
+    ] {
+        assert_eq!(report.transient_len, Some(34));
+        assert_eq!(report.period, Some(1));
+        assert_eq!(report.max_population, 31);
+        assert_eq!(report.min_population, 0);
+        assert_eq!(report.died_at, Some(34));
+        assert!(report.first_repeat == Some(Grid::default()));
+    }
+}
+
+#[test]
+fn blinker_oscillates() {
+    let blinker = Grid::from_text("\n\n\n..###");
+    for report in [
+        blinker.evolution_report(10),
+        blinker.evolution_report_hashed(10),
+    ] {
+        assert_eq!((report.transient_len, report.period), (Some(0), Some(2)));
+        assert_eq!((report.min_population, report.max_population), (3, 3));
+        assert_eq!(report.died_at, None);
+        assert!(report.first_repeat == Some(blinker.clone()));
+    }
+}
+
+#[test]
+fn diagonal_dies() {
+    let diagonal = Grid::from_text("#\n.#\n..#");
+    let report = diagonal.evolution_report(10);
+    assert_eq!((report.transient_len, report.period), (Some(2), Some(1)));
+    assert_eq!(report.died_at, Some(2));
+    assert_eq!((report.min_population, report.max_population), (0, 3));
+}
+
+#[test]
+fn gives_up_at_the_step_limit() {
+    // A glider comes back to where it started after 32 generations.
+    let glider = Grid::from_text(".#\n..#\n###");
+    for report in [
+        glider.evolution_report(16),
+        glider.evolution_report_hashed(16),
+    ] {
+        assert_eq!((report.transient_len, report.period), (None, None));
+        assert!(report.first_repeat.is_none());
+        assert_eq!((report.min_population, report.max_population), (5, 5));
+    }
+    assert_eq!(glider.evolution_report_hashed(32).period, Some(32));
+}
+
+#[test]
+fn modes_agree_on_soups() {
+    let mut rng = Rng::new(474);
+    for _ in 0..50 {
+        let mut soup = Grid::default();
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                if rng.gen_bool(0.4) {
+                    soup.set_wrapped(x, y, Cell::one());
+                }
+            }
+        }
+        let brent = soup.evolution_report(10_000);
+        assert!(brent == soup.evolution_report_hashed(10_000));
+        let (mu, lambda) = (brent.transient_len.unwrap(), brent.period.unwrap());
+        let first_repeat = stepped(&soup, mu);
+        assert!(brent.first_repeat.as_ref() == Some(&first_repeat));
+        assert!(stepped(&first_repeat, lambda) == first_repeat);
+        // The cycle is the shortest one, and starts as soon as possible.
+        assert!((1..lambda).all(|period| stepped(&first_repeat, period) != first_repeat));
+        if mu > 0 {
+            assert!(stepped(&soup, mu - 1) != stepped(&soup, mu - 1 + lambda));
+        }
+    }
+}
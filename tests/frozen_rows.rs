@@ -0,0 +1,58 @@
+//! Fully decided rows of a guess can never become undecided, so they are never worth branching on.
+
+use undeath::{grid::SIZE, testing, Cell, Grid, SearchResult, Searcher};
+
+fn glider() -> Grid {
+    Grid::from_text("\n.#\n..#\n###")
+}
+
+#[test]
+fn unknown_cells_skip_decided_rows() {
+    let guesses = (0..SIZE)
+        .map(|x| (x, 6, false))
+        .chain([(3, 0, true), (5, 2, false)])
+        .collect::<Vec<_>>();
+    let guess = testing::apply_guesses(&glider(), &guesses);
+    assert!(guess.row_decided(6));
+
+    let (alive, dead) = (guess.alive(), guess.dead());
+    let expected = (0..SIZE)
+        .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+        .filter(|&(x, y)| alive.get_wrapped(x, y).value == 0 && dead.get_wrapped(x, y).value == 0)
+        .collect::<Vec<_>>();
+    assert_eq!(guess.unknown_cells().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn frozen_rows_are_never_candidates() {
+    // Only the middle four rows may contain alive cells, so the rest are decided from the start.
+    let mut mask = Grid::default();
+    for y in 2..6 {
+        for x in 0..SIZE {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    let mut target = Grid::from_text("\n\n\n..###");
+    target.step();
+
+    let mut searcher = Searcher::with_mask(target, &mask);
+    let mut found = 0;
+    loop {
+        if let Some(guess) = searcher.current_guess() {
+            for (x, y) in searcher.candidates() {
+                assert!(
+                    !guess.row_decided(y),
+                    "({x}, {y}) is a candidate in a decided row\n{}",
+                    guess.render()
+                );
+            }
+        }
+        match searcher.search(1).unwrap() {
+            SearchResult::Found(_, _) => found += 1,
+            SearchResult::Working(_) => {}
+            SearchResult::Unsatisfiable => break,
+            _ => unreachable!(),
+        }
+    }
+    assert!(found > 0);
+}
@@ -0,0 +1,127 @@
+//! The contradiction heat map counts every contradiction the search detects, at the cell where it was detected.
+
+use std::sync::mpsc;
+
+use undeath::{
+    grid::SIZE,
+    parallel::{enumerate, ParallelOptions},
+    searcher::Strategy,
+    trace::TraceEvent,
+    Cell, Grid, SearchResult, Searcher,
+};
+
+/// A blinker, with predecessors confined to the 4×4 window around it.
+fn masked_blinker() -> (Grid, Grid) {
+    let blinker = Grid::from_text("\n\n\n..###");
+    let mut mask = Grid::default();
+    for y in 2..6 {
+        for x in 1..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    (blinker, mask)
+}
+
+/// Searches until the search space is exhausted or `max` iterations have been spent.
+fn run(searcher: &mut Searcher, max: usize) {
+    let mut remaining = max;
+    while remaining > 0 {
+        match searcher.search(remaining.min(100)).unwrap() {
+            SearchResult::Found(_, spent) | SearchResult::Working(spent) => {
+                remaining = remaining.saturating_sub(spent.max(1));
+            }
+            _ => return,
+        }
+    }
+}
+
+fn total(heatmap: &Grid) -> u64 {
+    (0..SIZE)
+        .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+        .map(|(x, y)| heatmap.get_wrapped(x, y).value as u64)
+        .sum()
+}
+
+#[test]
+fn counts_match_stats() {
+    let (blinker, mask) = masked_blinker();
+    let mut searcher = Searcher::with_mask(blinker, &mask);
+    run(&mut searcher, usize::MAX);
+    let contradictions = searcher.stats().contradictions;
+    assert!(contradictions > 0);
+    assert_eq!(total(&searcher.contradiction_heatmap()), contradictions);
+}
+
+#[test]
+fn counts_match_stats_with_block_propagation() {
+    let glider = Grid::from_text(".#\n..#\n###");
+    let mut searcher = Searcher::new(glider).block_propagation(true);
+    run(&mut searcher, 2_000);
+    let contradictions = searcher.stats().contradictions;
+    assert!(contradictions > 0);
+    assert_eq!(total(&searcher.contradiction_heatmap()), contradictions);
+}
+
+#[test]
+fn counts_match_stats_best_first() {
+    let glider = Grid::from_text(".#\n..#\n###");
+    let mut searcher = Searcher::new(glider).strategy(Strategy::BestFirst {
+        max_open: Strategy::DEFAULT_MAX_OPEN,
+    });
+    run(&mut searcher, 2_000);
+    let contradictions = searcher.stats().contradictions;
+    assert!(contradictions > 0);
+    assert_eq!(total(&searcher.contradiction_heatmap()), contradictions);
+}
+
+#[test]
+fn counts_match_contradictory_decisions_in_trace() {
+    let (blinker, mask) = masked_blinker();
+    let (sender, receiver) = mpsc::channel();
+    let mut searcher = Searcher::with_mask(blinker, &mask).trace_to(sender);
+    run(&mut searcher, usize::MAX);
+    let contradictory = receiver
+        .try_iter()
+        .filter(|event| {
+            matches!(
+                event,
+                TraceEvent::Decide {
+                    contradiction: true,
+                    ..
+                }
+            )
+        })
+        .count();
+    assert_eq!(searcher.stats().contradictions, contradictory as u64);
+}
+
+#[test]
+fn enumeration_sums_workers() {
+    let (blinker, mask) = masked_blinker();
+    let options = ParallelOptions {
+        threads: 3,
+        mask: Some(mask),
+        ..ParallelOptions::default()
+    };
+    let enumeration = enumerate(&blinker, &options).unwrap();
+    assert!(enumeration.exhausted);
+    assert_eq!(
+        total(&enumeration.heatmap),
+        enumeration.stats.contradictions
+    );
+}
+
+#[test]
+fn render_shades_by_count() {
+    let mut counts = Grid::default();
+    counts.set_wrapped(0, 0, Cell { value: 1 });
+    counts.set_wrapped(1, 0, Cell { value: 3 });
+    counts.set_wrapped(2, 0, Cell { value: 5 });
+    counts.set_wrapped(3, 0, Cell { value: 7 });
+    counts.set_wrapped(4, 0, Cell { value: 8 });
+    let rendered = counts.render_heatmap();
+    assert!(rendered.contains("│░░▒▒▓▓████      │"), "{rendered}");
+    assert!(!Grid::default()
+        .render_heatmap()
+        .contains(['░', '▒', '▓', '█']));
+}
@@ -0,0 +1,98 @@
+//! Hybrid search hands frames that local search nearly solved to depth-first search, which finishes them.
+
+use undeath::{
+    rng::{RandomSource, Rng},
+    searcher::Strategy,
+    Cell, Grid, Guess, SearchResult, Searcher,
+};
+
+/// A random half-full soup, stepped once so that it certainly has a predecessor.
+fn dense_target(seed: u64) -> Grid {
+    let mut rng = Rng::new(seed);
+    let mut grid = Grid::default();
+    for y in 0..8 {
+        for x in 0..8 {
+            if rng.gen_bool(0.5) {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    grid.step();
+    grid
+}
+
+fn complement(grid: &Grid) -> Grid {
+    let mut full = Grid::fill(Cell::one());
+    full.sub_assign_saturating(grid);
+    full
+}
+
+fn predecessors(mut searcher: Searcher, count: usize) -> Vec<Grid> {
+    let mut found = Vec::new();
+    while found.len() < count {
+        match searcher.search(1_000_000).unwrap() {
+            SearchResult::Found(grid, _) => found.push(grid),
+            SearchResult::Working(_) => {}
+            _ => panic!("hybrid search should keep finding predecessors"),
+        }
+    }
+    found
+}
+
+#[test]
+fn from_partial_decides_the_given_cells() {
+    let glider = Grid::from_text("\n.#\n..#\n###");
+    let mut predecessor = glider.clone();
+    for _ in 0..4 {
+        predecessor.step();
+    }
+    // A glider four generations on is a predecessor of one three generations on.
+    let mut target = predecessor.clone();
+    target.step();
+    let guess = Guess::from_partial(&target, &predecessor, &complement(&predecessor));
+    assert!(!guess.found_contradiction());
+    assert!(guess.alive() == predecessor);
+    assert_eq!(guess.unknown_cells().count(), 0);
+
+    let guess = Guess::from_partial(&target, &Grid::default(), &complement(&Grid::default()));
+    assert!(guess.found_contradiction());
+}
+
+/// With a generous threshold, local search hands off before finishing, so depth-first search finds these.
+#[test]
+fn handoffs_find_predecessors_of_dense_targets() {
+    for seed in [1, 2] {
+        let target = dense_target(seed);
+        let searcher = Searcher::new(target.clone()).strategy(Strategy::Hybrid {
+            max_violations: 8,
+            radius: 1,
+        });
+        for mut grid in predecessors(searcher, 2) {
+            grid.step();
+            assert!(grid == target);
+        }
+    }
+}
+
+#[test]
+fn keeps_to_the_mask() {
+    let blinker = Grid::from_text("\n\n\n..###");
+    let mut mask = Grid::default();
+    for y in 2..5 {
+        for x in 2..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    let searcher = Searcher::with_mask(blinker.clone(), &mask).strategy(Strategy::Hybrid {
+        max_violations: Strategy::DEFAULT_MAX_VIOLATIONS,
+        radius: Strategy::DEFAULT_RADIUS,
+    });
+    for grid in predecessors(searcher, 3) {
+        let mut outside = grid.clone();
+        outside.sub_assign_saturating(&mask);
+        assert!(outside == Grid::default());
+        let mut stepped = grid;
+        stepped.step();
+        assert!(stepped == blinker);
+    }
+}
@@ -0,0 +1,43 @@
+//! Impact is only tracked when the cell order asks for it, and then records the propagation each branch caused.
+
+use undeath::{
+    grid::SIZE,
+    order::{FirstCandidate, HighestImpact},
+    Grid, SearchResult, Searcher,
+};
+
+fn glider() -> Grid {
+    Grid::from_text("\n.#\n..#\n###")
+}
+
+/// Searches until the first predecessor, and returns the most impact, summed over the grid,
+/// that the current guess had along the way.
+fn most_impact(searcher: Searcher) -> i32 {
+    let mut searcher = searcher;
+    let mut most = 0;
+    loop {
+        let result = searcher.search(10).unwrap();
+        let guess = searcher.current_guess().unwrap();
+        let impact = guess.impact_grid();
+        let total = (0..SIZE)
+            .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                assert_eq!(impact.get_wrapped(x, y).value, guess.impact(x, y));
+                guess.impact(x, y)
+            })
+            .sum();
+        most = most.max(total);
+        if let SearchResult::Found(_, _) = result {
+            return most;
+        }
+    }
+}
+
+#[test]
+fn impact_is_only_tracked_when_used() {
+    assert_eq!(
+        most_impact(Searcher::new(glider()).cell_order(FirstCandidate)),
+        0
+    );
+    assert!(most_impact(Searcher::new(glider()).cell_order(HighestImpact)) > 0);
+}
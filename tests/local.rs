@@ -0,0 +1,102 @@
+//! Local search finds predecessors of dense targets, reproducibly from a seed, and never proves anything itself.
+
+use undeath::{
+    rng::{RandomSource, Rng},
+    searcher::Strategy,
+    Cell, Grid, SearchResult, Searcher,
+};
+
+const LOCAL: Strategy = Strategy::Local {
+    noise_percent: Strategy::DEFAULT_NOISE_PERCENT,
+    restart_after: Strategy::DEFAULT_RESTART_AFTER,
+};
+
+/// A random half-full soup, stepped once so that it certainly has a predecessor.
+fn dense_target(seed: u64) -> Grid {
+    let mut rng = Rng::new(seed);
+    let mut grid = Grid::default();
+    for y in 0..8 {
+        for x in 0..8 {
+            if rng.gen_bool(0.5) {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    grid.step();
+    grid
+}
+
+fn first_predecessor(mut searcher: Searcher) -> Grid {
+    match searcher.search(1_000_000).unwrap() {
+        SearchResult::Found(grid, _) => grid,
+        _ => panic!("local search should find a predecessor"),
+    }
+}
+
+#[test]
+fn finds_predecessors_of_dense_targets() {
+    for seed in 0..3 {
+        let target = dense_target(seed);
+        let mut stepped = first_predecessor(Searcher::new(target.clone()).strategy(LOCAL));
+        stepped.step();
+        assert!(stepped == target);
+    }
+}
+
+#[test]
+fn is_reproducible_from_a_seed() {
+    let target = dense_target(1);
+    let search = |seed| {
+        first_predecessor(
+            Searcher::new(target.clone())
+                .strategy(LOCAL)
+                .rng(Rng::new(seed)),
+        )
+    };
+    assert!(search(5) == search(5));
+}
+
+/// Cells the root guess decides, here from a mask, are never flipped.
+#[test]
+fn keeps_to_the_mask() {
+    let blinker = Grid::from_text("\n\n\n..###");
+    let mut mask = Grid::default();
+    for y in 2..5 {
+        for x in 2..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    let mut searcher = Searcher::with_mask(blinker.clone(), &mask).strategy(LOCAL);
+    for _ in 0..3 {
+        let SearchResult::Found(grid, _) = searcher.search(1_000_000).unwrap() else {
+            panic!("local search should find a predecessor");
+        };
+        let mut outside = grid.clone();
+        outside.sub_assign_saturating(&mask);
+        assert!(outside == Grid::default());
+        let mut stepped = grid;
+        stepped.step();
+        assert!(stepped == blinker);
+    }
+}
+
+/// Running out of flips only means the budget is spent; only a contradictory root proves anything.
+#[test]
+fn only_the_root_proves_unsatisfiability() {
+    let mut searcher = Searcher::new(dense_target(3)).strategy(LOCAL);
+    assert!(matches!(
+        searcher.search(1).unwrap(),
+        SearchResult::Working(1)
+    ));
+
+    let blinker = Grid::from_text("\n\n\n..###");
+    let mut row = Grid::default();
+    for x in 2..5 {
+        row.set_wrapped(x, 3, Cell::one());
+    }
+    let mut searcher = Searcher::with_mask(blinker, &row).strategy(LOCAL);
+    assert!(matches!(
+        searcher.search(1_000_000).unwrap(),
+        SearchResult::Unsatisfiable
+    ));
+}
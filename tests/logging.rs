@@ -0,0 +1,69 @@
+//! The searcher logs each decision and backtrack at debug level, once each.
+//!
+//! A process has only one logger, so this file holds a single test.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use undeath::{Cell, Grid, SearchResult, Searcher};
+
+/// Counts the searcher's debug events, throwing everything else away.
+struct Counting {
+    decisions: AtomicUsize,
+    backtracks: AtomicUsize,
+}
+
+impl Log for Counting {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() == Level::Debug && metadata.target().starts_with("undeath::searcher")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = record.args().to_string();
+        if message.starts_with("guessing (") {
+            self.decisions.fetch_add(1, Ordering::Relaxed);
+        } else if message == "backtracking" {
+            self.backtracks.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Counting = Counting {
+    decisions: AtomicUsize::new(0),
+    backtracks: AtomicUsize::new(0),
+};
+
+#[test]
+fn a_tiny_search_logs_each_decision_and_backtrack() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Debug);
+
+    // A blinker, with predecessors confined to the 4×4 window around it, searched to the end.
+    let blinker = Grid::from_text("\n\n\n..###");
+    let mut mask = Grid::default();
+    for y in 2..6 {
+        for x in 1..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    let mut searcher = Searcher::with_mask(blinker, &mask);
+    let mut found = 0;
+    loop {
+        match searcher.search(usize::MAX).unwrap() {
+            SearchResult::Found(..) => found += 1,
+            SearchResult::Unsatisfiable => break,
+            _ => panic!("depth-first search runs to the end"),
+        }
+    }
+
+    let decisions = LOGGER.decisions.load(Ordering::Relaxed);
+    let backtracks = LOGGER.backtracks.load(Ordering::Relaxed);
+    assert_eq!(decisions as u64, searcher.stats().decisions);
+    // One backtrack after each predecessor found, and one after each cell that could be neither alive nor dead.
+    assert_eq!((found, decisions, backtracks), (39, 1976, 548));
+}
@@ -0,0 +1,144 @@
+//! Loading patterns away from the edges of the grid: with a margin of dead cells, or in the middle.
+
+use undeath::{
+    grid::{LoadError, MAX_SIZE, SIZE},
+    Cell, Error, Grid, SearchResult, Searcher,
+};
+
+/// Writes `text` to a file called `name` in a fresh directory for `test`, and returns its path.
+fn write(test: &str, name: &str, text: &str) -> std::path::PathBuf {
+    let directory = std::env::temp_dir().join(format!("undeath-margin-{test}"));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join(name);
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+/// The distance from the alive cells to the nearest edge, on each side: left, top, right and bottom.
+fn margins(grid: &Grid) -> (i32, i32, i32, i32) {
+    let (x, y, width, height) = grid.bounding_box().unwrap();
+    (x, y, grid.width() - x - width, grid.height() - y - height)
+}
+
+#[test]
+fn patterns_are_moved_away_from_the_edges() {
+    // A block in the bottom right corner, straddling the seam of the torus.
+    let path = write("moved", "block.gol", "#......#\n\n\n\n\n\n\n#......#\n");
+    assert_eq!(
+        Grid::from_file(&path).unwrap().bounding_box(),
+        Some((7, 7, 2, 2))
+    );
+    for margin in 0..=3 {
+        let grid = Grid::from_file_with_margin(&path, margin as u32, SIZE, SIZE).unwrap();
+        assert_eq!((grid.width(), grid.height()), (SIZE, SIZE));
+        assert_eq!(margins(&grid), (margin, margin, 6 - margin, 6 - margin));
+        assert_eq!(grid.alive_cells().count(), 4);
+    }
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn patterns_need_room_for_their_margin() {
+    let path = write("room", "wide.rle", "x = 12, y = 2\n12o$o10bo!\n");
+    let grid = Grid::from_file_with_margin(&path, 3, 18, 8).unwrap();
+    assert_eq!((grid.width(), grid.height()), (18, 8));
+    assert_eq!(margins(&grid), (3, 3, 3, 3));
+    assert_eq!(grid.alive_cells().count(), 14);
+    let grid = Grid::from_file_with_margin(&path, 3, 20, 11).unwrap();
+    assert_eq!(margins(&grid), (3, 3, 5, 6));
+
+    // The grid is never grown to make room.
+    match Grid::from_file_with_margin(&path, 3, 17, 8) {
+        Err(error @ LoadError::NoRoom { .. }) => {
+            let message = error.to_string();
+            assert!(message.contains("12×2 pattern"), "{message}");
+            assert!(message.contains("needs 18×8"), "{message}");
+            assert!(message.contains("its 17×8 grid"), "{message}");
+            assert!(matches!(Error::from(error), Error::InvalidInput(_)));
+        }
+        other => panic!("expected no room for the margin, not {other:?}"),
+    }
+    assert!(matches!(
+        Grid::from_file_with_margin(&path, 3, 18, 7),
+        Err(LoadError::NoRoom { grid: (18, 7), .. })
+    ));
+
+    let margin = (MAX_SIZE as u32 - 12) / 2;
+    let largest = Grid::from_file_with_margin(&path, margin, MAX_SIZE, MAX_SIZE).unwrap();
+    assert_eq!(margins(&largest).0, margin as i32);
+    assert!(Grid::from_file_with_margin(&path, margin + 1, MAX_SIZE, MAX_SIZE).is_err());
+    assert!(Grid::from_file_with_margin(&path, u32::MAX, MAX_SIZE, MAX_SIZE).is_err());
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn empty_patterns_and_failures_to_read_pass_through() {
+    let path = write("empty", "empty.cells", "!Name: Nothing\n....\n");
+    let grid = Grid::from_file_with_margin(&path, 2, SIZE, SIZE).unwrap();
+    assert!(grid == Grid::default());
+    assert!(matches!(
+        Grid::from_file_with_margin(path.with_extension("gol"), 2, SIZE, SIZE),
+        Err(LoadError::Io { .. })
+    ));
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn a_mask_keeps_the_margin_dead_in_predecessors() {
+    let path = write("mask", "glider.gol", ".#\n..#\n###\n");
+    let target = Grid::from_file_with_margin(&path, 3, 9, 9).unwrap();
+    let mut inside = Grid::new(target.width(), target.height());
+    for y in 1..target.height() - 1 {
+        for x in 1..target.width() - 1 {
+            inside.set_wrapped(x, y, Cell::one());
+        }
+    }
+    let mut searcher = Searcher::with_mask(target.clone(), &inside);
+    let SearchResult::Found(parent, _) = searcher.search(usize::MAX).unwrap() else {
+        panic!("a glider has a predecessor away from the edges");
+    };
+    assert!(parent
+        .alive_cells()
+        .all(|(x, y)| inside.get_wrapped(x, y) == Cell::one()));
+    let mut stepped = parent;
+    stepped.step();
+    assert!(stepped == target);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn centred_patterns_map_back_to_the_file() {
+    for (name, text) in [
+        ("glider.cells", ".O\n..O\nOOO\n"),
+        ("block.gol", "#......#\n\n\n\n\n\n\n#......#\n"),
+        ("wide.rle", "x = 12, y = 2\n12o$o10bo!\n"),
+        ("blinker.gol", "\n\n\n\n\n\n###\n"),
+    ] {
+        let path = write("centred", name, text);
+        let loaded = Grid::from_file(&path).unwrap();
+        let (centred, (dx, dy)) = Grid::from_file_centered(&path).unwrap();
+        assert_eq!(
+            (centred.width(), centred.height()),
+            (loaded.width(), loaded.height())
+        );
+        assert_eq!(centred.alive_cells().count(), loaded.alive_cells().count());
+        assert!(loaded
+            .alive_cells()
+            .all(|(x, y)| centred.get_wrapped(x + dx, y + dy) == Cell::one()));
+        // Any odd cell left over goes to the right and the bottom.
+        let (left, top, right, bottom) = margins(&centred);
+        assert!(right - left == 0 || right - left == 1, "{name}");
+        assert!(bottom - top == 0 || bottom - top == 1, "{name}");
+    }
+    let path = write("centred", "empty.gol", "........\n");
+    let (empty, offset) = Grid::from_file_centered(&path).unwrap();
+    assert!(empty == Grid::default());
+    assert_eq!(offset, (0, 0));
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "each side of a grid must be between")]
+fn margins_need_a_valid_grid() {
+    let _ = Grid::from_file_with_margin("unread.gol", 1, 0, SIZE);
+}
@@ -0,0 +1,158 @@
+//! Guesses for the same target can be combined: searchers can share what they have deduced at their roots,
+//! and partial solutions of separate regions merge into one.
+
+use undeath::{bruteforce, searcher::Shaving, testing, Cell, Grid, Guess, SearchResult, Searcher};
+
+fn glider() -> Grid {
+    Grid::from_text("\n.#\n..#\n###")
+}
+
+/// An R-pentomino, whose root guess shaving can refine when confined to [`window`].
+fn r_pentomino() -> Grid {
+    testing::translate(&Grid::from_text(".##\n##.\n.#."), 2, 2)
+}
+
+/// The R-pentomino's bounding box, which confines predecessors tightly enough for shaving to help.
+fn window() -> Grid {
+    let mut mask = Grid::default();
+    for y in 2..5 {
+        for x in 2..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    mask
+}
+
+/// Every cell decided in `guess`, alive or dead.
+fn decided(guess: &undeath::Guess) -> Grid {
+    let mut decided = guess.alive();
+    decided += &guess.dead();
+    decided
+}
+
+fn search_all(mut searcher: Searcher) -> Vec<Grid> {
+    let mut result = Vec::new();
+    loop {
+        match searcher.search(1_000_000).unwrap() {
+            SearchResult::Found(grid, _) => result.push(grid),
+            SearchResult::Working(_) => {}
+            SearchResult::Unsatisfiable => break,
+            _ => unreachable!(),
+        }
+    }
+    result.sort();
+    result
+}
+
+#[test]
+fn disjoint_deductions_merge_into_both() {
+    let mut first = testing::apply_guesses(&glider(), &[(6, 6, false)]);
+    let second = testing::apply_guesses(&glider(), &[(1, 1, true)]);
+    let decisions = first.decided_cells();
+    let mut expected = decided(&first);
+    expected += &decided(&second);
+
+    testing::merge_root_implications(&mut first, &glider(), &second);
+    assert!(!first.found_contradiction());
+    let mut missing = expected;
+    missing.sub_assign_saturating(&decided(&first));
+    assert_eq!(missing.alive_cells().count(), 0);
+    // What was merged in counts as deduced, not guessed.
+    assert!(first.decided_cells() == decisions);
+    testing::check_guess(&first).unwrap();
+}
+
+#[test]
+fn conflicting_deductions_are_a_contradiction() {
+    let mut first = testing::apply_guesses(&glider(), &[(1, 1, true)]);
+    let second = testing::apply_guesses(&glider(), &[(1, 1, false)]);
+    testing::merge_root_implications(&mut first, &glider(), &second);
+    assert!(first.found_contradiction());
+}
+
+#[test]
+fn merging_a_shaved_root_keeps_every_predecessor() {
+    let mask = window();
+    let shaved = Searcher::with_mask(r_pentomino(), &mask).shave(Shaving::Root);
+    let mut plain = Searcher::with_mask(r_pentomino(), &mask);
+    assert!(plain.merge_root(shaved.root().unwrap()));
+    assert!(decided(plain.root().unwrap()) == decided(shaved.root().unwrap()));
+    // Merging the same implications again teaches nothing.
+    assert!(!plain.merge_root(&shaved.root().unwrap().clone()));
+
+    let expected = bruteforce::predecessors(&r_pentomino(), &mask).unwrap();
+    assert!(search_all(plain) == expected);
+}
+
+#[test]
+fn conflicting_roots_are_unsatisfiable() {
+    let mut searcher = Searcher::with_mask(r_pentomino(), &window());
+    // The mask rules out (7, 7) being alive.
+    let conflict = testing::apply_guesses(&r_pentomino(), &[(7, 7, true)]);
+    assert!(searcher.merge_root(&conflict));
+    assert!(searcher.root().is_none());
+    assert!(matches!(
+        searcher.search(1_000).unwrap(),
+        SearchResult::Unsatisfiable
+    ));
+}
+
+/// The columns `0..x`.
+fn columns_before(x: i32) -> Grid {
+    let mut columns = Grid::default();
+    for y in 0..8 {
+        for x in 0..x {
+            columns.set_wrapped(x, y, Cell::one());
+        }
+    }
+    columns
+}
+
+/// A guess deciding the cells of `predecessor` within `region`, and nothing else.
+fn part(next: &Grid, predecessor: &Grid, region: &Grid) -> Guess {
+    Guess::from_partial(
+        next,
+        &predecessor.and(region),
+        &predecessor.not().and(region),
+    )
+}
+
+#[test]
+fn halves_of_a_predecessor_merge_into_it() {
+    let Ok(SearchResult::Found(predecessor, _)) = Searcher::new(glider()).search(1_000_000) else {
+        panic!("a glider has predecessors");
+    };
+    let left = columns_before(4);
+    let (left, right) = (
+        part(&glider(), &predecessor, &left),
+        part(&glider(), &predecessor, &left.not()),
+    );
+    let merged = left.merge(&right, &glider()).unwrap();
+    assert!(!merged.found_contradiction());
+    assert_eq!(merged.decided_count(), 64);
+    assert!(merged.alive() == predecessor);
+    testing::check_guess(&merged).unwrap();
+    // Merging is symmetric.
+    assert!(right.merge(&left, &glider()).unwrap().alive() == predecessor);
+}
+
+#[test]
+fn merging_finds_conflicts_between_consistent_parts() {
+    // With nothing alive next frame, each of these dies alone, but together they give (2, 1) three neighbours.
+    let next = Grid::default();
+    let left = columns_before(2);
+    let left = part(&next, &Grid::from_text(".#\n.#"), &left);
+    let right = part(&next, &Grid::from_text("\n...#"), &columns_before(2).not());
+    assert!(!left.found_contradiction() && !right.found_contradiction());
+    let conflict = left.merge(&right, &next).err().expect("the parts conflict");
+    assert!((1..=3).contains(&conflict.x), "{conflict}");
+    assert!(!conflict.reason.contains("one guess"), "{conflict}");
+
+    // A cell decided both ways is named directly.
+    let clash = part(&next, &Grid::default(), &Grid::from_text(".#"));
+    let conflict = left
+        .merge(&clash, &next)
+        .err()
+        .expect("(1, 0) is alive on the left");
+    assert_eq!((conflict.x, conflict.y), (1, 0));
+}
@@ -0,0 +1,161 @@
+//! The solver under Life's rule in the von Neumann neighbourhood.
+
+use std::collections::BTreeSet;
+
+use undeath::{
+    grid::{Neighbourhood, SIZE},
+    rng::{RandomSource, Rng},
+    searcher::Strategy,
+    Cell, Error, Grid, SearchResult, Searcher,
+};
+
+const VON_NEUMANN: Neighbourhood = Neighbourhood::VonNeumann;
+
+fn stepped(grid: &Grid) -> Grid {
+    let mut grid = grid.clone();
+    grid.step_in(VON_NEUMANN);
+    grid
+}
+
+/// The 4×4 window from `(1, 2)` to `(4, 5)`.
+fn window() -> Vec<(i32, i32)> {
+    (2..6).flat_map(|y| (1..5).map(move |x| (x, y))).collect()
+}
+
+fn window_mask() -> Grid {
+    let mut mask = Grid::default();
+    for (x, y) in window() {
+        mask.set_wrapped(x, y, Cell::one());
+    }
+    mask
+}
+
+/// Every predecessor confined to `mask`, by stepping every subset of it.
+fn brute_force(target: &Grid) -> BTreeSet<Grid> {
+    let cells = window();
+    let mut found = BTreeSet::new();
+    for subset in 0u32..1 << cells.len() {
+        let mut grid = Grid::default();
+        for (i, &(x, y)) in cells.iter().enumerate() {
+            if subset & 1 << i != 0 {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+        if stepped(&grid) == *target {
+            found.insert(grid);
+        }
+    }
+    found
+}
+
+fn exhaust(mut searcher: Searcher) -> BTreeSet<Grid> {
+    let mut found = BTreeSet::new();
+    loop {
+        match searcher.search(usize::MAX).unwrap() {
+            SearchResult::Found(grid, _) => {
+                assert!(found.insert(grid), "found a predecessor twice")
+            }
+            SearchResult::Unsatisfiable => return found,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn steps_by_hand() {
+    // Only the middle of a row of three has two neighbours, and no cell around it has three.
+    assert!(stepped(&Grid::from_text("###")) == Grid::from_text(".#"));
+    // A block is still, since each of its cells has two neighbours.
+    let block = Grid::from_text("##\n##");
+    assert!(stepped(&block) == block);
+    // The cell in the middle of a T is born, and the arms, with no neighbours, die.
+    assert!(stepped(&Grid::from_text(".#\n#.#")) == Grid::from_text("\n.#"));
+    // The centre of a plus has four neighbours and the arms one, so every cell dies.
+    assert!(stepped(&Grid::from_text(".#\n###\n.#")) == Grid::default());
+}
+
+#[test]
+fn counts_four_neighbours() {
+    let mut rng = Rng::new(470);
+    let mut grid = Grid::default();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            if rng.gen_bool(0.5) {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    let counts = grid.neighbours_in(VON_NEUMANN);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let expected = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                .into_iter()
+                .map(|(dx, dy)| grid.get_wrapped(x + dx, y + dy).value)
+                .sum::<i32>();
+            assert_eq!(counts.get_wrapped(x, y).value, expected);
+        }
+    }
+    assert!(grid.neighbours_in(Neighbourhood::Moore) == grid.neighbours());
+}
+
+#[test]
+fn finds_predecessors_of_soups() {
+    let mut rng = Rng::new(470);
+    for _ in 0..8 {
+        let mut soup = Grid::default();
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                if rng.gen_bool(0.2) {
+                    soup.set_wrapped(x, y, Cell::one());
+                }
+            }
+        }
+        let target = stepped(&soup);
+        let mut searcher = Searcher::new(target.clone()).neighbourhood(VON_NEUMANN);
+        let parent = loop {
+            match searcher.search(1_000_000).unwrap() {
+                SearchResult::Found(grid, _) => break grid,
+                SearchResult::Working(_) => {}
+                _ => panic!("the soup is a predecessor"),
+            }
+        };
+        assert!(stepped(&parent) == target);
+    }
+}
+
+#[test]
+fn enumerates_masked_predecessors_exactly() {
+    let mask = window_mask();
+    for text in [
+        "\n\n\n..###",
+        "\n\n\n..#\n..#",
+        "\n\n..##\n..##",
+        "\n\n..#\n.#.#",
+    ] {
+        let target = Grid::from_text(text);
+        let expected = brute_force(&target);
+        for block_propagation in [false, true] {
+            let searcher = Searcher::with_mask(target.clone(), &mask)
+                .neighbourhood(VON_NEUMANN)
+                .block_propagation(block_propagation);
+            assert!(exhaust(searcher) == expected, "{}", target.render());
+        }
+    }
+}
+
+#[test]
+fn rejects_moore_only_features() {
+    let target = Grid::from_text("##\n##");
+    let local = Strategy::Local {
+        noise_percent: Strategy::DEFAULT_NOISE_PERCENT,
+        restart_after: Strategy::DEFAULT_RESTART_AFTER,
+    };
+    let mut searcher = Searcher::new(target.clone())
+        .neighbourhood(VON_NEUMANN)
+        .strategy(local);
+    assert!(matches!(searcher.search(100), Err(Error::InvalidInput(_))));
+    let windows = Searcher::new(target)
+        .neighbourhood(VON_NEUMANN)
+        .check_windows(3);
+    assert!(matches!(windows, Err(Error::InvalidInput(_))));
+}
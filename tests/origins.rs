@@ -0,0 +1,67 @@
+//! Guessed cells are told apart from cells deduced by propagation, and the searcher keeps count of both.
+
+use undeath::{testing, Grid, SearchResult, Searcher};
+
+fn glider() -> Grid {
+    Grid::from_text("\n.#\n..#\n###")
+}
+
+/// Five guesses, then an alive guess at (0, 2) that decides many cells by propagation.
+const SCRIPT: [(i32, i32, bool); 6] = [
+    (3, 4, false),
+    (0, 4, true),
+    (1, 2, true),
+    (3, 6, false),
+    (7, 0, false),
+    (0, 2, true),
+];
+
+#[test]
+fn a_cascading_guess_is_one_decision() {
+    let before = testing::apply_guesses(&glider(), &SCRIPT[..5]);
+    let after = testing::apply_guesses(&glider(), &SCRIPT);
+    assert!(!after.found_contradiction());
+
+    let mut new_decisions = after.decided_cells();
+    new_decisions -= &before.decided_cells();
+    assert_eq!(new_decisions.alive_cells().collect::<Vec<_>>(), [(0, 2)]);
+    assert!(
+        after.forced_cells().alive_cells().count() > before.forced_cells().alive_cells().count()
+    );
+    testing::check_guess(&after).unwrap();
+}
+
+#[test]
+fn origins_partition_the_decided_cells() {
+    let guess = testing::apply_guesses(&glider(), &SCRIPT);
+    let mut decided = guess.alive();
+    decided += &guess.dead();
+    let mut origins = guess.decided_cells();
+    origins += &guess.forced_cells();
+    assert!(origins == decided);
+    assert_eq!(decided.alive_cells().count(), guess.decided_count());
+}
+
+#[test]
+fn shaved_cells_are_forced() {
+    let mut guess = testing::apply_guesses(&glider(), &SCRIPT);
+    let (decisions, decided) = (guess.decided_cells(), guess.decided_count());
+    testing::shave(&mut guess, &glider());
+    assert!(guess.decided_count() > decided);
+    assert!(guess.decided_cells() == decisions);
+}
+
+#[test]
+fn stats_count_every_guess() {
+    let mut searcher = Searcher::new(glider());
+    assert_eq!(searcher.stats().decisions, 0);
+    assert_eq!(searcher.stats().deductions_per_decision(), 0.0);
+    while let SearchResult::Working(_) = searcher.search(10).unwrap() {}
+    let stats = searcher.stats();
+    assert!(stats.decisions > 0);
+    assert!(stats.deductions > 0);
+    assert_eq!(
+        stats.deductions_per_decision(),
+        stats.deductions as f64 / stats.decisions as f64
+    );
+}
@@ -0,0 +1,160 @@
+//! Parallel enumeration finds the same predecessors as a serial search, however the work is divided.
+
+use std::{collections::BTreeSet, time::Duration};
+
+use undeath::{
+    parallel::{enumerate, enumerate_streaming, Dedup, ParallelOptions},
+    Cell, Grid, SearchResult, Searcher,
+};
+
+/// A blinker, with predecessors confined to the 5×4 window around it, which has a few hundred of them.
+fn blinker_and_mask() -> (Grid, Grid) {
+    let blinker = Grid::from_text("\n\n\n..###");
+    let mut mask = Grid::default();
+    for y in 2..6 {
+        for x in 1..6 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    (blinker, mask)
+}
+
+fn serial(next: &Grid, mask: &Grid) -> BTreeSet<Grid> {
+    let mut searcher = Searcher::with_mask(next.clone(), mask);
+    let mut found = BTreeSet::new();
+    loop {
+        match searcher.search(usize::MAX).unwrap() {
+            SearchResult::Found(grid, _) => assert!(found.insert(grid)),
+            SearchResult::Unsatisfiable => return found,
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn options(threads: usize, split_depth: usize, mask: &Grid) -> ParallelOptions {
+    ParallelOptions {
+        threads,
+        split_depth,
+        // Small slices, so that workers check for the signal to stop often.
+        slice: 16,
+        mask: Some(mask.clone()),
+        ..ParallelOptions::default()
+    }
+}
+
+#[test]
+fn matches_serial_search() {
+    let (blinker, mask) = blinker_and_mask();
+    let expected = serial(&blinker, &mask);
+    assert!(expected.len() > 100);
+    for threads in [1, 2, 3, 8] {
+        for split_depth in [0, 3, 7] {
+            let enumeration = enumerate(&blinker, &options(threads, split_depth, &mask)).unwrap();
+            assert!(enumeration.exhausted);
+            assert_eq!(enumeration.duplicates, 0);
+            assert_eq!(
+                enumeration.predecessors,
+                expected.iter().cloned().collect::<Vec<_>>(),
+                "{threads} threads, split depth {split_depth}"
+            );
+        }
+    }
+}
+
+/// The empty grid, with predecessors confined to a band two rows high around the torus,
+/// so that the predecessors of each shape include its translations along the band.
+#[test]
+fn canonical_dedup_merges_translations() {
+    let mut band = Grid::default();
+    for y in 3..5 {
+        for x in 0..8 {
+            band.set_wrapped(x, y, Cell::one());
+        }
+    }
+    let empty = Grid::default();
+    let all = serial(&empty, &band);
+    let expected = all.iter().map(Grid::canonical).collect::<BTreeSet<_>>();
+    assert!(expected.len() < all.len());
+    for threads in [1, 4] {
+        let enumeration = enumerate(
+            &empty,
+            &ParallelOptions {
+                dedup: Dedup::Canonical,
+                ..options(threads, 5, &band)
+            },
+        )
+        .unwrap();
+        assert!(enumeration.exhausted);
+        assert_eq!(enumeration.duplicates, all.len() - expected.len());
+        assert_eq!(
+            enumeration.predecessors,
+            expected.iter().cloned().collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn limit_applies_across_workers() {
+    let (blinker, mask) = blinker_and_mask();
+    let expected = serial(&blinker, &mask);
+    for threads in [1, 4] {
+        let enumeration = enumerate(
+            &blinker,
+            &ParallelOptions {
+                limit: Some(5),
+                ..options(threads, 5, &mask)
+            },
+        )
+        .unwrap();
+        assert!(!enumeration.exhausted);
+        assert_eq!(enumeration.predecessors.len(), 5);
+        assert!(enumeration
+            .predecessors
+            .iter()
+            .all(|grid| expected.contains(grid)));
+    }
+}
+
+/// Workers that size their slices by time still search every subtree to the end.
+#[test]
+fn cadence_sized_slices_match_serial_search() {
+    let (blinker, mask) = blinker_and_mask();
+    let expected = serial(&blinker, &mask);
+    let enumeration = enumerate(
+        &blinker,
+        &ParallelOptions {
+            cadence: Some(Duration::from_millis(1)),
+            ..options(3, 5, &mask)
+        },
+    )
+    .unwrap();
+    assert!(enumeration.exhausted);
+    assert_eq!(
+        enumeration.predecessors,
+        expected.iter().cloned().collect::<Vec<_>>()
+    );
+}
+
+/// Streaming reports each distinct predecessor once, numbered in the order they were reported.
+#[test]
+fn streaming_reports_exactly_the_predecessors_found() {
+    let (blinker, mask) = blinker_and_mask();
+    for (dedup, limit) in [(Dedup::Exact, None), (Dedup::Canonical, Some(7))] {
+        let mut streamed = Vec::new();
+        let enumeration = enumerate_streaming(
+            &blinker,
+            &ParallelOptions {
+                dedup,
+                limit,
+                ..options(4, 5, &mask)
+            },
+            |discovery| {
+                assert_eq!(discovery.index, streamed.len());
+                streamed.push(discovery.predecessor.clone());
+            },
+        )
+        .unwrap();
+        let streamed = streamed.into_iter().collect::<BTreeSet<_>>();
+        assert!(streamed.into_iter().eq(enumeration.predecessors));
+    }
+}
@@ -0,0 +1,166 @@
+//! Regression tests for the `.gol` parser on malformed and unusual input.
+//! Inputs that the fuzz target in `fuzz/` finds problems with belong here.
+
+use undeath::{
+    grid::{LoadError, ParseGridError, MAX_SIZE, MIN_SIZE},
+    Cell, Error, Grid,
+};
+
+fn parse_error(bytes: &[u8]) -> (usize, usize) {
+    match Grid::from_bytes(bytes) {
+        Err(Error::Parse { line, column, .. }) => (line, column),
+        Err(error) => panic!("expected a parse error, got {error}"),
+        Ok(grid) => panic!("expected a parse error, got {grid:?}"),
+    }
+}
+
+#[test]
+fn empty_input_is_empty_grid() {
+    assert_eq!(Grid::from_bytes(b"").unwrap(), Grid::default());
+}
+
+#[test]
+fn multibyte_character_is_one_cell() {
+    let grid = Grid::from_bytes("█.█\n".as_bytes()).unwrap();
+    assert_eq!(grid, Grid::from_text("#.#"));
+}
+
+#[test]
+fn invalid_utf8_reports_character_column() {
+    // The stray byte follows a three-byte character, which counts as a single column.
+    assert_eq!(parse_error(b"........\n\xe2\x96\x88.\xff#\n"), (2, 3));
+}
+
+#[test]
+fn truncated_multibyte_character_at_end_of_input() {
+    assert_eq!(parse_error(b"..\xe2\x96"), (1, 3));
+}
+
+#[test]
+fn crlf_line_endings() {
+    assert_eq!(
+        Grid::from_bytes(b".#\r\n#.\r\n").unwrap(),
+        Grid::from_text(".#\n#.")
+    );
+}
+
+#[test]
+fn oversized_lines_wrap_around() {
+    let mut line = vec![b'.'; 1_000_000];
+    line.push(b'#');
+    let grid = Grid::from_bytes(&line).unwrap();
+    // The grid grows to hold long lines, up to the largest size, and wraps them around past that.
+    assert_eq!(grid.width(), undeath::grid::MAX_SIZE);
+    let x = 1_000_000 % undeath::grid::MAX_SIZE;
+    assert!(grid.get_wrapped(x, 0) == Cell::one());
+    assert_eq!(grid.alive_cells().count(), 1);
+}
+
+fn compact_guess_error(text: &str) -> (usize, usize) {
+    match undeath::Guess::parse_compact(text) {
+        Err(Error::Parse { line, column, .. }) => (line, column),
+        Err(error) => panic!("expected a parse error, got {error}"),
+        Ok(guess) => panic!("expected a parse error, got\n{guess}"),
+    }
+}
+
+#[test]
+fn malformed_compact_guesses_are_located() {
+    assert_eq!(compact_guess_error(""), (1, 1));
+    assert_eq!(compact_guess_error("0 of 64 decided, unsure"), (1, 18));
+    assert_eq!(compact_guess_error("0 of 99 decided, consistent"), (1, 1));
+    assert_eq!(
+        compact_guess_error("1 of 64 decided, consistent\n??x"),
+        (2, 3)
+    );
+    assert_eq!(
+        compact_guess_error("1 of 64 decided, consistent\n?????????"),
+        (2, 9)
+    );
+    // The header must agree with the cells.
+    assert_eq!(
+        compact_guess_error("2 of 64 decided, consistent\n#"),
+        (1, 1)
+    );
+}
+
+#[test]
+fn parsing_agrees_with_the_text_reader() {
+    for text in [
+        "#.#",
+        ".#\n..#\n###\n",
+        ".#\r\n#.\r\n",
+        "\n\n..#",
+        "...........#",
+    ] {
+        assert_eq!(
+            text.parse::<Grid>().unwrap(),
+            Grid::from_text(text),
+            "{text:?}"
+        );
+    }
+}
+
+#[test]
+fn parsing_rejects_what_the_text_reader_wraps() {
+    assert_eq!("".parse::<Grid>(), Err(ParseGridError::Empty));
+    let line = format!("\n{}#", ".".repeat(MAX_SIZE as usize));
+    assert_eq!(
+        line.parse::<Grid>(),
+        Err(ParseGridError::LineTooLong {
+            line: 2,
+            length: MAX_SIZE as usize + 1,
+            pattern: (MAX_SIZE as usize + 1, 2),
+        })
+    );
+    let rows = "#\n".repeat(MAX_SIZE as usize + 3);
+    assert_eq!(
+        rows.parse::<Grid>(),
+        Err(ParseGridError::TooManyRows {
+            rows: MAX_SIZE as usize + 3,
+            pattern: (1, MAX_SIZE as usize + 3),
+        })
+    );
+    // The largest grid itself is fine.
+    let grid = ".\n".repeat(MAX_SIZE as usize).parse::<Grid>().unwrap();
+    assert_eq!((grid.width(), grid.height()), (MIN_SIZE, MAX_SIZE));
+}
+
+#[test]
+fn files_share_the_parser() {
+    let directory = std::env::temp_dir().join("undeath-parse-files");
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("glider.gol");
+    std::fs::write(&path, ".#\n..#\n###\n").unwrap();
+    assert_eq!(
+        Grid::from_file(&path).unwrap(),
+        ".#\n..#\n###".parse().unwrap()
+    );
+    std::fs::write(&path, "").unwrap();
+    assert!(matches!(
+        Grid::from_file(&path),
+        Err(LoadError::Parse {
+            source: ParseGridError::Empty,
+            ..
+        })
+    ));
+    std::fs::write(&path, format!("#\n{}", "#".repeat(2000))).unwrap();
+    assert!(matches!(
+        Grid::from_file(&path),
+        Err(LoadError::Parse {
+            source: ParseGridError::LineTooLong {
+                line: 2,
+                length: 2000,
+                pattern: (2000, 2),
+            },
+            ..
+        })
+    ));
+    // Asked to, files wrap around as `Grid::from_text` does.
+    let wrapped = Grid::from_file_with_wrap(&path, true).unwrap();
+    assert!(wrapped == Grid::from_text(&std::fs::read_to_string(&path).unwrap()));
+    assert_eq!(wrapped.width(), MAX_SIZE);
+    std::fs::write(&path, "").unwrap();
+    assert!(Grid::from_file_with_wrap(&path, true).unwrap() == Grid::from_text(""));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
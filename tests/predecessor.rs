@@ -0,0 +1,29 @@
+//! The convenience methods on `Grid` agree with the solver they wrap.
+
+use undeath::{solve_all, Cell, Grid, PredecessorOutcome};
+
+#[test]
+fn finds_what_solve_finds() {
+    let glider = Grid::from_text(".#\n..#\n###");
+    let expected = solve_all(&glider, 4).unwrap();
+    assert_eq!(
+        glider.predecessor().unwrap(),
+        PredecessorOutcome::Found(expected[0].clone())
+    );
+    assert_eq!(
+        glider.predecessors_up_to(4).unwrap(),
+        PredecessorOutcome::Found(expected)
+    );
+}
+
+#[test]
+fn proves_a_full_grid_has_no_predecessor() {
+    // Every cell would need three alive neighbours, or two if alive itself, which no grid gives them all.
+    let full = Grid::fill(Cell::one());
+    assert_eq!(full.predecessor().unwrap(), PredecessorOutcome::ProvenNone);
+    assert_eq!(
+        full.predecessors_up_to(10).unwrap(),
+        PredecessorOutcome::ProvenNone
+    );
+    assert_eq!(full.predecessor().unwrap().found(), None);
+}
@@ -0,0 +1,97 @@
+//! The progress estimate never goes backwards, and reaches one when the search space runs out.
+
+use undeath::{searcher::Strategy, Cell, Grid, SearchResult, Searcher};
+
+/// A blinker, with predecessors confined to the 4×4 window around it.
+fn masked_blinker() -> (Grid, Grid) {
+    let blinker = Grid::from_text("\n\n\n..###");
+    let mut mask = Grid::default();
+    for y in 2..6 {
+        for x in 1..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    (blinker, mask)
+}
+
+/// Searches one iteration at a time until the search ends, recording the estimate after each.
+fn estimates(mut searcher: Searcher) -> (Vec<f64>, SearchResult) {
+    let mut estimates = vec![searcher.stats().progress_estimate];
+    loop {
+        let result = searcher.search(1).unwrap();
+        estimates.push(searcher.stats().progress_estimate);
+        if matches!(
+            result,
+            SearchResult::Unsatisfiable | SearchResult::Inconclusive
+        ) {
+            return (estimates, result);
+        }
+    }
+}
+
+#[test]
+fn rises_monotonically_to_one() {
+    let (blinker, mask) = masked_blinker();
+    let (estimates, result) = estimates(Searcher::with_mask(blinker, &mask));
+    assert!(matches!(result, SearchResult::Unsatisfiable));
+    assert_eq!(estimates[0], 0.0);
+    for pair in estimates.windows(2) {
+        assert!(
+            pair[0] <= pair[1],
+            "the estimate fell from {} to {}",
+            pair[0],
+            pair[1]
+        );
+    }
+    assert_eq!(*estimates.last().unwrap(), 1.0);
+    // Somewhere along the way, the estimate is strictly between the ends.
+    assert!(estimates
+        .iter()
+        .any(|&estimate| 0.0 < estimate && estimate < 1.0));
+}
+
+#[test]
+fn contradictory_roots_are_exhausted_at_once() {
+    // Nothing steps to a lone cell with every other cell forced dead.
+    let lone = Grid::from_text("\n\n\n...#");
+    let mut mask = Grid::default();
+    mask.set_wrapped(3, 3, Cell::one());
+    let (estimates, result) = estimates(Searcher::with_mask(lone, &mask));
+    assert!(matches!(result, SearchResult::Unsatisfiable));
+    assert_eq!(*estimates.last().unwrap(), 1.0);
+}
+
+#[test]
+fn other_strategies_only_report_exhaustion() {
+    let (blinker, mask) = masked_blinker();
+    let searcher =
+        Searcher::with_mask(blinker, &mask).strategy(Strategy::BestFirst { max_open: 4 });
+    let (estimates, result) = estimates(searcher);
+    assert!(matches!(result, SearchResult::Unsatisfiable));
+    assert!(estimates[..estimates.len() - 1]
+        .iter()
+        .all(|&estimate| estimate == 0.0));
+    assert_eq!(*estimates.last().unwrap(), 1.0);
+}
+
+#[test]
+fn depth_is_zero_outside_the_search() {
+    let (blinker, mask) = masked_blinker();
+    let mut searcher = Searcher::with_mask(blinker, &mask);
+    assert_eq!(searcher.depth(), 0);
+    let mut depths = Vec::new();
+    loop {
+        let result = searcher.search(1).unwrap();
+        depths.push(searcher.depth());
+        if matches!(result, SearchResult::Unsatisfiable) {
+            break;
+        }
+    }
+    assert_eq!(depths.last(), Some(&0));
+    // Each iteration guesses at most one more cell.
+    assert!(
+        depths.windows(2).all(|pair| pair[1] <= pair[0] + 1),
+        "{depths:?}"
+    );
+    assert!(depths.iter().any(|&depth| depth > 1));
+}
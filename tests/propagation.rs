@@ -0,0 +1,106 @@
+//! Pins down exactly what constraint propagation deduces, so that changes to its internals can be checked
+//! against the previous implementation.
+//!
+//! Each case applies a random sequence of guesses to a random target, and records every decided cell,
+//! the cells suggested to the search, and any contradiction found, in `tests/propagation/outcomes.txt`.
+//! A change meant to alter what propagation deduces should regenerate the file with
+//!
+//! ```text
+//! UPDATE_PROPAGATION=1 cargo test --test propagation
+//! ```
+//!
+//! and explain the differences; a refactor should leave it untouched.
+
+use std::{fmt::Write, path::PathBuf};
+
+use undeath::{
+    grid::SIZE,
+    rng::{RandomSource, Rng},
+    testing, Cell, Grid,
+};
+
+const CASES: u64 = 512;
+const MAX_GUESSES: u64 = 40;
+
+/// The cells of `grid` as a bitmask, with `(x, y)` at bit `x + SIZE * y`.
+fn bits(grid: &Grid) -> u64 {
+    grid.alive_cells()
+        .fold(0, |bits, (x, y)| bits | 1 << (x + SIZE * y))
+}
+
+fn outcome(case: u64) -> String {
+    let mut rng = Rng::new(case);
+    let mut next = Grid::default();
+    let density = rng.gen_range(1..6) as f64 / 10.0;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            if rng.gen_bool(density) {
+                next.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    let guesses = (0..rng.gen_range(1..MAX_GUESSES + 1))
+        .map(|_| {
+            (
+                rng.gen_range(0..SIZE as u64) as i32,
+                rng.gen_range(0..SIZE as u64) as i32,
+                rng.gen_bool(0.5),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let guess = testing::apply_guesses(&next, &guesses);
+    let (try_alive, try_dead) = testing::suggestions(&guess);
+    format!(
+        "{case} alive={:016x} dead={:016x} try_alive={:016x} try_dead={:016x} {}",
+        bits(&guess.alive()),
+        bits(&guess.dead()),
+        bits(&try_alive),
+        bits(&try_dead),
+        guess.contradiction().unwrap_or("consistent"),
+    )
+}
+
+#[test]
+fn propagation_is_unchanged() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/propagation/outcomes.txt");
+    let mut actual = String::new();
+    for case in 0..CASES {
+        writeln!(actual, "{}", outcome(case)).unwrap();
+    }
+    if std::env::var_os("UPDATE_PROPAGATION").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap();
+    for (expected, actual) in expected.lines().zip(actual.lines()) {
+        assert_eq!(
+            expected, actual,
+            "propagation changed; run with UPDATE_PROPAGATION=1 if this is intended"
+        );
+    }
+    assert_eq!(expected.lines().count(), actual.lines().count());
+}
+
+/// A cell that is born on the next frame needs exactly three alive neighbours.
+/// Once only three of its neighbours might be alive, deciding that the cell itself was dead forces all three.
+#[test]
+fn deciding_a_cell_revisits_its_own_constraint() {
+    let mut next = Grid::default();
+    next.set_wrapped(3, 3, Cell::one());
+    let mut guesses = [(2, 2), (3, 2), (4, 2), (2, 3), (4, 3)]
+        .map(|(x, y)| (x, y, false))
+        .to_vec();
+    guesses.push((3, 3, false));
+
+    let guess = testing::apply_guesses(&next, &guesses);
+    assert_eq!(guess.contradiction(), None);
+    for (x, y) in [(2, 4), (3, 4), (4, 4)] {
+        assert!(
+            guess.alive().get_wrapped(x, y) == Cell::one(),
+            "({x}, {y}) should be forced alive\n{}",
+            guess.render()
+        );
+    }
+}
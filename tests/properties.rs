@@ -0,0 +1,371 @@
+//! Properties of the simulation and the solver that should hold for every grid.
+
+use std::collections::BTreeSet;
+
+use proptest::prelude::*;
+use undeath::{
+    grid::{NeighbourField, Symmetry, Topology, MIN_SIZE, SIZE},
+    testing, Cell, Error, Guess, Options,
+};
+
+fn stepped(grid: &undeath::Grid) -> undeath::Grid {
+    let mut grid = grid.clone();
+    grid.step();
+    grid
+}
+
+/// Sums the values within Chebyshev distance `radius` of `(x, y)` by visiting each distinct cell in the square.
+fn naive_neighbourhood_count(grid: &undeath::Grid, x: i32, y: i32, radius: u32) -> i32 {
+    let radius = radius as i32;
+    let mut cells = BTreeSet::new();
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            cells.insert(((x + dx).rem_euclid(SIZE), (y + dy).rem_euclid(SIZE)));
+        }
+    }
+    cells
+        .into_iter()
+        .map(|(x, y)| grid.get_wrapped(x, y).value)
+        .sum()
+}
+
+/// The number of alive cells among the eight around `(x, y)`, looking each one up on its own.
+/// On a plane, the cells past the edges are dead.
+fn naive_moore_count(grid: &undeath::Grid, x: i32, y: i32) -> i32 {
+    let (width, height) = (grid.width(), grid.height());
+    let mut count = 0;
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let (nx, ny) = (x + dx, y + dy);
+            let on_grid = (0..width).contains(&nx) && (0..height).contains(&ny);
+            if (dx, dy) != (0, 0) && (on_grid || grid.topology() == Topology::Torus) {
+                count += grid.get_wrapped(nx, ny).value;
+            }
+        }
+    }
+    count
+}
+
+/// Two grids of the same random size whose cells take any value, with the extremes of `i32` and values near zero likely.
+/// The least translation of `grid`, found by building and comparing every one.
+fn naive_canonical(grid: &undeath::Grid) -> undeath::Grid {
+    (0..grid.height())
+        .flat_map(|dy| {
+            (0..grid.width())
+                .map(move |dx| testing::translate(grid, dx, dy).with_topology(grid.topology()))
+        })
+        .min()
+        .unwrap()
+}
+
+fn value_grids() -> impl Strategy<Value = (undeath::Grid, undeath::Grid)> {
+    (MIN_SIZE..=12, MIN_SIZE..=12).prop_flat_map(|(width, height)| {
+        let value = prop_oneof![
+            any::<i32>(),
+            Just(i32::MAX),
+            Just(i32::MIN),
+            Just(i32::MAX - 1),
+            Just(i32::MIN + 1),
+            -3..=3,
+        ];
+        let grid =
+            proptest::collection::vec(value, (width * height) as usize).prop_map(move |values| {
+                let mut grid = undeath::Grid::new(width, height);
+                for (i, value) in values.into_iter().enumerate() {
+                    let i = i as i32;
+                    grid.set_wrapped(i % width, i / width, Cell { value });
+                }
+                grid
+            });
+        (grid.clone(), grid)
+    })
+}
+
+/// Every width around a word's worth of cells, where packing a row into a word is most likely to go wrong,
+/// on each topology, with alive cells at the corners and along the edges.
+#[test]
+fn step_bitparallel_matches_step_around_a_word() {
+    for width in [MIN_SIZE, 31, 32, 33, 62, 63, 64, 65] {
+        for topology in [Topology::Torus, Topology::Plane] {
+            let mut grid = undeath::Grid::new(width, 5).with_topology(topology);
+            for (x, y) in [
+                (0, 0),
+                (width - 1, 0),
+                (0, 4),
+                (width - 1, 4),
+                (1, 2),
+                (width - 2, 2),
+            ] {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+            for x in 0..width {
+                grid.set_wrapped(x, 1, Cell::one());
+            }
+            let (mut bitparallel, mut scalar) = (grid.clone(), grid);
+            for generation in 0..8 {
+                bitparallel.step_bitparallel();
+                scalar.step();
+                assert!(
+                    bitparallel == scalar,
+                    "{width} wide on a {topology:?}, generation {generation}"
+                );
+            }
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn step_commutes_with_translation(
+        grid in testing::grid(0.4),
+        (dx, dy) in testing::coordinate(),
+    ) {
+        prop_assert!(
+            stepped(&testing::translate(&grid, dx, dy)) == testing::translate(&stepped(&grid), dx, dy)
+        );
+    }
+
+    #[test]
+    fn step_commutes_with_rotation(grid in testing::grid(0.4)) {
+        prop_assert!(stepped(&testing::rotate(&grid)) == testing::rotate(&stepped(&grid)));
+    }
+
+    #[test]
+    fn step_bitparallel_matches_step(grid in testing::grid(0.4)) {
+        let mut bitparallel = grid.clone();
+        bitparallel.step_bitparallel();
+        prop_assert!(bitparallel == stepped(&grid));
+    }
+
+    #[test]
+    fn step_bitparallel_matches_step_on_any_grid(
+        grid in (0.05f64..0.95).prop_flat_map(|density| testing::grid_of_any_size(70, density)),
+        generations in 1usize..6,
+    ) {
+        // Grids wider than a word are stepped by `step` itself, so both sides of 64 are covered.
+        let (mut bitparallel, mut scalar) = (grid.clone(), grid);
+        for _ in 0..generations {
+            bitparallel.step_bitparallel();
+            scalar.step();
+            prop_assert!(bitparallel == scalar);
+        }
+    }
+
+    #[test]
+    fn neighbour_counts_match_naive(grid in testing::grid_of_any_size(24, 0.4)) {
+        // Whatever was in the buffer before is overwritten.
+        let mut counts = undeath::Grid::new(grid.width(), grid.height()).not();
+        grid.neighbours_into(&mut counts);
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                prop_assert_eq!(counts.get_wrapped(x, y).value, naive_moore_count(&grid, x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn fused_neighbour_counts_match_separable(grid in testing::grid_of_any_size(40, 0.4)) {
+        let (mut fused, mut separable) = (grid.not(), grid.clone());
+        grid.neighbours_into(&mut fused);
+        testing::separable_neighbours_into(&grid, &mut separable, &mut Vec::new());
+        prop_assert!(fused == separable);
+    }
+
+    #[test]
+    fn neighbourhood_counts_match_naive(grid in testing::grid(0.4), radius in 0u32..10) {
+        let counts = grid.neighbourhood_counts(radius);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                prop_assert_eq!(
+                    counts.get_wrapped(x, y).value,
+                    naive_neighbourhood_count(&grid, x, y, radius)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rendering_into_a_buffer_matches_render(
+        grid in testing::grid_of_any_size(24, 0.4),
+        guesses in testing::guesses(12),
+    ) {
+        // The buffer is appended to, so what was there before stays in front.
+        let mut buffer = String::from("previous frame\n");
+        grid.render_into(&mut buffer);
+        let rendered = grid.render();
+        prop_assert_eq!(buffer.strip_prefix("previous frame\n"), Some(rendered.as_str()));
+
+        // Reused after clearing, it holds exactly the next frame.
+        let guess = testing::apply_guesses(&stepped(&grid), &guesses);
+        buffer.clear();
+        guess.render_into(&mut buffer);
+        prop_assert_eq!(&buffer, &guess.render());
+        buffer.clear();
+        grid.render_into(&mut buffer);
+        prop_assert_eq!(&buffer, &rendered);
+    }
+
+    #[test]
+    fn boolean_ops_are_set_algebra(a in testing::grid(0.4), b in testing::grid(0.4)) {
+        let empty = undeath::Grid::default();
+        // Neighbour counts have values above one, which the boolean operations treat as alive.
+        for (a, b) in [(a.clone(), b.clone()), (a.neighbours(), b.neighbours()), (a, b.neighbours())] {
+            let normalized = a.clamp_scalar(0, 1);
+            prop_assert!(a.xor(&a) == empty);
+            prop_assert!(a.or(&empty) == normalized);
+            prop_assert!(a.and(&a) == normalized);
+            prop_assert!(a.not().not() == normalized);
+            prop_assert!(a.and(&b).not() == a.not().or(&b.not()));
+            prop_assert!(a.xor(&b) == a.or(&b).and(&a.and(&b).not()));
+            let mut in_place = a.clone();
+            in_place.xor_assign(&b);
+            prop_assert!(in_place == a.xor(&b));
+        }
+    }
+
+    #[test]
+    fn grid_arithmetic_matches_cell_by_cell_arithmetic((a, b) in value_grids()) {
+        let (mut sum, mut difference, mut clamped) = (a.clone(), a.clone(), a.clone());
+        sum += &b;
+        difference -= &b;
+        clamped.sub_assign_saturating(&b);
+        for y in 0..a.height() {
+            for x in 0..a.width() {
+                let (a, b) = (a.get_wrapped(x, y).value, b.get_wrapped(x, y).value);
+                prop_assert_eq!(sum.get_wrapped(x, y).value, a.saturating_add(b));
+                prop_assert_eq!(difference.get_wrapped(x, y).value, a.saturating_sub(b));
+                prop_assert_eq!(clamped.get_wrapped(x, y).value, a.saturating_sub(b).max(0));
+            }
+        }
+    }
+
+    #[test]
+    fn scalar_ops_saturate(grid in testing::grid(0.4), factor in any::<i32>()) {
+        let counts = grid.neighbours();
+        prop_assert!(counts.mul_scalar(1) == counts);
+        let scaled = counts.mul_scalar(factor);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let value = counts.get_wrapped(x, y).value;
+                prop_assert_eq!(scaled.get_wrapped(x, y).value, value.saturating_mul(factor));
+            }
+        }
+        prop_assert!(scaled.clamp_scalar(0, 1) == scaled.or(&undeath::Grid::default()));
+    }
+
+    #[test]
+    fn neighbour_fields_match_fresh_counts_after_any_toggles(
+        grid in testing::grid_of_any_size(20, 0.4),
+        toggles in proptest::collection::vec((0..400i32, 0..400i32), 0..200),
+    ) {
+        let (width, height) = (grid.width(), grid.height());
+        let mut field = NeighbourField::new(grid);
+        for (x, y) in toggles {
+            field.toggle(x % width, y % height);
+        }
+        prop_assert!(*field.counts() == field.grid().neighbours());
+    }
+
+    #[test]
+    fn canonical_forms_are_the_least_translation(
+        grid in prop_oneof![
+            testing::grid_of_any_size(16, 0.3),
+            // Sparse and periodic grids have many translations that tie for a long way.
+            testing::grid_of_any_size(16, 0.02),
+            (1..=4i32, 2..=4i32, 1..=3i32).prop_map(|(width, height, period)| {
+                let mut grid = undeath::Grid::new(width * 3, height * 2);
+                for (x, y) in (0..width * 3).flat_map(|x| (0..height * 2).map(move |y| (x, y))) {
+                    if (x + y) % period == 0 {
+                        grid.set_wrapped(x, y, Cell::one());
+                    }
+                }
+                grid
+            }),
+            value_grids().prop_map(|(grid, _)| grid),
+        ],
+    ) {
+        prop_assert!(grid.canonical() == naive_canonical(&grid));
+    }
+
+    #[test]
+    fn symmetric_canonical_forms_ignore_orientation(
+        grid in testing::grid(0.3),
+        (dx, dy) in testing::coordinate(),
+    ) {
+        prop_assert!(grid.transformed(Symmetry::QuarterTurn) == testing::rotate(&grid));
+        let canonical = grid.canonical_symmetric();
+        let moved = testing::translate(&grid, dx, dy);
+        for symmetry in Symmetry::ALL {
+            let oriented = moved.transformed(symmetry);
+            prop_assert!(oriented.canonical_symmetric() == canonical);
+            // Symmetries are symmetries of every orientation, even if which ones they are changes.
+            prop_assert_eq!(oriented.symmetries().len(), grid.symmetries().len());
+        }
+    }
+
+    #[test]
+    fn components_partition_alive_cells(
+        grid in testing::grid(0.2),
+        (dx, dy) in testing::coordinate(),
+    ) {
+        let components = grid.components();
+        let mut cells: Vec<_> = components.iter().flat_map(|component| component.cells.clone()).collect();
+        cells.sort_by_key(|&(x, y)| (y, x));
+        prop_assert!(cells.into_iter().eq(grid.alive_cells()));
+        for component in &components {
+            let (left, top, width, height) = component.bounding_box;
+            for &(x, y) in &component.cells {
+                prop_assert!((x - left).rem_euclid(SIZE) < width && (y - top).rem_euclid(SIZE) < height);
+            }
+        }
+        prop_assert_eq!(grid.component_count(), components.len());
+        prop_assert_eq!(testing::translate(&grid, dx, dy).component_count(), components.len());
+    }
+
+    #[test]
+    fn guess_invariants_hold(
+        next in testing::grid(0.3),
+        guesses in testing::guesses(40),
+    ) {
+        let guess = testing::apply_guesses(&next, &guesses);
+        if let Err(problem) = testing::check_guess(&guess) {
+            prop_assert!(false, "{problem}\n{}", guess.render());
+        }
+    }
+
+    #[test]
+    fn compact_guesses_round_trip(
+        next in testing::grid(0.3),
+        guesses in testing::guesses(20),
+    ) {
+        let guess = testing::apply_guesses(&next, &guesses);
+        for encoded in [guess.to_string(), format!("{guess:#}")] {
+            let parsed = Guess::parse_compact(&encoded).unwrap();
+            prop_assert_eq!(parsed.to_string(), guess.to_string());
+            prop_assert_eq!(format!("{parsed:#}"), encoded.as_str());
+            prop_assert!(parsed.alive() == guess.alive() && parsed.dead() == guess.dead());
+            prop_assert_eq!(parsed.found_contradiction(), guess.found_contradiction());
+            if let Err(problem) = testing::check_guess(&parsed) {
+                prop_assert!(false, "{problem}\n{encoded}");
+            }
+        }
+    }
+}
+
+proptest! {
+    // Each case runs a search, so use fewer of them.
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn solutions_step_to_their_target(parent in testing::grid_in(2, 2, 4, 4, 0.4)) {
+        // Stepping a grid guarantees that the target has a predecessor.
+        let target = stepped(&parent);
+        let options = Options { max_iterations: 200_000 };
+        match undeath::solve_with(&target, &options) {
+            Ok(Some(solution)) => prop_assert!(stepped(&solution) == target),
+            Ok(None) => prop_assert!(false, "no predecessor found for\n{}", target.render()),
+            Err(Error::BudgetExhausted { .. }) => {}
+            Err(error) => prop_assert!(false, "{error}"),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Moving patterns between rectangles of the grid.
+
+use undeath::{
+    grid::{Anchor, ReembedError, SIZE},
+    Grid,
+};
+
+fn glider() -> Grid {
+    Grid::from_text(".#\n..#\n###")
+}
+
+#[test]
+fn grows_around_the_pattern() {
+    let (trimmed, size) = glider().trimmed();
+    assert_eq!(size, (3, 3));
+    let centred = trimmed.reembed(SIZE, SIZE, Anchor::Center).unwrap();
+    assert!(centred == Grid::from_text("\n\n...#\n....#\n..###"));
+    let offset = trimmed.reembed(SIZE, SIZE, Anchor::Offset(4, 1)).unwrap();
+    assert!(offset == Grid::from_text("\n.....#\n......#\n....###"));
+    assert!(centred.reembed(SIZE, SIZE, Anchor::TopLeft).unwrap() == glider());
+}
+
+#[test]
+fn shrinks_when_the_pattern_fits() {
+    let moved = Grid::from_text("\n\n\n\n\n...#\n....#\n..###");
+    assert!(moved.reembed(3, 3, Anchor::TopLeft).unwrap() == glider());
+    assert!(moved.reembed(3, 3, Anchor::Center).unwrap() == glider());
+}
+
+#[test]
+fn shrinking_refuses_to_cut_cells_off() {
+    assert_eq!(
+        glider().reembed(2, 3, Anchor::TopLeft),
+        Err(ReembedError::CutOff { cells: 2 })
+    );
+    assert_eq!(
+        glider().reembed(3, 3, Anchor::Offset(1, 1)),
+        Err(ReembedError::CutOff { cells: 4 })
+    );
+}
+
+#[test]
+fn clipping_drops_the_cells_outside() {
+    let clipped = glider().reembed_clipped(2, 3, Anchor::TopLeft).unwrap();
+    assert!(clipped == Grid::from_text(".#\n\n##"));
+    // Centring a wider pattern cuts off both sides, with more on the right.
+    let row = Grid::from_text("#####");
+    assert!(row.reembed_clipped(2, 1, Anchor::Center).unwrap() == Grid::from_text("##"));
+    assert_eq!(
+        row.reembed(2, 1, Anchor::Center),
+        Err(ReembedError::CutOff { cells: 3 })
+    );
+}
+
+#[test]
+fn moves_patterns_straddling_the_seam_whole() {
+    // A glider split across all four edges of the torus.
+    let straddling = undeath::testing::translate(&glider(), -1, -1);
+    assert_eq!(straddling.bounding_box(), Some((SIZE - 1, SIZE - 1, 3, 3)));
+    assert!(straddling.reembed(3, 3, Anchor::TopLeft).unwrap() == glider());
+    assert!(straddling.trimmed().0 == glider());
+}
+
+#[test]
+fn rejects_impossible_sizes() {
+    for (width, height) in [(0, 4), (4, SIZE + 1), (-1, -1)] {
+        assert_eq!(
+            glider().reembed(width, height, Anchor::TopLeft),
+            Err(ReembedError::InvalidSize { width, height })
+        );
+    }
+    assert!(Grid::default().reembed(1, 1, Anchor::Center).unwrap() == Grid::default());
+    assert_eq!(Grid::default().bounding_box(), None);
+}
@@ -0,0 +1,100 @@
+//! Reusing a searcher for a new target finds what a new searcher would, without allocating its buffers again.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell as StdCell,
+};
+
+use undeath::{order::HighestImpact, Grid, SearchResult, Searcher};
+
+/// Counts the allocations made on each thread, so that tests running in parallel don't disturb each other.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: StdCell<usize> = const { StdCell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // `try_with` fails once the thread is being torn down, and those allocations don't matter.
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// How many allocations `f` makes on this thread.
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(StdCell::get);
+    f();
+    ALLOCATIONS.with(StdCell::get) - before
+}
+
+/// The first few predecessors `searcher` finds, in order.
+fn solutions(searcher: &mut Searcher) -> Vec<Grid> {
+    let mut found = Vec::new();
+    while found.len() < 5 {
+        match searcher.search(1_000_000).unwrap() {
+            SearchResult::Found(grid, _) => found.push(grid),
+            SearchResult::Working(_) => {}
+            _ => break,
+        }
+    }
+    found
+}
+
+fn glider() -> Grid {
+    Grid::from_text(".#\n..#\n###")
+}
+
+fn blinker() -> Grid {
+    Grid::from_text("\n\n\n..###")
+}
+
+#[test]
+fn a_reset_searcher_finds_what_a_new_one_does() {
+    let mut searcher = Searcher::new(glider());
+    assert!(matches!(
+        searcher.search(1_000_000).unwrap(),
+        SearchResult::Found(..)
+    ));
+
+    // The blinker has fewer alive cells than the glider, so every buffer is already big enough.
+    let (target, copy) = (blinker(), blinker());
+    let reset = allocations(|| searcher.reset_with_target(target, false));
+    let fresh = allocations(|| drop(Searcher::new(copy)));
+    assert_eq!(reset, 0);
+    assert!(fresh > 0);
+
+    let mut fresh = Searcher::new(blinker());
+    assert_eq!(solutions(&mut searcher), solutions(&mut fresh));
+    assert_eq!(searcher.stats(), fresh.stats());
+}
+
+#[test]
+fn activity_is_only_kept_when_asked_for() {
+    let mut searcher = Searcher::new(glider()).cell_order(HighestImpact);
+    // Impact comes and goes as the search backtracks, so stop while the current guess has some.
+    let impact = loop {
+        searcher.search(1).unwrap();
+        let impact = searcher.current_guess().unwrap().impact_grid();
+        if impact != Grid::default() {
+            break impact;
+        }
+    };
+    let heatmap = searcher.contradiction_heatmap();
+
+    searcher.reset_with_target(blinker(), true);
+    assert!(searcher.contradiction_heatmap() == heatmap);
+    assert!(searcher.current_guess().unwrap().impact_grid() == impact);
+
+    searcher.reset_with_target(glider(), false);
+    assert!(searcher.contradiction_heatmap() == Grid::default());
+    assert!(searcher.current_guess().unwrap().impact_grid() == Grid::default());
+}
@@ -0,0 +1,282 @@
+//! Reading patterns exported from Golly, with their `#CXRLE` lines and comments.
+
+use undeath::{
+    grid::{LoadError, MAX_SIZE, MIN_SIZE, SIZE},
+    rle::{Rle, RleError, GENERATED_COMMENT},
+    Cell, Error, Grid,
+};
+
+/// The queen bee shuttle from the middle of the Gosper glider gun, as Golly exports a selection of it:
+/// columns 10 to 17 and rows 2 to 8 of the gun, which fit on the grid.
+const GUN_FRAGMENT: &str = "\
+#CXRLE Pos=-4,-3 Gen=123
+#N Gosper glider gun
+#O Bill Gosper
+#C A true period 30 glider gun.
+#C www.conwaylife.com/wiki/index.php?title=Gosper_glider_gun
+#R 0 0
+x = 8, y = 7, rule = B3/S23
+2b2o$bo3bo$o5bo$o3bob2o$o5bo$bo3bo$2b2o!
+";
+
+#[test]
+fn places_a_golly_fragment_by_its_position() {
+    let rle = Rle::parse(GUN_FRAGMENT).unwrap();
+    assert_eq!(rle.generation, Some(123));
+    assert_eq!(rle.position, Some((-4, -3)));
+    // Golly's origin is the middle of the grid, so the top left corner is one row down.
+    let expected = Grid::from_text("\n..##\n.#...#\n#.....#\n#...#.##\n#.....#\n.#...#\n..##");
+    assert!(rle.grid == expected, "{}", rle.grid.render());
+}
+
+#[test]
+fn keeps_comments_and_unknown_lines_verbatim() {
+    let rle = Rle::parse(GUN_FRAGMENT).unwrap();
+    assert_eq!(
+        rle.comments,
+        [
+            "#N Gosper glider gun",
+            "#O Bill Gosper",
+            "#C A true period 30 glider gun.",
+            "#C www.conwaylife.com/wiki/index.php?title=Gosper_glider_gun",
+            "#R 0 0",
+        ]
+    );
+    assert_eq!(rle.to_string(), GUN_FRAGMENT);
+}
+
+#[test]
+fn round_trips_after_the_pattern_moves() {
+    let mut rle = Rle::parse(GUN_FRAGMENT).unwrap();
+    rle.grid.step();
+    let written = rle.to_string();
+    let read = Rle::parse(&written).unwrap();
+    assert!(read.grid == rle.grid, "{written}");
+    // The position is where the pattern now is, straddling the left and right edges.
+    assert_eq!(read.position, Some((2, -3)));
+    assert_eq!(read.generation, Some(123));
+    assert_eq!(read.comments, rle.comments);
+}
+
+#[test]
+fn wraps_a_pattern_placed_across_an_edge() {
+    let rle = Rle::parse("#CXRLE Pos=3,-4\nx = 2, y = 2, rule = B3/S23\n2o$2o!").unwrap();
+    assert!(rle.grid == Grid::from_text("#......#\n#......#"));
+    assert_eq!(
+        rle.to_string(),
+        "#CXRLE Pos=3,-4\nx = 2, y = 2, rule = B3/S23\n2o$2o!\n"
+    );
+}
+
+#[test]
+fn marks_solutions_as_generated() {
+    let glider = Grid::from_text(".#\n..#\n###");
+    let written = Rle::solution(glider.clone()).to_string();
+    assert!(written.starts_with(&format!("{GENERATED_COMMENT}\n")));
+    let read = Rle::parse(&written).unwrap();
+    assert!(read.grid == glider);
+    assert_eq!(read.comments, [GENERATED_COMMENT]);
+    assert_eq!(read.position, None);
+}
+
+#[test]
+fn grows_the_grid_to_fit_the_pattern() {
+    let rle = Rle::parse("x = 9, y = 1, rule = B3/S23\n9o!").unwrap();
+    assert_eq!((rle.grid.width(), rle.grid.height()), (9, 3));
+    assert_eq!(rle.grid.alive_cells().count(), 9);
+    let small = Rle::parse("x = 3, y = 1\n3o!").unwrap().grid;
+    assert_eq!((small.width(), small.height()), (SIZE, SIZE));
+}
+
+#[test]
+fn rejects_what_it_cannot_read() {
+    for text in [
+        "x = 1025, y = 1, rule = B3/S23\n1025o!",
+        "x = 3, y = 1, rule = B3/S23\n4o!",
+        "x = 3, y = 1, rule = B36/S23\n3o!",
+        "#CXRLE Pos=a,b\nx = 1, y = 1\no!",
+        "3o",
+        "3q!",
+    ] {
+        assert!(
+            matches!(
+                Rle::parse(text),
+                Err(Error::Parse { .. } | Error::InvalidInput(_))
+            ),
+            "{text}"
+        );
+    }
+}
+
+#[test]
+fn reads_known_patterns_as_grids() {
+    for (rle, text) in [
+        ("#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n", ".#\n..#\n###"),
+        (
+            "#N Lightweight spaceship\n#C A c/2 orthogonal spaceship.\nx = 5, y = 4, rule = B3/S23\nbo2bo$o4b$o3bo$4o!\n",
+            ".#..#\n#\n#...#\n####",
+        ),
+    ] {
+        let grid = Grid::from_rle(rle).unwrap();
+        assert!(grid == Grid::from_text(text), "{rle}");
+        assert!(Grid::from_rle(&grid.to_rle()).unwrap() == grid, "{rle}");
+    }
+}
+
+#[test]
+fn reads_rle_files_by_their_extension() {
+    let directory = std::env::temp_dir().join("undeath-rle-files");
+    std::fs::create_dir_all(&directory).unwrap();
+    let lwss = "x = 5, y = 4, rule = B3/S23\nbo2bo$o4b$o3bo$4o!\n";
+    for name in ["lwss.rle", "lwss.RLE"] {
+        let path = directory.join(name);
+        std::fs::write(&path, lwss).unwrap();
+        assert!(Grid::from_file(&path).unwrap() == Grid::from_rle(lwss).unwrap());
+    }
+    // The same text in a `.gol` file is read as text, where every character but `.` is alive.
+    let path = directory.join("lwss.gol");
+    std::fs::write(&path, lwss).unwrap();
+    assert!(Grid::from_file(&path).unwrap() != Grid::from_rle(lwss).unwrap());
+    let path = directory.join("broken.rle");
+    std::fs::write(&path, "x = 2, y = 2\n3o!\n").unwrap();
+    assert!(matches!(
+        Grid::from_file(&path),
+        Err(LoadError::Rle {
+            source: RleError::OutOfBounds {
+                line: 2,
+                column: 2,
+                ..
+            },
+            ..
+        })
+    ));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn patterns_too_large_for_the_grid_do_not_wrap() {
+    // Without a header, the pattern must fit on the default grid.
+    assert_eq!(
+        Grid::from_rle("9o!"),
+        Err(RleError::OutOfBounds {
+            line: 1,
+            column: 2,
+            width: SIZE as i64,
+            height: SIZE as i64,
+            pattern: (9, 1)
+        })
+    );
+    assert_eq!(
+        Grid::from_rle("o8$o!"),
+        Err(RleError::OutOfBounds {
+            line: 1,
+            column: 4,
+            width: SIZE as i64,
+            height: SIZE as i64,
+            pattern: (1, 9)
+        })
+    );
+    assert_eq!(
+        Grid::from_rle("x = 2000, y = 3\n2000o!"),
+        Err(RleError::TooLarge {
+            width: 2000,
+            height: 3
+        })
+    );
+    assert_eq!(
+        Grid::from_rle("x = 3, y = 3, rule = B36/S23\n3o!"),
+        Err(RleError::UnsupportedRule {
+            rule: "B36/S23".to_owned()
+        })
+    );
+    let error = Grid::from_rle("x = 3, y = 3\n4o!").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "the pattern is 4×1, which does not fit in 3×3, from line 2, column 2"
+    );
+    // The whole pattern is measured, not only as far as the first cell outside.
+    assert!(matches!(
+        Grid::from_rle("x = 3, y = 3\n3o$4o$o3$2bo!"),
+        Err(RleError::OutOfBounds {
+            line: 2,
+            column: 5,
+            pattern: (4, 6),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn files_wrap_only_when_asked_to() {
+    let directory = std::env::temp_dir().join("undeath-rle-wrap");
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("wide.rle");
+    std::fs::write(&path, "x = 2000, y = 3\nbo1998bo!\n").unwrap();
+    assert!(matches!(
+        Grid::from_file(&path),
+        Err(LoadError::Rle {
+            source: RleError::TooLarge {
+                width: 2000,
+                height: 3
+            },
+            ..
+        })
+    ));
+    let grid = Grid::from_file_with_wrap(&path, true).unwrap();
+    assert_eq!((grid.width(), grid.height()), (MAX_SIZE, 3));
+    assert!(grid.alive_cells().eq([(1, 0), (2000 % MAX_SIZE, 0)]));
+
+    // Without a header, cells past the default grid fold back onto it.
+    std::fs::write(&path, "9o$$$$$$$$o!\n").unwrap();
+    assert!(Grid::from_file(&path).is_err());
+    let grid = Grid::from_file_with_wrap(&path, true).unwrap();
+    assert!(grid == Grid::from_text("########"));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn writes_only_the_bounding_box() {
+    let mut grid = Grid::new(40, 12);
+    for (x, y) in [(31, 5), (32, 6), (30, 7), (31, 7), (32, 7)] {
+        grid.set_wrapped(x, y, Cell::one());
+    }
+    assert_eq!(grid.to_rle(), "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+    // A pattern across an edge of the torus is written in one piece.
+    let mut wrapped = Grid::default();
+    for x in [7, 0] {
+        wrapped.set_wrapped(x, 7, Cell::one());
+        wrapped.set_wrapped(x, 0, Cell::one());
+    }
+    assert_eq!(wrapped.to_rle(), "x = 2, y = 2, rule = B3/S23\n2o$2o!\n");
+}
+
+#[test]
+fn an_empty_grid_is_still_a_pattern() {
+    let rle = Grid::new(20, 6).to_rle();
+    assert_eq!(rle, "x = 0, y = 0, rule = B3/S23\n!\n");
+    assert!(Grid::from_rle(&rle).unwrap() == Grid::default());
+}
+
+#[test]
+fn wraps_lines_at_seventy_characters() {
+    // Alternating cells make a run of every cell, so a 100-wide row is 99 characters of runs.
+    let mut grid = Grid::new(100, 6);
+    for x in (0..100).step_by(2) {
+        for y in [0, 2] {
+            grid.set_wrapped(x, y, Cell::one());
+        }
+    }
+    let rle = grid.to_rle();
+    let lines = rle.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "x = 99, y = 3, rule = B3/S23");
+    assert!(lines[1..].iter().all(|line| line.len() <= 70), "{rle}");
+    assert_eq!(lines[1].len(), 70, "{rle}");
+    // Runs are never split across lines.
+    assert!(lines[1..]
+        .iter()
+        .all(|line| !line.ends_with(|c: char| c.is_ascii_digit())));
+    assert!(lines.last().unwrap().ends_with('!'));
+    let read = Grid::from_rle(&rle).unwrap();
+    assert_eq!((read.width(), read.height()), (99, MIN_SIZE));
+    assert!(read.alive_cells().eq(grid.alive_cells()));
+}
@@ -0,0 +1,137 @@
+//! The search log records the milestones of searches as JSON lines.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use undeath::{
+    search_log::{LogEvent, SearchLog},
+    Cell, Grid, SearchResult, Searcher,
+};
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("undeath-search-log-{name}.jsonl"));
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(rotated(&path));
+    path
+}
+
+fn rotated(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    rotated.into()
+}
+
+fn read(path: &Path) -> Vec<Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+/// A blinker, with predecessors confined to the 4×4 window around it.
+fn masked_blinker() -> (Grid, Grid) {
+    let blinker = Grid::from_text("\n\n\n..###");
+    let mut mask = Grid::default();
+    for y in 2..6 {
+        for x in 1..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    (blinker, mask)
+}
+
+/// Searches to the end, returning how many predecessors were found.
+fn exhaust(mut searcher: Searcher) -> usize {
+    let mut found = 0;
+    loop {
+        match searcher.search(1_000).unwrap() {
+            SearchResult::Found(..) => found += 1,
+            SearchResult::Working(_) => {}
+            _ => return found,
+        }
+    }
+}
+
+#[test]
+fn records_a_bounded_search() {
+    let path = temp_path("bounded");
+    let log = SearchLog::open(&path, 1 << 20).unwrap();
+    let (blinker, mask) = masked_blinker();
+    let mut searcher = Searcher::with_mask(blinker, &mask).trace_to(log.clone());
+    let mut found = 0;
+    let mut iterations = 0;
+    loop {
+        match searcher.search(1_000).unwrap() {
+            SearchResult::Found(_, spent) => {
+                found += 1;
+                iterations += spent;
+            }
+            SearchResult::Working(spent) => iterations += spent,
+            _ => break,
+        }
+    }
+    let stats = searcher.stats();
+    log.log(&LogEvent::Progress {
+        iterations,
+        stats: &stats,
+    })
+    .unwrap();
+    log.log(&LogEvent::Finish {
+        outcome: "exhausted \"everything\"",
+    })
+    .unwrap();
+
+    let events = read(&path);
+    let kinds = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert!(found > 0);
+    let mut expected = vec!["start"];
+    expected.extend(std::iter::repeat_n("solution", found));
+    expected.extend(["progress", "finish"]);
+    assert_eq!(kinds, expected);
+    assert_eq!(events[0]["next"][3], "..###...");
+    assert_eq!(events[found + 1]["decisions"], stats.decisions);
+    assert_eq!(events[found + 2]["outcome"], "exhausted \"everything\"");
+    assert!(events
+        .windows(2)
+        .all(|pair| pair[0]["time_ms"].as_u64() <= pair[1]["time_ms"].as_u64()));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn later_searches_are_restarts() {
+    let path = temp_path("restarts");
+    let log = SearchLog::open(&path, 1 << 20).unwrap();
+    let (blinker, mask) = masked_blinker();
+    for _ in 0..3 {
+        exhaust(Searcher::with_mask(blinker.clone(), &mask).trace_to(log.clone()));
+    }
+    let starts = read(&path)
+        .into_iter()
+        .filter_map(|event| match event["event"].as_str().unwrap() {
+            "solution" => None,
+            kind => Some(kind.to_owned()),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(starts, ["start", "restart", "restart"]);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn rotates_at_the_size_limit() {
+    let path = temp_path("rotation");
+    let log = SearchLog::open(&path, 300).unwrap();
+    for _ in 0..20 {
+        log.log(&LogEvent::Finish { outcome: "done" }).unwrap();
+    }
+    let current = std::fs::metadata(&path).unwrap().len();
+    assert!(current <= 300, "{current}");
+    let older = read(&rotated(&path));
+    assert!(!older.is_empty());
+    assert!(older.len() + read(&path).len() <= 20);
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(rotated(&path)).unwrap();
+}
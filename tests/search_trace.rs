@@ -0,0 +1,182 @@
+//! Traces record enough of a depth-first search to replay it exactly.
+
+use std::{fs::File, io::BufWriter, sync::mpsc};
+
+use undeath::{
+    replay::Replay,
+    searcher::{Shaving, Strategy},
+    trace::{read_binary, BinaryTrace, JsonTrace, TraceEvent},
+    Cell, Grid, SearchResult, Searcher,
+};
+
+/// A blinker, with predecessors confined to the 4×4 window around it.
+fn masked_blinker() -> (Grid, Grid) {
+    let blinker = Grid::from_text("\n\n\n..###");
+    let mut mask = Grid::default();
+    for y in 2..6 {
+        for x in 1..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    (blinker, mask)
+}
+
+/// Searches to the end, returning every predecessor found.
+fn exhaust(mut searcher: Searcher) -> Vec<Grid> {
+    let mut found = Vec::new();
+    loop {
+        match searcher.search(100).unwrap() {
+            SearchResult::Found(grid, _) => found.push(grid),
+            SearchResult::Working(_) => {}
+            SearchResult::Unsatisfiable => return found,
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn recorded(searcher: Searcher) -> (Vec<Grid>, Vec<TraceEvent>) {
+    let (sender, receiver) = mpsc::channel();
+    let found = exhaust(searcher.trace_to(sender));
+    (found, receiver.iter().collect())
+}
+
+#[test]
+fn binary_trace_replays_the_search() {
+    let (blinker, mask) = masked_blinker();
+    let path = std::env::temp_dir().join(format!("undeath-trace-{}.bin", std::process::id()));
+    let sink = BinaryTrace::new(BufWriter::new(File::create(&path).unwrap()));
+    // Searching in small slices checks that iterations carry on across calls.
+    let found = exhaust(Searcher::with_mask(blinker, &mask).trace_to(sink));
+    let events = read_binary(File::open(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(found.len() > 1);
+
+    let replay = Replay::new(events).unwrap();
+    assert!(replay.solutions().unwrap() == found);
+    let last = (0..replay.len())
+        .rev()
+        .find(|&index| matches!(replay.events()[index], TraceEvent::Solution { .. }))
+        .unwrap();
+    assert!(replay.state_at(last).unwrap().alive() == *found.last().unwrap());
+    let iterations = replay
+        .events()
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::Decide { iteration, .. } => Some(*iteration),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(iterations.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn binary_format_round_trips_and_is_compact() {
+    let (blinker, mask) = masked_blinker();
+    let (_, events) = recorded(Searcher::with_mask(blinker, &mask));
+    let mut sink = BinaryTrace::new(Vec::new());
+    for event in &events {
+        undeath::trace::TraceSink::record(&mut sink, event).unwrap();
+    }
+    let bytes = sink.into_inner();
+    assert!(read_binary(bytes.as_slice()).unwrap() == events);
+    // Everything but the header and the start is a few bytes per event.
+    assert!(bytes.len() - 34 <= 4 * (events.len() - 1));
+}
+
+#[test]
+fn binary_format_records_the_size_of_the_grid() {
+    let blinker = Grid::from_text("\n\n\n..###\n\n\n\n\n\n\n\n...........");
+    assert_eq!((blinker.width(), blinker.height()), (11, 12));
+    let mut mask = Grid::new(11, 12);
+    for y in 2..6 {
+        for x in 1..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    let (found, events) = recorded(Searcher::with_mask(blinker, &mask));
+    let mut sink = BinaryTrace::new(Vec::new());
+    for event in &events {
+        undeath::trace::TraceSink::record(&mut sink, event).unwrap();
+    }
+    let read = read_binary(sink.into_inner().as_slice()).unwrap();
+    assert!(read == events);
+    assert!(Replay::new(read).unwrap().solutions().unwrap() == found);
+}
+
+#[test]
+fn reads_the_first_version_of_the_binary_format() {
+    let (blinker, mask) = masked_blinker();
+    let (_, events) = recorded(Searcher::with_mask(blinker, &mask));
+    let mut sink = BinaryTrace::new(Vec::new());
+    for event in &events {
+        undeath::trace::TraceSink::record(&mut sink, event).unwrap();
+    }
+    // The first version had no width and height after the start's tag, and every grid was 8×8.
+    let mut bytes = sink.into_inner();
+    assert_eq!(&bytes[..6], b"UDTR\x02\x00");
+    assert_eq!(bytes.drain(6..8).collect::<Vec<_>>(), [8, 8]);
+    bytes[4] = 1;
+    assert!(read_binary(bytes.as_slice()).unwrap() == events);
+}
+
+#[test]
+fn replays_with_block_propagation_and_shaving() {
+    let glider = Grid::from_text("\n.#\n..#\n###");
+    let mut mask = Grid::default();
+    for y in 0..5 {
+        for x in 0..5 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    let searcher = Searcher::with_mask(glider, &mask)
+        .block_propagation(true)
+        .shave(Shaving::Periodic(3));
+    let (found, events) = recorded(searcher);
+    assert!(!found.is_empty());
+    assert!(Replay::new(events).unwrap().solutions().unwrap() == found);
+}
+
+#[test]
+fn a_tampered_trace_does_not_replay() {
+    let (blinker, mask) = masked_blinker();
+    let (_, mut events) = recorded(Searcher::with_mask(blinker, &mask));
+    let decision = events
+        .iter_mut()
+        .find_map(|event| match event {
+            TraceEvent::Decide { contradiction, .. } => Some(contradiction),
+            _ => None,
+        })
+        .unwrap();
+    *decision = !*decision;
+    let replay = Replay::new(events).unwrap();
+    assert!(replay.solutions().is_err());
+}
+
+#[test]
+fn json_trace_has_a_line_per_event() {
+    let (blinker, mask) = masked_blinker();
+    let (_, events) = recorded(Searcher::with_mask(blinker.clone(), &mask));
+    let mut sink = JsonTrace::new(Vec::new());
+    for event in &events {
+        undeath::trace::TraceSink::record(&mut sink, event).unwrap();
+    }
+    let text = String::from_utf8(sink.into_inner()).unwrap();
+    let lines = text.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), events.len());
+    assert!(lines[0].starts_with("{\"event\":\"start\",\"next\":[\"........\""));
+    assert!(lines
+        .iter()
+        .skip(1)
+        .all(|line| line.starts_with("{\"event\":\"") && line.ends_with('}')));
+}
+
+#[test]
+fn only_depth_first_search_is_traced() {
+    let (blinker, mask) = masked_blinker();
+    let searcher = Searcher::with_mask(blinker, &mask).strategy(Strategy::BestFirst {
+        max_open: Strategy::DEFAULT_MAX_OPEN,
+    });
+    let (found, events) = recorded(searcher);
+    assert!(!found.is_empty());
+    assert!(events.is_empty());
+}
@@ -0,0 +1,142 @@
+//! Grids and cells with the `serde` feature, as rows of `.` and `#` and as integers.
+
+use undeath::{
+    grid::{ParseGridError, Topology, MAX_SIZE, MIN_SIZE},
+    rng::{RandomSource, Rng},
+    Cell, Error, Grid,
+};
+
+fn soup(rng: &mut Rng, width: i32, height: i32, density: f64) -> Grid {
+    let mut grid = Grid::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            if rng.gen_bool(density) {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    grid
+}
+
+/// The error that deserialising `rows` fails with.
+fn rows_error(rows: &[String]) -> String {
+    serde_json::from_value::<Grid>(serde_json::json!(rows))
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn grids_are_rows_of_text() {
+    let glider = Grid::from_text(".#\n..#\n###");
+    let json = serde_json::to_value(&glider).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            ".#......", "..#.....", "###.....", "........", "........", "........", "........",
+            "........"
+        ])
+    );
+    assert_eq!(
+        serde_json::to_value(Cell::one()).unwrap(),
+        serde_json::json!(1)
+    );
+    assert!(serde_json::from_str::<Cell>("-1").unwrap() == Cell::neg_one());
+}
+
+#[test]
+fn grids_of_any_size_round_trip() {
+    let mut rng = Rng::new(515);
+    for (width, height) in [
+        (8, 8),
+        (MIN_SIZE, MIN_SIZE),
+        (5, 7),
+        (20, 6),
+        (MAX_SIZE, MIN_SIZE),
+    ] {
+        let grid = soup(&mut rng, width, height, 0.4);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert!(
+            serde_json::from_str::<Grid>(&json).unwrap() == grid,
+            "{width}×{height}"
+        );
+    }
+    // Topology is not kept.
+    let plane = Grid::from_text("\n.###").with_topology(Topology::Plane);
+    let json = serde_json::to_string(&plane).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Grid>(&json).unwrap().topology(),
+        Topology::Torus
+    );
+}
+
+#[test]
+fn rows_are_checked_as_text_is() {
+    let row = |text: &str| text.to_owned();
+    let cases = [
+        (vec![], ParseGridError::Empty),
+        (
+            vec![row("..."), row(".O."), row("...")],
+            ParseGridError::UnexpectedCharacter {
+                line: 2,
+                column: 2,
+                character: 'O',
+            },
+        ),
+        (
+            vec![row("...."), row("...."), row("...")],
+            ParseGridError::UnevenRow {
+                line: 3,
+                length: 3,
+                width: 4,
+            },
+        ),
+        (
+            vec![row("#".repeat(2000).as_str()); 3],
+            ParseGridError::LineTooLong {
+                line: 1,
+                length: 2000,
+                pattern: (2000, 3),
+            },
+        ),
+        (
+            vec![row("..."); 2000],
+            ParseGridError::TooManyRows {
+                rows: 2000,
+                pattern: (3, 2000),
+            },
+        ),
+        (
+            vec![row(".."), row("..")],
+            ParseGridError::TooSmall {
+                width: 2,
+                height: 2,
+            },
+        ),
+    ];
+    for (rows, expected) in cases {
+        assert_eq!(rows_error(&rows), expected.to_string());
+    }
+    assert!(serde_json::from_str::<Grid>(r#"".#.""#).is_err());
+}
+
+#[test]
+fn the_new_errors_convert() {
+    let uneven = ParseGridError::UnevenRow {
+        line: 3,
+        length: 5,
+        width: 4,
+    };
+    assert!(matches!(
+        Error::from(uneven),
+        Error::Parse {
+            line: 3,
+            column: 5,
+            ..
+        }
+    ));
+    let small = ParseGridError::TooSmall {
+        width: 2,
+        height: 9,
+    };
+    assert!(matches!(Error::from(small), Error::InvalidInput(_)));
+}
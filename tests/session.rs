@@ -0,0 +1,58 @@
+//! A reused session finds exactly what solving each target from scratch finds.
+
+use undeath::{solve_all_with, Error, Grid, Options, Searcher, SolverSession};
+
+fn targets() -> Vec<Grid> {
+    vec![
+        Grid::from_text("\n\n\n..###"),
+        Grid::from_text(".#\n..#\n###"),
+        Grid::from_text("##\n##"),
+        Grid::from_text("\n..##\n.#..#\n..##"),
+        Grid::default(),
+    ]
+}
+
+#[test]
+fn matches_fresh_solves() {
+    let options = Options::default();
+    let mut session = SolverSession::new();
+    // Each target is solved twice, so that every one is also solved after a different one.
+    for target in targets().iter().chain(targets().iter().rev()) {
+        let expected = solve_all_with(target, 3, &options).unwrap();
+        assert!(session.solve_all(target, 3, &options).unwrap() == expected);
+    }
+}
+
+#[test]
+fn recovers_from_exhausted_budget() {
+    let glider = Grid::from_text(".#\n..#\n###");
+    let mut session = SolverSession::new();
+    let result = session.solve(&glider, &Options { max_iterations: 1 });
+    assert!(matches!(
+        result,
+        Err(Error::BudgetExhausted { iterations: 1 })
+    ));
+    let mut parent = session
+        .solve(&glider, &Options::default())
+        .unwrap()
+        .unwrap();
+    parent.step();
+    assert!(parent == glider);
+    assert!(session.stats().decisions > 0);
+}
+
+#[test]
+fn keeps_the_configuration_of_its_searcher() {
+    let options = Options::default();
+    let mut plain = SolverSession::new();
+    let mut blocks =
+        SolverSession::with_searcher(Searcher::new(Grid::default()).block_propagation(true));
+    for target in targets() {
+        // Both search in the same order, so they find the same predecessors, but blocks prune more.
+        assert!(
+            blocks.solve_all(&target, 3, &options).unwrap()
+                == plain.solve_all(&target, 3, &options).unwrap()
+        );
+        assert!(blocks.stats().decisions <= plain.stats().decisions);
+    }
+}
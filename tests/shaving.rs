@@ -0,0 +1,51 @@
+//! Shaving decides cells that propagation from either value of a single cell cannot.
+
+use undeath::{searcher::Shaving, testing, Grid, SearchResult, Searcher};
+
+fn target() -> Grid {
+    Grid::from_text(
+        "..#.....\n..#####.\n#.#.####\n#.#..#..\n..##...#\n......#.\n.......#\n.##.....",
+    )
+}
+
+const SCRIPT: [(i32, i32, bool); 4] = [(3, 2, true), (7, 2, false), (3, 1, true), (3, 3, true)];
+
+#[test]
+fn shaving_forces_cells_that_probing_alone_does_not() {
+    let guess = testing::apply_guesses(&target(), &SCRIPT);
+    let mut shaved = guess.clone();
+    testing::shave(&mut shaved, &target());
+    assert!(!shaved.found_contradiction());
+
+    // Neither value of (1, 0) contradicts the guess by propagation alone...
+    for alive in [true, false] {
+        let mut probe = SCRIPT.to_vec();
+        probe.push((1, 0, alive));
+        assert!(!testing::apply_guesses(&target(), &probe).found_contradiction());
+    }
+    // ...but shaving finds that it must be dead.
+    assert_eq!(guess.dead().get_wrapped(1, 0).value, 0);
+    assert_eq!(shaved.dead().get_wrapped(1, 0).value, 1);
+}
+
+#[test]
+fn shaving_keeps_every_predecessor() {
+    let mut parent = Grid::from_text("\n\n..#\n.##.#\n..##\n");
+    parent.step();
+    let all = |shaving| {
+        let mut searcher = Searcher::new(parent.clone()).shave(shaving);
+        let mut found = Vec::new();
+        while found.len() < 20 {
+            match searcher.search(usize::MAX).unwrap() {
+                SearchResult::Found(grid, _) => found.push(grid),
+                SearchResult::Unsatisfiable => break,
+                _ => {}
+            }
+        }
+        found.sort();
+        found
+    };
+    let unshaved = all(Shaving::Off);
+    assert!(!unshaved.is_empty());
+    assert!(all(Shaving::Root) == unshaved);
+}
@@ -0,0 +1,210 @@
+//! Grids of sizes other than the default, square or not, search, read and combine as the default ones do.
+
+use undeath::{
+    bruteforce,
+    grid::{Symmetry, MIN_SIZE, SIZE},
+    verify::{self, ChainError},
+    Cell, Error, Grid, Guess, SearchResult, Searcher,
+};
+
+/// A glider near the middle of a `width`×`height` grid.
+fn glider(width: i32, height: i32) -> Grid {
+    let mut grid = Grid::new(width, height);
+    for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+        grid.set_wrapped(width / 2 + x, height / 2 - 1 + y, Cell::one());
+    }
+    grid
+}
+
+/// A predecessor of `target`, checked to step to it.
+fn predecessor(target: &Grid) -> Grid {
+    let mut searcher = Searcher::new(target.clone());
+    let parent = loop {
+        match searcher.search(1_000).unwrap() {
+            SearchResult::Found(parent, _) => break parent,
+            SearchResult::Working(_) => {}
+            _ => panic!(
+                "a glider has a predecessor on a {}×{} grid",
+                target.width(),
+                target.height()
+            ),
+        }
+    };
+    let mut stepped = parent.clone();
+    stepped.step();
+    assert!(stepped == *target);
+    parent
+}
+
+#[test]
+fn finds_predecessors_on_larger_grids() {
+    for size in [12, 16] {
+        let parent = predecessor(&glider(size, size));
+        assert_eq!((parent.width(), parent.height()), (size, size));
+    }
+}
+
+#[test]
+fn finds_predecessors_on_wide_short_grids() {
+    for (width, height) in [(20, 6), (6, 20), (40, 5)] {
+        let parent = predecessor(&glider(width, height));
+        assert_eq!((parent.width(), parent.height()), (width, height));
+    }
+}
+
+#[test]
+fn the_smallest_grid_can_be_searched() {
+    let mut searcher = Searcher::new(Grid::new(MIN_SIZE, MIN_SIZE));
+    assert!(matches!(
+        searcher.search(10_000).unwrap(),
+        SearchResult::Found(..)
+    ));
+}
+
+#[test]
+fn searchers_switch_between_sizes() {
+    let mut searcher = Searcher::new(glider(16, 16));
+    searcher.search(100).unwrap();
+    searcher.reset_with_target(glider(20, 6), true);
+    let SearchResult::Found(parent, _) = searcher.search(100_000).unwrap() else {
+        panic!("a glider has a predecessor");
+    };
+    assert_eq!((parent.width(), parent.height()), (20, 6));
+}
+
+#[test]
+fn patterns_are_read_onto_grids_large_enough_for_them() {
+    let small = Grid::from_text(".#");
+    assert_eq!((small.width(), small.height()), (SIZE, SIZE));
+    let wide = Grid::from_text("...........#");
+    assert_eq!((wide.width(), wide.height()), (12, MIN_SIZE));
+    assert!(wide.get_wrapped(11, 0) == Cell::one());
+}
+
+#[test]
+fn a_wide_short_file_is_read_as_it_is() {
+    let text = [
+        "....................",
+        ".#..................",
+        "..#.........##......",
+    ]
+    .repeat(2)
+    .join("\n");
+    let grid = Grid::from_text(&text);
+    assert_eq!((grid.width(), grid.height()), (20, 6));
+    assert_eq!(grid.alive_cells().count(), 8);
+    // The torus wraps at 20 across and 6 down, not at a square size.
+    assert!(grid.get_wrapped(-7, 5) == Cell::one());
+    assert!(grid.get_wrapped(1, 7) == Cell::one());
+}
+
+#[test]
+fn renders_a_header_for_every_column() {
+    let rendered = Grid::new(20, 6).render();
+    let lines = rendered.lines().collect::<Vec<_>>();
+    // A row of tens, a row of units, the top and bottom borders, and the six rows.
+    assert_eq!(lines.len(), 6 + 4);
+    assert_eq!(lines[0].trim(), "10");
+    assert_eq!(lines[1].trim(), "0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9");
+    // Narrow grids have no row of tens.
+    assert_eq!(Grid::default().render().lines().count(), 8 + 3);
+}
+
+#[test]
+fn quarter_turns_swap_the_sides() {
+    let grid = glider(20, 6);
+    let turned = grid.transformed(Symmetry::QuarterTurn);
+    assert_eq!((turned.width(), turned.height()), (6, 20));
+    assert_eq!(turned.alive_cells().count(), 5);
+    let half = grid.transformed(Symmetry::HalfTurn);
+    assert_eq!((half.width(), half.height()), (20, 6));
+}
+
+#[test]
+fn compact_guesses_keep_their_size() {
+    let guess = Guess::new(16, 16);
+    let encoded = guess.to_string();
+    assert!(encoded.starts_with("0 of 256 decided"));
+    let decoded = Guess::parse_compact(&encoded).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (16, 16));
+    assert_eq!(decoded.to_string(), encoded);
+}
+
+#[test]
+fn compact_guesses_keep_both_sides() {
+    let next = glider(20, 6);
+    let mut alive = Grid::new(20, 6);
+    alive.set_wrapped(11, 3, Cell::one());
+    let guess = Guess::from_partial(&next, &alive, &Grid::new(20, 6));
+    let encoded = guess.to_string();
+    assert!(encoded.contains("of 20×6 decided"));
+    let decoded = Guess::parse_compact(&encoded).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (20, 6));
+    assert_eq!(decoded.to_string(), encoded);
+    assert!(Guess::parse_compact(&encoded.replace("20×6", "6×20")).is_err());
+}
+
+#[test]
+#[should_panic(expected = "cannot combine grids of different sizes, 8×8 and 20×6")]
+fn grids_of_different_sizes_do_not_combine() {
+    let _ = Grid::default().and(&Grid::new(20, 6));
+}
+
+#[test]
+#[should_panic]
+fn masks_must_match_the_target() {
+    let _ = Searcher::with_mask(glider(16, 16), &Grid::default());
+}
+
+#[test]
+fn checks_report_mismatched_sizes() {
+    assert!(matches!(
+        verify::verify_chain(&[Grid::default(), Grid::new(8, 16)]),
+        Err(ChainError::SizeMismatch {
+            index: 0,
+            size: (8, 8),
+            other: (8, 16)
+        })
+    ));
+    assert!(matches!(
+        bruteforce::predecessors(&Grid::default(), &Grid::new(16, 16)),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn mismatched_sizes_can_be_checked_without_panicking() {
+    let (small, large) = (Grid::default(), glider(16, 16));
+    assert!(small.check_same_size(&Grid::new(8, 8)).is_ok());
+    assert!(matches!(
+        small.check_same_size(&large),
+        Err(Error::InvalidInput(message)) if message == "cannot combine grids of different sizes, 8×8 and 16×16"
+    ));
+    assert!(matches!(
+        Searcher::try_with_mask(large.clone(), &small),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(Searcher::try_with_mask(large.clone(), &large).is_ok());
+
+    let mut out = small.clone();
+    out.set_wrapped(0, 0, Cell::one());
+    assert!(matches!(
+        large.try_neighbours_into(&mut out),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(out.get_wrapped(0, 0) == Cell::one());
+    let mut counts = Grid::new(16, 16);
+    large.try_neighbours_into(&mut counts).unwrap();
+    assert!(counts == large.neighbours());
+
+    assert!(matches!(
+        Guess::try_from_partial(&large, &small, &large),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        Guess::try_from_partial(&large, &large, &small),
+        Err(Error::InvalidInput(_))
+    ));
+    let guess = Guess::try_from_partial(&large, &Grid::new(16, 16), &Grid::new(16, 16)).unwrap();
+    assert_eq!(guess.decided_count(), 0);
+}
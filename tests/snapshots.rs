@@ -0,0 +1,148 @@
+//! Golden tests for everything that renders to a string.
+//!
+//! Each output is compared with a file in `tests/snapshots`.
+//! After an intended change to a renderer, regenerate the files with
+//!
+//! ```text
+//! UPDATE_SNAPSHOTS=1 cargo test --test snapshots
+//! ```
+//!
+//! and review the diff before committing it.
+
+use std::{path::PathBuf, time::Duration};
+
+use undeath::{
+    string::{
+        highlight_diff_styled, human_duration, large_number, layout_blocks, progress_bar, rate,
+        sparkline, sparkline_ascii, ColumnAlignment, DiffStyle, Table,
+    },
+    testing, Grid,
+};
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "could not read {}: {e}\nrun with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        )
+    });
+    assert!(
+        actual == expected,
+        "{name} no longer matches its snapshot\n--- expected\n{expected}\n--- actual\n{actual}\n\
+         run with UPDATE_SNAPSHOTS=1 if the change is intended"
+    );
+}
+
+fn blinker() -> Grid {
+    Grid::from_text("\n\n\n..###")
+}
+
+fn glider() -> Grid {
+    Grid::from_text("\n.#\n..#\n###")
+}
+
+/// A guess partway through a search for the predecessors of a glider.
+fn mid_search_guess() -> undeath::Guess {
+    testing::apply_guesses(
+        &glider(),
+        &[
+            (0, 0, false),
+            (2, 2, true),
+            (1, 3, true),
+            (5, 5, false),
+            (3, 1, false),
+        ],
+    )
+}
+
+#[test]
+fn grid_render() {
+    assert_snapshot("grid_blinker", &blinker().render());
+}
+
+#[test]
+fn grid_debug() {
+    assert_snapshot("grid_blinker_debug", &format!("{:#?}", blinker()));
+}
+
+#[test]
+fn guess_render() {
+    assert_snapshot("guess_mid_search", &mid_search_guess().render());
+}
+
+#[test]
+fn guess_compact() {
+    let guess = mid_search_guess();
+    assert_snapshot("guess_mid_search_compact", &format!("{guess:#}"));
+    let parsed = undeath::Guess::parse_compact(&format!("{guess:#}")).unwrap();
+    assert_eq!(parsed.render(), guess.render());
+}
+
+#[test]
+fn guess_render_frozen_rows() {
+    let dead_rows = (0..8)
+        .flat_map(|x| [(x, 6, false), (x, 7, false)])
+        .collect::<Vec<_>>();
+    assert_snapshot(
+        "guess_frozen_rows",
+        &testing::apply_guesses(&glider(), &dead_rows).render(),
+    );
+}
+
+#[test]
+fn guess_render_origins() {
+    let script = [(3, 4, false), (0, 4, true), (1, 2, true), (0, 2, true)];
+    assert_snapshot(
+        "guess_origins",
+        &testing::apply_guesses(&glider(), &script).render_origins(),
+    );
+}
+
+#[test]
+fn diff() {
+    let mut stepped = glider();
+    stepped.step();
+    let (expected, actual) = (glider().render(), stepped.render());
+    assert_snapshot(
+        "diff_ansi",
+        &highlight_diff_styled(&expected, &actual, DiffStyle::Ansi),
+    );
+    assert_snapshot(
+        "diff_brackets",
+        &highlight_diff_styled(&expected, &actual, DiffStyle::Brackets),
+    );
+}
+
+#[test]
+fn layout() {
+    let frames = [glider(), blinker(), Grid::default()]
+        .iter()
+        .map(Grid::render)
+        .collect::<Vec<_>>();
+    assert_snapshot("layout_blocks", &layout_blocks(&frames, 60, "   "));
+}
+
+#[test]
+fn stats_table() {
+    let history = [1.0, 2.0, 2.0, 3.0, 5.0, 5.0, 8.0, 13.0];
+    assert_snapshot(
+        "stats_table",
+        &Table::new()
+            .header(["Statistic", "Value"])
+            .align(1, ColumnAlignment::Right)
+            .add_row(["Iterations", &large_number(123_456_789u64)])
+            .add_row(["Rate", &rate(12_345u64, Duration::from_secs(1))])
+            .add_row(["Elapsed", &human_duration(Duration::from_millis(187_000))])
+            .add_row(["Progress", &progress_bar(0.52, 10)])
+            .add_row(["History", &sparkline(&history, 8)])
+            .add_row(["History (ASCII)", &sparkline_ascii(&history, 8)])
+            .render(),
+    );
+}
@@ -0,0 +1,57 @@
+//! Runs complete searches with contradiction validation enabled,
+//! so that any propagation rule that rules out a real predecessor panics with a description of where.
+
+use proptest::prelude::*;
+use undeath::{testing, Cell, Grid, SearchResult, Searcher};
+
+/// Predecessors are confined to this window, which is small enough to brute-force every contradiction.
+fn window() -> Grid {
+    let mut mask = Grid::default();
+    for y in 2..6 {
+        for x in 2..6 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    mask
+}
+
+fn search_validated(target: &Grid, block_propagation: bool) {
+    let mut searcher = Searcher::with_mask(target.clone(), &window())
+        .block_propagation(block_propagation)
+        .validate_contradictions(usize::MAX);
+    loop {
+        match searcher.search(1_000_000).unwrap() {
+            SearchResult::Found(_, _) | SearchResult::Working(_) => {}
+            SearchResult::Unsatisfiable => break,
+            _ => unreachable!(),
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(24))]
+
+    #[test]
+    fn contradictions_are_sound_for_reachable_targets(parent in testing::grid_in(2, 2, 4, 4, 0.4)) {
+        let mut target = parent;
+        target.step();
+        search_validated(&target, false);
+    }
+
+    #[test]
+    fn contradictions_are_sound_for_arbitrary_targets(target in testing::grid_in(2, 2, 4, 4, 0.3)) {
+        search_validated(&target, false);
+    }
+
+    #[test]
+    fn block_contradictions_are_sound_for_reachable_targets(parent in testing::grid_in(2, 2, 4, 4, 0.4)) {
+        let mut target = parent;
+        target.step();
+        search_validated(&target, true);
+    }
+
+    #[test]
+    fn block_contradictions_are_sound_for_arbitrary_targets(target in testing::grid_in(2, 2, 4, 4, 0.3)) {
+        search_validated(&target, true);
+    }
+}
@@ -0,0 +1,65 @@
+//! Summary statistics of grids, with centroids taken around the torus.
+
+use undeath::{grid::SIZE, testing, Grid};
+
+fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+    assert!(
+        (actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9,
+        "{actual:?} is not {expected:?}"
+    );
+}
+
+#[test]
+fn block_is_centred_between_its_cells() {
+    let stats = Grid::from_text("\n\n..##\n..##").stats();
+    assert_eq!(stats.population, 4);
+    assert!((stats.density - 4.0 / 64.0).abs() < 1e-12);
+    assert_close(stats.centroid.unwrap(), (2.5, 2.5));
+    assert!((stats.spread.unwrap() - 0.5f64.sqrt()).abs() < 1e-9);
+    assert_eq!(stats.bounding_box, (2, 2));
+    assert_eq!(stats.components, 1);
+}
+
+#[test]
+fn block_straddling_the_seam_is_centred_on_it() {
+    let corners = Grid::from_text("#......#\n\n\n\n\n\n\n#......#");
+    let stats = corners.stats();
+    assert_close(
+        stats.centroid.unwrap(),
+        (SIZE as f64 - 0.5, SIZE as f64 - 0.5),
+    );
+    assert!((stats.spread.unwrap() - 0.5f64.sqrt()).abs() < 1e-9);
+    assert_eq!(stats.bounding_box, (2, 2));
+    // Cells either side of zero average to zero, not to just below `SIZE`.
+    let (x, _) = Grid::from_text("\n.#.....#").stats().centroid.unwrap();
+    assert!(x.abs() < 1e-9, "{x}");
+}
+
+#[test]
+fn centroid_moves_with_the_pattern() {
+    let glider = Grid::from_text(".#\n..#\n###");
+    let (x, y) = glider.stats().centroid.unwrap();
+    for (dx, dy) in [(3, 0), (5, 6), (7, 7)] {
+        let moved = testing::translate(&glider, dx, dy).stats();
+        let expected = (
+            (x + dx as f64).rem_euclid(SIZE as f64),
+            (y + dy as f64).rem_euclid(SIZE as f64),
+        );
+        assert_close(moved.centroid.unwrap(), expected);
+        assert!((moved.spread.unwrap() - glider.stats().spread.unwrap()).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn balanced_and_empty_grids_have_no_centre() {
+    let row = Grid::from_text("\n\n\n########").stats();
+    assert_eq!((row.centroid, row.spread), (None, None));
+    assert_eq!(row.bounding_box, (SIZE, 1));
+    let empty = Grid::default().stats();
+    assert_eq!((empty.population, empty.components), (0, 0));
+    assert_eq!(empty.centroid, None);
+    assert_eq!(
+        empty.to_string(),
+        "0 alive (0.0%), no centre, in a 0×0 box, 0 components"
+    );
+}
@@ -0,0 +1,210 @@
+//! Grids on a bounded plane, whose cells past the edges are always dead, rather than on a torus.
+
+use std::collections::BTreeSet;
+
+use undeath::{
+    bruteforce,
+    grid::{Neighbourhood, Topology},
+    rng::{RandomSource, Rng},
+    searcher::Strategy,
+    Cell, Error, Grid, SearchResult, Searcher,
+};
+
+fn soup(rng: &mut Rng, width: i32, height: i32, density: f64) -> Grid {
+    let mut grid = Grid::new(width, height).with_topology(Topology::Plane);
+    for y in 0..height {
+        for x in 0..width {
+            if rng.gen_bool(density) {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    grid
+}
+
+fn stepped(grid: &Grid) -> Grid {
+    let mut grid = grid.clone();
+    grid.step();
+    grid
+}
+
+fn exhaust(mut searcher: Searcher) -> BTreeSet<Grid> {
+    let mut found = BTreeSet::new();
+    loop {
+        match searcher.search(usize::MAX).unwrap() {
+            SearchResult::Found(grid, _) => {
+                assert!(found.insert(grid), "found a predecessor twice")
+            }
+            SearchResult::Unsatisfiable => return found,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn edges_and_corners_have_fewer_neighbours() {
+    let plane = Grid::new(8, 6).with_topology(Topology::Plane);
+    let count = |x, y| plane.neighbour_positions(x, y).count();
+    assert_eq!([count(0, 0), count(7, 0), count(0, 5), count(7, 5)], [3; 4]);
+    assert_eq!([count(3, 0), count(0, 2), count(7, 3), count(4, 5)], [5; 4]);
+    assert_eq!(count(3, 2), 8);
+    let torus = Grid::new(8, 6);
+    assert!((0..6).all(|y| (0..8).all(|x| torus.neighbour_positions(x, y).count() == 8)));
+    assert!(plane.neighbour_positions(0, 0).eq([(0, 1), (1, 0), (1, 1)]));
+}
+
+#[test]
+fn every_way_of_stepping_agrees() {
+    let mut rng = Rng::new(510);
+    for (width, height) in [(8, 8), (20, 6), (64, 3), (70, 5)] {
+        for _ in 0..4 {
+            let grid = soup(&mut rng, width, height, 0.4);
+            let counts = grid.neighbours();
+            for y in 0..height {
+                for x in 0..width {
+                    let expected = (-1..=1)
+                        .flat_map(|dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+                        .filter(|&(nx, ny)| (nx, ny) != (x, y))
+                        .filter(|&(nx, ny)| (0..width).contains(&nx) && (0..height).contains(&ny))
+                        .map(|(nx, ny)| grid.get_wrapped(nx, ny).value)
+                        .sum::<i32>();
+                    assert_eq!(counts.get_wrapped(x, y).value, expected, "({x}, {y})");
+                }
+            }
+            let mut bitparallel = grid.clone();
+            bitparallel.step_bitparallel();
+            let mut moore = grid.clone();
+            moore.step_in(Neighbourhood::Moore);
+            assert!(bitparallel == stepped(&grid));
+            assert!(moore == stepped(&grid));
+            assert_eq!(bitparallel.topology(), Topology::Plane);
+        }
+    }
+}
+
+#[test]
+fn exhaustive_search_matches_brute_force_at_the_edge() {
+    // A window in the corner, where wrapping would make a difference.
+    let mut mask = Grid::default().with_topology(Topology::Plane);
+    for y in 0..4 {
+        for x in 0..4 {
+            mask.set_wrapped(x, y, Cell::one());
+        }
+    }
+    let mut rng = Rng::new(510);
+    let mut differs_from_torus = false;
+    for _ in 0..6 {
+        let target = stepped(&soup(&mut rng, 8, 8, 0.5).and(&mask));
+        let expected = bruteforce::predecessors(&target, &mask)
+            .unwrap()
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        assert!(!expected.is_empty());
+        assert!(expected
+            .iter()
+            .all(|grid| grid.topology() == Topology::Plane));
+        assert_eq!(
+            exhaust(Searcher::with_mask(target.clone(), &mask)),
+            expected
+        );
+        // Reasoning about 2×2 blocks must not join cells across an edge, nor is anything checked past one.
+        let blocks = Searcher::with_mask(target.clone(), &mask)
+            .block_propagation(true)
+            .validate_contradictions(usize::MAX);
+        assert_eq!(exhaust(blocks), expected);
+
+        let torus = target.clone().with_topology(Topology::Torus);
+        let on_torus = exhaust(Searcher::with_mask(torus, &mask));
+        differs_from_torus |= on_torus.len() != expected.len();
+    }
+    assert!(differs_from_torus);
+}
+
+#[test]
+fn predecessors_do_not_wrap_around() {
+    // A glider leaving through the bottom right corner.
+    let target =
+        Grid::from_text("\n\n\n\n\n......#\n.......#\n.....###").with_topology(Topology::Plane);
+    let mut searcher = Searcher::new(target.clone());
+    let parent = loop {
+        match searcher.search(1_000_000).unwrap() {
+            SearchResult::Found(parent, _) => break parent,
+            SearchResult::Working(_) => {}
+            _ => panic!("the glider's previous phase is a predecessor"),
+        }
+    };
+    assert_eq!(parent.topology(), Topology::Plane);
+    assert!(stepped(&parent) == target);
+}
+
+#[test]
+fn windows_do_not_wrap_around_a_plane() {
+    // A vertical blinker against the right edge steps to a shorter one, which wrapping would spoil.
+    let mut parent = Grid::new(8, 8).with_topology(Topology::Plane);
+    for y in 2..5 {
+        parent.set_wrapped(7, y, Cell::one());
+    }
+    let target = stepped(&parent);
+    let none = Grid::new(8, 8);
+    assert!(bruteforce::completion(&target, &parent, &none)
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        bruteforce::unsatisfiable_window(&target, &parent, &none, 4).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn windows_only_rule_out_what_brute_force_does() {
+    let mut rng = Rng::new(465);
+    let mut ruled_out = 0;
+    for trial in 0..300 {
+        let parent = soup(&mut rng, 8, 8, 0.4);
+        let mut alive = parent.clone();
+        let mut free = Grid::new(8, 8);
+        for _ in 0..12 {
+            let (x, y) = (rng.gen_range(0..8) as i32, rng.gen_range(0..8) as i32);
+            alive.set_wrapped(x, y, Cell::zero());
+            free.set_wrapped(x, y, Cell::one());
+        }
+        // Flipping a fixed cell usually leaves no predecessor.
+        if trial % 2 == 0 {
+            let (x, y) = (rng.gen_range(0..8) as i32, rng.gen_range(0..8) as i32);
+            if free.get_wrapped(x, y) == Cell::zero() {
+                let flipped = Cell::one() - alive.get_wrapped(x, y);
+                alive.set_wrapped(x, y, flipped);
+            }
+        }
+        let target = stepped(&parent);
+        let exists = bruteforce::completion(&target, &alive, &free)
+            .unwrap()
+            .is_some();
+        for window in 1..=bruteforce::MAX_WINDOW {
+            let found = bruteforce::unsatisfiable_window(&target, &alive, &free, window).unwrap();
+            assert!(
+                !(exists && found.is_some()),
+                "window {window} at {found:?} of a target with a predecessor"
+            );
+            ruled_out += found.is_some() as usize;
+        }
+    }
+    assert!(ruled_out > 0);
+}
+
+#[test]
+fn torus_only_features_are_rejected() {
+    let target = Grid::from_text("\n\n..##\n..##").with_topology(Topology::Plane);
+    let local = Strategy::Local {
+        noise_percent: Strategy::DEFAULT_NOISE_PERCENT,
+        restart_after: 500,
+    };
+    assert!(matches!(
+        Searcher::new(target.clone()).strategy(local).search(100),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        Searcher::new(target).check_windows(4),
+        Err(Error::InvalidInput(_))
+    ));
+}
@@ -0,0 +1,76 @@
+//! Contradictions can be explained by the chain of propagation that led to them.
+
+use undeath::{testing, Grid};
+
+fn glider() -> Grid {
+    Grid::from_text("\n.#\n..#\n###")
+}
+
+/// Four guesses around the glider, then an alive guess at (1, 1). That gives (2, 2) three alive neighbours,
+/// so its last undecided neighbour (1, 3) must be dead, which leaves (1, 2) with exactly three alive neighbours
+/// although it is dead on the next frame.
+const SCRIPT: [(i32, i32, bool); 5] = [
+    (1, 2, true),
+    (0, 2, true),
+    (2, 3, true),
+    (2, 0, true),
+    (1, 1, true),
+];
+
+#[test]
+fn three_step_contradiction() {
+    let guess = testing::apply_guesses(&glider(), &SCRIPT);
+    let trace = guess.contradiction_trace().unwrap();
+    let steps = trace
+        .iter()
+        .map(|step| (step.cell, step.decided, step.rule, step.centre))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        steps,
+        [
+            ((1, 1), Some(true), "guessed", (1, 1)),
+            (
+                (1, 3),
+                Some(false),
+                "at least three neighbours and alive next frame",
+                (2, 2)
+            ),
+            (
+                (1, 2),
+                None,
+                "exactly three neighbours but dead next frame",
+                (1, 2)
+            ),
+        ]
+    );
+    assert_eq!(
+        (
+            trace[1].alive_neighbours,
+            trace[1].dead_neighbours,
+            trace[1].next_alive
+        ),
+        (3, 1, true)
+    );
+    assert_eq!(guess.contradiction(), Some(trace[2].rule));
+
+    assert_eq!(
+        guess.render_trace().unwrap(),
+        "\
+1. (1, 1) guessed alive
+2. (1, 3) deduced dead
+   at least three neighbours and alive next frame
+   at (2, 2): 3 alive and 1 dead neighbours, alive next frame
+3. contradiction at (1, 2)
+   exactly three neighbours but dead next frame
+   at (1, 2): 3 alive and 5 dead neighbours, dead next frame
+"
+    );
+}
+
+#[test]
+fn no_trace_without_contradiction() {
+    let guess = testing::apply_guesses(&glider(), &SCRIPT[..4]);
+    assert!(!guess.found_contradiction());
+    assert!(guess.contradiction_trace().is_none());
+    assert!(guess.render_trace().is_none());
+}
@@ -0,0 +1,48 @@
+//! Sets of cells and per-cell counts, kept apart from plain grids.
+
+use undeath::{
+    grid::{Neighbourhood, SIZE},
+    typed_grid::BoolGrid,
+    Grid,
+};
+
+#[test]
+fn boolean_operations_match_set_operations() {
+    let a = BoolGrid::from_grid(&Grid::from_text("##.\n.##"));
+    let b = BoolGrid::from_grid(&Grid::from_text(".##\n##."));
+
+    let mut union = a.clone();
+    union |= &b;
+    assert!(union.to_grid() == Grid::from_text("###\n###"));
+
+    let mut intersection = a.clone();
+    intersection &= &b;
+    assert!(intersection.to_grid() == Grid::from_text(".#\n.#"));
+
+    let mut difference = a.clone();
+    difference -= &b;
+    assert!(difference.to_grid() == Grid::from_text("#\n..#"));
+
+    let complement = !&a;
+    assert_eq!(complement.count(), (SIZE * SIZE) as usize - a.count());
+    assert!(complement.iter().all(|(x, y)| !a.test(x, y)));
+}
+
+#[test]
+fn converting_a_grid_keeps_only_alive_cells() {
+    // Counts above one and negative values are not cells of a pattern.
+    let mut grid = Grid::default();
+    grid.set_wrapped(0, 0, undeath::Cell { value: 5 });
+    grid.set_wrapped(1, 0, undeath::Cell::neg_one());
+    let cells = BoolGrid::from_grid(&grid);
+    assert_eq!(cells.iter().collect::<Vec<_>>(), [(0, 0)]);
+    assert!(cells.to_grid().get_wrapped(0, 0).value == 1);
+}
+
+#[test]
+fn neighbour_counts_agree_with_grids() {
+    let glider = Grid::from_text(".#\n..#\n###");
+    let counts = BoolGrid::from_grid(&glider).neighbour_counts(Neighbourhood::Moore);
+    assert!(counts.to_grid() == glider.neighbours());
+    assert!(counts.nonzero().count() > glider.alive_cells().count());
+}
@@ -0,0 +1,109 @@
+//! Deliberately broken claims must be rejected by the `verify` module, with the right explanation.
+
+use undeath::{
+    string::strip_ansi,
+    verify::{verify_chain, verify_generations, verify_period, verify_predecessor, ChainError},
+    Cell, Grid,
+};
+
+fn glider_chain(length: usize) -> Vec<Grid> {
+    let mut frame = Grid::from_text("\n.#\n..#\n###");
+    let mut frames = vec![frame.clone()];
+    for _ in 1..length {
+        frame.step();
+        frames.push(frame.clone());
+    }
+    frames
+}
+
+#[test]
+fn valid_chains_pass() {
+    assert_eq!(verify_chain(&glider_chain(10)), Ok(()));
+    assert_eq!(verify_chain(&[]), Ok(()));
+    assert_eq!(verify_chain(&glider_chain(1)), Ok(()));
+}
+
+#[test]
+fn first_broken_link_is_reported() {
+    let mut chain = glider_chain(10);
+    chain[6].set_wrapped(7, 7, Cell::one());
+    chain[8] = Grid::default();
+    match verify_chain(&chain) {
+        // Frame 5 no longer steps to the modified frame 6.
+        Err(ChainError::Mismatch { index: 5, .. }) => {}
+        other => panic!("expected a mismatch at frame 5, got {other:?}"),
+    }
+}
+
+#[test]
+fn swapped_frames_are_rejected() {
+    let mut chain = glider_chain(4);
+    chain.swap(1, 2);
+    assert!(matches!(
+        verify_chain(&chain),
+        Err(ChainError::Mismatch { index: 0, .. })
+    ));
+}
+
+#[test]
+fn masked_predecessors_ignore_dont_care_cells() {
+    let chain = glider_chain(2);
+    let mut next = chain[1].clone();
+    next.set_wrapped(7, 7, Cell::one());
+
+    let everything = Grid::fill(Cell::one());
+    assert!(verify_predecessor(&chain[0], &next, &everything).is_err());
+
+    let mut mask = everything;
+    mask.set_wrapped(7, 7, Cell::zero());
+    assert_eq!(verify_predecessor(&chain[0], &next, &mask), Ok(()));
+}
+
+#[test]
+fn periods() {
+    let blinker = Grid::from_text("\n\n\n..###");
+    assert_eq!(verify_period(&blinker, 2), Ok(()));
+    assert_eq!(
+        verify_period(&blinker, 4),
+        Err(ChainError::ShorterPeriod {
+            claimed: 4,
+            period: 2
+        })
+    );
+    assert_eq!(
+        verify_period(&blinker, 1),
+        Err(ChainError::NotPeriodic { claimed: 1 })
+    );
+    assert_eq!(verify_period(&Grid::default(), 1), Ok(()));
+}
+
+#[test]
+fn mismatch_explains_itself() {
+    let error = verify_chain(&[Grid::from_text("\n\n\n..###"), Grid::default()]).unwrap_err();
+    let message = error.to_string();
+    assert!(message.starts_with("frame 0 does not step to frame 1"));
+    assert!(message.ends_with("differing positions."));
+}
+
+#[test]
+fn mismatches_highlight_the_cells_that_differ() {
+    let blinker = Grid::from_text("\n\n\n..###");
+    // The blinker turns vertical, so the stepped frame has three alive cells the empty one lacks.
+    let message = verify_chain(&[blinker.clone(), Grid::default()])
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("on 3 cells"));
+    // Each cell is drawn two characters wide, and each of the three is highlighted on its own row.
+    assert_eq!(message.matches("\x1b[7;31m██\x1b[0m").count(), 3);
+    assert!(message.ends_with("\n6 differing positions."));
+
+    // A glider moves one cell diagonally in four generations, sharing only one of its five cells.
+    let glider = Grid::from_text(".#\n..#\n###");
+    let message = verify_generations(&glider, &glider, 4)
+        .unwrap_err()
+        .to_string();
+    assert!(message
+        .starts_with("after 4 generations, the grid differs from the claimed one on 8 cells"));
+    assert!(message.contains("\x1b[7;31m"));
+    assert!(strip_ansi(&message).ends_with("\n16 differing positions."));
+}
@@ -0,0 +1,76 @@
+//! The window check proves targets unsatisfiable from a small window, where depth-first search has to branch.
+
+use undeath::{bruteforce, Cell, Grid, SearchResult, Searcher};
+
+/// A sparse target whose predecessors must avoid the holes in a dense mask,
+/// which rules out every predecessor within the 4×4 window at `(0, 5)`.
+fn seeded_orphan() -> (Grid, Grid) {
+    let target = Grid::from_text(
+        "#.#.##..\n.#.#..#.\n.#..#...\n..##..#.\n#...##..\n#.#.....\n#.....#.\n......#.",
+    );
+    let mask = Grid::from_text(
+        "####.###\n######.#\n#.######\n#.#.####\n.#.####.\n#######.\n..##.##.\n##.#####",
+    );
+    (target, mask)
+}
+
+fn decisions_to_exhaust(mut searcher: Searcher) -> u64 {
+    loop {
+        match searcher.search(usize::MAX).unwrap() {
+            SearchResult::Unsatisfiable => return searcher.stats().decisions,
+            SearchResult::Found(..) => panic!("the seeded orphan has no predecessor"),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn catches_the_seeded_orphan() {
+    let (target, mask) = seeded_orphan();
+    let searcher = Searcher::with_mask(target.clone(), &mask);
+    assert!(searcher.root().is_some());
+    assert!(decisions_to_exhaust(searcher) > 1000);
+
+    assert_eq!(
+        bruteforce::unsatisfiable_window(
+            &target,
+            &Grid::default(),
+            &mask,
+            bruteforce::DEFAULT_WINDOW
+        )
+        .unwrap(),
+        Some((0, 5))
+    );
+    let searcher = Searcher::with_mask(target, &mask)
+        .check_windows(bruteforce::DEFAULT_WINDOW)
+        .unwrap();
+    assert!(searcher.root().is_none());
+    assert_eq!(decisions_to_exhaust(searcher), 0);
+}
+
+/// Windows of targets with predecessors always have some, whatever their size.
+#[test]
+fn never_rules_out_a_glider() {
+    let glider = Grid::from_text("\n.#\n..#\n###");
+    let full = Grid::fill(Cell::one());
+    for window in 1..=bruteforce::MAX_WINDOW {
+        assert_eq!(
+            bruteforce::unsatisfiable_window(&glider, &Grid::default(), &full, window).unwrap(),
+            None
+        );
+    }
+    let mut searcher = Searcher::new(glider).check_windows(3).unwrap();
+    assert!(matches!(
+        searcher.search(usize::MAX).unwrap(),
+        SearchResult::Found(..)
+    ));
+}
+
+#[test]
+fn rejects_windows_that_do_not_fit() {
+    let glider = Grid::from_text("\n.#\n..#\n###");
+    assert!(Searcher::new(glider.clone()).check_windows(0).is_err());
+    assert!(Searcher::new(glider)
+        .check_windows(bruteforce::MAX_WINDOW + 1)
+        .is_err());
+}
@@ -0,0 +1,138 @@
+//! Writing grids out in the `.gol` text format, and reading them back in.
+
+use proptest::prelude::*;
+use undeath::{
+    grid::{ParseGridError, Topology, MAX_SIZE, MIN_SIZE, SIZE},
+    rng::{RandomSource, Rng},
+    testing, Cell, Error, Grid,
+};
+
+fn soup(rng: &mut Rng, width: i32, height: i32, density: f64) -> Grid {
+    let mut grid = Grid::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            if rng.gen_bool(density) {
+                grid.set_wrapped(x, y, Cell::one());
+            }
+        }
+    }
+    grid
+}
+
+#[test]
+fn files_read_back_as_the_same_grid() {
+    let directory = std::env::temp_dir().join("undeath-write-files");
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("grid.gol");
+    let mut rng = Rng::new(514);
+    for (width, height) in [
+        (SIZE, SIZE),
+        (SIZE + 1, SIZE + 1),
+        (20, 6),
+        (6, 20),
+        (MIN_SIZE, 12),
+        (MAX_SIZE, MIN_SIZE),
+    ] {
+        for density in [0.0, 0.3, 1.0] {
+            let grid = soup(&mut rng, width, height, density);
+            grid.to_file(&path).unwrap();
+            assert!(
+                Grid::from_file(&path).unwrap() == grid,
+                "{width}×{height} at {density}"
+            );
+            assert!(grid.to_gol_string().parse::<Grid>().unwrap() == grid);
+        }
+    }
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn every_row_is_written_in_full() {
+    let mut grid = Grid::new(12, 4);
+    grid.set_wrapped(1, 1, Cell::one());
+    assert_eq!(
+        grid.to_gol_string(),
+        "............\n.#..........\n............\n............\n"
+    );
+    // `to_text` leaves out what follows the last alive cell, so it cannot keep the size.
+    assert_eq!(grid.to_text(), "\n.#\n");
+    assert!(Grid::from_text(&grid.to_text()) != grid);
+}
+
+#[test]
+fn small_grids_and_planes_say_their_size() {
+    let small = soup(&mut Rng::new(514), 5, 5, 0.5);
+    assert!(small.to_gol_string().starts_with("!size 5x5\n"));
+    assert!(small.to_gol_string().parse::<Grid>().unwrap() == small);
+    let plane = Grid::from_text("\n.###").with_topology(Topology::Plane);
+    assert!(plane.to_gol_string().starts_with("!size 8x8 plane\n"));
+    // Grids that read back as they are without a size line are written without one.
+    assert!(!Grid::default().to_gol_string().starts_with('!'));
+    assert!(!Grid::new(20, 3).to_gol_string().starts_with('!'));
+    // Text with a size line may leave out dead cells, as other text may.
+    let read = "!size 3x4 torus\n.#".parse::<Grid>().unwrap();
+    assert_eq!((read.width(), read.height()), (3, 4));
+    assert_eq!(read.alive_cells().collect::<Vec<_>>(), [(1, 0)]);
+}
+
+#[test]
+fn size_lines_are_checked() {
+    for header in [
+        "!size 5",
+        "!size 5x",
+        "!size 2x5",
+        "!size 5x2000",
+        "!size 5x5 sphere",
+        "!size 5x5 plane torus",
+    ] {
+        assert_eq!(
+            format!("{header}\n...").parse::<Grid>().unwrap_err(),
+            ParseGridError::InvalidHeader {
+                header: header.to_owned()
+            }
+        );
+    }
+    assert_eq!(
+        "!size 4x3\n....\n...#\n....\n#"
+            .parse::<Grid>()
+            .unwrap_err(),
+        ParseGridError::OutsideHeader {
+            line: 5,
+            column: 1,
+            width: 4,
+            height: 3
+        }
+    );
+    // Only the first line can be a size line.
+    let read = "....\n!size 4x3".parse::<Grid>().unwrap();
+    assert_eq!(read.alive_cells().count(), 8);
+}
+
+#[test]
+fn only_dead_and_alive_cells_are_written() {
+    let directory = std::env::temp_dir().join("undeath-write-counts");
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("counts.gol");
+    let counts = Grid::from_text(".#\n..#\n###").neighbours();
+    assert!(matches!(counts.to_file(&path), Err(Error::InvalidInput(_))));
+    assert!(!path.exists());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+proptest! {
+    #[test]
+    fn every_grid_reads_back_as_itself(grid in testing::grid_of_any_size(24, 0.4)) {
+        prop_assert!(grid.to_gol_string().parse::<Grid>().unwrap() == grid);
+        let path = std::env::temp_dir().join(format!(
+            "undeath-write-{}x{}-{:?}-{}.gol",
+            grid.width(),
+            grid.height(),
+            grid.topology(),
+            std::process::id()
+        ));
+        grid.to_file(&path).unwrap();
+        let read = Grid::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        prop_assert!(read == grid);
+    }
+}